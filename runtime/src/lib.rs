@@ -0,0 +1,315 @@
+//! # `numeric-lut-runtime`
+//!
+//! Traits implemented by the lookup tables that `numeric-lut` generates, so that generic code can
+//! accept any table regardless of how it was generated.
+//!
+//! With the `runtime` feature of `numeric-lut`, tables in the item form implement [`LutN`] for
+//! their number of parameters, and thus [`Lut1`] or [`Lut2`] for one or two parameters.
+//!
+//! ## Examples
+//!
+//! ```
+//! use numeric_lut_runtime::Lut1;
+//!
+//! /// The sum of all entries of a table, for any table with `u32` entries.
+//! fn total(lut: &impl Lut1<Entry = u32>) -> u32 {
+//!     lut.iter().map(|(_, entry)| entry).sum()
+//! }
+//!
+//! struct Squares;
+//!
+//! impl numeric_lut_runtime::LutN<1> for Squares {
+//!     type Entry = u32;
+//!
+//!     fn ranges(&self) -> [core::ops::RangeInclusive<usize>; 1] {
+//!         [1..=3]
+//!     }
+//!
+//!     fn get(&self, [x]: [usize; 1]) -> u32 {
+//!         (x * x) as u32
+//!     }
+//! }
+//!
+//! assert_eq!(14, total(&Squares));
+//! ```
+#![no_std]
+#![deny(
+    missing_docs,
+    missing_debug_implementations,
+    missing_copy_implementations,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+use core::ops::RangeInclusive;
+
+/// A table with `N` parameters.
+pub trait LutN<const N: usize> {
+    /// The type of the entries.
+    type Entry;
+
+    /// The values of every parameter.
+    fn ranges(&self) -> [RangeInclusive<usize>; N];
+
+    /// Looks up the entry for the given parameters, which may panic if one of them is outside of
+    /// its range.
+    fn get(&self, params: [usize; N]) -> Self::Entry;
+
+    /// Looks up the entry for the given parameters, or returns `None` if one of them is outside of
+    /// its range.
+    fn get_checked(&self, params: [usize; N]) -> Option<Self::Entry> {
+        let ranges = self.ranges();
+        if ranges
+            .iter()
+            .zip(params.iter())
+            .all(|(range, p)| range.contains(p))
+        {
+            Some(self.get(params))
+        } else {
+            None
+        }
+    }
+
+    /// The number of values of every parameter.
+    fn shape(&self) -> [usize; N] {
+        let ranges = self.ranges();
+        let mut shape = [0; N];
+        for (len, range) in shape.iter_mut().zip(ranges.iter()) {
+            *len = range.end() + 1 - range.start();
+        }
+        shape
+    }
+
+    /// Iterates over the parameters and entries of the table, in which the last parameter changes
+    /// fastest.
+    fn iter(&self) -> Iter<'_, Self, N> {
+        Iter {
+            lut: self,
+            params: Params::new(self.ranges()),
+        }
+    }
+}
+
+/// A table with one parameter.
+///
+/// This is implemented for all tables that implement [`LutN<1>`], and takes the parameter as it is
+/// instead of in an array.
+pub trait Lut1 {
+    /// The type of the entries.
+    type Entry;
+
+    /// The values of the parameter.
+    fn range(&self) -> RangeInclusive<usize>;
+
+    /// Looks up the entry for the given parameter, which may panic if it is outside of its range.
+    fn get(&self, x: usize) -> Self::Entry;
+
+    /// Looks up the entry for the given parameter, or returns `None` if it is outside of its
+    /// range.
+    fn get_checked(&self, x: usize) -> Option<Self::Entry> {
+        if self.range().contains(&x) {
+            Some(self.get(x))
+        } else {
+            None
+        }
+    }
+
+    /// The number of values of the parameter.
+    fn shape(&self) -> usize {
+        let range = self.range();
+        range.end() + 1 - range.start()
+    }
+
+    /// Iterates over the parameters and entries of the table in ascending order.
+    fn iter(&self) -> Iter1<'_, Self> {
+        Iter1 {
+            lut: self,
+            params: Params::new([self.range()]),
+        }
+    }
+}
+
+impl<L: LutN<1> + ?Sized> Lut1 for L {
+    type Entry = L::Entry;
+
+    fn range(&self) -> RangeInclusive<usize> {
+        let [range] = LutN::ranges(self);
+        range
+    }
+
+    fn get(&self, x: usize) -> Self::Entry {
+        LutN::get(self, [x])
+    }
+}
+
+/// A table with two parameters.
+///
+/// This is implemented for all tables that implement [`LutN<2>`], and takes the parameters as they
+/// are instead of in an array.
+pub trait Lut2 {
+    /// The type of the entries.
+    type Entry;
+
+    /// The values of both parameters.
+    fn ranges(&self) -> (RangeInclusive<usize>, RangeInclusive<usize>);
+
+    /// Looks up the entry for the given parameters, which may panic if one of them is outside of
+    /// its range.
+    fn get(&self, x: usize, y: usize) -> Self::Entry;
+
+    /// Looks up the entry for the given parameters, or returns `None` if one of them is outside of
+    /// its range.
+    fn get_checked(&self, x: usize, y: usize) -> Option<Self::Entry> {
+        let (xs, ys) = self.ranges();
+        if xs.contains(&x) && ys.contains(&y) {
+            Some(self.get(x, y))
+        } else {
+            None
+        }
+    }
+
+    /// The number of values of both parameters.
+    fn shape(&self) -> (usize, usize) {
+        let (xs, ys) = self.ranges();
+        (xs.end() + 1 - xs.start(), ys.end() + 1 - ys.start())
+    }
+
+    /// Iterates over the parameters and entries of the table, in which the second parameter
+    /// changes fastest.
+    fn iter(&self) -> Iter2<'_, Self> {
+        let (xs, ys) = self.ranges();
+        Iter2 {
+            lut: self,
+            params: Params::new([xs, ys]),
+        }
+    }
+}
+
+impl<L: LutN<2> + ?Sized> Lut2 for L {
+    type Entry = L::Entry;
+
+    fn ranges(&self) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
+        let [xs, ys] = LutN::ranges(self);
+        (xs, ys)
+    }
+
+    fn get(&self, x: usize, y: usize) -> Self::Entry {
+        LutN::get(self, [x, y])
+    }
+}
+
+/// The parameters of all entries of a table, in which the last parameter changes fastest.
+#[derive(Clone, Debug)]
+struct Params<const N: usize> {
+    ranges: [RangeInclusive<usize>; N],
+    next: usize,
+    len: usize,
+}
+
+impl<const N: usize> Params<N> {
+    fn new(ranges: [RangeInclusive<usize>; N]) -> Self {
+        let len = ranges
+            .iter()
+            .map(|range| range.end() + 1 - range.start())
+            .product();
+        Params {
+            ranges,
+            next: 0,
+            len,
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Params<N> {
+    type Item = [usize; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next == self.len {
+            return None;
+        }
+        let mut flat = self.next;
+        let mut params = [0; N];
+        for (param, range) in params.iter_mut().zip(self.ranges.iter()).rev() {
+            let len = range.end() + 1 - range.start();
+            *param = range.start() + flat % len;
+            flat /= len;
+        }
+        self.next += 1;
+        Some(params)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.next;
+        (len, Some(len))
+    }
+}
+
+/// The iterator of [`LutN::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter<'a, L: ?Sized, const N: usize> {
+    lut: &'a L,
+    params: Params<N>,
+}
+
+impl<'a, L: LutN<N> + ?Sized, const N: usize> Iterator for Iter<'a, L, N> {
+    type Item = ([usize; N], L::Entry);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let params = self.params.next()?;
+        Some((params, self.lut.get(params)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.params.size_hint()
+    }
+}
+
+impl<'a, L: LutN<N> + ?Sized, const N: usize> ExactSizeIterator for Iter<'a, L, N> {}
+
+/// The iterator of [`Lut1::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter1<'a, L: ?Sized> {
+    lut: &'a L,
+    params: Params<1>,
+}
+
+impl<'a, L: Lut1 + ?Sized> Iterator for Iter1<'a, L> {
+    type Item = (usize, L::Entry);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let [x] = self.params.next()?;
+        Some((x, self.lut.get(x)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.params.size_hint()
+    }
+}
+
+impl<'a, L: Lut1 + ?Sized> ExactSizeIterator for Iter1<'a, L> {}
+
+/// The iterator of [`Lut2::iter`].
+#[derive(Clone, Debug)]
+pub struct Iter2<'a, L: ?Sized> {
+    lut: &'a L,
+    params: Params<2>,
+}
+
+impl<'a, L: Lut2 + ?Sized> Iterator for Iter2<'a, L> {
+    type Item = ((usize, usize), L::Entry);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let [x, y] = self.params.next()?;
+        Some(((x, y), self.lut.get(x, y)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.params.size_hint()
+    }
+}
+
+impl<'a, L: Lut2 + ?Sized> ExactSizeIterator for Iter2<'a, L> {}
@@ -0,0 +1,92 @@
+use numeric_lut_runtime::{Lut1, Lut2, LutN};
+
+struct Product;
+
+impl LutN<2> for Product {
+    type Entry = u32;
+
+    fn ranges(&self) -> [core::ops::RangeInclusive<usize>; 2] {
+        [1..=3, 2..=3]
+    }
+
+    fn get(&self, [x, y]: [usize; 2]) -> u32 {
+        (x * y) as u32
+    }
+}
+
+struct Cube;
+
+impl LutN<3> for Cube {
+    type Entry = usize;
+
+    fn ranges(&self) -> [core::ops::RangeInclusive<usize>; 3] {
+        [0..=1, 0..=1, 5..=5]
+    }
+
+    fn get(&self, [x, y, z]: [usize; 3]) -> usize {
+        x * 100 + y * 10 + z
+    }
+}
+
+struct Letters;
+
+impl LutN<1> for Letters {
+    type Entry = char;
+
+    fn ranges(&self) -> [core::ops::RangeInclusive<usize>; 1] {
+        [0..=2]
+    }
+
+    fn get(&self, [x]: [usize; 1]) -> char {
+        (b'a' + x as u8) as char
+    }
+}
+
+#[test]
+fn lut_n() {
+    assert_eq!([2, 2, 1], LutN::shape(&Cube));
+    assert_eq!(Some(105), LutN::get_checked(&Cube, [1, 0, 5]));
+    assert_eq!(None, LutN::get_checked(&Cube, [1, 0, 4]));
+    let entries = LutN::iter(&Cube).collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ([0, 0, 5], 5),
+            ([0, 1, 5], 15),
+            ([1, 0, 5], 105),
+            ([1, 1, 5], 115)
+        ],
+        entries
+    );
+}
+
+#[test]
+fn lut1() {
+    assert_eq!('b', Lut1::get(&Letters, 1));
+    assert_eq!(None, Lut1::get_checked(&Letters, 3));
+    assert_eq!(3, Lut1::shape(&Letters));
+    let entries = Lut1::iter(&Letters).collect::<Vec<_>>();
+    assert_eq!(vec![(0, 'a'), (1, 'b'), (2, 'c')], entries);
+}
+
+#[test]
+fn lut2() {
+    assert_eq!(9, Lut2::get(&Product, 3, 3));
+    assert_eq!(Some(4), Lut2::get_checked(&Product, 2, 2));
+    assert_eq!(None, Lut2::get_checked(&Product, 0, 2));
+    assert_eq!((3, 2), Lut2::shape(&Product));
+    let mut iter = Lut2::iter(&Product);
+    assert_eq!(6, iter.len());
+    assert_eq!(Some(((1, 2), 2)), iter.next());
+    assert_eq!(Some(((1, 3), 3)), iter.next());
+    assert_eq!(Some(((2, 2), 4)), iter.next());
+    assert_eq!(3, iter.len());
+}
+
+#[test]
+fn generic() {
+    fn total<L: Lut1<Entry = char>>(lut: &L) -> String {
+        lut.iter().map(|(_, entry)| entry).collect()
+    }
+
+    assert_eq!("abc", total(&Letters));
+}
@@ -0,0 +1,121 @@
+//! Criterion benchmarks that compare evaluating the body of a table with looking it up.
+//!
+//! Both are measured over the same parameters, once in the order of the stored entries and once in
+//! a pseudo-random order, since tables that fit into the cache only pay off for the former.
+
+/// The number of lookups per iteration of a benchmark.
+const SAMPLES: usize = 4096;
+
+/// The input of `lut_bench!`.
+struct Bench {
+    name: syn::Ident,
+    spec: proc_macro2::TokenStream,
+}
+
+impl syn::parse::Parse for Bench {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        Ok(Bench {
+            name,
+            spec: input.parse()?,
+        })
+    }
+}
+
+/// Generates a function that runs the benchmarks of a table.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let Bench { name, spec } = syn::parse2(input)?;
+    if cfg!(not(feature = "criterion")) {
+        return Err(syn::Error::new(
+            name.span(),
+            "`lut_bench!` requires the `criterion` feature of `numeric-lut`",
+        ));
+    }
+    let lut: super::Lut = syn::parse2(spec.clone())?;
+    if let super::Form::Struct { ident, .. } = &lut.form {
+        return Err(syn::Error::new(
+            ident.span(),
+            "`lut_bench!` only supports the closure form",
+        ));
+    }
+    // Closures with a return type need a block, which the call of the function path form isn't.
+    let body = match &lut.body {
+        body @ syn::Expr::Block(_) => quote::quote!(#body),
+        body => quote::quote!({ #body }),
+    };
+    let return_type = match &lut.return_type {
+        ty if super::is_inferred(ty) => proc_macro2::TokenStream::new(),
+        ty => quote::quote!(-> #ty),
+    };
+    if lut.inputs.is_empty() {
+        return Err(syn::Error::new(
+            name.span(),
+            "`lut_bench!` requires at least one parameter",
+        ));
+    }
+    let table = proc_macro2::TokenStream::from(super::lut(spec.into()));
+
+    let params = super::params(lut.inputs.iter());
+    let rank = lut.inputs.len();
+    let len = lut.inputs.iter().map(super::Param::len).product::<usize>();
+    let unflatten = super::unflatten(lut.inputs.iter());
+    let (sequential, random) = match len {
+        1 => {
+            let all = quote::quote!(::std::vec![__params(0); #SAMPLES]);
+            (all.clone(), all)
+        }
+        len => (
+            quote::quote! {
+                (0..#SAMPLES)
+                    .map(|__i| __params(__i % #len))
+                    .collect::<::std::vec::Vec<_>>()
+            },
+            quote::quote!({
+                // A xorshift generator, which is random enough to defeat prefetching.
+                let mut __state: u64 = 0x9e37_79b9_7f4a_7c15;
+                (0..#SAMPLES)
+                    .map(|_| {
+                        __state ^= __state << 13;
+                        __state ^= __state >> 7;
+                        __state ^= __state << 17;
+                        __params((__state % #len as u64) as usize)
+                    })
+                    .collect::<::std::vec::Vec<_>>()
+            }),
+        ),
+    };
+    let args = (0..rank).map(|i| quote::quote!(__p[#i]));
+    let args = quote::quote!(#(::criterion::black_box(#args)),*);
+    let group = name.to_string();
+    Ok(quote::quote! {
+        /// Benchmarks evaluating the body of the table against looking it up.
+        fn #name(c: &mut ::criterion::Criterion) {
+            let __lut = #table;
+            let __direct = |#(#params),*| #return_type #body;
+
+            let __params = |__flat: usize| -> [usize; #rank] { [#(#unflatten),*] };
+            let __sequential = #sequential;
+            let __random = #random;
+
+            let mut __group = c.benchmark_group(#group);
+            for (__pattern, __samples) in [("sequential", &__sequential), ("random", &__random)] {
+                __group.bench_function(::criterion::BenchmarkId::new("direct", __pattern), |b| {
+                    b.iter(|| {
+                        for __p in __samples.iter() {
+                            ::criterion::black_box(__direct(#args));
+                        }
+                    })
+                });
+                __group.bench_function(::criterion::BenchmarkId::new("table", __pattern), |b| {
+                    b.iter(|| {
+                        for __p in __samples.iter() {
+                            ::criterion::black_box(__lut(#args));
+                        }
+                    })
+                });
+            }
+            __group.finish();
+        }
+    })
+}
@@ -0,0 +1,289 @@
+//! Evaluation of tables in build scripts, for bodies that can't be evaluated as constants.
+//!
+//! `lut_build!` is invoked in the build script of a crate, where it expands to code that evaluates
+//! the body for every entry at runtime and writes the entries as a Rust expression to
+//! `$OUT_DIR/numeric-lut/<name>.rs`.  The `lut!` invocations of the crate itself then declare the
+//! same ranges with `#[built = "name"]` instead of a body, and include the file once to look up
+//! their entries in it.  The file also records the ranges, so that both sides can't drift apart.
+//!
+//! The entries are written as the little-endian bytes of a single byte string literal, which the
+//! compiler parses much faster than one token per entry, and are decoded during constant
+//! evaluation.
+
+/// The input of `lut_build!`.
+struct Build {
+    name: syn::LitStr,
+    inputs: syn::punctuated::Punctuated<super::Param, syn::Token![,]>,
+    return_type: syn::Type,
+    body: syn::Block,
+}
+
+impl syn::parse::Parse for Build {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        input.parse::<syn::Token![|]>()?;
+        let mut inputs = syn::punctuated::Punctuated::new();
+        while !input.peek(syn::Token![|]) {
+            inputs.push_value(input.parse()?);
+            if input.peek(syn::Token![|]) {
+                break;
+            }
+            inputs.push_punct(input.parse()?);
+        }
+        input.parse::<syn::Token![|]>()?;
+        input.parse::<syn::Token![->]>()?;
+        let return_type = input.parse()?;
+        let body = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(Build {
+            name,
+            inputs,
+            return_type,
+            body,
+        })
+    }
+}
+
+/// How the entries of an element type are stored in the bytes of the file.
+struct Encoding {
+    /// The type whose little-endian bytes hold an entry.
+    stored: syn::Ident,
+    /// The entry as the stored type, given the entry as `__entry`.
+    encode: proc_macro2::TokenStream,
+    /// Converts the stored type back to the entry.
+    decode: fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+    /// Any value of the element type.
+    zero: proc_macro2::TokenStream,
+}
+
+impl Encoding {
+    fn of(ty: &syn::Type) -> syn::Result<Self> {
+        use syn::spanned::Spanned;
+
+        let name = match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident(),
+            _ => None,
+        }
+        .map(syn::Ident::to_string)
+        .filter(|name| {
+            name == "bool" || name == "char" || super::preset::primitive_name(ty).is_some()
+        })
+        .ok_or_else(|| {
+            syn::Error::new(
+                ty.span(),
+                "only numeric types, `bool` and `char` can be evaluated in build scripts",
+            )
+        })?;
+        type Decode = fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream;
+        let same: Decode = |stored| stored;
+        // The sizes of `usize` and `isize` differ between the build script and the target, so
+        // they are stored as 64 bits, and entries that don't fit into the target fail to build.
+        let (stored, encode, decode, zero): (_, _, Decode, _) = match name.as_str() {
+            "usize" => (
+                "u64",
+                quote::quote!(__entry as u64),
+                |stored| {
+                    quote::quote! {
+                        match #stored {
+                            __stored if __stored as usize as u64 == __stored => __stored as usize,
+                            _ => ::core::panic!("`usize` entry doesn't fit into the `usize` of the target"),
+                        }
+                    }
+                },
+                quote::quote!(0),
+            ),
+            "isize" => (
+                "i64",
+                quote::quote!(__entry as i64),
+                |stored| {
+                    quote::quote! {
+                        match #stored {
+                            __stored if __stored as isize as i64 == __stored => __stored as isize,
+                            _ => ::core::panic!("`isize` entry doesn't fit into the `isize` of the target"),
+                        }
+                    }
+                },
+                quote::quote!(0),
+            ),
+            "bool" => (
+                "u8",
+                quote::quote!(u8::from(__entry)),
+                |stored| quote::quote!(#stored != 0),
+                quote::quote!(false),
+            ),
+            "char" => (
+                "u32",
+                quote::quote!(u32::from(__entry)),
+                |stored| {
+                    quote::quote! {
+                        match ::core::char::from_u32(#stored) {
+                            ::core::option::Option::Some(__c) => __c,
+                            ::core::option::Option::None => ::core::panic!("invalid `char` entry"),
+                        }
+                    }
+                },
+                quote::quote!('\0'),
+            ),
+            float if float.starts_with('f') => {
+                (float, quote::quote!(__entry), same, quote::quote!(0.0))
+            }
+            int => (int, quote::quote!(__entry), same, quote::quote!(0)),
+        };
+        Ok(Encoding {
+            stored: syn::Ident::new(stored, ty.span()),
+            encode,
+            decode,
+            zero,
+        })
+    }
+
+    /// The number of bytes of an entry.
+    fn size(&self) -> usize {
+        let bits = self.stored.to_string()[1..].parse::<usize>();
+        bits.expect("stored types are sized integers or floats") / 8
+    }
+}
+
+/// The path of the file with the entries of the table with the given name, relative to `OUT_DIR`.
+fn file(name: &str) -> String {
+    format!("/numeric-lut/{}.rs", name)
+}
+
+/// Generates the code of a build script that writes the entries of a table.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let build: Build = syn::parse2(input)?;
+    let return_type = &build.return_type;
+    let encoding = Encoding::of(return_type)?;
+    let encode = &encoding.encode;
+
+    let ranges = build
+        .inputs
+        .iter()
+        .map(|param| format!("({}, {})", param.lo, param.len()))
+        .collect::<Vec<_>>();
+    let header = format!("([{}], *b\"", ranges.join(", "));
+    let body = &build.body;
+    // Floats are written as their bits, so that they round-trip exactly (including NaNs).
+    let mut evaluate = quote::quote!({
+        let __entry: #return_type = #body;
+        __bytes.extend_from_slice(&(#encode).to_le_bytes());
+    });
+    for param in build.inputs.iter().rev() {
+        let ident = &param.ident;
+        let (lo, hi) = (param.lo, param.lo + param.len());
+        evaluate = quote::quote! {
+            for #ident in #lo..#hi {
+                #evaluate
+            }
+        };
+    }
+    let name = build.name.value();
+    let file = file(&name);
+    let file = file.trim_start_matches('/');
+    Ok(quote::quote!({
+        let mut __bytes = ::std::vec::Vec::<u8>::new();
+        #evaluate
+        let mut __out = ::std::string::String::from(#header);
+        for __byte in __bytes {
+            __out.push_str(&::std::format!("\\x{:02x}", __byte));
+        }
+        __out.push_str("\")\n");
+
+        let __out_dir = ::std::env::var_os("OUT_DIR")
+            .expect("`lut_build!` must be invoked in a build script, where `OUT_DIR` is set");
+        let __path = ::std::path::Path::new(&__out_dir).join(#file);
+        ::std::fs::create_dir_all(__path.parent().expect("the file is in a directory"))
+            .and_then(|()| ::std::fs::write(&__path, __out))
+            .unwrap_or_else(|err| {
+                ::std::panic!("failed to write {}: {}", __path.display(), err)
+            });
+    }))
+}
+
+/// Generates the body of a table with `#[built = "name"]`, which looks up the entry in the constant
+/// of the included entries.
+pub(crate) fn body(
+    inputs: &syn::punctuated::Punctuated<super::Param, syn::Token![,]>,
+    name: &syn::LitStr,
+) -> syn::Expr {
+    let constant = constant(name);
+    let index = super::flat_index(inputs.iter());
+    syn::Expr::Verbatim(quote::quote_spanned!(name.span()=> #constant[#index]))
+}
+
+/// Generates the constant holding the included entries of a table with `#[built = "name"]`.
+pub(crate) fn include(
+    input: &super::Lut,
+    name: &syn::LitStr,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let constant = constant(name);
+    let encoding = Encoding::of(&input.return_type)?;
+    let size = encoding.size();
+    let return_type = &input.return_type;
+    let rank = input.inputs.len();
+    let len = input
+        .inputs
+        .iter()
+        .map(super::Param::len)
+        .product::<usize>();
+    let file = file(&name.value());
+    let checks = input.inputs.iter().enumerate().map(|(i, param)| {
+        let (lo, len) = (param.lo, param.len());
+        let message = format!(
+            "`{}` was built for another range of `{}`; update the `lut_build!` invocation",
+            file.trim_start_matches('/'),
+            param.ident
+        );
+        quote::quote! {
+            if __BUILT.0[#i].0 != #lo || __BUILT.0[#i].1 != #len {
+                ::core::panic!(#message);
+            }
+        }
+    });
+    let Encoding {
+        stored,
+        decode,
+        zero,
+        ..
+    } = encoding;
+    let entry = decode(quote::quote!(#stored::from_le_bytes(__stored)));
+    let bytes = len * size;
+    Ok(quote::quote_spanned! {name.span()=>
+        #[allow(non_upper_case_globals)]
+        const #constant: [#return_type; #len] = {
+            const __BUILT: ([(usize, usize); #rank], [u8; #bytes]) =
+                ::core::include!(::core::concat!(::core::env!("OUT_DIR"), #file));
+            #(#checks)*
+            let mut __entries = [#zero; #len];
+            let mut __i = 0;
+            while __i < #len {
+                let mut __stored = [0; #size];
+                let mut __k = 0;
+                while __k < #size {
+                    __stored[__k] = __BUILT.1[__i * #size + __k];
+                    __k += 1;
+                }
+                __entries[__i] = #entry;
+                __i += 1;
+            }
+            __entries
+        };
+    })
+}
+
+/// The name of the constant holding the included entries of a table with `#[built = "name"]`.
+fn constant(name: &syn::LitStr) -> syn::Ident {
+    let name = name
+        .value()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .to_uppercase();
+    syn::Ident::new(
+        &format!("__NUMERIC_LUT_BUILT_{}", name),
+        proc_macro2::Span::call_site(),
+    )
+}
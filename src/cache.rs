@@ -0,0 +1,136 @@
+//! An on-disk cache of the tokens that macros compute while expanding, like the entries of presets
+//! and of data files.
+//!
+//! The cache is a directory named by the `NUMERIC_LUT_CACHE` environment variable, typically under
+//! `target/`, with a file per expansion that is named after the `spec_hash` of the macro input (as
+//! in the metadata of tables).  The file starts with a line that records the version of this crate
+//! and a hash of any other inputs, like the contents of a data file, so that entries of other
+//! versions or of changed files are computed again.  Only successful expansions are cached, since
+//! tokens that are parsed back from a file lose their spans, which errors need.
+
+use super::metadata::fnv1a;
+
+/// Computes the tokens for the given macro input with `generate`, unless the cache already holds
+/// them for the same input and `contents`.
+///
+/// `kind` tells the tokens of different macros for the same input apart, and `contents` holds any
+/// inputs of the expansion other than the macro input.
+pub(crate) fn cached(
+    kind: &str,
+    input: proc_macro2::TokenStream,
+    contents: &[u8],
+    generate: impl FnOnce(proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let dir = match std::env::var_os(CACHE_VAR) {
+        Some(dir) if !dir.is_empty() => std::path::PathBuf::from(dir),
+        _ => return generate(input),
+    };
+    cached_in(&dir, kind, input, contents, generate)
+}
+
+/// Like `cached`, with the cache in the given directory.
+fn cached_in(
+    dir: &std::path::Path,
+    kind: &str,
+    input: proc_macro2::TokenStream,
+    contents: &[u8],
+    generate: impl FnOnce(proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let spec_hash = fnv1a(input.to_string().as_bytes());
+    let path = dir.join(format!("{}-{:016x}.rs", kind, spec_hash));
+    let header = format!(
+        "// numeric-lut {} {:016x}\n",
+        env!("CARGO_PKG_VERSION"),
+        fnv1a(contents)
+    );
+    let hit = std::fs::read_to_string(&path).ok().and_then(|cached| {
+        cached
+            .strip_prefix(&header)
+            .and_then(|tokens| tokens.parse().ok())
+    });
+    if let Some(tokens) = hit {
+        return Ok(tokens);
+    }
+
+    let tokens = generate(input)?;
+    // The cache only saves time, so expansions don't fail when it can't be written.  Parallel
+    // builds can expand the same input at once, so every file is written under a name of its own
+    // first and then renamed into place.
+    let temporary = path.with_extension(format!("{}.tmp", std::process::id()));
+    let written = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&temporary, format!("{}{}", header, tokens)))
+        .and_then(|()| std::fs::rename(&temporary, &path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    Ok(tokens)
+}
+
+/// The environment variable naming the directory of the cache.
+const CACHE_VAR: &str = "NUMERIC_LUT_CACHE";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dir(test: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("numeric-lut-cache-{}-{}", test, std::process::id()))
+    }
+
+    #[test]
+    fn reuses_cached_tokens() {
+        let dir = dir("reuse");
+        let input = quote::quote!(bits = 8, ty = f64);
+        let sin = |_| Ok(quote::quote!([1.0, 0.5]));
+        let tokens = cached_in(&dir, "lut_sin", input.clone(), &[], sin).unwrap();
+        assert_eq!("[1.0 , 0.5]", tokens.to_string());
+        let cached = |_| panic!("the tokens are cached");
+        let tokens = cached_in(&dir, "lut_sin", input.clone(), &[], cached).unwrap();
+        assert_eq!("[1.0 , 0.5]", tokens.to_string());
+
+        // Other macros and other inputs don't share the tokens.
+        let cos = |_| Ok(quote::quote!([0.0]));
+        let tokens = cached_in(&dir, "lut_cos", input, &[], cos).unwrap();
+        assert_eq!("[0.0]", tokens.to_string());
+        let sin = |_| Ok(quote::quote!([0.0]));
+        let tokens = cached_in(&dir, "lut_sin", quote::quote!(bits = 9), &[], sin).unwrap();
+        assert_eq!("[0.0]", tokens.to_string());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recomputes_changed_contents() {
+        let dir = dir("contents");
+        let input = quote::quote!("data.json", |x @ 0..2| -> u8);
+        let read = |_| Ok(quote::quote!(1, 2));
+        cached_in(&dir, "lut_from_file", input.clone(), b"[1, 2]", read).unwrap();
+        let read = |_| Ok(quote::quote!(1, 3));
+        let tokens = cached_in(&dir, "lut_from_file", input, b"[1, 3]", read).unwrap();
+        assert_eq!("1 , 3", tokens.to_string());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recomputes_damaged_files() {
+        let dir = dir("damaged");
+        let input = quote::quote!(mul);
+        let mul = |_| Ok(quote::quote!([0]));
+        cached_in(&dir, "lut_gf256", input.clone(), &[], mul).unwrap();
+        let path = dir.join(format!("lut_gf256-{:016x}.rs", fnv1a(b"mul")));
+        let cached = std::fs::read_to_string(&path).unwrap();
+        // The bracket is missing.
+        std::fs::write(&path, &cached[..cached.len() - 1]).unwrap();
+        let mul = |_| Ok(quote::quote!([1]));
+        let tokens = cached_in(&dir, "lut_gf256", input, &[], mul).unwrap();
+        assert_eq!("[1]", tokens.to_string());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let dir = dir("errors");
+        let error = |_| Err(syn::Error::new(proc_macro2::Span::call_site(), "invalid"));
+        assert!(cached_in(&dir, "lut_sbox", quote::quote!(aes), &[], error).is_err());
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,239 @@
+//! Parameters that only exist in some build configurations.
+//!
+//! A proc macro can't evaluate the `cfg` predicates of the invoking crate, so a spec with
+//! `#[cfg(..)]` attributes on its parameters is expanded once for every combination of its
+//! predicates, without the parameters whose predicates don't hold.  Every expansion is put under a
+//! `#[cfg(..)]` that holds in exactly that combination, so that only one of them is compiled.
+
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+
+/// The most distinct predicates a spec can have, which bounds the number of expansions.
+const MAX_PREDICATES: usize = 6;
+
+/// A spec whose parameters have `#[cfg(..)]` attributes.
+pub(crate) struct Split {
+    /// Whether the spec is in the item form, whose expansion is a sequence of items rather than an
+    /// expression.
+    item: bool,
+    /// The spec of every combination of the predicates, under the predicate of the combination.
+    variants: Vec<(TokenStream, TokenStream)>,
+}
+
+/// Where the parameters are in the tokens of a spec.
+enum Params {
+    /// The parameters are the tokens in this range, as in the closure and function path forms.
+    Tokens(usize, usize),
+    /// The parameters are the contents of the group at this index, as in the item form.
+    Group(usize),
+}
+
+/// A parameter with the predicates of its `#[cfg(..)]` attributes, as indices of atoms and whether
+/// they are negated.
+struct Param {
+    predicates: Vec<(usize, bool)>,
+    tokens: Vec<TokenTree>,
+}
+
+/// Splits the spec into its build configurations, or returns `None` if no parameter has a
+/// `#[cfg(..)]` attribute.
+pub(crate) fn split(spec: TokenStream) -> syn::Result<Option<Split>> {
+    let tokens = spec.into_iter().collect::<Vec<_>>();
+    let at = match find_params(&tokens) {
+        Some(at) => at,
+        None => return Ok(None),
+    };
+    let inner = match at {
+        Params::Tokens(start, end) => tokens[start..end].to_vec(),
+        Params::Group(index) => match &tokens[index] {
+            TokenTree::Group(group) => group.stream().into_iter().collect(),
+            _ => unreachable!("the parameters are in a group"),
+        },
+    };
+
+    let mut atoms = Vec::<TokenStream>::new();
+    let mut params = Vec::new();
+    for declaration in inner.split(|token| is_punct(token, ',')) {
+        let mut param = Param {
+            predicates: Vec::new(),
+            tokens: Vec::new(),
+        };
+        let mut rest = declaration;
+        while let Some((attr, predicate)) = cfg_attr(rest) {
+            rest = &rest[2..];
+            let (atom, negated) = atom(predicate);
+            let index = match atoms
+                .iter()
+                .position(|known| known.to_string() == atom.to_string())
+            {
+                Some(index) => index,
+                None if atoms.len() == MAX_PREDICATES => {
+                    return Err(syn::Error::new(
+                        attr,
+                        format!(
+                            "a table can have at most {} distinct `cfg` predicates",
+                            MAX_PREDICATES
+                        ),
+                    ))
+                }
+                None => {
+                    atoms.push(atom);
+                    atoms.len() - 1
+                }
+            };
+            param.predicates.push((index, negated));
+        }
+        param.tokens = rest.to_vec();
+        if !param.tokens.is_empty() {
+            params.push(param);
+        }
+    }
+    if atoms.is_empty() {
+        return Ok(None);
+    }
+
+    let variants = (0..1usize << atoms.len())
+        .map(|combination| {
+            let holds = |index: usize| combination & (1 << index) != 0;
+            let terms = atoms.iter().enumerate().map(|(index, atom)| {
+                if holds(index) {
+                    quote::quote!(#atom)
+                } else {
+                    quote::quote!(not(#atom))
+                }
+            });
+            let predicate = quote::quote!(all(#(#terms),*));
+            let kept = params
+                .iter()
+                .filter(|param| {
+                    param
+                        .predicates
+                        .iter()
+                        .all(|&(index, negated)| holds(index) != negated)
+                })
+                .map(|param| param.tokens.iter().cloned().collect::<TokenStream>());
+            let kept = quote::quote!(#(#kept),*);
+            let spec = match at {
+                Params::Tokens(start, end) => tokens[..start]
+                    .iter()
+                    .cloned()
+                    .chain(kept)
+                    .chain(tokens[end..].iter().cloned())
+                    .collect(),
+                Params::Group(index) => {
+                    let mut group = Group::new(Delimiter::Parenthesis, kept);
+                    group.set_span(tokens[index].span());
+                    tokens[..index]
+                        .iter()
+                        .cloned()
+                        .chain(Some(TokenTree::Group(group)))
+                        .chain(tokens[index + 1..].iter().cloned())
+                        .collect()
+                }
+            };
+            (predicate, spec)
+        })
+        .collect();
+
+    Ok(Some(Split {
+        item: matches!(at, Params::Group(_)),
+        variants,
+    }))
+}
+
+impl Split {
+    /// Expands every variant with the given function, each under its predicate.
+    pub(crate) fn expand(self, expand: impl Fn(TokenStream) -> TokenStream) -> TokenStream {
+        if self.item {
+            self.variants
+                .into_iter()
+                .map(|(predicate, spec)| {
+                    let output = expand(spec);
+                    match syn::parse2::<syn::File>(output.clone()) {
+                        Ok(file) => {
+                            let items = file.items;
+                            quote::quote!(#(#[cfg(#predicate)] #items)*)
+                        }
+                        Err(_) => quote::quote!(#[cfg(#predicate)] const _: () = { #output };),
+                    }
+                })
+                .collect()
+        } else {
+            let variants = self.variants.into_iter().map(|(predicate, spec)| {
+                let output = expand(spec);
+                quote::quote!(#[cfg(#predicate)] let __lut = #output;)
+            });
+            quote::quote!({
+                #(#variants)*
+                __lut
+            })
+        }
+    }
+}
+
+/// Finds the parameters of a spec, which follow its options.
+fn find_params(tokens: &[TokenTree]) -> Option<Params> {
+    let mut start = 0;
+    while is_punct(tokens.get(start)?, '#') {
+        start += 2;
+    }
+    if is_punct(tokens.get(start)?, '|') {
+        let end = start
+            + 1
+            + tokens[start + 1..]
+                .iter()
+                .position(|token| is_punct(token, '|'))?;
+        return Some(Params::Tokens(start + 1, end));
+    }
+    if let Some(index) = tokens[start..]
+        .iter()
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident == "struct"))
+    {
+        return match tokens.get(start + index + 2)? {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                Some(Params::Group(start + index + 2))
+            }
+            _ => None,
+        };
+    }
+    let semi = tokens[start..]
+        .iter()
+        .position(|token| is_punct(token, ';'))?;
+    Some(Params::Tokens(start + semi + 1, tokens.len()))
+}
+
+/// Returns the span and predicate of the `#[cfg(..)]` attribute at the start of the tokens.
+fn cfg_attr(tokens: &[TokenTree]) -> Option<(proc_macro2::Span, TokenStream)> {
+    match tokens {
+        [pound, TokenTree::Group(attr), ..]
+            if is_punct(pound, '#') && attr.delimiter() == Delimiter::Bracket =>
+        {
+            match attr.stream().into_iter().collect::<Vec<_>>().as_slice() {
+                [TokenTree::Ident(cfg), TokenTree::Group(predicate)]
+                    if cfg == "cfg" && predicate.delimiter() == Delimiter::Parenthesis =>
+                {
+                    Some((attr.span(), predicate.stream()))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Strips any number of `not(..)` from the predicate, so that `feature = "a"` and
+/// `not(feature = "a")` are treated as two sides of the same predicate.
+fn atom(predicate: TokenStream) -> (TokenStream, bool) {
+    match predicate.clone().into_iter().collect::<Vec<_>>().as_slice() {
+        [TokenTree::Ident(not), TokenTree::Group(inner)]
+            if not == "not" && inner.delimiter() == Delimiter::Parenthesis =>
+        {
+            let (atom, negated) = atom(inner.stream());
+            (atom, !negated)
+        }
+        _ => (predicate, false),
+    }
+}
+
+fn is_punct(token: &TokenTree, ch: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == ch)
+}
@@ -0,0 +1,177 @@
+//! Tables that look up the entries of one spec in another, fused into a single table.
+//!
+//! The entries of the first spec are the values of the parameter of the second one, so the fused
+//! table holds the composition of both and a lookup reads memory only once.
+
+/// The input of `lut_compose!`.
+struct Compose {
+    /// The options of the first table, which are those of the fused table.
+    attrs: Vec<syn::Attribute>,
+    inner: super::Lut,
+    outer: super::Lut,
+}
+
+impl syn::parse::Parse for Compose {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let attrs = input.fork().call(syn::Attribute::parse_outer)?;
+        let inner = parse_spec(input)?;
+        input.parse::<syn::Token![,]>()?;
+        if input.peek(syn::Token![#]) {
+            let attr = input.call(syn::Attribute::parse_outer)?.remove(0);
+            return Err(syn::Error::new_spanned(
+                attr,
+                "the second table of `lut_compose!` isn't stored, so options go on the first one",
+            ));
+        }
+        let outer = parse_spec(input)?;
+        if input.peek(syn::Token![,]) {
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(Compose {
+            attrs,
+            inner,
+            outer,
+        })
+    }
+}
+
+/// Parses one of the specs that are composed, which must be in the closure form.
+fn parse_spec(input: syn::parse::ParseStream) -> syn::parse::Result<super::Lut> {
+    let fork = input.fork();
+    fork.call(syn::Attribute::parse_outer)?;
+    if !fork.peek(syn::Token![|]) {
+        return Err(
+            fork.error("expected a table in the closure form, like `|x @ 0..4| -> T { .. }`")
+        );
+    }
+    input.parse()
+}
+
+/// Generates a table whose entries are the entries of the second spec for the entries of the first.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let Compose {
+        attrs,
+        inner,
+        outer,
+    } = syn::parse2(input)?;
+    // These change what the body returns, which must be the parameter of the second table.
+    let options = &inner.options;
+    let conflicts = [
+        ("cover", options.cover.as_ref().map(|cover| cover.span)),
+        ("guard", options.guard.as_ref().map(|guard| guard.span)),
+        ("nalgebra(entries)", options.nalgebra_entries),
+        ("built", options.built.as_ref().map(syn::LitStr::span)),
+    ];
+    for (name, span) in conflicts.iter() {
+        if let Some(span) = span {
+            return Err(syn::Error::new(
+                *span,
+                format!("`lut_compose!` can't be combined with `#[{}]`", name),
+            ));
+        }
+    }
+    let param = match outer.inputs.len() {
+        1 => &outer.inputs[0],
+        _ => {
+            let span = outer
+                .inputs
+                .first()
+                .map_or_else(proc_macro2::Span::call_site, |param| param.ident.span());
+            return Err(syn::Error::new(
+                span,
+                "the second table of `lut_compose!` must have a single parameter, which takes the entries of the first one",
+            ));
+        }
+    };
+    let inner_type = &inner.return_type;
+    let name = super::preset::primitive_name(inner_type)
+        .filter(|name| !name.starts_with('f'))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                inner_type,
+                "the entries of the first table of `lut_compose!` must be integers, which are the values of the parameter of the second one",
+            )
+        })?;
+
+    // The entry is compared in a type that holds both it and the range, which for `u128` rules
+    // out negative bounds.
+    let ident = &param.ident;
+    let (lo, len) = (param.lo, param.len());
+    let hi = lo + len - 1;
+    let (lo_lit, hi_lit, len_lit) = (
+        proc_macro2::Literal::usize_unsuffixed(lo),
+        proc_macro2::Literal::usize_unsuffixed(hi),
+        proc_macro2::Literal::usize_unsuffixed(len),
+    );
+    let wide = match name.as_str() {
+        "i128" | "u128" => quote::quote!(__entry),
+        _ => quote::quote!(__entry as i128),
+    };
+    // Comparing an unsigned value against zero trips clippy in the invoking crate.
+    let above_lo = match (name.as_str(), lo) {
+        ("u128", 0) => None,
+        _ => Some(quote::quote!(__wide >= #lo_lit)),
+    };
+    let value = match param.policy {
+        super::Policy::Panic => {
+            let message = format!(
+                "an entry of the first table is outside of the values of `{}`, {}..={}",
+                ident, lo, hi
+            );
+            let in_range = match &above_lo {
+                Some(above_lo) => quote::quote!(#above_lo && __wide <= #hi_lit),
+                None => quote::quote!(__wide <= #hi_lit),
+            };
+            let assert =
+                quote::quote_spanned!(ident.span()=> ::core::assert!(#in_range, #message););
+            quote::quote! {
+                #assert
+                __wide as usize
+            }
+        }
+        super::Policy::Clamp(_) => {
+            let clamp_lo = above_lo.map(|_| {
+                quote::quote! {
+                    if __wide < #lo_lit {
+                        #lo_lit
+                    } else
+                }
+            });
+            quote::quote! {
+                #clamp_lo if __wide > #hi_lit {
+                    #hi_lit
+                } else {
+                    __wide as usize
+                }
+            }
+        }
+        super::Policy::Wrap(_) => match lo {
+            0 => quote::quote!(__wide.rem_euclid(#len_lit) as usize),
+            _ if len == 1 => quote::quote!(#lo_lit),
+            // Subtracting `lo` first would overflow for `u128` entries below it.
+            _ => {
+                let offset = proc_macro2::Literal::usize_unsuffixed(len - lo % len);
+                quote::quote! {
+                    ((__wide.rem_euclid(#len_lit) + #offset) % #len_lit + #lo_lit) as usize
+                }
+            }
+        },
+    };
+
+    let params = inner.inputs.iter().map(super::zip::param);
+    let inner_body = &inner.body;
+    let outer_type = &outer.return_type;
+    let outer_body = super::zip::statements(&outer.body);
+    let spec = quote::quote! {
+        #(#attrs)*
+        |#(#params),*| -> #outer_type {
+            let __entry: #inner_type = #inner_body;
+            let #ident: usize = {
+                let __wide = #wide;
+                #value
+            };
+            #outer_body
+        }
+    };
+    Ok(super::lut(spec.into()).into())
+}
@@ -0,0 +1,996 @@
+//! Tables whose entries are read from JSON, TOML, CSV or NumPy files while expanding the macro.
+//!
+//! JSON and TOML files are parsed into a tree of values, and the entries are the nested arrays of
+//! it: one level per parameter, with one element per value of the parameter.  CSV files instead
+//! have a row per entry, with a column per parameter and one for the entry, and `.npy` files hold
+//! the binary entries of an array along with its element type and shape.  The entries are
+//! validated against the declared ranges and element type, and written into a constant that the
+//! body of an ordinary table looks up, so that all options of `lut!` apply.  Errors name the file
+//! and the line of the offending value.
+
+/// The input of `lut_from_file!`.
+struct FromFile {
+    path: syn::LitStr,
+    spec: proc_macro2::TokenStream,
+}
+
+impl syn::parse::Parse for FromFile {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let path = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        Ok(FromFile {
+            path,
+            spec: input.parse()?,
+        })
+    }
+}
+
+/// The supported formats of data files, by their extension.
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Toml,
+    Csv,
+    Npy,
+}
+
+/// A value in a data file.
+struct Value {
+    /// The line that the value starts on, counting from one.
+    line: usize,
+    kind: Kind,
+}
+
+enum Kind {
+    Null,
+    Bool(bool),
+    /// A number or another bare word, as written in the file.
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+}
+
+/// An error at the given line of a data file.
+type Error = (usize, String);
+
+/// Generates a table with the entries of a data file.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let FromFile { path, spec } = syn::parse2(input.clone())?;
+    if let Some(proc_macro2::TokenTree::Group(group)) = spec.clone().into_iter().last() {
+        if group.delimiter() == proc_macro2::Delimiter::Brace {
+            return Err(syn::Error::new(
+                group.span(),
+                "the entries of `lut_from_file!` are read from the file, so the table has no body",
+            ));
+        }
+    }
+    let lut: super::Lut = syn::parse2(quote::quote!(#spec {}))?;
+    // These change what the body returns, while the file holds plain entries.
+    let options = &lut.options;
+    let conflicts = [
+        ("cover", options.cover.as_ref().map(|cover| cover.span)),
+        ("guard", options.guard.as_ref().map(|guard| guard.span)),
+        ("nalgebra(entries)", options.nalgebra_entries),
+        ("built", options.built.as_ref().map(syn::LitStr::span)),
+    ];
+    for (name, span) in conflicts.iter() {
+        if let Some(span) = span {
+            return Err(syn::Error::new(
+                *span,
+                format!("`lut_from_file!` can't be combined with `#[{}]`", name),
+            ));
+        }
+    }
+    let element = Element::of(&lut.return_type)?;
+    let format = match path.value().rsplit('.').next() {
+        Some("json") => Format::Json,
+        Some("toml") => Format::Toml,
+        Some("csv") => Format::Csv,
+        Some("npy") => Format::Npy,
+        _ => {
+            return Err(syn::Error::new(
+                path.span(),
+                "expected a `.json`, `.toml`, `.csv` or `.npy` file",
+            ))
+        }
+    };
+
+    let full_path = super::manifest_path(&path)?;
+    let bytes = std::fs::read(&full_path).map_err(|err| {
+        syn::Error::new(path.span(), format!("couldn't read {}: {}", full_path, err))
+    })?;
+    let located = |(line, message): Error| {
+        syn::Error::new(
+            path.span(),
+            format!("{}:{}: {}", path.value(), line, message),
+        )
+    };
+    let params = lut.inputs.iter().collect::<Vec<_>>();
+    // Only the entries are cached, since the table itself is expanded by `lut!`, which writes
+    // sidecar files and reports on every expansion.
+    let entries = super::cache::cached("lut_from_file", input, &bytes, |_| {
+        let literals = if format == Format::Npy {
+            // Binary files have no lines to point at.
+            npy(&bytes, &params, &element, path.span()).map_err(|message| {
+                syn::Error::new(path.span(), format!("{}: {}", path.value(), message))
+            })?
+        } else {
+            let text = std::str::from_utf8(&bytes).map_err(|_| {
+                syn::Error::new(path.span(), format!("{} isn't valid UTF-8", path.value()))
+            })?;
+            match format {
+                Format::Csv => rows(text, &params, &element, path.span()).map_err(located)?,
+                Format::Npy => unreachable!("NumPy files aren't text"),
+                Format::Json | Format::Toml => {
+                    let document = Parser::new(text, format).document().map_err(located)?;
+                    let entries = find_entries(&document, format).map_err(located)?;
+                    let mut literals = Vec::new();
+                    flatten(entries, &params, &element, path.span(), &mut literals)
+                        .map_err(located)?;
+                    literals
+                }
+            }
+        };
+        Ok(quote::quote!(#(#literals),*))
+    })?;
+    let literals = split_entries(entries);
+    Ok(generate_table(&lut, spec, &path, &full_path, &literals))
+}
+
+/// Splits comma-separated entries into the tokens of every entry.
+fn split_entries(entries: proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
+    let mut literals = vec![proc_macro2::TokenStream::new()];
+    for token in entries {
+        match token {
+            proc_macro2::TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                literals.push(proc_macro2::TokenStream::new())
+            }
+            token => literals.last_mut().unwrap().extend(Some(token)),
+        }
+    }
+    literals
+}
+
+/// Generates the table that looks up the given entries.
+fn generate_table(
+    lut: &super::Lut,
+    spec: proc_macro2::TokenStream,
+    path: &syn::LitStr,
+    full_path: &str,
+    literals: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let constant = match &lut.form {
+        super::Form::Struct { ident, .. } => format!(
+            "__NUMERIC_LUT_DATA_{}",
+            super::snake_case(&ident.to_string()).to_uppercase()
+        ),
+        super::Form::Closure => "__NUMERIC_LUT_DATA".to_owned(),
+    };
+    let constant = syn::Ident::new(&constant, path.span());
+    let index = super::flat_index(lut.inputs.iter());
+    let return_type = &lut.return_type;
+    let len = literals.len();
+    let table = proc_macro2::TokenStream::from(super::lut(
+        quote::quote!(#spec { #constant[#index] }).into(),
+    ));
+    // Including the file makes Cargo rebuild the crate when it changes.
+    let items = quote::quote_spanned! {path.span()=>
+        const _: &[u8] = ::core::include_bytes!(#full_path);
+        const #constant: [#return_type; #len] = [#(#literals),*];
+    };
+    match lut.form {
+        super::Form::Struct { .. } => quote::quote! {
+            #items
+            #table
+        },
+        super::Form::Closure => quote::quote!({
+            #items
+            #table
+        }),
+    }
+}
+
+/// Finds the nested arrays of the entries in a document.
+fn find_entries(document: &Value, format: Format) -> Result<&Value, Error> {
+    let entries = match &document.kind {
+        Kind::Table(pairs) => pairs.iter().find(|(key, _)| key == "entries"),
+        _ => None,
+    };
+    match (entries, &document.kind, format) {
+        (Some((_, entries)), _, _) => Ok(entries),
+        (None, Kind::Array(_), Format::Json) => Ok(document),
+        (None, _, Format::Json) => Err((
+            document.line,
+            "expected an array of entries or an object with an `entries` key".to_owned(),
+        )),
+        (None, _, Format::Toml) => Err((1, "expected an `entries` key".to_owned())),
+        (None, _, Format::Csv) | (None, _, Format::Npy) => {
+            unreachable!("only JSON and TOML files have a document")
+        }
+    }
+}
+
+/// Collects the entries of the rows of a CSV file in memory order.
+///
+/// The header names the columns, which are matched to the parameters by name.  The entries are in
+/// the only other column, or in the one named `value` if there are several others.
+fn rows(
+    text: &str,
+    params: &[&super::Param],
+    element: &Element,
+    span: proc_macro2::Span,
+) -> Result<Vec<proc_macro2::TokenStream>, Error> {
+    use syn::ext::IdentExt;
+
+    let mut rows = csv(text)?.into_iter();
+    let (header_line, header) = rows
+        .next()
+        .ok_or_else(|| (1, "expected a header row".to_owned()))?;
+    let mut columns = Vec::new();
+    for param in params {
+        let name = param.ident.unraw().to_string();
+        let column = header
+            .iter()
+            .position(|column| *column == name)
+            .ok_or_else(|| (header_line, format!("expected a column for `{}`", name)))?;
+        columns.push(column);
+    }
+    let others = (0..header.len())
+        .filter(|column| !columns.contains(column))
+        .collect::<Vec<_>>();
+    let value = match others.as_slice() {
+        [value] => *value,
+        _ => header
+            .iter()
+            .position(|column| column == "value")
+            .ok_or_else(|| {
+                (
+                    header_line,
+                    "expected a single column besides the parameters, or a `value` column"
+                        .to_owned(),
+                )
+            })?,
+    };
+
+    let len = params.iter().map(|param| param.len()).product::<usize>();
+    let mut entries: Vec<Option<(usize, proc_macro2::TokenStream)>> = vec![None; len];
+    for (line, row) in rows {
+        if row.len() != header.len() {
+            return Err((
+                line,
+                format!("expected {} columns but found {}", header.len(), row.len()),
+            ));
+        }
+        let mut flat = 0;
+        for (param, &column) in params.iter().zip(&columns) {
+            let (lo, len) = (param.lo, param.len());
+            let field = &row[column];
+            let x = field.parse::<usize>().map_err(|_| {
+                (
+                    line,
+                    format!(
+                        "expected a value of `{}` but found `{}`",
+                        param.ident, field
+                    ),
+                )
+            })?;
+            if !(lo..lo + len).contains(&x) {
+                return Err((
+                    line,
+                    format!(
+                        "`{}` = {} is outside of {}..={}",
+                        param.ident,
+                        x,
+                        lo,
+                        lo + len - 1
+                    ),
+                ));
+            }
+            flat = flat * len + x - lo;
+        }
+        if let Some((first, _)) = &entries[flat] {
+            return Err((
+                line,
+                format!(
+                    "duplicate entry for {}, which is already on line {}",
+                    describe(params, flat),
+                    first
+                ),
+            ));
+        }
+        let kind = match row[value].as_str() {
+            "true" => Kind::Bool(true),
+            "false" => Kind::Bool(false),
+            field => Kind::Number(field.to_owned()),
+        };
+        let literal = element.literal(&Value { line, kind }, span)?;
+        entries[flat] = Some((line, literal));
+    }
+
+    if let Some(flat) = entries.iter().position(Option::is_none) {
+        // Point at the row of the previous entry, after which the missing one belongs.
+        let line = flat
+            .checked_sub(1)
+            .and_then(|previous| entries[previous].as_ref())
+            .map_or(header_line, |(line, _)| *line);
+        return Err((
+            line,
+            format!("missing the entry for {}", describe(params, flat)),
+        ));
+    }
+    Ok(entries
+        .into_iter()
+        .flatten()
+        .map(|(_, literal)| literal)
+        .collect())
+}
+
+/// Collects the entries of a NumPy `.npy` file in memory order.
+///
+/// The file starts with a magic string, the version and the length of a header, which is a Python
+/// dictionary literal with the `descr`iption of the element type, whether the array is in
+/// `fortran_order` and its `shape`.  The entries follow as raw bytes.
+fn npy(
+    bytes: &[u8],
+    params: &[&super::Param],
+    element: &Element,
+    span: proc_macro2::Span,
+) -> Result<Vec<proc_macro2::TokenStream>, String> {
+    let rest = bytes
+        .strip_prefix(b"\x93NUMPY")
+        .ok_or_else(|| "not a NumPy `.npy` file".to_owned())?;
+    let (header_len, rest) = match rest {
+        [1, _, lo, hi, rest @ ..] => (usize::from(u16::from_le_bytes([*lo, *hi])), rest),
+        [2..=3, _, a, b, c, d, rest @ ..] => (u32::from_le_bytes([*a, *b, *c, *d]) as usize, rest),
+        _ => return Err("unsupported version of the `.npy` format".to_owned()),
+    };
+    if rest.len() < header_len {
+        return Err("the header is truncated".to_owned());
+    }
+    let (header, data) = rest.split_at(header_len);
+    let header = String::from_utf8_lossy(header);
+    let field = |key: &str| {
+        let start = header
+            .find(&format!("'{}':", key))
+            .ok_or_else(|| format!("the header has no `{}`", key))?;
+        Ok::<_, String>(header[start + key.len() + 3..].trim_start())
+    };
+
+    let descr = field("descr")?;
+    let descr = descr
+        .strip_prefix('\'')
+        .and_then(|descr| descr.split('\'').next())
+        .ok_or_else(|| "expected the `descr` to be a string".to_owned())?;
+    let (expected, size) = element.npy_type();
+    let (little_endian, ty) = match descr.chars().next() {
+        Some('<') | Some('|') => (true, &descr[1..]),
+        Some('>') => (false, &descr[1..]),
+        Some(_) => (true, descr),
+        None => return Err("the `descr` is empty".to_owned()),
+    };
+    if ty != expected {
+        return Err(format!(
+            "expected entries of type `{}` but found `{}`",
+            expected, descr
+        ));
+    }
+
+    let fortran_order = field("fortran_order")?.starts_with("True");
+    let shape = field("shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|shape| shape.split(')').next())
+        .ok_or_else(|| "expected the `shape` to be a tuple".to_owned())?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|len| !len.is_empty())
+        .map(|len| {
+            len.parse::<usize>()
+                .map_err(|_| format!("invalid length `{}`", len))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let lens = params.iter().map(|param| param.len()).collect::<Vec<_>>();
+    if shape != lens {
+        let shape = shape.iter().map(usize::to_string).collect::<Vec<_>>();
+        let lens = lens.iter().map(usize::to_string).collect::<Vec<_>>();
+        return Err(format!(
+            "expected an array of shape ({}) but found ({})",
+            lens.join(", "),
+            shape.join(", ")
+        ));
+    }
+    let len = lens.iter().product::<usize>();
+    if data.len() != len * size {
+        return Err(format!(
+            "expected {} bytes of entries but found {}",
+            len * size,
+            data.len()
+        ));
+    }
+
+    // Arrays in Fortran order store the first index fastest.
+    let mut strides = Vec::new();
+    let mut stride = 1;
+    for len in &lens {
+        strides.push(stride);
+        stride *= len;
+    }
+    let mut literals = Vec::with_capacity(len);
+    for flat in 0..len {
+        let offset = match fortran_order {
+            true => {
+                let (mut rest, mut offset) = (flat, 0);
+                for (len, stride) in lens.iter().zip(&strides).rev() {
+                    offset += rest % len * stride;
+                    rest /= len;
+                }
+                offset
+            }
+            false => flat,
+        };
+        let mut entry = data[offset * size..(offset + 1) * size].to_vec();
+        if !little_endian {
+            entry.reverse();
+        }
+        let text = element.npy_text(&entry);
+        let value = Value {
+            line: 0,
+            kind: match text.as_str() {
+                "true" => Kind::Bool(true),
+                "false" => Kind::Bool(false),
+                _ => Kind::Number(text),
+            },
+        };
+        literals.push(
+            element
+                .literal(&value, span)
+                .map_err(|(_, message)| message)?,
+        );
+    }
+    Ok(literals)
+}
+
+/// Describes the parameters of the entry at the given index in memory order, like `x = 1, y = 2`.
+fn describe(params: &[&super::Param], mut flat: usize) -> String {
+    let mut values = Vec::new();
+    for param in params.iter().rev() {
+        values.push(format!(
+            "{} = {}",
+            param.ident,
+            param.lo + flat % param.len()
+        ));
+        flat /= param.len();
+    }
+    values.reverse();
+    values.join(", ")
+}
+
+/// Splits a CSV file into the fields of its non-empty rows, each with the line that it starts on.
+///
+/// Fields are trimmed, and may be quoted with `"` to contain commas, line breaks or `""` for a
+/// quote.
+fn csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let mut rows = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut row = Vec::new();
+        let mut field = String::new();
+        loop {
+            match chars.next() {
+                Some('"') if field.trim().is_empty() => {
+                    field.clear();
+                    loop {
+                        match chars.next() {
+                            Some('"') if chars.peek() == Some(&'"') => {
+                                chars.next();
+                                field.push('"');
+                            }
+                            Some('"') => break,
+                            Some(c) => {
+                                if c == '\n' {
+                                    line += 1;
+                                }
+                                field.push(c);
+                            }
+                            None => return Err((start, "unterminated quoted field".to_owned())),
+                        }
+                    }
+                }
+                Some(',') => row.push(std::mem::take(&mut field).trim().to_owned()),
+                Some('\n') => {
+                    line += 1;
+                    break;
+                }
+                Some(c) => field.push(c),
+                None => break,
+            }
+        }
+        row.push(field.trim().to_owned());
+        if row.len() > 1 || !row[0].is_empty() {
+            rows.push((start, row));
+        }
+    }
+    Ok(rows)
+}
+
+/// Checks that the value has one nested array per parameter of the right length, and collects
+/// the entries in it in memory order.
+fn flatten(
+    value: &Value,
+    params: &[&super::Param],
+    element: &Element,
+    span: proc_macro2::Span,
+    entries: &mut Vec<proc_macro2::TokenStream>,
+) -> Result<(), Error> {
+    let (param, rest) = match params.split_first() {
+        Some(split) => split,
+        None => {
+            entries.push(element.literal(value, span)?);
+            return Ok(());
+        }
+    };
+    let (lo, len) = (param.lo, param.len());
+    match &value.kind {
+        Kind::Array(items) if items.len() == len => {
+            for item in items {
+                flatten(item, rest, element, span, entries)?;
+            }
+            Ok(())
+        }
+        Kind::Array(items) => Err((
+            value.line,
+            format!(
+                "expected {} entries for `{}` in {}..={} but found {}",
+                len,
+                param.ident,
+                lo,
+                lo + len - 1,
+                items.len()
+            ),
+        )),
+        _ => Err((
+            value.line,
+            format!("expected an array of the entries for `{}`", param.ident),
+        )),
+    }
+}
+
+/// The element types that can be read from data files.
+enum Element {
+    Bool,
+    Int {
+        name: String,
+        signed: bool,
+        bits: u32,
+    },
+    Float(String),
+}
+
+impl Element {
+    fn of(ty: &syn::Type) -> syn::Result<Self> {
+        use syn::spanned::Spanned;
+
+        let name = match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident(),
+            _ => None,
+        }
+        .map(syn::Ident::to_string)
+        .unwrap_or_default();
+        let element = match name.as_str() {
+            "bool" => Element::Bool,
+            "f32" | "f64" => Element::Float(name),
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+            | "i128" | "isize" => {
+                let bits = match &name[1..] {
+                    // The target isn't known while expanding the macro.
+                    "size" => 64,
+                    bits => bits.parse().unwrap(),
+                };
+                Element::Int {
+                    signed: name.starts_with('i'),
+                    name,
+                    bits,
+                }
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "only numeric types and `bool` can be read from data files",
+                ))
+            }
+        };
+        Ok(element)
+    }
+
+    /// The NumPy type code of the element type without the byte order, like `f4`, and its size.
+    fn npy_type(&self) -> (String, usize) {
+        match self {
+            Element::Bool => ("b1".to_owned(), 1),
+            Element::Int { signed, bits, .. } => {
+                let kind = if *signed { 'i' } else { 'u' };
+                (format!("{}{}", kind, bits / 8), *bits as usize / 8)
+            }
+            Element::Float(name) if name == "f32" => ("f4".to_owned(), 4),
+            Element::Float(_) => ("f8".to_owned(), 8),
+        }
+    }
+
+    /// Writes the little-endian bytes of an entry as they would appear in a text file.
+    fn npy_text(&self, bytes: &[u8]) -> String {
+        let mut buffer = [0; 16];
+        buffer[..bytes.len()].copy_from_slice(bytes);
+        let value = u128::from_le_bytes(buffer);
+        match self {
+            Element::Bool => (value != 0).to_string(),
+            Element::Int { signed: false, .. } => value.to_string(),
+            Element::Int { bits, .. } => {
+                // Sign-extend by shifting the sign bit into the top.
+                let shift = 128 - bits;
+                ((value << shift) as i128 >> shift).to_string()
+            }
+            // The debug representation is the shortest one that round-trips.
+            Element::Float(_) if bytes.len() == 4 => match f32::from_bits(value as u32) {
+                value if value.is_nan() => "nan".to_owned(),
+                value => format!("{:?}", value),
+            },
+            Element::Float(_) => match f64::from_bits(value as u64) {
+                value if value.is_nan() => "nan".to_owned(),
+                value => format!("{:?}", value),
+            },
+        }
+    }
+
+    /// The literal of an entry.
+    fn literal(
+        &self,
+        value: &Value,
+        span: proc_macro2::Span,
+    ) -> Result<proc_macro2::TokenStream, Error> {
+        let mismatch = |name: &str| {
+            let found = match &value.kind {
+                Kind::Null => "`null`".to_owned(),
+                Kind::Bool(value) => format!("`{}`", value),
+                Kind::Number(text) => format!("`{}`", text),
+                Kind::String(text) => format!("the string {:?}", text),
+                Kind::Array(_) => "an array".to_owned(),
+                Kind::Table(_) => "a table".to_owned(),
+            };
+            Err((
+                value.line,
+                format!("expected a `{}` but found {}", name, found),
+            ))
+        };
+        match (self, &value.kind) {
+            (Element::Bool, Kind::Bool(value)) => Ok(quote::quote_spanned!(span=> #value)),
+            (Element::Bool, _) => mismatch("bool"),
+            (Element::Int { name, signed, bits }, Kind::Number(text)) => {
+                let text = text.replace('_', "");
+                let (negative, digits) = match text.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, text.strip_prefix('+').unwrap_or(&text)),
+                };
+                let magnitude = match digits.parse::<u128>() {
+                    Ok(magnitude) if digits.bytes().all(|b| b.is_ascii_digit()) => magnitude,
+                    _ => return mismatch(name),
+                };
+                let max = match (*signed, negative) {
+                    (false, true) => 0,
+                    (false, false) => u128::MAX >> (128 - bits),
+                    (true, false) => (1 << (bits - 1)) - 1,
+                    (true, true) => 1 << (bits - 1),
+                };
+                if magnitude > max {
+                    return mismatch(name);
+                }
+                let literal = syn::LitInt::new(&format!("{}{}", magnitude, name), span);
+                Ok(match negative && magnitude > 0 {
+                    true => quote::quote!(-#literal),
+                    false => quote::quote!(#literal),
+                })
+            }
+            (Element::Float(name), Kind::Number(text)) => {
+                let ty = syn::Ident::new(name, span);
+                let text = text.replace('_', "");
+                let (negative, digits) = match text.strip_prefix('-') {
+                    Some(digits) => (true, digits),
+                    None => (false, text.strip_prefix('+').unwrap_or(&text)),
+                };
+                let literal = match digits {
+                    "inf" => quote::quote_spanned!(span=> #ty::INFINITY),
+                    "nan" => quote::quote_spanned!(span=> #ty::NAN),
+                    digits => {
+                        let valid = digits
+                            .bytes()
+                            .all(|b| b.is_ascii_digit() || b"+-.eE".contains(&b))
+                            && digits.starts_with(|c: char| c.is_ascii_digit())
+                            && matches!(digits.parse::<f64>(), Ok(value) if value.is_finite());
+                        if !valid {
+                            return mismatch(name);
+                        }
+                        let point = match digits.contains(['.', 'e', 'E']) {
+                            true => "",
+                            false => ".0",
+                        };
+                        let literal =
+                            syn::LitFloat::new(&format!("{}{}{}", digits, point, name), span);
+                        quote::quote!(#literal)
+                    }
+                };
+                Ok(match negative {
+                    true => quote::quote!(-#literal),
+                    false => literal,
+                })
+            }
+            (Element::Int { name, .. }, _) | (Element::Float(name), _) => mismatch(name),
+        }
+    }
+}
+
+/// A parser for the subset of JSON and TOML that data files use.
+///
+/// JSON is parsed completely, apart from validating escape sequences in strings.  For TOML, the
+/// document holds `key = value` pairs, optionally under `[table]` headers, and values are numbers,
+/// booleans, basic strings and arrays.  Only the keys at the top level are kept.
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    format: Format,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str, format: Format) -> Self {
+        Parser {
+            chars: text.chars().peekable(),
+            line: 1,
+            format,
+        }
+    }
+
+    fn document(&mut self) -> Result<Value, Error> {
+        match self.format {
+            Format::Json => {
+                let value = self.value()?;
+                self.skip();
+                match self.chars.peek() {
+                    None => Ok(value),
+                    Some(c) => Err((
+                        self.line,
+                        format!("expected the end of the file, found `{}`", c),
+                    )),
+                }
+            }
+            Format::Toml => {
+                let mut pairs = Vec::new();
+                let mut top_level = true;
+                loop {
+                    self.skip();
+                    match self.chars.peek() {
+                        None => break,
+                        Some('[') => {
+                            // Keys after a table header belong to that table.
+                            top_level = false;
+                            while !matches!(self.chars.next(), None | Some('\n')) {}
+                            self.line += 1;
+                        }
+                        Some(_) => {
+                            let key = self.key()?;
+                            self.skip();
+                            self.expect('=')?;
+                            let value = self.value()?;
+                            if top_level {
+                                pairs.push((key, value));
+                            }
+                        }
+                    }
+                }
+                Ok(Value {
+                    line: 1,
+                    kind: Kind::Table(pairs),
+                })
+            }
+            Format::Csv | Format::Npy => unreachable!("only JSON and TOML files have a document"),
+        }
+    }
+
+    /// Skips whitespace, and comments in TOML.
+    fn skip(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '\n' => self.line += 1,
+                '#' if self.format == Format::Toml => {
+                    while !matches!(self.chars.peek(), None | Some('\n')) {
+                        self.chars.next();
+                    }
+                    continue;
+                }
+                c if c.is_whitespace() => {}
+                _ => break,
+            }
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err((self.line, format!("expected `{}`, found `{}`", expected, c))),
+            None => Err((
+                self.line,
+                format!("expected `{}`, found the end of the file", expected),
+            )),
+        }
+    }
+
+    /// A bare word, like a number, `true` or a bare TOML key.
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.')) {
+                break;
+            }
+            word.push(c);
+            self.chars.next();
+        }
+        word
+    }
+
+    fn key(&mut self) -> Result<String, Error> {
+        if self.chars.peek() == Some(&'"') {
+            return self.string();
+        }
+        match self.word() {
+            word if word.is_empty() => Err(self.unexpected()),
+            word => Ok(word),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => string.push(c),
+                    None => break,
+                },
+                Some('\n') | None => break,
+                Some(c) => string.push(c),
+            }
+        }
+        Err((self.line, "unterminated string".to_owned()))
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip();
+        let line = self.line;
+        let kind = match self.chars.peek() {
+            Some('[') => {
+                self.chars.next();
+                let mut items = Vec::new();
+                loop {
+                    self.skip();
+                    if self.chars.peek() == Some(&']') {
+                        self.chars.next();
+                        break;
+                    }
+                    items.push(self.value()?);
+                    self.skip();
+                    match self.chars.next() {
+                        Some(',') => {}
+                        Some(']') => break,
+                        _ => return Err((self.line, "expected `,` or `]`".to_owned())),
+                    }
+                }
+                Kind::Array(items)
+            }
+            Some('{') if self.format == Format::Json => {
+                self.chars.next();
+                let mut pairs = Vec::new();
+                loop {
+                    self.skip();
+                    if self.chars.peek() == Some(&'}') && pairs.is_empty() {
+                        self.chars.next();
+                        break;
+                    }
+                    let key = self.string()?;
+                    self.skip();
+                    self.expect(':')?;
+                    pairs.push((key, self.value()?));
+                    self.skip();
+                    match self.chars.next() {
+                        Some(',') => {}
+                        Some('}') => break,
+                        _ => return Err((self.line, "expected `,` or `}`".to_owned())),
+                    }
+                }
+                Kind::Table(pairs)
+            }
+            Some('"') => Kind::String(self.string()?),
+            _ => match self.word().as_str() {
+                "" => return Err(self.unexpected()),
+                "true" => Kind::Bool(true),
+                "false" => Kind::Bool(false),
+                "null" if self.format == Format::Json => Kind::Null,
+                word => Kind::Number(word.to_owned()),
+            },
+        };
+        Ok(Value { line, kind })
+    }
+
+    fn unexpected(&mut self) -> Error {
+        match self.chars.peek() {
+            Some(c) => (self.line, format!("unexpected `{}`", c)),
+            None => (self.line, "unexpected end of the file".to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads a `.npy` file into a table of two `u8`s.
+    fn npy_error(bytes: &[u8]) -> String {
+        let lut = syn::parse_str::<super::super::Lut>("struct T(x @ 0..2) -> u8 {}").unwrap();
+        let params = lut.inputs.iter().collect::<Vec<_>>();
+        let element = Element::of(&lut.return_type).unwrap();
+        let span = proc_macro2::Span::call_site();
+        npy(bytes, &params, &element, span).map(|_| ()).unwrap_err()
+    }
+
+    /// A `.npy` file of two `u8`s with the given header.
+    fn with_header(header: &str) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend([1, 2]);
+        bytes
+    }
+
+    #[test]
+    fn npy_garbage_descr() {
+        let descr = |descr| {
+            with_header(&format!(
+                "{{'descr': '{}', 'fortran_order': False, 'shape': (2,), }}",
+                descr
+            ))
+        };
+        assert_eq!("the `descr` is empty", npy_error(&descr("")));
+        assert_eq!(
+            "expected entries of type `u1` but found `\u{e9}u1`",
+            npy_error(&descr("\u{e9}u1"))
+        );
+        assert_eq!(
+            "expected entries of type `u1` but found `>i2`",
+            npy_error(&descr(">i2"))
+        );
+    }
+
+    #[test]
+    fn npy_truncated_header() {
+        assert_eq!(
+            "the header has no `descr`",
+            npy_error(&with_header("{'des"))
+        );
+        assert_eq!(
+            "expected the `descr` to be a string",
+            npy_error(&with_header("{'descr':"))
+        );
+        assert_eq!(
+            "the header is truncated",
+            npy_error(b"\x93NUMPY\x01\x00\x10\x00{'descr'")
+        );
+        assert_eq!(
+            "unsupported version of the `.npy` format",
+            npy_error(b"\x93NUMPY\x01")
+        );
+        assert_eq!("not a NumPy `.npy` file", npy_error(b"\x93NUM"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Lookups over tables that are defined elsewhere, like in another crate with `#[export(..)]`, in
+//! an object file written in C or in a blob placed by the linker.
+//!
+//! The spec only declares the shape, the element type and the symbol of the table, so the macro
+//! declares the symbol in an `extern` block and generates the lookups of the item form over it.
+
+/// The input of `lut_extern!`, which is declared as an `unsafe struct`.
+struct Extern {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+    /// The symbol of the table, from `#[link_name = "..."]`.
+    link_name: syn::LitStr,
+    inputs: syn::punctuated::Punctuated<super::Param, syn::Token![,]>,
+    return_type: syn::Type,
+}
+
+impl syn::parse::Parse for Extern {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        use syn::spanned::Spanned;
+        let mut attrs = Vec::new();
+        let mut link_name = None;
+        for attr in input.call(syn::Attribute::parse_outer)? {
+            if !attr.path.is_ident("link_name") {
+                attrs.push(attr);
+                continue;
+            }
+            match attr.parse_meta()? {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    lit: syn::Lit::Str(name),
+                    ..
+                }) => link_name = Some(name),
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "expected `#[link_name = \"...\"]`",
+                    ))
+                }
+            }
+        }
+        let vis = input.parse()?;
+        let unsafety = input.parse::<Option<syn::Token![unsafe]>>()?;
+        let struct_token = input.parse::<syn::Token![struct]>()?;
+        if unsafety.is_none() {
+            return Err(syn::Error::new(
+                struct_token.span,
+                "`lut_extern!` requires `unsafe struct`, since the compiler can't check that the symbol holds a table of the declared shape and element type",
+            ));
+        }
+        let ident: syn::Ident = input.parse()?;
+        let link_name = link_name.ok_or_else(|| {
+            syn::Error::new(
+                ident.span(),
+                "`lut_extern!` requires `#[link_name = \"...\"]` naming the symbol of the table",
+            )
+        })?;
+
+        let content;
+        syn::parenthesized!(content in input);
+        let inputs = content.parse_terminated(super::Param::parse)?;
+        if inputs.is_empty() {
+            return Err(syn::Error::new(
+                ident.span(),
+                "`lut_extern!` requires at least one parameter",
+            ));
+        }
+        for (i, param) in inputs.iter().enumerate() {
+            if inputs
+                .iter()
+                .take(i)
+                .any(|other| other.ident == param.ident)
+            {
+                return Err(syn::Error::new(
+                    param.ident.span(),
+                    format!("duplicate parameter `{}`", param.ident),
+                ));
+            }
+            // These describe how the stored table was generated, which the declaration can't know.
+            let generated = [
+                ("contiguous", param.contiguous),
+                ("permute(..)", param.permute.map(|(span, _)| span)),
+            ];
+            for (name, span) in generated.iter() {
+                if let Some(span) = span {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!(
+                            "`#[{}]` isn't supported by `lut_extern!`, which declares the parameters in the order of the stored table",
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
+
+        input.parse::<syn::Token![->]>()?;
+        let return_type = input.parse()?;
+        input.parse::<syn::Token![;]>()?;
+
+        Ok(Extern {
+            attrs,
+            vis,
+            ident,
+            link_name,
+            inputs,
+            return_type,
+        })
+    }
+}
+
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let Extern {
+        attrs,
+        vis,
+        ident,
+        link_name,
+        inputs,
+        return_type,
+    } = syn::parse2(input)?;
+
+    let options = super::Options::default();
+    let ty = inputs
+        .iter()
+        .rev()
+        .fold(quote::quote!(#return_type), |ty, param| {
+            let len = param.len();
+            quote::quote!([#ty; #len])
+        });
+    let access = inputs.iter().fold(quote::quote!(__LUT), |expr, param| {
+        let index = param.index(&options);
+        quote::quote!(#expr[#index])
+    });
+    let lut_params = super::params(inputs.iter()).collect::<Vec<_>>();
+    let table_doc = format!(
+        "The stored entries, which are defined elsewhere under the symbol `{}`.",
+        link_name.value()
+    );
+
+    let fix = match inputs.iter().collect::<Vec<_>>().split_first() {
+        Some((first, rest)) if !rest.is_empty() => {
+            let first_ident = &first.ident;
+            let rest_params = super::params(rest.iter().copied());
+            let rest_types = rest.iter().map(|_| quote::quote!(usize));
+            let idents = inputs.iter().map(|param| &param.ident);
+            quote::quote! {
+                /// Fixes the first parameter, returning a lookup over the remaining parameters.
+                #vis fn fix(#first_ident: usize) -> impl Copy + Fn(#(#rest_types),*) -> #return_type {
+                    move |#(#rest_params),*| Self::get(#(#idents),*)
+                }
+            }
+        }
+        _ => proc_macro2::TokenStream::new(),
+    };
+
+    // Reading an extern static is unsafe, but the declaration promises with `unsafe struct` that
+    // the symbol holds the entries of the table, which are never written.
+    Ok(quote::quote! {
+        #(#attrs)*
+        #[derive(Clone, Copy)]
+        #vis struct #ident;
+
+        impl #ident {
+            #[doc = #table_doc]
+            #vis fn table() -> &'static #ty {
+                extern "Rust" {
+                    #[link_name = #link_name]
+                    static __TABLE: #ty;
+                }
+                unsafe { &__TABLE }
+            }
+
+            /// Looks up the entry for the given parameters.
+            #[inline]
+            #vis fn get(#(#lut_params),*) -> #return_type {
+                let __LUT = Self::table();
+                #access
+            }
+
+            #fix
+        }
+    })
+}
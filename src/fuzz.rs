@@ -0,0 +1,212 @@
+//! libFuzzer targets that compare the lookups of a table with evaluating its body.
+//!
+//! The input of the fuzzer is split into one value per parameter.  The first byte decides whether
+//! the values are moved into their ranges, which checks the stored entries, or are used as they
+//! are, which checks that out-of-range values follow the declared policy: `#[mask]`, `#[clamp]`
+//! and `#[wrap]` move them into the range, and `#[fallback(f)]` calls `f`.  Parameters that would
+//! panic are always moved into their ranges, since libFuzzer aborts on any panic.  Lookups outside
+//! of the band of `#[banded(..)]` are compared with its default instead of the body.
+
+/// The number of bytes of the fuzzer input that make up the value of a parameter.
+const VALUE_BYTES: usize = 8;
+
+/// Generates a fuzz target for a table.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    use syn::spanned::Spanned;
+
+    if cfg!(not(feature = "libfuzzer-sys")) {
+        return Err(syn::Error::new(
+            input.span(),
+            "`lut_fuzz!` requires the `libfuzzer-sys` feature of `numeric-lut`",
+        ));
+    }
+    let lut: super::Lut = syn::parse2(input.clone())?;
+    if let super::Form::Struct { ident, .. } = &lut.form {
+        return Err(syn::Error::new(
+            ident.span(),
+            "`lut_fuzz!` only supports the closure form",
+        ));
+    }
+    if lut.inputs.is_empty() {
+        return Err(syn::Error::new(
+            input.span(),
+            "`lut_fuzz!` requires at least one parameter",
+        ));
+    }
+    // These make lookups differ from the body by design.
+    let options = &lut.options;
+    let conflicts = [
+        ("f16", options.f16),
+        (
+            options.codec.as_ref().map_or("codec", super::Codec::name),
+            options.codec.as_ref().map(|codec| codec.span),
+        ),
+        ("cover", options.cover.as_ref().map(|cover| cover.span)),
+        ("guard", options.guard.as_ref().map(|guard| guard.span)),
+        ("nalgebra(entries)", options.nalgebra_entries),
+        ("built", options.built.as_ref().map(syn::LitStr::span)),
+        (
+            "permute(..)",
+            lut.inputs
+                .iter()
+                .find_map(|param| param.permute.map(|(span, _)| span)),
+        ),
+    ];
+    for (name, span) in conflicts.iter() {
+        if let Some(span) = span {
+            return Err(syn::Error::new(
+                *span,
+                format!(
+                    "`lut_fuzz!` can't compare lookups with the body for `#[{}]`",
+                    name
+                ),
+            ));
+        }
+    }
+    let table = proc_macro2::TokenStream::from(super::lut(input.into()));
+
+    let body = match &lut.body {
+        body @ syn::Expr::Block(_) => quote::quote!(#body),
+        body => quote::quote!({ #body }),
+    };
+    let return_type = match &lut.return_type {
+        ty if super::is_inferred(ty) => proc_macro2::TokenStream::new(),
+        ty => quote::quote!(-> #ty),
+    };
+    let params = super::params(lut.inputs.iter());
+    let idents = lut
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    // Whether a value outside of the range of the parameter doesn't panic.
+    let keeps = |param: &super::Param| {
+        options.mask || options.fallback.is_some() || !matches!(param.policy, super::Policy::Panic)
+    };
+    let mut values = proc_macro2::TokenStream::new();
+    if lut.inputs.iter().any(keeps) {
+        values.extend(quote::quote! {
+            let __out_of_range = ::core::matches!(__data.first(), Some(__mode) if __mode & 1 == 1);
+        });
+    }
+    for (i, param) in lut.inputs.iter().enumerate() {
+        let ident = &param.ident;
+        let start = i * VALUE_BYTES + 1;
+        let in_range = offset(param.lo, quote::quote!(__raw), param.len());
+        let value = if keeps(param) {
+            quote::quote!(if __out_of_range { __raw } else { #in_range })
+        } else {
+            in_range
+        };
+        values.extend(quote::quote! {
+            let #ident = {
+                let mut __bytes = [0; #VALUE_BYTES];
+                for (__byte, __input) in __bytes.iter_mut().zip(__data.iter().skip(#start)) {
+                    *__byte = *__input;
+                }
+                let __raw = u64::from_le_bytes(__bytes) as usize;
+                #value
+            };
+        });
+    }
+
+    // The parameters that the body sees for the given values.
+    let normalized = lut.inputs.iter().filter_map(|param| {
+        let ident = &param.ident;
+        let (lo, len) = (param.lo, param.len());
+        let value = if options.mask {
+            let mask = param.storage_len(options) - 1;
+            let masked = quote::quote!((#ident.wrapping_sub(#lo) & #mask));
+            if len == 1 {
+                offset(lo, quote::quote!(#ident), len)
+            } else if len == mask + 1 {
+                offset(lo, masked, 0)
+            } else {
+                // Padded dimensions repeat the entries from the lower bound.
+                offset(lo, masked, len)
+            }
+        } else {
+            param.in_range(options)?
+        };
+        Some(quote::quote!(let #ident = #value;))
+    });
+    // Lookups outside of the band of `#[banded(..)]` return the default instead of the body.
+    let evaluate = match &options.layout {
+        super::Layout::Banded { width, default, .. } => {
+            let (x, y) = (idents[0], idents[1]);
+            quote::quote! {
+                if #x.abs_diff(#y) > #width {
+                    #default
+                } else {
+                    __direct(#(#idents),*)
+                }
+            }
+        }
+        _ => quote::quote!(__direct(#(#idents),*)),
+    };
+    let direct = quote::quote!({
+        #(#normalized)*
+        #evaluate
+    });
+    let expected = match &options.fallback {
+        Some(fallback) => {
+            let checks = lut
+                .inputs
+                .iter()
+                .filter(|param| matches!(param.policy, super::Policy::Panic))
+                .map(|param| {
+                    let ident = &param.ident;
+                    let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+                    quote::quote!((#lo..=#hi).contains(&#ident))
+                });
+            quote::quote! {
+                if #(#checks)&&* {
+                    #direct
+                } else {
+                    let __fallback = #fallback;
+                    __fallback(#(#idents),*)
+                }
+            }
+        }
+        None => direct,
+    };
+    let message = format!(
+        "lookup of {}",
+        idents
+            .iter()
+            .map(|ident| format!("{} = {{}}", ident))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(quote::quote! {
+        ::libfuzzer_sys::fuzz_target!(|__data: &[u8]| {
+            let __lut = #table;
+            let __direct = |#(#params),*| #return_type #body;
+            #values
+            let __actual = __lut(#(#idents),*);
+            let __expected = #expected;
+            ::core::assert_eq!(__actual, __expected, #message, #(#idents),*);
+        });
+    })
+}
+
+/// An expression that moves `index` into the range `lo..lo + len`, or only adds `lo` if `len` is
+/// zero, without the identities and reductions modulo one that clippy warns about in the invoking
+/// crate.
+fn offset(lo: usize, index: proc_macro2::TokenStream, len: usize) -> proc_macro2::TokenStream {
+    let index = match len {
+        0 => index,
+        1 => {
+            return quote::quote!({
+                let _ = #index;
+                #lo
+            })
+        }
+        len => quote::quote!(#index % #len),
+    };
+    match lo {
+        0 => index,
+        lo => quote::quote!(#lo + #index),
+    }
+}
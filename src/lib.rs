@@ -1,12 +1,10 @@
 //! # `numeric-lut`
 //!
-//! A library for generating numeric lookup functions.  Currently, it requires the use of the
-//! `proc_macro_hygiene` nightly feature.
+//! A library for generating numeric lookup functions.
 //!
 //! ## Examples
 //!
 //! ```
-//! #![feature(proc_macro_hygiene)]
 //! let lut = numeric_lut::lut!(|x @ 0..8, y @ 0..16| -> u32 { x as u32 + y as u32 });
 //! let x = lut(3, 10);
 //! assert_eq!(13, x);
@@ -22,210 +20,9977 @@
     unused_import_braces,
     unused_qualifications
 )]
+#![cfg_attr(feature = "nightly", allow(unstable_features))]
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
 
 extern crate proc_macro;
 
+mod bench;
+mod build_time;
+mod cache;
+mod cfg;
+mod compose;
+mod data;
+mod external;
+mod fuzz;
+mod map;
+mod metadata;
+mod module;
+mod prefix;
+mod preset;
+mod registry;
+mod shader;
+mod warning;
+mod zip;
+
 struct Lut {
-    #[allow(unused)]
-    or1_token: syn::Token![|],
+    options: Options,
+    form: Form,
     inputs: syn::punctuated::Punctuated<Param, syn::Token![,]>,
-    #[allow(unused)]
-    or2_token: syn::Token![|],
-    #[allow(unused)]
-    arrow_token: syn::Token![->],
     return_type: syn::Type,
     body: syn::Expr,
 }
 
+/// What kind of code the macro expands to.
+enum Form {
+    /// A closure expression, for `|x @ 0..4| -> T { ... }`.
+    Closure,
+    /// A unit struct with associated lookup functions, for `struct Name(x @ 0..4) -> T { ... }`.
+    Struct {
+        attrs: Vec<syn::Attribute>,
+        vis: syn::Visibility,
+        ident: syn::Ident,
+    },
+}
+
 struct Param {
     ident: syn::Ident,
     lo: usize,
     exclusive_end: bool,
     hi: usize,
+    /// What happens to values outside of the range.
+    policy: Policy,
+    /// The span of the `#[contiguous]` option, which stores the entries along this parameter next
+    /// to each other.
+    contiguous: Option<proc_macro2::Span>,
+    /// The `#[permute(..)]` option, which stores the entry of every value at the index that the
+    /// permutation maps it to.
+    permute: Option<(proc_macro2::Span, Permutation)>,
 }
 
-/// Generates a numeric lookup function.
-///
-/// The macro is function-like and accepts an expression that looks like a closure.  Only parameters
-/// that use range patterns (like `x @ 0..1`) are accepted.  All parameters are implicitly of type
-/// `usize` since they will be used as indices for lookup tables.
-#[proc_macro]
-pub fn lut(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = syn::parse_macro_input!(input as Lut);
+/// A standard permutation of the indices of a parameter, with `#[permute(..)]`.
+#[derive(Clone, Copy)]
+enum Permutation {
+    /// The bits of the index are reversed, as in the order of an FFT, with `bit_reverse`.
+    BitReverse,
+    /// The index is the Gray code of the value, as counted by Gray code counters, with `gray`.
+    Gray,
+}
 
-    let table_data = input.inputs.iter().rev().fold(input.body, |body, param| {
-        if param.exclusive_end {
-            generate_array(&param.ident, param.lo..param.hi, body)
-        } else {
-            generate_array(&param.ident, param.lo..=param.hi, body)
+impl Permutation {
+    /// The value that the body sees for the entry at the given index, of the given number of bits.
+    fn value(self, index: usize, bits: u32) -> usize {
+        match self {
+            Permutation::BitReverse if bits == 0 => index,
+            Permutation::BitReverse => index.reverse_bits() >> (usize::BITS - bits),
+            Permutation::Gray => {
+                let (mut value, mut shifted) = (index, index >> 1);
+                while shifted != 0 {
+                    value ^= shifted;
+                    shifted >>= 1;
+                }
+                value
+            }
         }
-    });
+    }
+}
 
-    let lut_access = input
-        .inputs
-        .iter()
-        .fold(quote::quote!(__LUT), |expr, param| {
-            let ident = &param.ident;
-            quote::quote!(#expr[#ident])
-        });
+/// How a parameter treats values outside of its range.
+#[derive(Clone, Copy)]
+enum Policy {
+    /// Lookups panic.
+    Panic,
+    /// Values are clamped into the range, with `#[clamp]`.
+    Clamp(proc_macro2::Span),
+    /// Values wrap around the range, with `#[wrap]`.
+    Wrap(proc_macro2::Span),
+}
 
-    let lut_params = input.inputs.iter().map(|param| {
-        let ident = &param.ident;
-        quote::quote!(#ident: usize)
-    });
+#[derive(Default)]
+struct Options {
+    mask: bool,
+    layout: Layout,
+    transpose: Option<proc_macro2::Span>,
+    verify: Option<syn::LitStr>,
+    /// The shapes that `#[check(..)]` requires of the entries along parameters.
+    shape_checks: Vec<ShapeCheck>,
+    hash: Option<proc_macro2::Span>,
+    /// The span of the `#[redundant]` option and whether the copy of the table is bit-inverted.
+    redundant: Option<(proc_macro2::Span, bool)>,
+    /// The span of the `#[patchable]` option, which stores the entries in atomics that can be
+    /// overwritten at runtime.
+    patchable: Option<proc_macro2::Span>,
+    /// The span of the `#[reload]` option, which adds a function that replaces the patchable
+    /// entries with the ones in a file.
+    reload: Option<proc_macro2::Span>,
+    /// The span of the `#[heap]` option, which evaluates the entries into a table on the heap the
+    /// first time it is used instead of embedding it in the binary.
+    heap: Option<proc_macro2::Span>,
+    /// The span of the `#[compressed]` option, which stores the entries compressed and decompresses
+    /// them into RAM with `init`.
+    compressed: Option<proc_macro2::Span>,
+    /// The span of the `#[crc]` option, which adds a checksum and a function that verifies it.
+    crc: Option<proc_macro2::Span>,
+    /// The span of the `#[inverse]` option, which adds the inverse of an injective table.
+    inverse: Option<proc_macro2::Span>,
+    /// The span of the `#[invert]` option, which adds a binary search over a monotone table, and
+    /// whether the search interpolates between entries.
+    invert: Option<(proc_macro2::Span, bool)>,
+    /// The span of the `#[find_nearest]` option, which adds a search for the nearest entry.
+    find_nearest: Option<proc_macro2::Span>,
+    /// The span of the `#[aggregates]` option, which adds reductions over the entries.
+    aggregates: Option<proc_macro2::Span>,
+    /// The span of the `#[bytes]` option, which adds a byte view of the table.
+    bytes: Option<proc_macro2::Span>,
+    /// The span of the `#[try_get]` option, which adds a lookup returning a `Result`.
+    try_get: Option<proc_macro2::Span>,
+    /// The span of the `#[get_or]` option, which adds a lookup with a fallback.
+    get_or: Option<proc_macro2::Span>,
+    /// The span of the `#[transform]` option, which adds functions that map buffers through the
+    /// table in place.
+    transform: Option<proc_macro2::Span>,
+    /// The `#[interpolate(..)]` option, which adds a function that samples the table at fractional
+    /// coordinates.
+    interpolate: Option<Interpolate>,
+    /// The span of the `#[index]` option, which implements `Index` for the struct.
+    index: Option<proc_macro2::Span>,
+    /// The span of the `#[dump]` option, which implements `Debug` and `Display` for the struct.
+    dump: Option<proc_macro2::Span>,
+    /// The span of the `#[csv]` option, which adds a function writing the entries as CSV.
+    csv: Option<proc_macro2::Span>,
+    /// The span of the `#[heatmap]` option, which adds a grayscale image of a two-dimensional
+    /// table.
+    heatmap: Option<proc_macro2::Span>,
+    /// The function that `#[fallback(f)]` calls with out-of-range parameters.
+    fallback: Option<syn::Expr>,
+    /// The span of the `#[total]` option, which rejects lookups that can panic.
+    total: Option<proc_macro2::Span>,
+    /// The span of `no_panic` in `#[total(no_panic)]`, which annotates the accessors with
+    /// `#[no_panic]`.
+    no_panic: Option<proc_macro2::Span>,
+    /// The span of the `#[trace]` option and the callback that it passes lookups to, if any.
+    trace: Option<(proc_macro2::Span, Option<syn::Expr>)>,
+    /// The span of the `#[sample]` option, which adds a function returning a random entry.
+    sample: Option<proc_macro2::Span>,
+    /// The span of the `#[ndarray]` option, which adds an `ndarray` view of the table.
+    ndarray: Option<proc_macro2::Span>,
+    /// The span of the `#[runtime]` option, which implements the traits of `numeric-lut-runtime`.
+    runtime: Option<proc_macro2::Span>,
+    sidecar: Option<(proc_macro2::Span, Option<syn::LitStr>)>,
+    /// The name of the file with the entries of a table with `#[built = "name"]`, which were
+    /// evaluated in a build script.
+    built: Option<syn::LitStr>,
+    /// The registry that `#[register(..)]` adds a description of the table to.
+    register: Option<syn::Path>,
+    shard_entries: Option<usize>,
+    /// The span of the `#[index_width(..)]` option and the integer type in which lookups compute
+    /// offsets into the stored entries.
+    index_width: Option<(proc_macro2::Span, syn::Ident)>,
+    /// The span of the `#[indirect]` option, which reads the table through a reference that is
+    /// set at runtime.
+    indirect: Option<proc_macro2::Span>,
+    /// The span of the `#[const_table]` option, which stores the entries in a `const` instead of
+    /// a `static`.
+    const_table: Option<proc_macro2::Span>,
+    /// The span of the `#[call(direct)]` option, which makes `call` match on the parameters and
+    /// call the handler of every entry directly.
+    direct_call: Option<proc_macro2::Span>,
+    /// The number of stored entries above which to warn about the size of the table.
+    warn_above: Option<usize>,
+    /// The span of the `#[wide_entries]` option, which keeps integer entries wider than their
+    /// values without a warning.
+    wide_entries: Option<proc_macro2::Span>,
+    const_eval: ConstEval,
+    export: Option<Export>,
+    /// The span of the `#[wasm_bindgen]` option and the JavaScript name of the lookup, if any.
+    wasm_bindgen: Option<(proc_macro2::Span, Option<syn::LitStr>)>,
+    shaders: Vec<shader::Language>,
+    shaders_span: Option<proc_macro2::Span>,
+    /// The span of the `#[strategy(..)]` option, if any.
+    strategy_span: Option<proc_macro2::Span>,
+    /// The number of entries up to which tables are looked up with a `match` instead.
+    match_up_to: Option<usize>,
+    /// The number of entries above which entries are computed on every lookup instead.
+    compute_above: Option<usize>,
+    /// The span of the `#[soa(..)]` option and the fields that are stored in separate tables.
+    soa: Option<(proc_macro2::Span, Vec<syn::Field>)>,
+    /// The span of the `#[split]` option, which stores 128-bit entries as two `u64` halves.
+    split: Option<proc_macro2::Span>,
+    /// The span of the `#[f16]` option, which stores `f32` entries as `half::f16`.
+    f16: Option<proc_macro2::Span>,
+    codec: Option<Codec>,
+    cover: Option<Cover>,
+    guard: Option<Guard>,
+    /// The span of the `#[nalgebra(entries)]` option, which builds matrix entries from arrays.
+    nalgebra_entries: Option<proc_macro2::Span>,
+    /// The span of the `#[nalgebra(view)]` option, which adds a matrix view of the table.
+    nalgebra_view: Option<proc_macro2::Span>,
+}
 
-    let lut_type = input
-        .inputs
-        .iter()
-        .rev()
-        .fold(input.return_type, |ty, param| {
-            let count = if param.exclusive_end {
-                param.hi - param.lo
-            } else {
-                param.hi - param.lo + 1
-            };
-            quote::quote!([#ty; #count]).into()
-        });
+/// A condition that the parameters must satisfy for the body to be evaluated.
+struct Guard {
+    span: proc_macro2::Span,
+    condition: syn::Expr,
+    /// The entry for parameters that don't satisfy the condition.
+    default: Option<syn::Expr>,
+    /// Whether lookups return `None` for parameters that don't satisfy the condition.
+    absent: bool,
+}
 
-    let output = quote::quote!({
-        static __LUT: #lut_type = #table_data;
-        |#(#lut_params),*| #lut_access
-    });
+/// The `#[interpolate(..)]` option.
+struct Interpolate {
+    span: proc_macro2::Span,
+    /// The coordinates that the values of parameters are mapped to.  The coordinates of other
+    /// parameters are their values.
+    domains: Vec<Domain>,
+    /// What `sample` does with coordinates outside of the domain, with `extrapolate = ..`.
+    extrapolate: Extrapolate,
+}
 
-    output.into()
+/// The coordinates that the values of a parameter are mapped to in `#[interpolate(..)]`.
+struct Domain {
+    param: syn::Ident,
+    start: syn::Expr,
+    end: syn::Expr,
+    /// Whether the domain is periodic, with `param = periodic(start..end)`, so that `end` wraps
+    /// around to `start` and the last value is followed by the first.  Otherwise the domain is
+    /// `param = start..=end`, with the first value at `start` and the last at `end`.
+    periodic: bool,
 }
 
-fn generate_array(
-    ident: &syn::Ident,
-    range: impl Iterator<Item = usize>,
-    body: syn::Expr,
-) -> syn::Expr {
-    let items = range.map(|n| {
+/// What `sample` does with coordinates outside of the domain of `#[interpolate(..)]`.
+#[derive(Clone, Copy, PartialEq)]
+enum Extrapolate {
+    /// The coordinates are clamped to the domain, which repeats the entries at its edges.
+    Clamp,
+    /// The entries are extended with the slopes at the edges of the domain.
+    Linear,
+    /// `sample` returns an `Option`, which is `None` outside of the domain.
+    None,
+}
+
+/// A shape that the entries must have along a parameter, for every value of the other parameters.
+struct ShapeCheck {
+    span: proc_macro2::Span,
+    shape: Shape,
+    param: syn::Ident,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Shape {
+    MonotoneIncreasing,
+    MonotoneDecreasing,
+    StrictlyIncreasing,
+    StrictlyDecreasing,
+    Convex,
+    Concave,
+}
+
+impl Shape {
+    const ALL: [(&'static str, Shape); 6] = [
+        ("monotone_increasing", Shape::MonotoneIncreasing),
+        ("monotone_decreasing", Shape::MonotoneDecreasing),
+        ("strictly_increasing", Shape::StrictlyIncreasing),
+        ("strictly_decreasing", Shape::StrictlyDecreasing),
+        ("convex", Shape::Convex),
+        ("concave", Shape::Concave),
+    ];
+
+    /// Whether the shape is increasing or decreasing, if it is monotone.
+    fn is_increasing(self) -> Option<bool> {
+        match self {
+            Shape::MonotoneIncreasing | Shape::StrictlyIncreasing => Some(true),
+            Shape::MonotoneDecreasing | Shape::StrictlyDecreasing => Some(false),
+            Shape::Convex | Shape::Concave => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        Shape::ALL
+            .iter()
+            .find(|(_, shape)| *shape == self)
+            .map(|(name, _)| *name)
+            .unwrap()
+    }
+}
+
+/// A user-defined representation of the stored entries.
+struct Codec {
+    span: proc_macro2::Span,
+    /// The type of the stored entries.
+    stored: syn::Type,
+    /// How entries are converted to and from the stored type.
+    conversion: Conversion,
+}
+
+/// How a [`Codec`] converts entries to and from the stored type.
+enum Conversion {
+    /// With `#[codec(..)]`, the `const fn` that converts an entry to the stored type and the
+    /// function that converts a stored entry back to the return type.
+    Functions {
+        encode: syn::Path,
+        decode: syn::Path,
+    },
+    /// With `#[bitfields(..)]`, the fields of the struct return type that are packed into the
+    /// stored integer, starting at its least significant bit.
+    Bitfields(Vec<Bitfield>),
+}
+
+/// A field of the return type that `#[bitfields(..)]` packs into some bits of the stored integer.
+struct Bitfield {
+    ident: syn::Ident,
+    ty: syn::Type,
+    bits: u32,
+}
+
+impl Codec {
+    /// The name of the option that declared the codec.
+    fn name(&self) -> &'static str {
+        match self.conversion {
+            Conversion::Functions { .. } => "codec",
+            Conversion::Bitfields(_) => "bitfields",
+        }
+    }
+
+    /// Converts an entry of the return type to the stored type, during constant evaluation.
+    fn encode(
+        &self,
+        entry: proc_macro2::TokenStream,
+        return_type: &syn::Type,
+    ) -> proc_macro2::TokenStream {
+        let fields = match &self.conversion {
+            Conversion::Functions { encode, .. } => return quote::quote!(#encode(#entry)),
+            Conversion::Bitfields(fields) => fields,
+        };
+        let stored = &self.stored;
+        let width = integer_bits(stored).expect("validated");
+        let mut shift = 0;
+        let parts = fields.iter().map(|field| {
+            let ident = &field.ident;
+            let bits = field.bits;
+            // Casting to the same type or shifting by zero trips clippy in the invoking crate.
+            let value = match same_type(&field.ty, stored) {
+                true => quote::quote!(__entry.#ident),
+                false => quote::quote!((__entry.#ident as #stored)),
+            };
+            let message = format!(
+                "the `{}` field of an entry doesn't fit into {} bits",
+                ident, bits
+            );
+            let check = match bits < width {
+                true => quote::quote!(assert!(#value >> #bits == 0, #message);),
+                false => proc_macro2::TokenStream::new(),
+            };
+            let part = match shift {
+                0 => value,
+                shift => quote::quote!(#value << #shift),
+            };
+            shift += bits;
+            (check, part)
+        });
+        let (checks, parts): (Vec<_>, Vec<_>) = parts.unzip();
         quote::quote!({
-            #[allow(non_upper_case_globals)]
-            const #ident: usize = #n;
-            #body
+            let __entry: #return_type = #entry;
+            #(#checks)*
+            #(#parts)|*
         })
-    });
-    quote::quote!([#(#items),*]).into()
+    }
+
+    /// Converts a stored entry back to the return type, on every lookup.
+    fn decode(
+        &self,
+        entry: proc_macro2::TokenStream,
+        return_type: &syn::Type,
+    ) -> proc_macro2::TokenStream {
+        let fields = match &self.conversion {
+            Conversion::Functions { decode, .. } => return quote::quote!(#decode(#entry)),
+            Conversion::Bitfields(fields) => fields,
+        };
+        let stored = &self.stored;
+        let width = integer_bits(stored).expect("validated");
+        let mut shift = 0;
+        let fields = fields.iter().map(|field| {
+            let (ident, ty) = (&field.ident, &field.ty);
+            let mut value = match shift {
+                0 => quote::quote!(__packed),
+                shift => quote::quote!((__packed >> #shift)),
+            };
+            if shift + field.bits < width {
+                let mask = proc_macro2::Literal::u128_unsuffixed((1 << field.bits) - 1);
+                value = quote::quote!((#value & #mask));
+            }
+            shift += field.bits;
+            let value = match ty {
+                syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("bool") => {
+                    quote::quote!(#value != 0)
+                }
+                ty if same_type(ty, stored) => value,
+                ty => quote::quote!(#value as #ty),
+            };
+            quote::quote!(#ident: #value)
+        });
+        quote::quote!({
+            let __packed: #stored = #entry;
+            type __Entry = #return_type;
+            __Entry { #(#fields),* }
+        })
+    }
 }
 
-impl Param {
-    fn from_pat(pat: syn::Pat) -> syn::Result<Self> {
-        use syn::spanned::Spanned;
-        match pat {
-            syn::Pat::Ident(pat_ident) => Self::from_pat_ident(pat_ident),
-            other => Err(syn::Error::new(
-                other.span(),
-                "this parameter must have a range pattern (e.g. `x @ 1..2` or `y @ 3..=4`)",
-            )),
+/// The number of bits of an unsigned primitive integer type.
+fn integer_bits(ty: &syn::Type) -> Option<u32> {
+    match quote::quote!(#ty).to_string().as_str() {
+        "u8" => Some(8),
+        "u16" => Some(16),
+        "u32" => Some(32),
+        "u64" => Some(64),
+        "u128" => Some(128),
+        _ => None,
+    }
+}
+
+fn same_type(a: &syn::Type, b: &syn::Type) -> bool {
+    quote::quote!(#a).to_string() == quote::quote!(#b).to_string()
+}
+
+/// Which parameter values must have an entry, for bodies that return an `Option`.
+struct Cover {
+    span: proc_macro2::Span,
+    /// Inclusive ranges of values that must have an entry.
+    ranges: Vec<(usize, usize)>,
+    /// The entry for values outside of the ranges without an entry.
+    default: Option<syn::Expr>,
+}
+
+/// How the lookup is exported for use from other languages, with `#[export_c]`, or how the table
+/// is exported for use from other crates, with `#[export]`.
+struct Export {
+    span: proc_macro2::Span,
+    /// Whether the lookup function is exported with the C ABI, which is the case for `#[export_c]`.
+    c: bool,
+    /// The symbol name of the exported lookup function.
+    name: Option<syn::LitStr>,
+    /// The symbol name of the exported table, if it is exported.
+    table: Option<syn::LitStr>,
+}
+
+impl Export {
+    /// The name of the exported lookup function, defaulting to the snake case struct name.
+    fn name(&self, ident: &syn::Ident) -> syn::Ident {
+        match &self.name {
+            Some(name) => syn::Ident::new(&name.value(), name.span()),
+            None => syn::Ident::new(&snake_case(&ident.to_string()), ident.span()),
         }
     }
 
-    fn from_pat_ident(pat_ident: syn::PatIdent) -> syn::Result<Self> {
-        use syn::spanned::Spanned;
-        match pat_ident {
-            syn::PatIdent {
-                ident,
-                subpat,
-                ..
-            } => match subpat {
-                Some((_, pat)) => {
-                    let pat_span = pat.span();
-                    match *pat {
-                        syn::Pat::Range(syn::PatRange {
-                            lo,
-                            limits,
-                            hi,
-                            ..
-                        }) => match *lo {
-                            syn::Expr::Lit(syn::ExprLit {
-                                lit: syn::Lit::Int(lo),
-                                ..
-                            }) => {
-                                let lo = lo.base10_parse()?;
-                                match *hi {
-                                    syn::Expr::Lit(syn::ExprLit {
-                                        lit: syn::Lit::Int(hi),
-                                        ..
-                                    }) => {
-                                        let hi = hi.base10_parse()?;
-                                        if hi < lo {
-                                            return Err(syn::Error::new(pat_span, format!("range lower bound {} must be less than upper bound {}", lo, hi)));
-                                        }
-                                        let exclusive_end = match limits {
-                                            syn::RangeLimits::Closed(_) => false,
-                                            syn::RangeLimits::HalfOpen(_) => true,
-                                        };
-                                        Ok(Param {
-                                            ident,
-                                            lo,
-                                            exclusive_end,
-                                            hi,
-                                        })
-                                    }
-                                    expr => Err(syn::Error::new(
-                                        expr.span(),
-                                        "must be an integer literal",
-                                    )),
-                                }
-                            }
-                            expr => Err(syn::Error::new(expr.span(), "must be an integer literal")),
-                        },
-                        pat => Err(syn::Error::new(
-                            pat.span(),
-                            "only range patterns allowed (e.g. `1..2` or `3..=4`)",
-                        )),
-                    }
-                }
-                None => Err(syn::Error::new(
-                    ident.span(),
-                    format!(
-                        "this parameter must have a specified range pattern (e.g. `{} @ 1..2`)",
-                        ident
-                    ),
-                )),
-            },
+    /// The name of the option, for error messages.
+    fn option(&self) -> &'static str {
+        if self.c {
+            "export_c"
+        } else {
+            "export"
+        }
+    }
+
+    /// The name of the option that exports the table, for error messages.
+    fn table_option(&self) -> &'static str {
+        if self.c {
+            "export_c(table)"
+        } else {
+            "export(table)"
         }
     }
 }
 
-impl syn::parse::Parse for Lut {
-    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
-        let or1_token: syn::Token![|] = input.parse()?;
+/// Converts a camel case name like `FooBar` to snake case like `foo_bar`.
+fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
 
-        let mut inputs = syn::punctuated::Punctuated::new();
-        loop {
-            if input.peek(syn::Token![|]) {
-                break;
-            }
-            let value = Param::from_pat(input.parse::<syn::Pat>()?)?;
-            inputs.push_value(value);
-            if input.peek(syn::Token![|]) {
-                break;
-            }
-            let punct: syn::Token![,] = input.parse()?;
-            inputs.push_punct(punct);
+/// Converts a camel case name like `FooBar` to lower camel case like `fooBar`.
+fn lower_camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// How the generated items interact with limits on constant evaluation.
+#[derive(Default)]
+struct ConstEval {
+    /// The level of the `long_running_const_eval` lint on the generated items.
+    lint: Option<syn::Ident>,
+    /// Whether each entry is evaluated in a constant of its own.
+    isolate: Option<proc_macro2::Span>,
+    /// The number of entries up to which each entry is evaluated in a constant of its own.
+    isolate_up_to: Option<usize>,
+}
+
+impl ConstEval {
+    /// Generates the lint attribute for items that perform constant evaluation.
+    fn lint_attr(&self) -> proc_macro2::TokenStream {
+        match &self.lint {
+            Some(level) => quote::quote!(#[#level(long_running_const_eval)]),
+            None => proc_macro2::TokenStream::new(),
         }
+    }
+}
 
-        let or2_token: syn::Token![|] = input.parse()?;
+/// The number of entries above which tables are split into shards by default.
+const DEFAULT_SHARD_ENTRIES: usize = 1 << 16;
 
-        let arrow_token: syn::Token![->] = input.parse()?;
-        let return_type: syn::Type = input.parse()?;
-        let body: syn::Block = input.parse()?;
-        let body = syn::Expr::Block(syn::ExprBlock {
-            attrs: Vec::new(),
-            label: None,
-            block: body,
-        });
+/// The number of entries up to which `#[strategy(auto)]` looks up entries with a `match`.
+const DEFAULT_MATCH_UP_TO: usize = 16;
 
-        Ok(Lut {
-            or1_token,
-            inputs,
-            or2_token,
-            arrow_token,
-            return_type,
-            body,
-        })
+/// The number of entries above which `#[strategy(auto)]` computes entries on every lookup.
+const DEFAULT_COMPUTE_ABOVE: usize = 1 << 20;
+
+/// The number of entries up to which every entry is evaluated in a constant of its own by default.
+const DEFAULT_ISOLATE_UP_TO: usize = 4096;
+
+/// The number of stored entries above which the macro warns about the size of a table by default.
+const DEFAULT_WARN_ABOVE: usize = 1 << 16;
+
+/// The number of values from the smallest to the largest entry up to which `#[inverse]` stores an
+/// inverse table.
+const MAX_INVERSE_SPAN: i128 = 1 << 20;
+
+/// The number of colliding entries that the error of `#[inverse]` lists.
+const INVERSE_COLLISIONS: usize = 8;
+
+/// The number of values of every parameter that the `Display` implementation of `#[dump]` shows.
+const DUMP_VALUES: usize = 8;
+
+/// The number of entries that the `Debug` implementation of `#[dump]` shows.
+const DUMP_ENTRIES: usize = 16;
+
+/// How the entries of a table are laid out in memory.
+#[derive(Default)]
+enum Layout {
+    /// One nested array dimension per parameter.
+    #[default]
+    Dense,
+    /// Only one triangle of a two-dimensional table with `f(x, y) == f(y, x)`.
+    Symmetric(proc_macro2::Span),
+    /// Only the band `|x - y| <= width` of a two-dimensional table, with a default elsewhere.
+    Banded {
+        span: proc_macro2::Span,
+        width: usize,
+        default: Box<syn::Expr>,
+    },
+    /// Every unique row (the entries for one combination of the other parameters) once, with an
+    /// index of the row for every combination.
+    Deduplicated(proc_macro2::Span),
+}
+
+/// How the lookup obtains the entries.
+#[derive(Clone, Copy, PartialEq)]
+enum Strategy {
+    /// By reading from the table.
+    Table,
+    /// With a `match` on the parameters that has the entries as constant arms.
+    Match,
+    /// By evaluating the body on every lookup, without generating a table.
+    Compute,
+}
+
+/// The generated static table and the expression that reads from it.
+struct Table {
+    ty: proc_macro2::TokenStream,
+    data: proc_macro2::TokenStream,
+    access: proc_macro2::TokenStream,
+    /// The number of nested array levels of `ty`.
+    rank: usize,
+    /// The total number of stored entries.
+    len: usize,
+    /// Statics that `data` refers to, if the table is split into shards.
+    items: proc_macro2::TokenStream,
+    /// How the table is split into shards, if it is.
+    shards: Option<Shards>,
+    /// Whether `access` reads from the table at all.
+    strategy: Strategy,
+    /// Whether the table is a pair of a slice of the unique rows and the index of the row for every
+    /// combination of the other parameters, as with `#[dedup]`.
+    deduplicated: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Shards {
+    /// The number of shards.
+    count: usize,
+    /// The number of rows (values of the first parameter) per shard, a power of two.
+    rows: usize,
+}
+
+impl Table {
+    /// Generates expressions that evaluate to slices of the stored entries covering the entire
+    /// table, given an expression that evaluates to a reference to the table.
+    fn entry_slices(&self, lut: &proc_macro2::TokenStream) -> Vec<proc_macro2::TokenStream> {
+        if self.deduplicated {
+            return vec![quote::quote!((#lut).0.as_flattened())];
+        }
+        match self.shards {
+            None => vec![flattened(quote::quote!((#lut)), self.rank)],
+            Some(Shards { count, .. }) => (0..count)
+                .map(|k| flattened(quote::quote!((#lut)[#k]), self.rank))
+                .collect(),
+        }
+    }
+}
+
+/// Generates a numeric lookup function.
+///
+/// The macro is function-like and accepts an expression that looks like a closure.  Only parameters
+/// that use range patterns (like `x @ 0..1`) are accepted.  All parameters are implicitly of type
+/// `usize` since they will be used as indices for lookup tables.
+///
+/// The lower bound may be omitted, defaulting to zero (`x @ ..8` is `x @ 0..8`).  The bounds are
+/// integer literals in any notation, so register domains can be written in hex.  Empty ranges like
+/// `x @ 0..0` are rejected, since the table would have no entries:
+///
+/// ```
+/// let parity = numeric_lut::lut!(|reg @ 0x00..=0xFF| -> bool { reg.count_ones() % 2 == 1 });
+/// assert!(parity(0b0111_0000));
+/// ```
+///
+/// A trailing comma after the last parameter is accepted, and without any parameters (`|| -> T {
+/// ... }`) the body is evaluated once into a constant.  Both are handy when the invocation is
+/// produced by `macro_rules!`.
+///
+/// ```
+/// let answer = numeric_lut::lut!(|| -> u32 { 6 * 7 });
+/// assert_eq!(42, answer());
+/// ```
+///
+/// ## Options
+///
+/// The closure may be preceded by attributes that tweak the generated code:
+///
+///   * `#[mask]`: pads every dimension to the next power of two and sanitizes indices with a
+///     bitmask instead of bounds checks, so the lookup contains no panicking branches at all.  An
+///     out-of-range index silently wraps around: the index `i` (relative to the lower bound) reads
+///     the entry at `(i & (n - 1)) % len`, where `n` is the padded length of the dimension and
+///     `len` the length of the range.  Padding entries repeat the table from its lower bound.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[mask] |x @ 0..4| -> u8 { x as u8 * 10 });
+///     assert_eq!(20, lut(2));
+///     assert_eq!(10, lut(5));
+///     ```
+///
+///     Individual parameters can instead be annotated with `#[clamp]`, which clamps values into
+///     the range, or `#[wrap]`, which wraps them around the range (modulo its length), while the
+///     other parameters keep panicking.  These only support the default layout and can't be
+///     combined with `#[mask]`.
+///
+///     ```
+///     let lut = numeric_lut::lut!(|#[clamp] gain @ 0..4, #[wrap] phase @ 0..8| -> u32 {
+///         (gain * 8 + phase) as u32
+///     });
+///     assert_eq!(31, lut(9, 7));
+///     assert_eq!(1, lut(0, 9));
+///     ```
+///
+///   * `#[contiguous]` on a parameter: stores the entries along that parameter next to each other,
+///     by making it the last dimension of the stored table, while the lookup keeps the declared
+///     order of the parameters.  Loops that vary this parameter fastest then read consecutive
+///     memory.  `TABLE` and the other views of the stored entries have the stored order.  Only one
+///     parameter can be contiguous, and it requires the default layout.  Options that read the
+///     entries in the declared order (`#[check]`, `#[aggregates]`, `#[reload]`, `#[heatmap]`,
+///     `#[ndarray]` and `#[nalgebra(view)]`) are rejected.
+///
+///     ```
+///     let lut = numeric_lut::lut!(|#[contiguous] x @ 0..4, y @ 0..1000| -> u32 {
+///         (x * 1000 + y) as u32
+///     });
+///     assert_eq!(2500, lut(2, 500));
+///     ```
+///
+///   * `#[permute(bit_reverse)]` or `#[permute(gray)]` on a parameter: stores the entry of every
+///     value at the index that the permutation maps it to, so that indices in bit-reversed order
+///     (like those of an FFT) or Gray code (like those of hardware counters) look up the table
+///     directly, without decoding them first.  The body sees the decoded value, while lookups take
+///     the raw index.  The range must be `0..2^n`, and the table must be stored with the default
+///     layout.
+///
+///     ```
+///     let twiddles = numeric_lut::lut!(|#[permute(bit_reverse)] k @ 0..8| -> usize { k * 10 });
+///     assert_eq!(40, twiddles(0b001));
+///     assert_eq!(60, twiddles(0b011));
+///     let position = numeric_lut::lut!(|#[permute(gray)] count @ 0..8| -> usize { count });
+///     assert_eq!(3, position(0b010));
+///     assert_eq!(4, position(0b110));
+///     ```
+///
+///   * `#[cfg(..)]` on a parameter: only declares the parameter in the build configurations where
+///     the predicate holds, so that one invocation produces differently shaped tables, for example
+///     with alternative ranges of the same parameter.  The macro can't evaluate the predicates, so
+///     it expands the spec once for every combination of them (up to six distinct predicates, where
+///     `not(p)` counts as the same predicate as `p`), each under a `#[cfg(..)]` of its own.
+///
+///     ```
+///     let lut = numeric_lut::lut!(|
+///         #[cfg(debug_assertions)] x @ 0..16,
+///         #[cfg(not(debug_assertions))] x @ 0..1024,
+///     | -> u32 { x as u32 * 3 });
+///     assert_eq!(45, lut(15));
+///     ```
+///
+///   * `#[fallback(f)]`: calls `f` with the parameters instead of panicking if one of them is out
+///     of range, so that applications can log, substitute a sentinel or handle the input in some
+///     other domain-specific way.  `f` is a path to a function or a closure taking the parameters
+///     as `usize`s and returning the element type.  The fallback is used by the lookup (and in the
+///     item form, by `get` and the functions built on it), but can't be combined with `#[mask]`.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[fallback(|x| -(x as i32))] |x @ 0..4| -> i32 { x as i32 });
+///     assert_eq!(3, lut(3));
+///     assert_eq!(-7, lut(7));
+///     ```
+///
+///   * `#[trace]`: with the `log` feature of this crate, logs every lookup with its parameters and
+///     entry as a `trace` event with the target `numeric_lut` in debug builds, to follow
+///     table-driven state machines without adding prints around every call site.  The invoking
+///     crate must depend on `log`, and the element type must implement `Debug`.  Lookups are named
+///     after the struct in the item form and after their location in the closure form.  Release
+///     builds don't trace at all.
+///
+///     With `#[trace(f)]`, lookups are instead passed to `f`, a path to a function or a closure
+///     that takes the name, the parameters as a `&[usize]` and a reference to the entry, which
+///     needs no feature:
+///
+///     ```
+///     fn trace(name: &str, params: &[usize], entry: &u8) {
+///         println!("{}{:?} = {}", name, params, entry);
+///     }
+///
+///     let lut = numeric_lut::lut!(#[trace(trace)] |x @ 0..4| -> u8 { x as u8 * 2 });
+///     assert_eq!(6, lut(3));
+///     ```
+///
+///   * `#[total]`: guarantees that lookups can't panic, for code that must be shown to be
+///     panic-free.  Every parameter must stay in range, with `#[clamp]` or `#[wrap]` on each of
+///     them or with `#[mask]`, so every index is in bounds by construction and optimizing builds
+///     elide all bounds checks.  Options whose lookups can panic, like `#[redundant]`, are
+///     rejected.  The body itself is only evaluated during constant evaluation, so it may panic
+///     freely, which rules out tables that compute their entries on every lookup.
+///
+///     With `#[total(no_panic)]` and the `no-panic` feature of this crate, the item form annotates
+///     `get`, as well as `try_get` and `get_or` if requested, with `#[no_panic]` of the `no-panic`
+///     crate, so that builds fail to link if the optimizer leaves a panic in any of them.  This
+///     only works in builds with optimizations, and the invoking crate must depend on `no-panic`.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[total] |#[clamp] x @ 0..4, #[wrap] y @ 0..4| -> u8 {
+///         (x * 4 + y) as u8
+///     });
+///     assert_eq!(13, lut(100, 5));
+///     ```
+///
+///     ```compile_fail
+///     let lut = numeric_lut::lut!(#[total] |#[clamp] x @ 0..4, y @ 0..4| -> u8 {
+///         (x * 4 + y) as u8
+///     });
+///     ```
+///
+///   * `#[symmetric]`: declares that a two-dimensional table over identical ranges satisfies
+///     `f(x, y) == f(y, x)`.  Only one triangle of the table is stored (roughly halving its size),
+///     and the lookup swaps the indices when needed.  The body is only evaluated for `x >= y`.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[symmetric] |x @ 0..8, y @ 0..8| -> u32 {
+///         (x as u32).abs_diff(y as u32)
+///     });
+///     assert_eq!(3, lut(2, 5));
+///     assert_eq!(3, lut(5, 2));
+///     ```
+///
+///   * `#[banded(k, default = expr)]`: declares that a two-dimensional table is only meaningful
+///     within the band `|x - y| <= k`.  Only the band is stored (`2 * k + 1` entries per value of
+///     `x`), and lookups outside of it return the constant `expr` without evaluating the body.
+///
+///     ```
+///     let lut = numeric_lut::lut!(
+///         #[banded(1, default = u32::MAX)]
+///         |x @ 0..100, y @ 0..100| -> u32 { (x + y) as u32 }
+///     );
+///     assert_eq!(99, lut(50, 49));
+///     assert_eq!(u32::MAX, lut(50, 10));
+///     ```
+///
+///   * `#[dedup]`: stores every unique row of a table with at least two parameters once, along
+///     with the index of the row for every combination of the other parameters (the rows hold the
+///     entries of the last parameter).  Tables whose entries repeat along the other parameters,
+///     like Unicode property data, shrink by the number of repetitions.  The rows are compared
+///     during constant evaluation, so the element type must be primitive, and comparing all rows
+///     with each other can take long for tables with many rows (see `#[const_eval(allow)]`).
+///     With `#[hash]` and similar options, the stored entries are the unique rows.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[dedup] |x @ 0..1024, y @ 0..16| -> u8 { (x % 3 * y) as u8 });
+///     assert_eq!(30, lut(1001, 15));
+///     ```
+///
+///   * `#[index_width(u16 | u32 | u64 | usize)]`: computes the offsets into the stored entries of
+///     a `#[symmetric]` or `#[dedup]` table in the given integer type, which must be wide enough
+///     for the largest offset.  By default, offsets that fit into 16 bits are computed in `usize`,
+///     and larger ones in `u32` on 16-bit targets (where `usize` would overflow) and in `usize`
+///     elsewhere.  A narrower type can be cheaper on targets whose registers are narrower than
+///     `usize`.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[symmetric] #[index_width(u32)] |x @ 0..8, y @ 0..8| -> u32 {
+///         (x * y) as u32
+///     });
+///     assert_eq!(35, lut(7, 5));
+///     ```
+///
+///   * `#[guard(condition, default = expr)]`: only evaluates the body for parameters that satisfy
+///     the condition, which is an expression of the parameters like `x + y < 16`.  The entries for
+///     the other parameters are the constant `expr`, which can be left out for primitive element
+///     types to use their default value (zero, `false` or `'\0'`).  With `#[guard(condition,
+///     absent)]`, lookups instead return an `Option` of the element type given after `->`, which is
+///     `None` for the parameters that don't satisfy the condition.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[guard(x + y < 4)] |x @ 0..4, y @ 0..4| -> u8 {
+///         (3 - x - y) as u8
+///     });
+///     assert_eq!(1, lut(1, 1));
+///     assert_eq!(0, lut(3, 3));
+///
+///     let lut = numeric_lut::lut!(#[guard(x > 0, absent)] |x @ 0..4| -> u8 { 12 / x as u8 });
+///     assert_eq!(Some(4), lut(3));
+///     assert_eq!(None, lut(0));
+///     ```
+///
+///   * `#[f16]`: with the `half` feature of this crate, stores the entries of an `f32` table as
+///     `half::f16`, halving its size for data that tolerates the reduced precision.  The body still
+///     returns `f32`, which is rounded to the nearest `f16` during constant evaluation, and lookups
+///     convert the entry back to `f32`.  The invoking crate must depend on `half`, and only tables
+///     with the default layout are supported.
+///
+///     ```ignore
+///     let loudness = numeric_lut::lut!(#[f16] |x @ 0..1024| -> f32 { ... });
+///     ```
+///
+///   * `#[codec(Stored, encode = f, decode = g)]`: stores the entries as `Stored` instead, like
+///     bitfields, scaled integers or custom enums.  The `const fn` `f` converts each entry that the
+///     body returns to `Stored` during constant evaluation, and `g` converts a stored entry back on
+///     every lookup, so everything else still sees the return type.  Only tables with the default
+///     layout are supported.
+///
+///     ```
+///     /// Packs a value between `0.0` and `1.0` into a byte.
+///     const fn encode(value: f32) -> u8 {
+///         (value * 255.0 + 0.5) as u8
+///     }
+///
+///     fn decode(byte: u8) -> f32 {
+///         f32::from(byte) / 255.0
+///     }
+///
+///     let opacity = numeric_lut::lut!(
+///         #[codec(u8, encode = encode, decode = decode)]
+///         |x @ 0..16| -> f32 { x as f32 / 15.0 }
+///     );
+///     assert_eq!(1.0, opacity(15));
+///     assert!((opacity(7) - 7.0 / 15.0).abs() < 1.0 / 255.0);
+///     ```
+///
+///   * `#[bitfields(Stored, field: Type = bits, ...)]`: packs the fields of a struct element type
+///     into the given numbers of bits of the unsigned integer `Stored`, starting at its least
+///     significant bit, like several flags and a small cost in one byte.  The declared fields
+///     must be all fields of the struct, and can be unsigned integers or `bool`.  A field that
+///     doesn't fit into its bits fails the build, and lookups unpack the entry with shifts and
+///     masks.  In the item form, `get_<field>(..)` looks up a single field.  Like `#[codec]`,
+///     this only supports the default layout.
+///
+///     ```
+///     #[derive(Clone, Copy, Debug, PartialEq)]
+///     pub struct Tile {
+///         solid: bool,
+///         water: bool,
+///         cost: u8,
+///     }
+///
+///     numeric_lut::lut! {
+///         #[bitfields(u8, solid: bool = 1, water: bool = 1, cost: u8 = 5)]
+///         pub struct Tiles(kind @ 0..4) -> Tile {
+///             Tile { solid: kind == 3, water: kind == 1, cost: [1, 4, 2, 31][kind] }
+///         }
+///     }
+///
+///     assert_eq!(::core::mem::size_of::<u8>(), ::core::mem::size_of_val(&Tiles::TABLE[0]));
+///     assert_eq!(Tile { solid: false, water: true, cost: 4 }, Tiles::get(1));
+///     assert_eq!(31, Tiles::get_cost(3));
+///     ```
+///
+///   * `#[nalgebra(entries)]`: with the `nalgebra` feature of this crate, lets the body of a table
+///     of statically sized `nalgebra` matrices (like `SMatrix<f32, 3, 3>` or `Vector3<f32>`) return
+///     the entry as an array of rows (or an array of elements for vectors), which is converted to
+///     the matrix during constant evaluation.  The invoking crate must depend on `nalgebra`.
+///
+///     ```ignore
+///     let rotation = numeric_lut::lut!(#[nalgebra(entries)] |quarter @ 0..4| -> Matrix2<i32> {
+///         let (cos, sin) = [(1, 0), (0, 1), (-1, 0), (0, -1)][quarter];
+///         [[cos, -sin], [sin, cos]]
+///     });
+///     ```
+///
+/// ## Function path form
+///
+/// Instead of a body, the path of a `const fn` can be given, followed by the parameters.  The
+/// function is called with the parameter values for every entry, so that one function can back
+/// several tables with different domains.
+///
+/// ```
+/// const fn weight(x: usize, y: usize) -> u32 {
+///     (x * y) as u32
+/// }
+///
+/// let small = numeric_lut::lut!(weight; x @ 0..4, y @ 0..4);
+/// let large = numeric_lut::lut!(weight -> u32; x @ 0..256, y @ 0..4);
+/// assert_eq!(6, small(2, 3));
+/// assert_eq!(600, large(200, 3));
+/// ```
+///
+/// The element type is inferred if the return type is omitted, but options that need to name it or
+/// add items next to the table (`#[verify]`, `#[check(..)]`, `#[built]` and `#[const_eval(..)]`
+/// with a lint level or `isolate`) require an explicit return type, and inferred tables are never
+/// sharded.  A function that returns a different type than the declared one fails to
+/// build with the mismatch underlined at its path:
+///
+/// ```compile_fail
+/// const fn weight(x: usize, y: usize) -> u32 {
+///     (x * y) as u32
+/// }
+///
+/// let lut = numeric_lut::lut!(weight -> u8; x @ 0..4, y @ 0..4);
+/// ```
+///
+/// ## Item form
+///
+/// Instead of a closure, the macro also accepts a struct-like item declaration, which expands to a
+/// unit struct with the lookup available as associated functions.  Any attributes that aren't
+/// options (like doc comments) are passed on to the struct.  Its documentation is followed by a
+/// generated "Table" section, which lists the range of every parameter and what happens outside of
+/// it, the number of entries and their size in bytes, and the expression that computes them.  Like
+/// in the closure form, the entries are stored in a single `static`, which the associated `TABLE`
+/// constant refers to, so that every crate that looks them up reads the same memory.  Tables that
+/// store their entries in another representation (like `#[compressed]` or `#[split]`) or look them
+/// up with a `match` only keep `TABLE` as a constant, so that the full entries only end up in the
+/// binary if they are read at runtime.
+///
+/// ```
+/// numeric_lut::lut! {
+///     /// The sum of two numbers.
+///     pub struct Sum(x @ 0..8, y @ 0..16) -> u32 { x as u32 + y as u32 }
+/// }
+///
+/// assert_eq!(13, Sum::get(3, 10));
+/// ```
+///
+/// The struct has the following associated functions:
+///
+///   * `get(x, y, ...)`: looks up the entry for the given parameters.
+///   * `fix(x)`: for tables with more than one parameter, fixes the first parameter and returns a
+///     cheap lookup over the remaining ones.  For the default layout it holds a reference to the
+///     selected row of the table, so the outer index doesn't need to be recomputed per lookup.
+///
+///     ```
+///     # numeric_lut::lut! { pub struct Sum(x @ 0..8, y @ 0..16) -> u32 { x as u32 + y as u32 } }
+///     let row = Sum::fix(3);
+///     assert_eq!(13, row(10));
+///     assert_eq!(14, row(11));
+///     ```
+///
+///   * `call(x, y, ..., args...)`: for tables of function pointers (like `-> fn(&mut Cpu)`),
+///     looks up the function for the given parameters and calls it with the remaining arguments,
+///     which makes a classic dispatch table with the range checks of the lookup.  The body names
+///     the handler for every entry, typically with a `match`.
+///
+///     ```
+///     pub struct Cpu {
+///         a: u8,
+///     }
+///
+///     fn nop(_: &mut Cpu) {}
+///
+///     fn inc(cpu: &mut Cpu) {
+///         cpu.a = cpu.a.wrapping_add(1);
+///     }
+///
+///     fn dec(cpu: &mut Cpu) {
+///         cpu.a = cpu.a.wrapping_sub(1);
+///     }
+///
+///     numeric_lut::lut! {
+///         pub struct Opcodes(op @ 0..=0xFF) -> fn(&mut Cpu) {
+///             match op {
+///                 0x3C => inc,
+///                 0x3D => dec,
+///                 _ => nop,
+///             }
+///         }
+///     }
+///
+///     let mut cpu = Cpu { a: 0 };
+///     Opcodes::call(0x3C, &mut cpu);
+///     Opcodes::call(0x3C, &mut cpu);
+///     Opcodes::call(0x3D, &mut cpu);
+///     Opcodes::call(0x00, &mut cpu);
+///     assert_eq!(1, cpu.a);
+///     ```
+///
+///     With `#[call(direct)]`, `call` matches on the parameters instead and calls the handler of
+///     every entry directly, which avoids mispredicted indirect branches and lets the compiler
+///     inline the handlers (and plays along with control-flow integrity), at the cost of code
+///     size.  Parameters outside of the table go through `get`, with its checks and fallback.
+///
+///     ```
+///     # pub struct Cpu { a: u8 }
+///     # fn nop(_: &mut Cpu) {}
+///     # fn inc(cpu: &mut Cpu) { cpu.a += 1; }
+///     numeric_lut::lut! {
+///         #[call(direct)]
+///         pub struct Opcodes(op @ 0..4) -> fn(&mut Cpu) {
+///             match op {
+///                 1 => inc,
+///                 _ => nop,
+///             }
+///         }
+///     }
+///
+///     let mut cpu = Cpu { a: 0 };
+///     Opcodes::call(1, &mut cpu);
+///     assert_eq!(1, cpu.a);
+///     ```
+///
+///   * `get_t(.., y, x)`: with the `#[transpose]` option, looks up the entry with the parameters
+///     given in reverse order, reading the same storage as `get`.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[transpose]
+///         pub struct Index(row @ 0..4, col @ 0..8) -> u32 { row as u32 * 8 + col as u32 }
+///     }
+///
+///     assert_eq!(Index::get(1, 6), Index::get_t(6, 1));
+///     ```
+///
+///   * `try_get(x, y, ...)`: with the `#[try_get]` option, looks up the entry like `get`, but
+///     returns an error instead of panicking if a parameter is outside of its range (even with
+///     `#[mask]`), so that external input can be validated with an actionable message.  The error
+///     type is generated next to the struct and named after it, like `NameError`.  It names the
+///     first offending parameter along with its value and range, and implements
+///     `core::error::Error`.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[try_get]
+///         pub struct Grid(x @ 0..4, y @ 1..=3) -> u32 { (x * y) as u32 }
+///     }
+///
+///     assert_eq!(Ok(6), Grid::try_get(2, 3));
+///     let err = Grid::try_get(2, 4).unwrap_err();
+///     assert_eq!("y", err.parameter);
+///     assert_eq!(1..=3, err.range);
+///     assert_eq!("`y` must be in 1..=3 but is 4", err.to_string());
+///     ```
+///
+///   * `get_or(x, y, ..., default)`: with the `#[get_or]` option, looks up the entry like `get`,
+///     but returns `default` instead of panicking if a parameter is outside of its range (even
+///     with `#[mask]`).
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[get_or]
+///         pub struct Weekday(day @ 1..=7) -> &'static str {
+///             ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"][day - 1]
+///         }
+///     }
+///
+///     assert_eq!("Wed", Weekday::get_or(3, "?"));
+///     assert_eq!("?", Weekday::get_or(0, "?"));
+///     ```
+///
+///   * `transform(buffer)` and `transform_by(buffer, index)`: with the `#[transform]` option, for
+///     tables over a single parameter, replace every element of a mutable slice of entries with the
+///     entry for it, like to gamma-correct a frame buffer in place.  `transform_by` takes the
+///     parameter from a closure that extracts it from each element, while `transform` (for integer
+///     entries) converts the element itself with `as usize`.  The buffer is processed in chunks of
+///     a cache line, whose indices are all extracted before their entries are looked up.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[transform]
+///         pub struct Gamma(#[clamp] x @ 0..256) -> u8 { (x * x / 255) as u8 }
+///     }
+///
+///     let mut frame = [0, 16, 128, 255];
+///     Gamma::transform(&mut frame);
+///     assert_eq!([0, 1, 64, 255], frame);
+///     ```
+///
+///   * `sample(x, y, ...)`: with the `#[interpolate]` option, for tables of `f32` or `f64` entries
+///     or arrays of them, samples the table at fractional coordinates by interpolating linearly
+///     between the entries around them (bilinearly over two parameters, trilinearly over three, as
+///     in 3D color grading LUTs).  By default the coordinate of a parameter is its value, and
+///     `#[interpolate(x = start..=end, ...)]` maps the coordinates `start..=end` onto the values of
+///     `x` instead, with the first value at `start` and the last at `end`.
+///
+///     What happens outside of the domain is selected with `extrapolate = ..` among the arguments
+///     of the option: `clamp` (the default) clamps the coordinates to the domain, `linear` extends
+///     the entries with the slopes at its edges, and `none` makes `sample` return an `Option` that
+///     is `None` for coordinates outside of the domain (or NaN).
+///
+///     `#[interpolate(x = periodic(start..end))]` instead makes the domain of `x` periodic, for
+///     tables over angles or phases: the values of `x` are spread evenly over `start..end`, and
+///     `end` wraps around to `start`, so that the last value is interpolated with the first.  The
+///     coordinates of a periodic parameter are never outside of its domain.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[interpolate(r = 0.0..=1.0, g = 0.0..=1.0, b = 0.0..=1.0)]
+///         pub struct Warm(r @ 0..5, g @ 0..5, b @ 0..5) -> [f32; 3] {
+///             [1.0f32.min(r as f32 / 3.0), g as f32 / 4.0, b as f32 / 5.0]
+///         }
+///     }
+///
+///     assert_eq!([0.5, 0.5, 0.4], Warm::sample(0.375, 0.5, 0.5));
+///     assert_eq!([0.0, 0.0, 0.0], Warm::sample(-1.0, -1.0, -1.0));
+///
+///     numeric_lut::lut! {
+///         #[interpolate(volts = 0.0..=3.0, extrapolate = none)]
+///         pub struct Thermistor(volts @ 0..4) -> f32 { [80.0, 50.0, 30.0, 20.0][volts] }
+///     }
+///
+///     assert_eq!(Some(40.0), Thermistor::sample(1.5));
+///     assert_eq!(None, Thermistor::sample(3.5));
+///
+///     numeric_lut::lut! {
+///         #[interpolate(angle = periodic(0.0..360.0))]
+///         pub struct Compass(angle @ 0..4) -> f32 { [0.0, 1.0, 0.0, -1.0][angle] }
+///     }
+///
+///     assert_eq!(-0.5, Compass::sample(315.0));
+///     assert_eq!(0.5, Compass::sample(-315.0));
+///     ```
+///
+///   * `invert(value)`: with the `#[invert]` option, for tables over a single parameter with a
+///     `#[check]` that the entries are monotone, finds the first parameter whose entry is at least
+///     `value` (or at most, for decreasing entries), or the last parameter if there is none.  This
+///     samples from a cumulative distribution by inverting it.  The binary search takes the same
+///     steps for every value, so it doesn't suffer from mispredicted branches.  With
+///     `#[invert(interpolate)]`, it instead returns an `f64` between the two parameters whose
+///     entries enclose `value`, for inverting sensor calibrations.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[check(monotone_increasing(x))]
+///         #[invert]
+///         pub struct Cdf(x @ 0..4) -> f32 { [0.25, 0.5, 0.5, 1.0][x] }
+///     }
+///
+///     assert_eq!(0, Cdf::invert(0.1));
+///     assert_eq!(1, Cdf::invert(0.5));
+///     assert_eq!(3, Cdf::invert(0.7));
+///
+///     numeric_lut::lut! {
+///         #[check(strictly_decreasing(celsius))]
+///         #[invert(interpolate)]
+///         pub struct Thermistor(celsius @ 20..24) -> u16 { [900, 700, 600, 300][celsius - 20] }
+///     }
+///
+///     assert_eq!(21.5, Thermistor::invert(650));
+///     assert_eq!(23.0, Thermistor::invert(0));
+///     ```
+///
+///   * `find_nearest(value)`: with the `#[find_nearest]` option, for numeric entries, finds the
+///     entry that is nearest to `value` and returns it along with its parameters, or the first one
+///     if several are equally near.  This quantizes arbitrary values onto the entries, like a
+///     requested frequency onto the ones that a clock divider can produce.  It compares every
+///     entry, unless the table has a single parameter with a `#[check]` that the entries are
+///     monotone, which makes it a binary search like `invert`.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[check(strictly_decreasing(div))]
+///         #[find_nearest]
+///         pub struct Clock(div @ 1..=16) -> u32 { 48_000_000 / div as u32 }
+///     }
+///
+///     assert_eq!(([3], 16_000_000), Clock::find_nearest(15_000_000));
+///     assert_eq!(([16], 3_000_000), Clock::find_nearest(0));
+///     ```
+///
+///   * `Index`: with the `#[index]` option, the struct implements `Index<usize>` (for one
+///     parameter) or `Index<(usize, usize, ...)>` (for several), so that entries can be read as
+///     `Name[(x, y)]`, or through a value of the struct stored elsewhere.  Only tables with the
+///     default layout are supported.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[index]
+///         pub struct Product(x @ 0..4, y @ 0..4) -> u32 { (x * y) as u32 }
+///     }
+///
+///     let product = Product;
+///     assert_eq!(6, product[(2, 3)]);
+///     assert_eq!(&9, &Product[(3, 3)]);
+///     ```
+///
+///   * `Debug` and `Display`: with the `#[dump]` option, the struct implements both traits for
+///     eyeballing the contents of the table, which requires the element type to implement `Debug`.
+///     `Debug` shows the ranges of the parameters and the first entries, while `Display` renders
+///     an aligned grid over the last two parameters for every value of the others.  Only the first
+///     few values of every parameter are shown, and `...` marks the omitted ones.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[dump]
+///         pub struct Steps(x @ 0..2, y @ 0..3) -> i32 { x as i32 * 10 - y as i32 }
+///     }
+///
+///     assert_eq!(
+///         "Steps { x: 0..2, y: 0..3, entries: [0, -1, -2, 10, 9, 8] }",
+///         format!("{:?}", Steps)
+///     );
+///     assert_eq!(
+///         "Steps(x @ 0..2, y @ 0..3)\n\
+///          x\\y |  0  1  2\n  \
+///            0 |  0 -1 -2\n  \
+///            1 | 10  9  8",
+///         Steps.to_string()
+///     );
+///     ```
+///
+///   * `write_csv(writer)`: with the `#[csv]` option and the `std` feature of this crate (which is
+///     enabled by default), writes all entries to an `std::io::Write`r as CSV, for attaching the
+///     exact data compiled into a binary to a bug report.  The header names the parameters and a
+///     final `value` column, which holds the entries formatted with `Display` (and quoted if
+///     needed).
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[csv]
+///         pub struct Ratio(x @ 1..3, y @ 1..3) -> f32 { x as f32 / y as f32 }
+///     }
+///
+///     let mut csv = Vec::new();
+///     Ratio::write_csv(&mut csv).unwrap();
+///     assert_eq!("x,y,value\n1,1,1\n1,2,0.5\n2,1,2\n2,2,1\n", String::from_utf8(csv).unwrap());
+///     ```
+///
+///   * `HEATMAP_PGM`: with the `#[heatmap]` option, a grayscale image of a two-dimensional table
+///     of integers or floating point numbers in the binary PGM format, for spotting
+///     discontinuities in a surface at a glance.  The entries are only known during constant
+///     evaluation, which renders the image too, so the constant can be written to a file by a test
+///     or a small binary.  Only tables with the default layout are supported.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[heatmap]
+///         pub struct Ramp(x @ 0..2, y @ 0..3) -> i32 { (x * 3 + y) as i32 }
+///     }
+///
+///     assert_eq!(b"P5\n3 2\n255\n\x00\x33\x66\x99\xcc\xff", &Ramp::HEATMAP_PGM);
+///     // std::fs::write("ramp.pgm", Ramp::HEATMAP_PGM)?;
+///     ```
+///
+///   * `sample(rng)`: with the `#[sample]` option and the `rand` feature of this crate, picks an
+///     entry uniformly at random from the ranges of the parameters, and returns the parameters
+///     (as a tuple if there are several) together with the entry.  The invoking crate must depend
+///     on `rand` 0.8.
+///
+///     ```ignore
+///     numeric_lut::lut! {
+///         #[sample]
+///         pub struct Table(x @ 0..8, y @ 0..8) -> u32 { ... }
+///     }
+///
+///     let ((x, y), entry) = Table::sample(&mut rand::thread_rng());
+///     ```
+///
+///   * `get_<field>(x, y, ...)`: with the `#[soa(field: Type, ...)]` option, looks up a single
+///     field of the entry.  The option declares fields of a struct element type, and each of them
+///     is stored in a table of its own (as a struct of arrays), so that lookups of one field don't
+///     pull the other fields into the cache.  `get` reassembles the entry from all field tables, so
+///     the declared fields must be all fields of the struct.  The field types must be `Copy`, and
+///     only tables with the default layout are supported.
+///
+///     ```
+///     #[derive(Clone, Copy, Debug, PartialEq)]
+///     pub struct Pair {
+///         sum: i32,
+///         diff: i32,
+///     }
+///
+///     numeric_lut::lut! {
+///         #[soa(sum: i32, diff: i32)]
+///         pub struct SumDiff(x @ 0..2, y @ 0..2) -> Pair {
+///             Pair { sum: (x + y) as i32, diff: x as i32 - y as i32 }
+///         }
+///     }
+///
+///     assert_eq!(-1, SumDiff::get_diff(0, 1));
+///     assert_eq!(Pair { sum: 1, diff: 1 }, SumDiff::get(1, 0));
+///     ```
+///
+///   * `get_hi(x, y, ...)` and `get_lo(x, y, ...)`: with the `#[split]` option for `u128` or `i128`
+///     entries, look up the high and low 64 bits of the entry.  The halves are stored in two tables
+///     of `u64`, for targets where 16-byte loads are slow or need stricter alignment, and `get`
+///     joins them again.  Splitting the entries is checked during constant evaluation, so a
+///     toolchain that evaluates 128-bit integers incorrectly fails the build.  Only tables with the
+///     default layout are supported.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[split]
+///         pub struct Wide(x @ 0..4) -> i128 { -(1i128 << (64 + x)) }
+///     }
+///
+///     assert_eq!(-(1 << 66), Wide::get(2));
+///     assert_eq!(u64::MAX << 2, Wide::get_hi(2));
+///     assert_eq!(0, Wide::get_lo(2));
+///     ```
+///
+///   * `view()`: with the `#[ndarray]` option and the `ndarray` feature of this crate, returns an
+///     `ndarray::ArrayView` of the table with one axis per parameter, in the order of the
+///     parameters.  Indices along each axis are relative to the lower bound of the parameter, and
+///     with `#[mask]` the padding is skipped by the strides of the view.  The invoking crate must
+///     depend on `ndarray`, tables with up to six parameters and the default layout are supported,
+///     and such tables are never sharded.
+///
+///     ```ignore
+///     numeric_lut::lut! {
+///         #[ndarray]
+///         pub struct Grid(x @ 1..4, y @ 0..8) -> f64 { ... }
+///     }
+///
+///     let column_sums = Grid::view().sum_axis(ndarray::Axis(0));
+///     ```
+///
+///   * `matrix()`: with the `#[nalgebra(view)]` option and the `nalgebra` feature of this crate,
+///     returns the table of a two-dimensional table as a statically sized `nalgebra::MatrixView`,
+///     with a row per value of the first parameter.  Indices are relative to the lower bounds of
+///     the parameters.  The invoking crate must depend on `nalgebra`, only tables with the default
+///     layout are supported, and such tables are never sharded.
+///
+///     ```ignore
+///     numeric_lut::lut! {
+///         #[nalgebra(view)]
+///         pub struct Costs(from @ 0..4, to @ 0..4) -> f64 { ... }
+///     }
+///
+///     let total = (Costs::matrix() * nalgebra::Vector4::repeat(1.0)).sum();
+///     ```
+///
+///   * `as_bytes()`: with the `#[bytes]` option and the `bytemuck` feature of this crate, returns
+///     the stored entries in memory order as a `&'static [u8]`, in the native byte order, so that
+///     the table can be handed to GPU uploads, DMA or hashing without `unsafe` code.  The element
+///     type must implement `bytemuck::Pod`, and the invoking crate must depend on `bytemuck`.  Such
+///     tables are never sharded, so that the entries are contiguous.
+///
+///     ```ignore
+///     numeric_lut::lut! {
+///         #[bytes]
+///         pub struct Gamma(x @ 0..256) -> u16 { ... }
+///     }
+///
+///     queue.write_buffer(&buffer, 0, Gamma::as_bytes());
+///     ```
+///
+///   * `LutN`: with the `#[runtime]` option and the `runtime` feature of this crate, the struct
+///     implements the `LutN` trait of the `numeric-lut-runtime` crate (and thus `Lut1` or `Lut2`
+///     for one or two parameters), so that generic code can accept any table.  The invoking crate
+///     must depend on `numeric-lut-runtime`.
+///
+///     ```ignore
+///     use numeric_lut_runtime::Lut2;
+///
+///     fn total(lut: &impl Lut2<Entry = u32>) -> u32 {
+///         lut.iter().map(|(_, entry)| entry).sum()
+///     }
+///
+///     numeric_lut::lut! {
+///         #[runtime]
+///         pub struct Sum(x @ 0..8, y @ 0..16) -> u32 { x as u32 + y as u32 }
+///     }
+///
+///     assert_eq!((8, 16), Sum.shape());
+///     assert_eq!(None, Sum.get_checked(8, 0));
+///     assert_eq!(1408, total(&Sum));
+///     ```
+///
+/// ## Exports
+///
+/// `#[export_c]` additionally generates a `#[no_mangle] pub extern "C" fn` that performs the
+/// lookup, so that it can be called from C, assembly or dynamically loaded code.  Its symbol name
+/// defaults to the struct name in snake case and can be set with `#[export_c(name = "...")]`.  With
+/// `#[export_c(table = "...")]`, the table itself is exported as a `#[no_mangle] pub static` of
+/// that name as well (and is never sharded).  The parameters are passed as `size_t`, and the
+/// element type must be FFI-safe.  The function looks the entry up with `get`, so a parameter
+/// outside of its range panics like there, unless it is `#[clamp]`ed or `#[wrap]`ped, and since a
+/// panic can't unwind out of an `extern "C"` function, this aborts the process.  Callers must check
+/// the parameters before the call.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[export_c(name = "square_lookup", table = "SQUARE_TABLE")]
+///     pub struct Square(x @ 0..16) -> u32 { (x * x) as u32 }
+/// }
+///
+/// assert_eq!(49, square_lookup(7));
+/// assert_eq!(49, SQUARE_TABLE[7]);
+/// ```
+///
+/// `#[export(table = "...")]` exports the table for other Rust crates instead: the entries are
+/// stored once, in a `#[no_mangle] pub static` of that name in the crate that declares the table,
+/// and `get`, which reads it, is `#[inline]`.  Crates that use the table then inline the lookup
+/// without expanding the spec or evaluating its entries again, and without a copy of the entries of
+/// their own, so that a big table used across a workspace can be declared once in a crate of
+/// tables.  Crates that don't depend on that crate can declare the table with `lut_extern!`.
+///
+/// ```
+/// mod tables {
+///     numeric_lut::lut! {
+///         #[export(table = "CUBE_TABLE")]
+///         pub struct Cube(x @ 0..16) -> u32 { (x * x * x) as u32 }
+///     }
+/// }
+///
+/// assert_eq!(343, tables::Cube::get(7));
+/// assert_eq!(343, tables::CUBE_TABLE[7]);
+/// ```
+///
+/// With the `wasm-bindgen` feature of this crate, `#[wasm_bindgen]` generates a function annotated
+/// with `#[wasm_bindgen]` that performs the lookup, so that it can be called from JavaScript.  The
+/// parameters are passed as `u32`, the function is called `<snake_case_name>_wasm` in Rust, and its
+/// JavaScript name defaults to the struct name in lower camel case and can be set with
+/// `#[wasm_bindgen(name = "...")]`.  The invoking crate must depend on `wasm-bindgen`, and the
+/// element type must be supported by it.
+///
+/// ```ignore
+/// numeric_lut::lut! {
+///     #[wasm_bindgen(name = "gammaCorrect")]
+///     pub struct Gamma(x @ 0..256) -> u8 { ... }
+/// }
+/// ```
+///
+/// ## Sharding
+///
+/// Tables with more than 65536 entries are transparently split into several statics along the
+/// first parameter, which keeps the constant evaluation of each static within practical limits.
+/// Every shard holds the entries of the largest power of two of values of the first parameter that
+/// fit into that many entries, or of a single value if its entries alone exceed them.  The lookup
+/// picks the right shard with a shift and a mask.  The threshold can be changed with
+/// `#[shard(entries = n)]`.  Tables using `#[mask]` or one of the two-dimensional layouts are never
+/// sharded.
+///
+/// ```
+/// let lut = numeric_lut::lut!(#[shard(entries = 64)] |x @ 0..100, y @ 0..10| -> u32 {
+///     (x * 10 + y) as u32
+/// });
+/// assert_eq!(999, lut(99, 9));
+/// ```
+///
+/// ## Constant tables
+///
+/// By default, the entries are stored in a `static`, which lookups read from memory even when the
+/// parameters are literals; in the item form, `TABLE` is a reference to it.  Only tables of trait
+/// objects (see below) and tables whose element type is inferred, which can't name the type of a
+/// static, are stored in a `const` by default, as is `TABLE` of tables that look up their entries
+/// in another representation or with a `match`.  With `#[const_table]`, the entries of any table
+/// are stored in a `const`, so that the compiler sees them in every crate that looks them up, and
+/// can fold lookups with constant parameters into the entry itself.  In the item form, the
+/// constant is the associated `ENTRIES` (which `TABLE` refers to), which can be read by other
+/// constants as well, and `get` is `#[inline]`, so that calls from other crates are inlined with
+/// it.  Constant tables are never sharded, and can't be combined with options that keep the
+/// entries in a static by design (`#[crc]`, `#[redundant]`, `#[patchable]` and
+/// `#[export_c(table = "...")]`).
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[const_table]
+///     pub struct Pow10(x @ 0..10) -> u64 { 10u64.pow(x as u32) }
+/// }
+///
+/// const KILO: u64 = Pow10::ENTRIES[3];
+/// assert_eq!(KILO, Pow10::get(3));
+/// ```
+///
+/// ## Trait objects
+///
+/// Entries can be references to statics that implement a trait, like `&'static dyn Handler`,
+/// for dispatch tables whose handlers differ in type.  Lookups return the reference, and calls
+/// through it go through the vtable of each handler.  Since trait objects aren't `Sync` in
+/// general, the entries are stored in a `const` like with `#[const_table]` (and not sharded), so
+/// the trait doesn't need `Sync` as a supertrait.
+///
+/// ```
+/// pub trait Handler {
+///     fn handle(&self, value: u32) -> u32;
+/// }
+///
+/// pub struct Offset(u32);
+///
+/// impl Handler for Offset {
+///     fn handle(&self, value: u32) -> u32 {
+///         value + self.0
+///     }
+/// }
+///
+/// pub struct Double;
+///
+/// impl Handler for Double {
+///     fn handle(&self, value: u32) -> u32 {
+///         value * 2
+///     }
+/// }
+///
+/// static ONE: Offset = Offset(1);
+/// static DOUBLE: Double = Double;
+///
+/// let handler = numeric_lut::lut!(|kind @ 0..4| -> &'static dyn Handler {
+///     match kind {
+///         3 => &DOUBLE,
+///         _ => &ONE,
+///     }
+/// });
+/// assert_eq!(6, handler(0).handle(5));
+/// assert_eq!(10, handler(3).handle(5));
+/// ```
+///
+/// ## Indirect storage
+///
+/// On embedded targets, large tables may have to live in memory-mapped external flash, or be
+/// copied to faster RAM at boot, instead of in the `.rodata` of the main image.  With
+/// `#[indirect]`, `get` of the item form reads the table through a reference that is set at
+/// runtime with `init(table)`, and panics if it hasn't been set yet.  `TABLE` is the image of the
+/// table to place there, and is only linked into the program if it is used, like to copy it or to
+/// read the table from the main image after all.  A table in a section that the linker script
+/// places in external flash can be declared with the image and then handed to `init`.  Options
+/// that read `TABLE` directly, like `#[crc]` or `#[bytes]`, are rejected, as are `#[soa]`,
+/// `#[split]` and lookups with a `match`, and the table is never sharded.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[indirect]
+///     pub struct Curve(x @ 0..64) -> u16 { (x * x) as u16 }
+/// }
+///
+/// // Copies the table to RAM, like at boot.
+/// Curve::init(Box::leak(Box::new(*Curve::TABLE)));
+/// assert_eq!(49, Curve::get(7));
+/// ```
+///
+/// ```text
+/// #[link_section = ".qspi"]
+/// static CURVE: [u16; 64] = *Curve::TABLE;
+///
+/// Curve::init(&CURVE);
+/// ```
+///
+/// ## Compressed storage
+///
+/// On embedded targets with more RAM than flash, `#[compressed]` stores the entries of the item
+/// form compressed in the image and decompresses them into a buffer in RAM with `init()`, which
+/// must be called before the first lookup (lookups panic otherwise) and does nothing when called
+/// again, apart from waiting for another thread that is still decompressing them.  An interrupt
+/// handler must not make the first call: one that interrupts `init()` would wait for it forever.
+/// On targets without compare-and-swap (like `thumbv6m`), `init()` claims the buffer with a load
+/// and a store instead, so it must not race with an interrupt handler at all.  The entries are
+/// compressed once during constant evaluation with a simple LZ77 that suits tables with runs and
+/// repeated stretches, and `COMPRESSED_SIZE` is the size of the compressed entries in bytes.  The
+/// entries must be stored as primitive integers or floats (like with `#[codec(..)]` or
+/// `#[bitfields(..)]`), and the table must have the default layout.  Like with `#[indirect]`,
+/// options that read `TABLE` directly are rejected, as are lookups with a `match`.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[compressed]
+///     pub struct Steps(x @ 0..1024) -> u16 { (x / 128) as u16 }
+/// }
+///
+/// Steps::init();
+/// assert_eq!(3, Steps::get(400));
+/// assert!(Steps::COMPRESSED_SIZE < 2048 / 8);
+/// ```
+///
+/// ## Tables on the heap
+///
+/// Tables of tens of megabytes are too big to embed in the binary (and to evaluate while
+/// compiling).  With `#[heap]` and the `std` feature of this crate, the item form evaluates the
+/// body for every entry at runtime instead, into a table on the heap that is built once and then
+/// read by every lookup, with the same `get` and the functions built on it.  The table is built by
+/// the first lookup, or by `init()`, which builds it at startup so that the first lookup doesn't
+/// stall.  The body must be callable at runtime and can't use the parameters in constant
+/// expressions.  There is no `TABLE`, so options that read it, like `#[crc]` or `#[bytes]`, are
+/// rejected, and the table must have at least one parameter and the default layout.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[heap]
+///     pub struct Distance(x @ 0..1024, y @ 0..1024) -> u32 { (x * x + y * y) as u32 }
+/// }
+///
+/// Distance::init();
+/// assert_eq!(25, Distance::get(3, 4));
+/// ```
+///
+/// ## Lookup strategy
+///
+/// For tiny tables, reading from memory can be slower than not having a table at all.  With
+/// `#[strategy(match_up_to = n)]`, tables with at most `n` stored entries are looked up with a
+/// `match` on the parameters instead, with every entry as a constant arm, so that the compiler can
+/// pick between a jump table, arithmetic on the parameters or a branch-free select.  Only tables
+/// with the default layout are matched.  The associated constants and checks still see the table.
+///
+/// ```
+/// let lut = numeric_lut::lut!(#[strategy(match_up_to = 16)] |x @ 0..4| -> u32 { 1 << (x * 8) });
+/// assert_eq!(0x0001_0000, lut(2));
+/// ```
+///
+/// Conversely, with `#[strategy(compute_above = n)]`, no table is generated at all if it would have
+/// more than `n` stored entries, and the body is evaluated on every lookup instead, so it must be
+/// valid at runtime as well.  This trades speed for memory (and build time) for tables that turn
+/// out to be too large for the target.  Options that need the stored entries (`#[verify]`,
+/// `#[hash]`, `#[shader]` and exported tables) are rejected for computed tables.
+///
+/// `#[strategy(auto)]` picks between all three based on the size of the table, matching tables
+/// with up to 16 entries and computing tables with more than 1048576 entries.  Either threshold
+/// can be overridden, as in `#[strategy(auto, compute_above = 4096)]`.
+///
+/// ```
+/// let lut = numeric_lut::lut!(#[strategy(auto, compute_above = 4096)] |x @ 0..8192| -> u32 {
+///     (x as u32).count_ones()
+/// });
+/// assert_eq!(13, lut(8191));
+/// ```
+///
+/// ## Constant evaluation
+///
+/// Tables with expensive bodies can run into the limits that the compiler places on constant
+/// evaluation, which are reported deep inside of the generated code.  `#[const_eval(...)]` takes
+/// the following arguments to deal with that:
+///
+///   * `allow`, `warn` or `deny`: sets the level of the `long_running_const_eval` lint on the
+///     generated items.
+///   * `isolate`: evaluates every entry in a constant of its own, named after the parameter values
+///     (like `__lut_entry_x_3_y_10`).  The limits then apply to each entry separately, and errors
+///     during evaluation name the offending entry.  This is the default for tables with up to 4096
+///     entries and an explicit return type, so that a body that panics or overflows for some
+///     entries reports the parameter values for which it does (as in "evaluation of
+///     `__lut_entry_x_3_y_10` failed").  For larger tables it is opt-in, since every constant
+///     adds to the build time: isolating the entries of a table with cheap entries roughly
+///     doubles the time spent on it, at about a tenth of a millisecond per entry.
+///   * `isolate_up_to = n`: isolates the entries of tables with up to `n` entries instead of 4096,
+///     so `isolate_up_to = 0` turns off the default for builds where it is too slow.
+///
+/// A body that overflows for a single entry of a table like the following then fails the build
+/// with an error that names `__lut_entry_x_3`:
+///
+/// ```compile_fail
+/// let lut = numeric_lut::lut!(|x @ 0..4| -> u8 { 100 * x as u8 });
+/// ```
+///
+/// ```
+/// let lut = numeric_lut::lut!(#[const_eval(allow, isolate)] |x @ 0..4| -> u64 {
+///     let mut sum = 0;
+///     let mut i = 0;
+///     while i < 1000 * x as u64 {
+///         sum += i;
+///         i += 1;
+///     }
+///     sum
+/// });
+/// assert_eq!(1_999_000, lut(2));
+/// ```
+///
+/// The entries of `lut!` are only computed by the compiler after the macro has expanded, so
+/// incremental compilation is what reuses the evaluated tables of unchanged code, and moving
+/// expensive tables into a crate of their own keeps them from being evaluated again on every change
+/// to the rest of the project.  The presets (like `lut_sin!`) and `lut_from_file!` compute their
+/// entries while expanding instead, and can cache them across builds and crates in the directory
+/// named by the `NUMERIC_LUT_CACHE` environment variable, with a file per macro input (and contents
+/// of the data file) named after its `spec_hash`:
+///
+/// ```text
+/// NUMERIC_LUT_CACHE=$PWD/target/numeric-lut cargo build
+/// ```
+///
+/// ## Checks
+///
+/// The following options add checks that are performed during constant evaluation of the table,
+/// so that violating them fails the build:
+///
+///   * `#[verify = "path"]`: compares the table against a reference file, given relative to the
+///     crate root.  The file holds the stored entries in memory order (the same order in which they
+///     are laid out in the table), each encoded with `to_le_bytes`, so this only works for integer
+///     and floating point entries.  This protects against accidental changes to the body.
+///
+///     ```
+///     let lut = numeric_lut::lut!(#[verify = "tests/data/triple.bin"] |x @ 0..4| -> u16 {
+///         x as u16 * 3
+///     });
+///     assert_eq!(9, lut(3));
+///     ```
+///
+///     ```compile_fail
+///     let lut = numeric_lut::lut!(#[verify = "tests/data/triple.bin"] |x @ 0..4| -> u16 {
+///         x as u16 * 4
+///     });
+///     ```
+///
+///   * `#[check(shape(x), ..)]`: requires the entries to have a shape along the parameter `x`, for
+///     every value of the other parameters, which code that inverts or interpolates a table relies
+///     on.  The shapes are `monotone_increasing` and `monotone_decreasing` (allowing equal
+///     neighbors), `strictly_increasing`, `strictly_decreasing`, `convex` and `concave`, and
+///     numeric entries are required.  `NaN` entries fail every check.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[check(strictly_increasing(x), convex(x))]
+///         pub struct Response(x @ 0..8) -> f32 { (x * x) as f32 / 4.0 }
+///     }
+///
+///     assert_eq!(4.0, Response::get(4));
+///     ```
+///
+///     ```compile_fail
+///     let lut = numeric_lut::lut!(#[check(monotone_increasing(x))] |x @ 0..4| -> i32 {
+///         [0, 2, 1, 3][x]
+///     });
+///     ```
+///
+///   * `#[cover(ranges.., default = expr)]`: for decode tables over a single parameter, where not
+///     every value has an entry.  The body returns an `Option` of the element type given after
+///     `->`, and it must return `Some` for every value in the covered ranges (like `0x10..=0x1f`,
+///     `0x10..0x20` or just `0x10`).  Values outside of the ranges for which the body returns
+///     `None` get the `default` entry, which is required if there are any.  This catches opcodes
+///     that were forgotten while maintaining the body.
+///
+///     ```
+///     #[derive(Clone, Copy, Debug, PartialEq)]
+///     enum Op {
+///         Load(u8),
+///         Halt,
+///         Invalid,
+///     }
+///
+///     let decode = numeric_lut::lut!(
+///         #[cover(0x10..=0x13, 0xff, default = Op::Invalid)]
+///         |op @ 0..=255| -> Op {
+///             match op {
+///                 0x10..=0x13 => Some(Op::Load(op as u8 - 0x10)),
+///                 0xff => Some(Op::Halt),
+///                 _ => None,
+///             }
+///         }
+///     );
+///     assert_eq!(Op::Load(2), decode(0x12));
+///     assert_eq!(Op::Halt, decode(0xff));
+///     assert_eq!(Op::Invalid, decode(0x14));
+///     ```
+///
+///     ```compile_fail
+///     let decode = numeric_lut::lut!(
+///         #[cover(0x10..=0x13, default = 0)]
+///         |op @ 0..=255| -> u8 {
+///             match op {
+///                 0x10..=0x12 => Some(op as u8),
+///                 _ => None,
+///             }
+///         }
+///     );
+///     ```
+///
+/// ## Constants
+///
+/// The item form can additionally provide constants derived from the table contents:
+///
+///   * `#[hash]`: adds a `TABLE_HASH: u64` constant, the 64-bit FNV-1a hash of the stored entries
+///     encoded like for `#[verify]`.  The hash is stable across builds and platforms, so it can be
+///     persisted to detect when data was derived from a different version of the table.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[hash]
+///         pub struct Bytes(x @ 0..2) -> u8 { x as u8 + b'a' }
+///     }
+///
+///     assert_eq!(0x089c_4407_b545_986a, Bytes::TABLE_HASH);
+///     ```
+///
+///   * `#[crc]`: adds a `TABLE_CRC: u32` constant, the CRC-32 (as used by zlib and Ethernet) of the
+///     stored entries encoded like for `#[hash]`, and a `verify_integrity()` function that computes
+///     the checksum of the static table in memory again, with volatile reads that the compiler
+///     can't fold into a constant, and compares it with the constant.  Firmware can call it at
+///     boot to detect corrupted flash.  The table must be read by lookups, so it can't be combined
+///     with `#[soa]` or `#[split]`, or be looked up with a `match`, and it must be stored in a
+///     static, so it can't be combined with `#[const_table]`.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[crc]
+///         pub struct Digits(x @ 0..9) -> u8 { b'1' + x as u8 }
+///     }
+///
+///     assert_eq!(0xcbf4_3926, Digits::TABLE_CRC);
+///     assert!(Digits::verify_integrity());
+///     ```
+///
+///   * `#[inverse]`: for tables over a single parameter with integer or `char` entries, which
+///     encode values that must be decoded again.  Requires every entry to be unique, and otherwise
+///     fails the build with a message that lists the parameters whose entries collide.  Adds an
+///     `inverse(entry)` function that looks up the parameter of an entry, or returns `None` if it
+///     isn't one, so the decoder can't get out of sync with the encoder.  It reads an `INVERSE`
+///     table, which holds the parameter for every value from `INVERSE_MIN`, the smallest entry, to
+///     the largest one, so the entries must span at most 1048576 values.  The table is
+///     inverted as stored, so it can't be combined with options that store other entries, or be
+///     looked up with a `match`.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[inverse]
+///         pub struct Gray(x @ 0..32) -> u8 { (x ^ (x >> 1)) as u8 }
+///     }
+///
+///     assert_eq!(0b10101, Gray::get(0b11001));
+///     assert_eq!(Some(0b11001), Gray::inverse(0b10101));
+///     assert_eq!(None, Gray::inverse(32));
+///     ```
+///
+///     ```compile_fail
+///     numeric_lut::lut! {
+///         #[inverse]
+///         pub struct Square(x @ 0..32) -> i32 { (x as i32 - 16) * (x as i32 - 16) }
+///     }
+///     ```
+///
+///   * `#[aggregates]`: for numeric entries, adds `SUM`, `MIN`, `MAX` and `MEAN` constants
+///     reduced from the entries, so normalization factors and bounds elsewhere can't drift from
+///     the table.  `SUM` is an `f64` for floats and a 128-bit integer for integers, and `MEAN` is
+///     an `f64`.  `NaN` entries make `SUM` and `MEAN` `NaN`, and are skipped by `MIN` and `MAX`.
+///     The `ARGMIN` and `ARGMAX` constants hold the parameters where `MIN` and `MAX` first occur,
+///     in the order in which the last parameter changes fastest, like the peak of a response
+///     curve.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[aggregates]
+///         pub struct Weights(x @ 0..4) -> u8 { [10, 40, 30, 20][x] }
+///     }
+///
+///     assert_eq!(100, Weights::SUM);
+///     assert_eq!((10, 40), (Weights::MIN, Weights::MAX));
+///     assert_eq!(25.0, Weights::MEAN);
+///     assert_eq!(([0], [1]), (Weights::ARGMIN, Weights::ARGMAX));
+///     ```
+///
+///   * `#[shader(wgsl)]`, `#[shader(glsl)]` or both: adds `WGSL` and `GLSL` string constants with
+///     the stored entries declared as a constant array in that shading language, so that the exact
+///     same data can be included in shaders.  The array is one-dimensional, holds the entries in
+///     memory order and is named after the struct in upper snake case.  Floats are declared by
+///     reinterpreting their bits, so they are bit-for-bit identical.  Only `u32`, `i32` and `f32`
+///     entries are supported.
+///
+///     ```
+///     numeric_lut::lut! {
+///         #[shader(wgsl, glsl)]
+///         pub struct Steps(x @ 0..3) -> i32 { x as i32 - 1 }
+///     }
+///
+///     assert_eq!("const STEPS: array<i32, 3> = array<i32, 3>(-1i, 0i, 1i);\n", Steps::WGSL);
+///     assert_eq!("const int STEPS[3] = int[3](-1, 0, 1);\n", Steps::GLSL);
+///     ```
+///
+/// ## Redundant storage
+///
+/// For decision tables in safety-critical code, `#[redundant]` stores a second copy of the table
+/// in a static of its own, and `get` of the item form reads the entry from both statics with
+/// volatile reads, which the compiler can't fold into the entries known at compile time, and
+/// panics if they differ, which traps on targets that abort on panics.  With
+/// `#[redundant(inverted)]`, the copy holds the bitwise complement of every entry instead, so that
+/// a fault that sets or clears the same bits in both copies is detected as well; this requires
+/// integer or `bool` entries, and can't be combined with `#[banded(..)]`, whose default is not
+/// stored in either copy.  The table must be read by lookups, so it can't be combined with
+/// `#[soa]`, `#[split]` or `#[f16]`, or be looked up with a `match`, and it is never sharded.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[redundant(inverted)]
+///     pub struct Decision(speed @ 0..4, load @ 0..4) -> u8 { (speed * load > 4) as u8 }
+/// }
+///
+/// assert_eq!(1, Decision::get(3, 2));
+/// ```
+///
+/// ## Patchable entries
+///
+/// For calibration values that are trimmed per device at provisioning time, `#[patchable]` seeds
+/// a static of atomics with the entries computed at compile time, and `get` of the item form reads
+/// them from there.  It adds a `set` function that takes the parameters and the new entry, which
+/// later lookups from any thread return, and a `reset()` function that restores every entry to
+/// the computed one.  Entries are loaded and stored with relaxed ordering, so a lookup never sees
+/// a torn entry but may see the stores to different entries out of order.  This requires integer
+/// entries of up to 64 bits, or `bool`, `f32` or `f64` entries, on a target with atomics of their
+/// size.  Constants like `#[hash]` keep describing the computed entries, while options that read
+/// `TABLE` instead of looking entries up, like `#[bytes]` or `#[index]`, are rejected, as are
+/// `#[mask]` and lookups with a `match`.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[patchable]
+///     pub struct Offset(channel @ 0..4) -> i16 { channel as i16 * -2 }
+/// }
+///
+/// assert_eq!(-4, Offset::get(2));
+/// Offset::set(2, 17);
+/// assert_eq!(17, Offset::get(2));
+/// Offset::reset();
+/// assert_eq!(-4, Offset::get(2));
+/// ```
+///
+/// With `#[reload]` as well and the `std` feature of this crate, a `reload(path)` function
+/// replaces every entry with the one in a file, for tuning curves of a running program without
+/// recompiling it.  A file with the extension `csv` holds the entries like `write_csv` of
+/// `#[csv]` writes them, in any order, while any other file holds them as little-endian bytes in
+/// the order in which the last parameter changes fastest, with `bool` entries as `0` or `1`.  The
+/// whole file is validated before any entry is replaced, so a file with missing, duplicate or
+/// invalid entries fails with an `InvalidData` error that names the offending line, and leaves the
+/// entries as they were.
+///
+/// ```
+/// numeric_lut::lut! {
+///     #[patchable]
+///     #[reload]
+///     pub struct Damage(level @ 1..=3) -> u16 { level as u16 * 10 }
+/// }
+///
+/// let path = std::env::temp_dir().join("numeric-lut-damage.csv");
+/// std::fs::write(&path, "level,value\n3,45\n1,5\n2,20\n")?;
+/// Damage::reload(&path)?;
+/// assert_eq!(45, Damage::get(3));
+///
+/// std::fs::write(&path, "level,value\n1,5\n")?;
+/// assert!(Damage::reload(&path).is_err());
+/// assert_eq!(5, Damage::get(1));
+/// # std::io::Result::Ok(())
+/// ```
+///
+/// ## Metadata
+///
+///   * `#[sidecar]` or `#[sidecar = "name"]`: writes a JSON description of the table to
+///     `$OUT_DIR/numeric-lut/<name>.json`, so that external tooling can take inventory of all
+///     tables in a build.  The name defaults to the name of the struct in the item form, and must
+///     be unique within the crate: tables in different modules that would write different
+///     descriptions to the same file fail to build instead.  This requires the invoking crate to
+///     have a build script, since `OUT_DIR` isn't set otherwise.
+///
+///     The description contains the name, the `name`, `lo`wer bound and `len`gth of every
+///     dimension, the `element_type`, the number of stored `entries`, their `byte_size` (or `null`
+///     if the element type isn't a primitive type), the lookup `strategy` (`"table"`, `"match"` or
+///     `"compute"`) and a `spec_hash` of the macro input.  The entries are only known after
+///     constant evaluation; use `#[hash]` for a hash of the contents.
+///
+///   * `#[register(path::to::LUTS)]`: with the `linkme` feature, adds a description of the table to
+///     a registry declared with `lut_registry!`, so that the tables can be listed at runtime.
+///
+/// To audit all tables of a build at once, set the `NUMERIC_LUT_REPORT` environment variable to
+/// the path of a report file.  Every invocation then appends its description as a line of JSON to
+/// the file, with the name of the invoking `crate` added and a `null` name for closures without a
+/// sidecar name.  Cargo doesn't know that expansions depend on this variable, so run a clean build
+/// (e.g. after `cargo clean`) to get a complete report:
+///
+/// ```text
+/// NUMERIC_LUT_REPORT=$PWD/luts.jsonl cargo build
+/// ```
+///
+/// ## Warnings
+///
+/// The macro warns about likely mistakes, with a suggested rewrite:
+///
+///   * comparing a parameter with the excluded end of its range, like `x == 8` for `x @ 0..8`,
+///     which suggests that the range was meant to be inclusive.
+///   * a stored table whose body indexes an array of integer literals whose values all fit into a
+///     narrower integer type than the declared one.  Tables that need the wider type, for example
+///     to match an interface, can keep it without a warning with `#[wide_entries]`.  Tables that
+///     are looked up with a `match` or computed store nothing and never warn.
+///   * a table that stores more than 65536 entries, which catches typos in ranges like `0..=65536`
+///     early.  Intentionally large tables can raise the threshold with `#[warn_above(entries =
+///     n)]`.  Tables whose entries are computed on every lookup store nothing and never warn.
+///
+/// Stable Rust has no API for warnings from proc macros, so they are reported as uses of a
+/// deprecated item whose note holds the message.  The `nightly` feature of this crate reports them
+/// as proper warnings through the unstable diagnostics API of nightly Rust instead, which lint
+/// levels like `#![deny(deprecated)]` don't apply to.
+///
+#[cfg_attr(not(feature = "nightly"), doc = "```compile_fail")]
+#[cfg_attr(feature = "nightly", doc = "```ignore")]
+/// #![deny(deprecated)]
+/// let lut = numeric_lut::lut!(|x @ 0..20| -> u32 {
+///     [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4][x]
+/// });
+/// ```
+///
+/// ```
+/// #![deny(deprecated)]
+/// let lut = numeric_lut::lut!(#[wide_entries] |x @ 0..20| -> u32 {
+///     [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9, 3, 2, 3, 8, 4][x]
+/// });
+/// let small = numeric_lut::lut!(#[strategy(match_up_to = 4)] |x @ 0..4| -> u32 {
+///     [1, 2, 3, 4][x]
+/// });
+/// assert_eq!(lut(2) + small(3), 8);
+/// ```
+#[proc_macro]
+pub fn lut(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = proc_macro2::TokenStream::from(input);
+    match cfg::split(input.clone()) {
+        Ok(Some(split)) => split.expand(expand),
+        Ok(None) => expand(input),
+        Err(err) => err.to_compile_error(),
+    }
+    .into()
+}
+
+/// Expands a spec of `lut!` without `#[cfg(..)]` attributes on its parameters.
+fn expand(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let spec_hash = metadata::fnv1a(input.to_string().as_bytes());
+    let input = match syn::parse2::<Lut>(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let table = input.table();
+
+    if let Err(err) = input.validate_strategy(table.strategy) {
+        return err.to_compile_error();
+    }
+
+    let lut = match &input.form {
+        Form::Closure if input.stores_in_const() => quote::quote!(&__ENTRIES),
+        Form::Closure => quote::quote!(&__LUT),
+        Form::Struct { ident, .. } => quote::quote!(#ident::TABLE),
+    };
+    let associated = match &input.form {
+        Form::Closure => Ok(proc_macro2::TokenStream::new()),
+        Form::Struct { vis, ident, .. } => generate_associated(&input, vis, ident, &table),
+    };
+    let associated = match associated {
+        Ok(associated) => associated,
+        Err(err) => return err.to_compile_error(),
+    };
+    let checks = match generate_checks(&input, &table, lut) {
+        Ok(checks) => checks,
+        Err(err) => return err.to_compile_error(),
+    };
+    if let Some((span, name)) = &input.options.sidecar {
+        let call_site = proc_macro::Span::call_site();
+        let invocation = format!(
+            "{}:{}:{}",
+            call_site.file(),
+            call_site.line(),
+            call_site.column()
+        );
+        let metadata = input.metadata(&table, name, spec_hash);
+        let written = std::env::var_os("OUT_DIR")
+            .ok_or_else(|| {
+                "`OUT_DIR` is not set; sidecar files require the crate to have a build script"
+                    .to_owned()
+            })
+            .and_then(|out_dir| metadata.write_sidecar(out_dir.as_ref(), &invocation));
+        if let Err(message) = written {
+            return syn::Error::new(*span, message).to_compile_error();
+        }
+    }
+    let name = input
+        .options
+        .sidecar
+        .as_ref()
+        .and_then(|(_, name)| name.clone());
+    if let Err(message) = input.metadata(&table, &name, spec_hash).append_to_report() {
+        return syn::Error::new(proc_macro2::Span::call_site(), message).to_compile_error();
+    }
+
+    let warnings = warning::check(&input)
+        .into_iter()
+        .chain(warning::narrower_type(&input, &table))
+        .chain(warning::size(&input, &table))
+        .collect::<Vec<_>>();
+    let warnings = warnings
+        .iter()
+        .map(warning::Warning::emit)
+        .collect::<proc_macro2::TokenStream>();
+
+    let index = match &input.form {
+        Form::Closure => proc_macro2::TokenStream::new(),
+        Form::Struct { ident, .. } => generate_index(&input, ident, &table),
+    };
+    let dump = match &input.form {
+        Form::Closure => proc_macro2::TokenStream::new(),
+        Form::Struct { ident, .. } => generate_dump(&input, ident),
+    };
+    let registration = match &input.form {
+        Form::Closure => proc_macro2::TokenStream::new(),
+        Form::Struct { ident, .. } => registry::register(&input, ident, &table),
+    };
+    let doc = match &input.form {
+        Form::Closure => proc_macro2::TokenStream::new(),
+        Form::Struct { attrs, .. } => generate_doc(&input, attrs, &table),
+    };
+
+    let Table {
+        ty,
+        data,
+        access,
+        items,
+        strategy,
+        rank,
+        ..
+    } = table;
+
+    let lut_params = params(input.inputs.iter()).collect::<Vec<_>>();
+    let lint = input.options.const_eval.lint_attr();
+    let built = input
+        .options
+        .built
+        .as_ref()
+        .map(|name| build_time::include(&input, name))
+        .transpose();
+    let built = match built {
+        Ok(built) => built.unwrap_or_default(),
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let output = match &input.form {
+        Form::Closure if strategy != Strategy::Table && checks.is_empty() => {
+            let access = with_trace(&input, with_fallback(&input, access));
+            quote::quote!({
+                #built
+                |#(#lut_params),*| #access
+            })
+        }
+        // Inferred tables are never sharded, and the options that add checks, a lint level or the
+        // entries of a build script are rejected for them in `Lut::validate`.
+        Form::Closure if is_inferred(&input.return_type) => {
+            let access = with_trace(&input, with_fallback(&input, access));
+            quote::quote!({
+                #[allow(non_snake_case)]
+                let __LUT: &'static #ty = &const { #data };
+                move |#(#lut_params),*| #access
+            })
+        }
+        Form::Closure if input.stores_in_const() => {
+            let access = with_trace(&input, with_fallback(&input, access));
+            quote::quote!({
+                #built
+                #items
+                #lint
+                const __ENTRIES: #ty = #data;
+                #checks
+                let __LUT: &'static #ty = &__ENTRIES;
+                move |#(#lut_params),*| #access
+            })
+        }
+        Form::Closure => {
+            let access = with_trace(&input, with_fallback(&input, access));
+            quote::quote!({
+                #built
+                #items
+                #lint
+                static __LUT: #ty = #data;
+                #checks
+                |#(#lut_params),*| #access
+            })
+        }
+        Form::Struct { attrs, vis, ident } => {
+            let return_type = &input.return_type;
+            let transposed = generate_transposed(&input, vis);
+            let exported_table = input
+                .options
+                .export
+                .as_ref()
+                .and_then(|export| export.table.as_ref())
+                .map(|name| syn::Ident::new(&name.value(), name.span()));
+            let (table, mut export) = match exported_table {
+                Some(name) => (
+                    quote::quote!(&#name),
+                    quote::quote! {
+                        #[no_mangle]
+                        #lint
+                        pub static #name: #ty = #data;
+                    },
+                ),
+                None if input.options.const_table.is_some() => (
+                    quote::quote!(&Self::ENTRIES),
+                    proc_macro2::TokenStream::new(),
+                ),
+                // Tables of trait objects aren't `Sync`, so they can't be stored in a static.
+                // Options that store the entries in another representation, or look them up with
+                // a `match`, only read the full table during constant evaluation, so it is left
+                // out of the binary unless a function like `entries` reads it at runtime.
+                None if input.stores_in_const()
+                    || strategy == Strategy::Match
+                    || input.options.compressed.is_some()
+                    || input.options.indirect.is_some()
+                    || input.options.soa.is_some()
+                    || input.options.split.is_some() =>
+                {
+                    (
+                        quote::quote!({
+                            #items
+                            &#data
+                        }),
+                        proc_macro2::TokenStream::new(),
+                    )
+                }
+                // The entries are stored in a static, so that they have a single address in
+                // memory, and `TABLE` is a reference to it.
+                None => (
+                    quote::quote!({
+                        #items
+                        static __TABLE: #ty = #data;
+                        &__TABLE
+                    }),
+                    proc_macro2::TokenStream::new(),
+                ),
+            };
+            let entries = match input.options.const_table {
+                Some(_) if strategy == Strategy::Table => quote::quote! {
+                    /// The stored entries, as a value that constant evaluation and the optimizer
+                    /// can see through.
+                    #lint
+                    #vis const ENTRIES: #ty = {
+                        #items
+                        #data
+                    };
+                },
+                _ => proc_macro2::TokenStream::new(),
+            };
+            // Lookups of exported tables are inlined into the crates that use them, which read the
+            // entries from the one static of the exported table.
+            let inline = match (&input.options.const_table, &input.options.export) {
+                (Some(_), _) => quote::quote!(#[inline]),
+                (None, Some(Export { c: false, .. })) => quote::quote!(#[inline]),
+                (None, _) => proc_macro2::TokenStream::new(),
+            };
+            if let Some((_, js_name)) = &input.options.wasm_bindgen {
+                let js_name = js_name
+                    .as_ref()
+                    .map(syn::LitStr::value)
+                    .unwrap_or_else(|| lower_camel_case(&ident.to_string()));
+                let name = quote::format_ident!("{}_wasm", snake_case(&ident.to_string()));
+                let wasm_params = input.inputs.iter().map(|param| {
+                    let ident = &param.ident;
+                    quote::quote!(#ident: u32)
+                });
+                let idents = input.inputs.iter().map(|param| &param.ident);
+                export.extend(quote::quote! {
+                    /// Looks up the entry for the given parameters, exported to JavaScript.
+                    #[::wasm_bindgen::prelude::wasm_bindgen(js_name = #js_name)]
+                    pub fn #name(#(#wasm_params),*) -> #return_type {
+                        #ident::get(#(#idents as usize),*)
+                    }
+                });
+            }
+            let exported_lookup = input.options.export.as_ref().filter(|e| e.c);
+            if let Some(name) = exported_lookup.map(|e| e.name(ident)) {
+                let idents = input.inputs.iter().map(|param| &param.ident);
+                export.extend(quote::quote! {
+                    /// Looks up the entry for the given parameters, exported with the C ABI.
+                    ///
+                    /// Like `get`, this panics if a parameter is outside of its range, which
+                    /// aborts the process since the panic can't unwind into the caller.
+                    #[no_mangle]
+                    pub extern "C" fn #name(#(#lut_params),*) -> #return_type {
+                        #ident::get(#(#idents),*)
+                    }
+                });
+            }
+            let try_get = generate_try_get(&input, vis, ident);
+            let runtime = generate_runtime(&input, ident);
+            let (table, get) = match (strategy, &input.options.soa) {
+                (Strategy::Table, None) if input.options.split.is_some() => {
+                    let idents = input.inputs.iter().map(|param| &param.ident);
+                    let idents = quote::quote!(#(#idents),*);
+                    let join = split_join(
+                        &quote::quote!(Self::get_hi(#idents)),
+                        &quote::quote!(Self::get_lo(#idents)),
+                        &input.return_type,
+                    );
+                    (
+                        quote::quote! {
+                            #lint
+                            #[allow(dead_code)]
+                            const TABLE: &'static #ty = #table;
+                        },
+                        join,
+                    )
+                }
+                (Strategy::Table, Some((_, fields))) => {
+                    use syn::spanned::Spanned;
+                    let idents = input.inputs.iter().map(|param| &param.ident);
+                    let idents = quote::quote!(#(#idents),*);
+                    let fields = fields.iter().map(|field| {
+                        let name = field.ident.as_ref().expect("parsed as a named field");
+                        let get = quote::format_ident!("get_{}", name);
+                        quote::quote!(#name: Self::#get(#idents))
+                    });
+                    (
+                        quote::quote! {
+                            #lint
+                            #[allow(dead_code)]
+                            const TABLE: &'static #ty = #table;
+                        },
+                        quote::quote_spanned! {input.return_type.span()=>
+                            type __Entry = #return_type;
+                            __Entry { #(#fields),* }
+                        },
+                    )
+                }
+                (Strategy::Table, None) if input.options.patchable.is_some() => {
+                    let (atomic, _) = atomic_type(&input.return_type).expect("validated");
+                    let load = patched_load(&atomic, &quote::quote!(#access));
+                    (
+                        quote::quote!(#lint const TABLE: &'static #ty = #table;),
+                        quote::quote! {
+                            let __LUT = Self::__patched();
+                            #load
+                        },
+                    )
+                }
+                (Strategy::Table, None) if input.options.redundant.is_some() => (
+                    quote::quote!(#lint const TABLE: &'static #ty = #table;),
+                    generate_redundant(&input, ident, &ty, rank, &access),
+                ),
+                (Strategy::Table, None)
+                    if input.options.indirect.is_some() || input.options.compressed.is_some() =>
+                {
+                    (
+                        quote::quote! {
+                            #lint
+                            #[allow(dead_code)]
+                            const TABLE: &'static #ty = #table;
+                        },
+                        quote::quote! {
+                            let __LUT = Self::__table();
+                            #access
+                        },
+                    )
+                }
+                (Strategy::Table, None) => (
+                    quote::quote!(#lint const TABLE: &'static #ty = #table;),
+                    quote::quote! {
+                        let __LUT = Self::TABLE;
+                        #access
+                    },
+                ),
+                (Strategy::Match, _) => (
+                    quote::quote! {
+                        #lint
+                        #[allow(dead_code)]
+                        const TABLE: &'static #ty = #table;
+                    },
+                    access,
+                ),
+                (Strategy::Compute, _) => (proc_macro2::TokenStream::new(), access),
+            };
+            let get = with_trace(&input, with_fallback(&input, get));
+            let no_panic = no_panic(&input);
+            quote::quote! {
+                #(#attrs)*
+                #doc
+                #[derive(Clone, Copy)]
+                #vis struct #ident;
+
+                #built
+
+                impl #ident {
+                    #entries
+
+                    #table
+
+                    /// Looks up the entry for the given parameters.
+                    #inline
+                    #no_panic
+                    #vis fn get(#(#lut_params),*) -> #return_type {
+                        #get
+                    }
+
+                    #transposed
+
+                    #associated
+                }
+
+                #try_get
+
+                #runtime
+
+                #index
+
+                #dump
+
+                #registration
+
+                #export
+
+                #checks
+            }
+        }
+    };
+
+    match &input.form {
+        Form::Closure if !warnings.is_empty() => quote::quote!({ #warnings #output }),
+        _ => quote::quote!(#output #warnings),
+    }
+}
+
+/// Declares a table that is defined elsewhere, like in another crate with `#[export(..)]` of
+/// `lut!`, in an object file written in C or in a blob placed by the linker, and generates the
+/// lookups of the item form over it.
+///
+/// The declaration looks like the item form of `lut!` without a body, declared as an
+/// `unsafe struct`, and names the symbol of the table with `#[link_name = "..."]`.  The table must
+/// be stored as nested arrays of the element type, with one dimension per parameter in the declared
+/// order, like tables exported with `#[export(..)]` or `#[export_c(table = "...")]` (without
+/// `#[mask]` or `#[contiguous]`).  The macro generates a unit struct with the associated functions
+/// `table`, which returns a reference to the stored entries, `get`, which looks up an entry like in
+/// the item form of `lut!` (with `#[clamp]` and `#[wrap]` on the parameters), and `fix` if there is
+/// more than one parameter.
+///
+/// The symbol is declared in an `extern` block, so the declaration must match the definition: the
+/// linker only checks the name of the symbol, and a mismatched shape or element type is undefined
+/// behavior, which lookups with `get` can't detect.  The `unsafe` keyword is where the declaration
+/// promises that they match, so that `table` and `get` can be safe to call.
+///
+/// ```
+/// // In a crate of tables:
+/// numeric_lut::lut! {
+///     #[export(table = "GAMMA_TABLE")]
+///     pub struct Gamma(x @ 0..256) -> u8 { ((x * x) / 255) as u8 }
+/// }
+///
+/// // In a crate that doesn't depend on it:
+/// numeric_lut::lut_extern! {
+///     #[link_name = "GAMMA_TABLE"]
+///     pub unsafe struct ExternGamma(#[clamp] x @ 0..256) -> u8;
+/// }
+///
+/// assert_eq!(Gamma::get(128), ExternGamma::get(128));
+/// assert_eq!(255, ExternGamma::get(1000));
+/// ```
+///
+/// Without `unsafe`, the declaration fails to build:
+///
+/// ```compile_fail
+/// numeric_lut::lut_extern! {
+///     #[link_name = "GAMMA_TABLE"]
+///     pub struct ExternGamma(x @ 0..256) -> u8;
+/// }
+/// ```
+#[proc_macro]
+pub fn lut_extern(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    external::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a static map from string keys to values, like a table of keywords.
+///
+/// The entries are given as `"key" => value`, where the values are constant expressions of the
+/// value type given after `->`, which must be `Copy`.  The keys are placed with a minimal perfect
+/// hash that is found while expanding the macro, so a lookup hashes the key and compares it
+/// against a single stored key.  Duplicate keys are rejected.
+///
+/// In the closure form, `|key| -> T { ... }`, the macro evaluates to a closure taking a `&str` and
+/// returning an `Option<T>`:
+///
+/// ```
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Keyword {
+///     If,
+///     Else,
+///     While,
+/// }
+///
+/// let keyword = numeric_lut::lut_map!(|name| -> Keyword {
+///     "if" => Keyword::If,
+///     "else" => Keyword::Else,
+///     "while" => Keyword::While,
+/// });
+/// assert_eq!(Some(Keyword::Else), keyword("else"));
+/// assert_eq!(None, keyword("loop"));
+/// ```
+///
+/// In the item form, `struct Name -> T { ... }`, the macro generates a unit struct with an
+/// associated `get` function and a `KEYS` constant holding the keys in declaration order:
+///
+/// ```
+/// numeric_lut::lut_map! {
+///     pub struct Precedence -> u8 {
+///         "||" => 1,
+///         "&&" => 2,
+///         "==" => 3,
+///         "+" => 4,
+///         "*" => 5,
+///     }
+/// }
+///
+/// assert_eq!(Some(4), Precedence::get("+"));
+/// assert_eq!(None, Precedence::get("-"));
+/// assert_eq!(["||", "&&", "==", "+", "*"], Precedence::KEYS);
+/// ```
+#[proc_macro]
+pub fn lut_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    map::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a longest-prefix-match lookup over fixed-width unsigned integer keys, like a routing
+/// table or the memory map of an address space.
+///
+/// The entries are given as `start/len => value`, which matches all keys whose first `len` bits
+/// (starting at the most significant bit) are those of `start`, so the remaining bits of `start`
+/// must be zero.  Prefixes can be nested, and a lookup returns the value of the longest prefix that
+/// matches the key, or `None` if none does.  The prefix `0/0` matches every key, so it can be used
+/// as a default.  The value expressions are constant expressions of the type given after `->`,
+/// which must be `Copy`.
+///
+/// The prefixes are flattened into disjoint ranges while expanding the macro, so a lookup is a
+/// binary search over the starts of the ranges, independent of how deeply the prefixes are nested.
+///
+/// In the closure form, `|key: K| -> T { ... }`, the macro evaluates to a closure returning an
+/// `Option<T>`:
+///
+/// ```
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Region {
+///     Flash,
+///     Sram,
+///     Peripheral,
+///     Gpio,
+/// }
+///
+/// let region = numeric_lut::lut_prefix!(|addr: u32| -> Region {
+///     0x0000_0000/4 => Region::Flash,
+///     0x2000_0000/3 => Region::Sram,
+///     0x4000_0000/3 => Region::Peripheral,
+///     0x4800_0000/16 => Region::Gpio,
+/// });
+/// assert_eq!(Some(Region::Flash), region(0x0800_1234));
+/// assert_eq!(Some(Region::Sram), region(0x3fff_ffff));
+/// assert_eq!(Some(Region::Gpio), region(0x4800_0400));
+/// assert_eq!(Some(Region::Peripheral), region(0x4801_0000));
+/// assert_eq!(None, region(0x1000_0000));
+/// ```
+///
+/// In the item form, `struct Name(key: K) -> T { ... }`, the macro generates a unit struct with an
+/// associated `get` function:
+///
+/// ```
+/// numeric_lut::lut_prefix! {
+///     pub struct Route(addr: u32) -> u8 {
+///         0/0 => 0,
+///         0x0a00_0000/8 => 1,
+///         0x0a01_0000/16 => 2,
+///     }
+/// }
+///
+/// assert_eq!(Some(2), Route::get(0x0a01_0203));
+/// assert_eq!(Some(1), Route::get(0x0a02_0000));
+/// assert_eq!(Some(0), Route::get(0xc0a8_0001));
+/// ```
+#[proc_macro]
+pub fn lut_prefix(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    prefix::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Declares a registry of tables that can be listed at runtime, for example by a diagnostic
+/// command that takes inventory of all tables baked into a firmware image.
+///
+/// `lut_registry!(pub static LUTS)` declares `LUTS` as a slice of `LutInfo`s, a struct declared
+/// next to it, and `lut!` tables in the item form add a description of themselves to it with
+/// `#[register(path::to::LUTS)]`.  The description holds the `name` and `module_path` of the table,
+/// its `shape` (the number of values of every parameter), its `element_type` and `element_size`,
+/// the `byte_size` of the stored entries (or zero if they are computed on every lookup) and its
+/// `TABLE_HASH` if it has the `#[hash]` option.
+///
+/// The slice is gathered by the linker with `linkme`, so tables can be registered from any module
+/// or crate without keeping a central list.  This requires the `linkme` feature of this crate, and
+/// the invoking crate must depend on `linkme`.
+///
+/// ```ignore
+/// numeric_lut::lut_registry!(pub static LUTS);
+///
+/// numeric_lut::lut! {
+///     #[register(LUTS)]
+///     #[hash]
+///     pub struct Gamma(x @ 0..256) -> u8 { ... }
+/// }
+///
+/// for lut in LUTS.iter() {
+///     println!("{}: {:?} x {}, {} bytes", lut.name, lut.shape, lut.element_type, lut.byte_size);
+/// }
+/// ```
+#[proc_macro]
+pub fn lut_registry(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    registry::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates Criterion benchmarks that compare evaluating the body of a table on every call with
+/// looking it up, to find out whether a table pays off at all.
+///
+/// `lut_bench!(name, spec)` takes a name and the input of a `lut!` in the closure form (including
+/// its options), and expands to a function `fn name(c: &mut Criterion)` for `criterion_group!`.
+/// The benchmark group of the function is called `name` and measures `direct` evaluation and
+/// `table` lookups over the same 4096 parameters per iteration, once `sequential`ly in the order
+/// of the stored entries and once in a pseudo-`random` order.  The body must be valid at runtime
+/// as well, and the table must have at least one parameter.
+///
+/// This requires the `criterion` feature of this crate, and the invoking crate must depend on
+/// `criterion`:
+///
+/// ```ignore
+/// // benches/popcount.rs
+/// numeric_lut::lut_bench!(popcount, |x @ 0..256| -> u32 { (x as u32).count_ones() });
+///
+/// criterion::criterion_group!(benches, popcount);
+/// criterion::criterion_main!(benches);
+/// ```
+#[proc_macro]
+pub fn lut_bench(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    bench::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a libFuzzer target that compares the lookups of a table with evaluating its body, for
+/// continuous differential testing of table-backed code with `cargo fuzz`.
+///
+/// `lut_fuzz!(spec)` takes the input of a `lut!` in the closure form (including its options) and
+/// expands to a `fuzz_target!`.  The fuzzer input is split into 8 bytes per parameter (after a
+/// leading mode byte), which are moved into the ranges to check that every lookup returns what
+/// the body does.  If the lowest bit of the mode byte is set, values are instead passed as they
+/// are to parameters that don't panic when out of range, to check that `#[mask]`, `#[clamp]`,
+/// `#[wrap]` and `#[fallback(f)]` behave as declared.  Parameters that panic are always moved into
+/// their ranges, since libFuzzer aborts on panics.  Outside of the band of `#[banded(..)]`, lookups
+/// are expected to return its default instead of the body.
+///
+/// The body must be valid at runtime as well, the table must have at least one parameter, and the
+/// element type must implement `PartialEq` and `Debug`.  Options that make lookups differ from the
+/// body by design (`#[f16]`, `#[codec]`, `#[bitfields]`, `#[cover]`, `#[guard]`,
+/// `#[nalgebra(entries)]` and `#[built]`) are rejected.  This requires the `libfuzzer-sys` feature
+/// of this crate, and the invoking crate must depend on `libfuzzer-sys`:
+///
+/// ```ignore
+/// // fuzz/fuzz_targets/gamma.rs
+/// #![no_main]
+///
+/// numeric_lut::lut_fuzz!(|#[clamp] x @ 0..256| -> u8 { ((x * x) >> 8) as u8 });
+/// ```
+#[proc_macro]
+pub fn lut_fuzz(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    fuzz::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a module around a table, with the functions and constants that most code using the
+/// table would otherwise write by hand.
+///
+/// `lut_mod!` takes the input of a `lut!` in the item form with `mod name` instead of
+/// `struct Name`.  The table is declared as `Table` inside of the module, while the doc comments
+/// of the invocation document the module, and all other options apply to the table.  The module
+/// imports the items of its parent, so the body can refer to them, and contains:
+///
+///   * `TABLE`: the static holding the stored entries, unless they are looked up with a `match` or
+///     computed on every lookup.
+///   * `X_RANGE`, `Y_RANGE`, ...: the values of every parameter as a `RangeInclusive<usize>`, and
+///     `SHAPE` and `LEN`, the number of values of every parameter and of entries.
+///   * `get(x, y, ...)`: looks up the entry like `Table::get`, which panics if a parameter is
+///     outside of its range unless its options say otherwise.
+///   * `get_checked(x, y, ...)`: returns `None` if a parameter is outside of its range.
+///   * `get_clamped(x, y, ...)`: moves every parameter to the nearest value in its range.
+///   * `get_raw(index)`: looks up the entry at the given index in the order of `entries()`.
+///   * `entries()`: iterates over all parameters and their entries, in which the last parameter
+///     changes fastest.
+///   * A test that compares every entry with the value of the body at runtime, so the body must be
+///     valid at runtime as well and the element type must implement `PartialEq` and `Debug`.  It
+///     is left out for options that make entries differ from the body by design (`#[f16]`,
+///     `#[codec]`, `#[bitfields]`, `#[cover]`, `#[guard]` and `#[nalgebra(entries)]`), and
+///     compares entries outside of the band of `#[banded(..)]` with its default.
+///
+/// ```
+/// numeric_lut::lut_mod! {
+///     /// The product of two numbers.
+///     #[try_get]
+///     pub mod product(x @ 1..=4, y @ 0..3) -> u32 { (x * y) as u32 }
+/// }
+///
+/// assert_eq!(6, product::get(3, 2));
+/// assert_eq!(None, product::get_checked(5, 2));
+/// assert_eq!(8, product::get_clamped(9, 2));
+/// assert_eq!(2, product::get_raw(4));
+/// assert_eq!(1..=4, product::X_RANGE);
+/// assert_eq!([4, 3], product::SHAPE);
+/// assert_eq!(Some(([4, 2], 8)), product::entries().last());
+/// assert_eq!([0, 2, 4], product::TABLE[1]);
+/// assert!(product::Table::try_get(0, 0).is_err());
+/// ```
+#[proc_macro]
+pub fn lut_mod(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    module::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Evaluates a table in a build script, for bodies that can't be evaluated as constants, like ones
+/// that call `libm`, read data files or use iterators.
+///
+/// `lut_build!("name", |x @ 0..256| -> T { ... })` evaluates the body for every combination of
+/// parameters when the build script runs, and writes the entries to
+/// `$OUT_DIR/numeric-lut/name.rs`.  A `lut!` in the crate itself then declares the same parameters
+/// and element type with `#[built = "name"]` in place of the body, and looks up the entries in the
+/// included file.  All other options work as usual, except for `#[cover]`, `#[guard]` and
+/// `#[nalgebra(entries)]`, which change what the body returns.  If the ranges of the two
+/// invocations differ, compilation fails with an error that names the parameter.
+///
+/// The body can be arbitrary Rust, since it's evaluated at runtime, and the entries must have a
+/// primitive type.  The file holds the little-endian bytes of the entries in a single byte string
+/// literal, which is much faster to compile than a token per entry for large tables, and floats
+/// round-trip exactly.  This crate must be a build dependency of the invoking crate as well:
+///
+/// ```ignore
+/// // build.rs
+/// fn main() {
+///     numeric_lut::lut_build!("gamma", |x @ 0..256| -> f32 { libm::powf(x as f32 / 255.0, 2.2) });
+/// }
+///
+/// // src/lib.rs
+/// numeric_lut::lut! {
+///     #[built = "gamma"]
+///     pub struct Gamma(x @ 0..256) -> f32;
+/// }
+/// ```
+#[proc_macro]
+pub fn lut_build(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    build_time::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Builds a table from the entries in a JSON, TOML, CSV or NumPy file, for data like calibration
+/// curves that comes from measurements rather than formulas.
+///
+/// `lut_from_file!("path", spec)` takes the path of the file relative to the root of the invoking
+/// crate, and the input of a `lut!` in either form without the body.  The file is read while
+/// expanding the macro, and the table looks up its entries just like one with a body, so all
+/// options work as usual except for `#[cover]`, `#[guard]`, `#[nalgebra(entries)]` and `#[built]`.
+/// The crate is rebuilt when the file changes.
+///
+/// The entries are nested arrays with one level per parameter, holding one element per value of
+/// the parameter in ascending order.  In a JSON file, they are either the whole document or the
+/// `entries` key of an object, and in a TOML file, they are the top-level `entries` key, so that
+/// other keys can describe the data.  Entries must have a numeric type or `bool`, and are checked
+/// against the ranges and the type, with the file and line of the offending value in the error:
+///
+/// ```
+/// numeric_lut::lut_from_file! {
+///     "tests/data/calibration.json",
+///     pub struct Calibration(sensor @ 1..=3, step @ 0..4) -> f32
+/// }
+///
+/// assert_eq!(0.5, Calibration::get(1, 1));
+/// ```
+///
+/// ```text
+/// {
+///     "instrument": "thermocouple bench",
+///     "entries": [
+///         [0.0, 0.5, 1.25, -2.5e-1],
+///         [1, 2, 3, 4],
+///         [10.5, 11.5, 12.5, 13.5]
+///     ]
+/// }
+/// ```
+///
+/// JSON is supported completely.  Of TOML, comments, `key = value` pairs of numbers, booleans,
+/// basic strings and arrays, and `[table]` headers are supported, which covers what data files
+/// need.
+///
+/// A CSV file has a header row and one row per entry, in any order.  The header names a column
+/// after every parameter, which holds its value, and the entries are in the only other column (or
+/// the one named `value`, if there are more).  Every entry must have exactly one row, so missing
+/// and duplicated rows are errors as well.  Fields may be quoted with `"`:
+///
+/// ```text
+/// channel,band,value,note
+/// 0,1,0.5,
+/// 0,2,0.75,"warm, drifting"
+/// 1,2,1.5,
+/// 1,1,1.25,
+/// ```
+///
+/// A NumPy `.npy` file, as written by `numpy.save`, holds the entries in binary, so floats are
+/// embedded exactly.  Its element type must be the declared one (like `<f4` for `f32` or `|b1`
+/// for `bool`) in either byte order, and its shape must be the number of values of every
+/// parameter.  Arrays in C and Fortran order are supported.
+#[proc_macro]
+pub fn lut_from_file(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    data::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a lookup function whose entries combine the entries of two tables elementwise.
+///
+/// The macro accepts two tables in the closure form over the same parameter values, followed by a
+/// closure that combines an entry of each.  Both tables are only evaluated while computing the
+/// entries of the fused table, so neither of them gets a static of its own when only the combined
+/// entries are ever looked up.
+///
+/// ```
+/// let gain = numeric_lut::lut_zip!(
+///     |x @ 0..4| -> f32 { [1.0, 0.5, 0.25, 0.125][x] },
+///     |x @ 0..4| -> f32 { x as f32 + 1.0 },
+///     |attenuation, level| -> f32 { attenuation * level },
+/// );
+/// assert_eq!(0.75, gain(2));
+/// ```
+///
+/// The parameters may have different names in both tables, and the fused table has the names of
+/// the first one, which can be used by the closure as well.  Values outside of the ranges are
+/// treated like in the first table.  Without a return type, it is inferred from the closure.
+/// Options of the fused table go before the closure, since the tables themselves aren't stored:
+///
+/// ```
+/// let mixed = numeric_lut::lut_zip!(
+///     |x @ 0..4, y @ 0..2| -> u8 { (x * 2 + y) as u8 },
+///     |row @ 0..4, col @ 0..2| -> u8 { (row + col * 8) as u8 },
+///     #[mask]
+///     |a, b| a ^ b,
+/// );
+/// assert_eq!(12, mixed(3, 1));
+/// assert_eq!(mixed(3, 1), mixed(7, 1));
+/// ```
+#[proc_macro]
+pub fn lut_zip(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    zip::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a lookup function that looks up the entries of one table in another.
+///
+/// The macro accepts two tables in the closure form, where the entries of the first one are
+/// integers that are the values of the single parameter of the second one.  The fused table has
+/// the parameters of the first table and the entries of the second one, so chained lookups only
+/// read memory once.  Its options are those of the first table.
+///
+/// ```
+/// // Applies a gamma of 2 to 8-bit values, then quantizes them to 4 bits.
+/// let to_linear = numeric_lut::lut_compose!(
+///     |encoded @ 0..=255| -> u16 { (encoded * encoded / 255) as u16 },
+///     |linear @ 0..256| -> u8 { (linear >> 4) as u8 },
+/// );
+/// assert_eq!(0, to_linear(0));
+/// assert_eq!(4, to_linear(128));
+/// assert_eq!(15, to_linear(255));
+/// ```
+///
+/// An entry of the first table that isn't a value of the parameter of the second one fails the
+/// build, unless the parameter is `#[clamp]` or `#[wrap]`, which then applies to the entry:
+///
+/// ```
+/// let saturated = numeric_lut::lut_compose!(
+///     |x @ 0..8| -> i32 { x as i32 * 3 - 6 },
+///     |#[clamp] level @ 0..=9| -> char { (b'0' + level as u8) as char },
+/// );
+/// assert_eq!('0', saturated(0));
+/// assert_eq!('3', saturated(3));
+/// assert_eq!('9', saturated(7));
+/// ```
+#[proc_macro]
+pub fn lut_compose(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    compose::generate(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a sine lookup over a phase of `2^bits` steps per period.
+///
+/// The lookup takes the phase as a `usize` and wraps it around, so that `lut(1 << bits)` is
+/// `lut(0)`.  Only a quarter of the period is stored, and the other quarters are reconstructed by
+/// symmetry.  The sine itself is computed in `f64` while expanding the macro, scaled by the
+/// amplitude and, for integer element types, rounded to the nearest value.
+///
+/// The arguments are:
+///
+///   * `bits = n`: the phase resolution, between 3 and 20 bits.
+///   * `amplitude = expr`: the peak value, defaulting to `1.0` for floating point and the maximum
+///     value for integer element types.
+///   * `ty = T`, or just `T`: the element type, which must be a signed integer, `f32` or `f64`.
+///     It is inferred from amplitudes like `i16::MAX` or `1000i32`, and defaults to `f32`.
+///
+/// ```
+/// let sin = numeric_lut::lut_sin!(bits = 10, amplitude = i16::MAX);
+/// assert_eq!(0, sin(0));
+/// assert_eq!(23170, sin(128));
+/// assert_eq!(i16::MAX, sin(256));
+/// assert_eq!(-i16::MAX, sin(768));
+/// assert_eq!(sin(3), sin(1024 + 3));
+/// ```
+///
+/// ```compile_fail
+/// let sin = numeric_lut::lut_sin!(bits = 8, ty = u8);
+/// ```
+#[proc_macro]
+pub fn lut_sin(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_sin", input.into(), &[], |input| {
+        preset::trig::generate(preset::trig::Wave::Sin, input)
+    })
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Generates a cosine lookup over a phase of `2^bits` steps per period.
+///
+/// This is like [`lut_sin!`] with the phase shifted by a quarter period, and takes the same
+/// arguments.
+///
+/// ```
+/// let cos = numeric_lut::lut_cos!(bits = 8, ty = f64);
+/// assert_eq!(1.0, cos(0));
+/// assert_eq!(-1.0, cos(128));
+/// assert!((cos(32) - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-15);
+/// ```
+#[proc_macro]
+pub fn lut_cos(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_cos", input.into(), &[], |input| {
+        preset::trig::generate(preset::trig::Wave::Cos, input)
+    })
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Generates a lookup for arithmetic in the finite field GF(2^8).
+///
+/// The first argument selects the table:
+///
+///   * `mul`: the product of two elements, as `|a: u8, b: u8| -> u8` over a table of 65536
+///     entries.
+///   * `exp`: the powers of a generator of the multiplicative group (the antilogarithm), as
+///     `|i: u8| -> u8`.  The powers repeat with a period of 255, so `exp(255) == exp(0) == 1`.
+///   * `log`: the discrete logarithm to the base of the generator, the inverse of `exp`, as
+///     `|a: u8| -> u8`.  The logarithm of zero is undefined and looked up as zero.
+///
+/// The following arguments can come after it:
+///
+///   * `poly = p`: the irreducible reduction polynomial of degree 8, as a 9-bit integer with the
+///     coefficient of `x^i` in bit `i`.  It defaults to `0x11b` (`x^8 + x^4 + x^3 + x + 1`, as used
+///     by AES); Reed-Solomon codes and RAID 6 commonly use `0x11d`.
+///   * `generator = g`: the generator used by `exp` and `log`, defaulting to the smallest one.
+///
+/// ```
+/// let mul = numeric_lut::lut_gf256!(mul);
+/// let exp = numeric_lut::lut_gf256!(exp, poly = 0x11d, generator = 2);
+/// let log = numeric_lut::lut_gf256!(log, poly = 0x11d, generator = 2);
+/// assert_eq!(0xc1, mul(0x57, 0x83));
+/// assert_eq!(0x1d, exp(8));
+/// assert_eq!(8, log(0x1d));
+/// ```
+#[proc_macro]
+pub fn lut_gf256(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_gf256", input.into(), &[], preset::gf256::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a lookup that converts color components between sRGB and linear light.
+///
+/// The first argument selects the conversion, using the piecewise transfer function of IEC
+/// 61966-2-1 evaluated in `f64` while expanding the macro:
+///
+///   * `to_linear`: decodes an sRGB-encoded component to linear light.
+///   * `to_srgb`: encodes a linear light component as sRGB.
+///
+/// The following arguments can come after it:
+///
+///   * `input = T`: the type of the quantized component that is converted, either `u8` (the
+///     default, for a table of 256 entries) or `u16` (for a table of 65536 entries).
+///   * `output = T`: the type of the converted component, either `f32` (the default) for values in
+///     `0.0..=1.0` or `u8` or `u16` for values scaled to the full range of the type and rounded to
+///     the nearest integer.
+///
+/// ```
+/// let to_linear = numeric_lut::lut_srgb!(to_linear);
+/// let to_srgb = numeric_lut::lut_srgb!(to_srgb, input = u16, output = u8);
+/// assert_eq!(1.0, to_linear(255));
+/// assert!((to_linear(128) - 0.2158605).abs() < 1e-6);
+/// assert_eq!(128, to_srgb(14146));
+/// ```
+#[proc_macro]
+pub fn lut_srgb(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_srgb", input.into(), &[], preset::srgb::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a lookup of a window function, as used for spectral analysis.
+///
+/// The first argument selects the window, which is evaluated in `f64` while expanding the macro:
+///
+///   * `hann`, `hamming`, `blackman` or `blackman_harris` (the four-term variant).
+///   * `kaiser`, which requires a `beta = x` argument for the shape of the window.
+///
+/// The following arguments can come after it:
+///
+///   * `len = n`: the number of samples, which are looked up with indices in `0..n`.
+///   * `periodic = true`: generates the periodic variant of the window (as used for FFTs), which
+///     leaves out the last sample of the symmetric variant of length `n + 1`.  The default is the
+///     symmetric variant (as used for filter design).
+///   * `f32` (the default) or `f64`: the element type.
+///
+/// ```
+/// let hann = numeric_lut::lut_window!(hann, len = 1024, f32);
+/// let kaiser = numeric_lut::lut_window!(kaiser, beta = 8.6, len = 8, periodic = true, f64);
+/// assert_eq!(0.0, hann(0));
+/// assert_eq!(0.0, hann(1023));
+/// assert_eq!(1.0, kaiser(4));
+/// assert_eq!(kaiser(3), kaiser(5));
+/// ```
+#[proc_macro]
+pub fn lut_window(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_window", input.into(), &[], preset::window::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates an easing curve for animations, tabulated at evenly spaced points.
+///
+/// The first argument selects the curve, as defined on <https://easings.net>: `ease_in_cubic`,
+/// `ease_out_cubic`, `ease_in_out_cubic`, `ease_in_elastic`, `ease_out_elastic`, `ease_in_bounce`,
+/// `ease_out_bounce` or `ease_in_out_bounce`.  The following arguments can come after it:
+///
+///   * `len = n`: the number of points, which are placed at `t = i / (n - 1)`.
+///   * `f32` (the default) or `f64`: the element type.
+///   * `kernel = linear` (the default), `kernel = hermite` or `kernel = catmull_rom`: how `sample`
+///     interpolates between the points.  `hermite` interpolates with cubic Hermite splines through
+///     the slopes of the curve, which are tabulated alongside the points, and `catmull_rom` with
+///     Catmull-Rom splines, which estimate the slopes from the neighboring points instead.  Both
+///     follow the curve much more closely than linear interpolation between few points.
+///
+/// The macro evaluates to a value with two methods: `sample(t)`, which samples the curve at `t`
+/// (clamped to `0.0..=1.0`) by interpolating between the two closest points, and `get(i)`, which
+/// looks up the point with index `i`.
+///
+/// ```
+/// let ease = numeric_lut::lut_easing!(ease_in_out_cubic, len = 65);
+/// assert_eq!(0.0, ease.sample(-1.0));
+/// assert_eq!(0.5, ease.sample(0.5));
+/// assert_eq!(1.0, ease.get(64));
+/// assert!((ease.sample(0.25) - 0.0625).abs() < 1e-3);
+///
+/// let smooth = numeric_lut::lut_easing!(ease_in_cubic, len = 5, f64, kernel = hermite);
+/// assert!((smooth.sample(0.1) - 0.001).abs() < 1e-12);
+/// ```
+#[proc_macro]
+pub fn lut_easing(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_easing", input.into(), &[], preset::easing::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a lookup that converts between decibels and linear amplitude gains, for audio.
+///
+/// The first argument selects the conversion, which is evaluated in `f64` while expanding the
+/// macro:
+///
+///   * `to_gain`: converts decibels to the gain `10^(dB / 20)`, as `|db: f32| -> T` (or `f64` for
+///     `f64` entries).  The table holds the gains at `min`, `min + step`, ..., `max`, and the
+///     decibels are rounded to the nearest of them, so decibels outside of the range are clamped
+///     to it.  It takes the arguments `min = x` (required), `max = x` (defaulting to `0.0`) and
+///     `step = x` (defaulting to `1.0`), and `max - min` must be a multiple of `step`.
+///   * `to_db`: converts quantized levels to decibels, as `|level: usize| -> T` for levels in
+///     `0..2^bits`, where the level `2^bits - 1` is at full scale.  It takes the arguments
+///     `bits = n` (required, at most 16), `max = x`, the decibels at full scale (defaulting to
+///     `0.0`), and `min = x`, a floor for quiet levels and level zero (defaulting to the decibels
+///     of level one).
+///
+/// Both take the element type as `ty = T`, or just `T`: `f32` (the default), `f64`, or an integer
+/// type for fixed-point entries, which requires `frac_bits = n`, the number of fractional bits.
+/// Fixed-point entries are rounded to the nearest value, and must fit into the type.
+///
+/// ```
+/// let gain = numeric_lut::lut_decibel!(to_gain, min = -60.0, max = 12.0, step = 0.5);
+/// assert_eq!(1.0, gain(0.0));
+/// assert!((gain(-6.0) - 0.501_187).abs() < 1e-6);
+/// assert_eq!(gain(-60.0), gain(-100.0));
+///
+/// let q14 = numeric_lut::lut_decibel!(to_gain, min = -48.0, max = 6.0, i16, frac_bits = 14);
+/// assert_eq!(16384, q14(0.0));
+///
+/// let db = numeric_lut::lut_decibel!(to_db, bits = 8, min = -40.0);
+/// assert_eq!(0.0, db(255));
+/// assert!((db(128) + 5.987).abs() < 1e-3);
+/// assert_eq!(-40.0, db(0));
+/// ```
+#[proc_macro]
+pub fn lut_decibel(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_decibel", input.into(), &[], preset::decibel::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates a threshold matrix for ordered dithering.
+///
+/// The first argument selects the matrix:
+///
+///   * `bayer`: the Bayer matrix, with the classic cross-hatched patterns.
+///   * `blue_noise`: a matrix with a blue noise spectrum generated with the void-and-cluster
+///     method, which has less visible structure.  An optional `seed = n` argument selects a
+///     different pseudo-random initial pattern.
+///
+/// `size = n` sets the width and height of the matrix, which must be a power of two (up to 256
+/// for Bayer and 64 for blue noise matrices).  The lookup takes `(x, y)` pixel coordinates and
+/// wraps them around, so that the matrix tiles the image.  Every rank in `0..n * n` occurs exactly
+/// once, so a pixel with intensity `v` in `0..=n * n` is set if `v > lut(x, y)`.  The element type
+/// defaults to `u8` for matrices with up to 256 entries and `u16` otherwise, and can be set with
+/// `ty = T` (or just `T`), which must hold every rank.
+///
+/// ```
+/// let bayer = numeric_lut::lut_dither!(bayer, size = 4);
+/// assert_eq!([0, 8, 2, 10], [0, 1, 2, 3].map(|x| bayer(x, 0)));
+/// assert_eq!(bayer(1, 3), bayer(5, 7));
+///
+/// let noise = numeric_lut::lut_dither!(blue_noise, size = 16, seed = 7);
+/// assert_eq!(noise(3, 4), noise(19, 20));
+/// ```
+#[proc_macro]
+pub fn lut_dither(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_dither", input.into(), &[], preset::dither::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates an S-box (substitution box) for block ciphers, as `|x: u8| -> u8`.
+///
+/// The S-box maps every byte to the affine transform of its multiplicative inverse in GF(2^8), and
+/// is generated while expanding the macro, so that no opaque byte arrays need to be embedded.  The
+/// first argument selects the S-box:
+///
+///   * `aes`: the S-box of AES (Rijndael).
+///   * `affine`: a generic S-box, with the affine transform `rotl(b, k1) ^ rotl(b, k2) ^ ... ^ c`
+///     given by `rotations = r` (a byte with bit `k` set for every rotation by `k` bits) and
+///     `constant = c` (defaulting to 0).  The reduction polynomial of the field can be set with
+///     `poly = p`, as for [`lut_gf256!`].
+///
+/// The following arguments can come after it:
+///
+///   * `inverse = true`: generates the inverse S-box instead, as used for decryption.
+///   * `constant_time = false`: looks up the entry directly.  By default, every lookup reads the
+///     entire table and masks out all other entries, so that the timing and cache footprint of the
+///     lookup don't leak the input, at the cost of being much slower.
+///
+/// ```
+/// let sbox = numeric_lut::lut_sbox!(aes);
+/// let inverse = numeric_lut::lut_sbox!(aes, inverse = true, constant_time = false);
+/// assert_eq!(0x63, sbox(0x00));
+/// assert_eq!(0xed, sbox(0x53));
+/// assert_eq!(0x53, inverse(0xed));
+/// ```
+#[proc_macro]
+pub fn lut_sbox(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_sbox", input.into(), &[], preset::sbox::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates decode tables for a canonical prefix code, like the Huffman codes of DEFLATE.
+///
+/// The code is given by `lengths = [..]`, the length of the code of every symbol (or 0 for
+/// symbols that don't occur), from which the canonical codes are assigned as in RFC 1951.  The
+/// symbols are the indices into `lengths` unless they are given with `symbols = [..]`.  Codes can
+/// be up to 16 bits long.
+///
+/// Codes of up to `fast_bits = n` bits (9 by default) are decoded with a single lookup in a table
+/// of `2^n` entries, and longer codes with one more lookup in an overflow table.  The macro
+/// evaluates to a value with a `decode(bits)` method, which takes the next bits of the stream
+/// (packed starting with the least significant bit, and with codes starting with their most
+/// significant bit, as in DEFLATE) and returns the decoded symbol and the number of bits of its
+/// code, or `None` if the bits don't start with any code.  `bits` must hold at least as many bits
+/// as the longest code, or be padded with zeroes.
+///
+/// ```
+/// // The code `0` for `b'a'`, `10` for `b'b'` and `11` for `b'c'`.
+/// let code =
+///     numeric_lut::lut_huffman!(lengths = [1, 2, 2], symbols = [97, 98, 99], fast_bits = 2);
+/// assert_eq!(Some((97, 1)), code.decode(0b10));
+/// assert_eq!(Some((98, 2)), code.decode(0b01));
+/// assert_eq!(Some((99, 2)), code.decode(0b11));
+/// ```
+///
+/// Over-subscribed codes are rejected.
+#[proc_macro]
+pub fn lut_huffman(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    cache::cached("lut_huffman", input.into(), &[], preset::huffman::generate)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Generates the associated items of the item form that depend on the table.
+fn generate_associated(
+    input: &Lut,
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    table: &Table,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut associated = generate_fix(input, vis, table);
+    associated.extend(generate_call(input, vis));
+    associated.extend(generate_get_or(input, vis));
+    associated.extend(generate_transform(input, vis));
+    associated.extend(generate_interpolate(input, vis));
+    associated.extend(generate_sample(input, vis));
+    associated.extend(generate_csv(input, vis));
+    associated.extend(generate_hash(input, vis, table));
+    associated.extend(generate_crc(input, vis, table));
+    associated.extend(generate_patchable(input, vis, ident, table));
+    associated.extend(generate_reload(input, vis, table));
+    associated.extend(generate_indirect(input, vis, ident, table));
+    associated.extend(generate_heap(input, vis));
+    associated.extend(generate_compressed(input, vis, ident, table));
+    associated.extend(generate_inverse(input, vis, ident, table));
+    associated.extend(generate_invert(input, vis));
+    associated.extend(generate_find_nearest(input, vis));
+    associated.extend(generate_aggregates(input, vis, table));
+    associated.extend(generate_bytes(input, vis, table));
+    associated.extend(generate_ndarray(input, vis, table));
+    associated.extend(generate_nalgebra_view(input, vis, table));
+    associated.extend(generate_heatmap(input, vis, table));
+    associated.extend(generate_soa(input, vis, table));
+    associated.extend(generate_bitfields(input, vis));
+    associated.extend(generate_split(input, vis, table));
+
+    let name = snake_case(&ident.to_string());
+    let entry_slices = table.entry_slices(&quote::quote!(#ident::TABLE));
+    for &language in &input.options.shaders {
+        associated.extend(shader::generate(
+            language,
+            vis,
+            &name,
+            &input.return_type,
+            table.len,
+            &entry_slices,
+        )?);
+    }
+
+    Ok(associated)
+}
+
+/// Generates an item that checks the contents of the table during constant evaluation.
+///
+/// The `lut` expression must evaluate to a reference to the table.
+fn generate_checks(
+    input: &Lut,
+    table: &Table,
+    lut: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let return_type = &input.return_type;
+    let mut checks = proc_macro2::TokenStream::new();
+
+    if let Some(path) = &input.options.verify {
+        let full_path = manifest_path(path)?;
+        let message = format!("table contents don't match `{}`", path.value());
+        checks.extend(quote::quote! {
+            let __expected: &[u8] = include_bytes!(#full_path);
+            let __size = ::core::mem::size_of::<#return_type>();
+            let mut __offset = 0;
+            let mut __s = 0;
+            while __s < __shards.len() {
+                let __entries = __shards[__s];
+                let mut __i = 0;
+                while __i < __entries.len() {
+                    let __bytes = __entries[__i].to_le_bytes();
+                    let mut __j = 0;
+                    while __j < __size {
+                        assert!(__offset < __expected.len(), #message);
+                        assert!(__bytes[__j] == __expected[__offset], #message);
+                        __offset += 1;
+                        __j += 1;
+                    }
+                    __i += 1;
+                }
+                __s += 1;
+            }
+            assert!(__offset == __expected.len(), #message);
+        });
+    }
+
+    if input.options.split.is_some() && table.strategy == Strategy::Table {
+        // Make sure that the constant evaluator derives the halves from the entries correctly,
+        // since not all toolchains have supported 128-bit integers equally well.
+        let (hi, lo) = split_halves(&quote::quote!(__entry), return_type);
+        let join = split_join(&hi, &lo, return_type);
+        checks.extend(quote::quote! {
+            let mut __s = 0;
+            while __s < __shards.len() {
+                let __entries = __shards[__s];
+                let mut __i = 0;
+                while __i < __entries.len() {
+                    let __entry = __entries[__i];
+                    assert!(
+                        #join == __entry,
+                        "splitting a 128-bit entry into two halves changed its value"
+                    );
+                    __i += 1;
+                }
+                __s += 1;
+            }
+        });
+    }
+
+    if !input.options.shape_checks.is_empty() {
+        checks.extend(generate_shape_checks(input));
+    }
+
+    if checks.is_empty() {
+        return Ok(checks);
+    }
+
+    let entry_slices = table.entry_slices(&lut);
+    Ok(quote::quote! {
+        const _: () = {
+            let __shards: &[&[#return_type]] = &[#(#entry_slices),*];
+            #checks
+        };
+    })
+}
+
+/// Generates the checks of `#[check(..)]` over the stored entries in `__shards`.
+fn generate_shape_checks(input: &Lut) -> proc_macro2::TokenStream {
+    let return_type = &input.return_type;
+    let len = input.inputs.iter().map(Param::len).product::<usize>();
+    let (strides, indices, offset) = logical_offsets(input);
+    // Casting to the same type trips clippy in the invoking crate.
+    let widen =
+        |entry: proc_macro2::TokenStream| match preset::primitive_name(return_type).as_deref() {
+            Some("f64") => entry,
+            Some("f32") => quote::quote!((#entry as f64)),
+            _ => quote::quote!((#entry as i128)),
+        };
+
+    let mut checks = proc_macro2::TokenStream::new();
+    for check in &input.options.shape_checks {
+        let (k, param) = input
+            .inputs
+            .iter()
+            .enumerate()
+            .find(|(_, param)| param.ident == check.param)
+            .unwrap();
+        if param.len() == 1 {
+            continue;
+        }
+        let index = &indices[k];
+        let stride = strides[k];
+        let last = param.len() - 1;
+        let message = format!(
+            "the entries aren't {} in `{}`",
+            check.shape.name().replace('_', " "),
+            check.param
+        );
+        // Failing checks point at the check itself.
+        let assert = |check: proc_macro2::TokenStream, span: proc_macro2::Span| quote::quote_spanned!(span=> ::core::assert!(#check, #message););
+        let span = check.span;
+        let check = match check.shape {
+            Shape::MonotoneIncreasing => {
+                quote::quote!(__at(__shards, __offset - #stride) <= __at(__shards, __offset))
+            }
+            Shape::MonotoneDecreasing => {
+                quote::quote!(__at(__shards, __offset - #stride) >= __at(__shards, __offset))
+            }
+            Shape::StrictlyIncreasing => {
+                quote::quote!(__at(__shards, __offset - #stride) < __at(__shards, __offset))
+            }
+            Shape::StrictlyDecreasing => {
+                quote::quote!(__at(__shards, __offset - #stride) > __at(__shards, __offset))
+            }
+            Shape::Convex | Shape::Concave => {
+                // The slopes on both sides of the entry, widened so that they can't overflow.
+                let previous = widen(quote::quote!(__at(__shards, __offset - #stride)));
+                let current = widen(quote::quote!(__at(__shards, __offset)));
+                let next = widen(quote::quote!(__at(__shards, __offset + #stride)));
+                let slopes = quote::quote! {
+                    let __before = #current - #previous;
+                    let __after = #next - #current;
+                };
+                let check = match check.shape {
+                    Shape::Convex => assert(quote::quote!(__before <= __after), span),
+                    _ => assert(quote::quote!(__before >= __after), span),
+                };
+                checks.extend(quote::quote! {
+                    let mut __flat = 0;
+                    while __flat < #len {
+                        let __index = #index;
+                        if __index > 0 && __index < #last {
+                            let __offset = #offset;
+                            #slopes
+                            #check
+                        }
+                        __flat += 1;
+                    }
+                });
+                continue;
+            }
+        };
+        let check = assert(check, span);
+        checks.extend(quote::quote! {
+            let mut __flat = 0;
+            while __flat < #len {
+                if #index > 0 {
+                    let __offset = #offset;
+                    #check
+                }
+                __flat += 1;
+            }
+        });
+    }
+    let at = generate_at(return_type);
+    quote::quote! {
+        #at
+        #checks
+    }
+}
+
+/// The stride of every parameter among the stored entries, which are padded with `#[mask]`, along
+/// with the index of every parameter in the logical entry `__flat` and its offset among the stored
+/// entries.
+fn logical_offsets(
+    input: &Lut,
+) -> (
+    Vec<usize>,
+    Vec<proc_macro2::TokenStream>,
+    proc_macro2::TokenStream,
+) {
+    let mut strides = Vec::new();
+    let (mut logical, mut stored) = (1, 1);
+    for param in input.inputs.iter().rev() {
+        strides.push((logical, stored));
+        logical *= param.len();
+        stored *= param.storage_len(&input.options);
+    }
+    strides.reverse();
+    let indices = input
+        .inputs
+        .iter()
+        .zip(&strides)
+        .map(|(param, &(logical, _))| {
+            let index = match logical {
+                1 => quote::quote!(__flat),
+                logical => quote::quote!(__flat / #logical),
+            };
+            match param.len() {
+                1 => quote::quote!(0),
+                len => quote::quote!(#index % #len),
+            }
+        })
+        .collect::<Vec<_>>();
+    let offset = input
+        .inputs
+        .iter()
+        .zip(&strides)
+        .zip(&indices)
+        .filter(|((param, _), _)| param.len() > 1)
+        .map(|((_, &(_, stored)), index)| match stored {
+            1 => quote::quote!(#index),
+            stored => quote::quote!((#index) * #stored),
+        })
+        .collect::<Vec<_>>();
+    let offset = match offset.is_empty() {
+        true => quote::quote!(0),
+        false => quote::quote!(#(#offset)+*),
+    };
+    let strides = strides.into_iter().map(|(_, stored)| stored).collect();
+    (strides, indices, offset)
+}
+
+/// Generates `__at`, which looks up a stored entry by its offset across all shards during
+/// constant evaluation.
+fn generate_at(return_type: &syn::Type) -> proc_macro2::TokenStream {
+    quote::quote! {
+        const fn __at(__shards: &[&[#return_type]], mut __offset: usize) -> #return_type {
+            let mut __s = 0;
+            while __offset >= __shards[__s].len() {
+                __offset -= __shards[__s].len();
+                __s += 1;
+            }
+            __shards[__s][__offset]
+        }
+    }
+}
+
+/// Generates the `as_bytes` function of the item form, if requested.
+fn generate_bytes(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.bytes.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let element_type = input.element_type();
+    let entries = table
+        .entry_slices(&quote::quote!(Self::TABLE))
+        .pop()
+        .expect("tables with `#[bytes]` are never sharded");
+    quote::quote! {
+        /// The stored entries in memory order, as bytes in the native byte order.
+        #vis fn as_bytes() -> &'static [u8] {
+            let __entries: &'static [#element_type] = #entries;
+            ::bytemuck::cast_slice(__entries)
+        }
+    }
+}
+
+/// The scalar type, the number of rows and the number of columns (or `None` for vectors) of a
+/// statically sized `nalgebra` matrix type, like `SMatrix<f32, 3, 4>`, `SVector<f32, 3>`,
+/// `Matrix3<f32>` or `Vector3<f32>`.
+fn matrix_shape(ty: &syn::Type) -> Option<(syn::Type, usize, Option<usize>)> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().collect::<Vec<_>>(),
+        _ => return None,
+    };
+    let dim = |arg: &syn::GenericArgument| match arg {
+        syn::GenericArgument::Const(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        })) => value.base10_parse::<usize>().ok(),
+        _ => None,
+    };
+    let name = segment.ident.to_string();
+    let (scalar, rows, columns) = match (name.as_str(), args.as_slice()) {
+        ("SMatrix", [scalar, rows, columns]) => (*scalar, dim(rows)?, Some(dim(columns)?)),
+        ("SVector", [scalar, rows]) => (*scalar, dim(rows)?, None),
+        (name, [scalar]) => {
+            let (size, is_vector) = match name.strip_prefix("Matrix") {
+                Some(size) => (size, false),
+                None => (name.strip_prefix("Vector")?, true),
+            };
+            let size = match size {
+                "1" | "2" | "3" | "4" | "5" | "6" => size.parse().ok()?,
+                _ => return None,
+            };
+            (*scalar, size, if is_vector { None } else { Some(size) })
+        }
+        _ => return None,
+    };
+    match scalar {
+        syn::GenericArgument::Type(scalar) if rows > 0 && columns != Some(0) => {
+            Some((scalar.clone(), rows, columns))
+        }
+        _ => None,
+    }
+}
+
+/// Generates the `HEATMAP_PGM` constant of the item form, if requested.
+///
+/// The entries are only known during constant evaluation, so the image is rendered there too.
+fn generate_heatmap(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.heatmap.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let (x, y) = (&input.inputs[0], &input.inputs[1]);
+    let (x_ident, y_ident) = (&x.ident, &y.ident);
+    let (x_lo, x_end) = (x.lo, x.lo + x.len());
+    let (y_lo, y_end) = (y.lo, y.lo + y.len());
+    let header = format!("P5\n{} {}\n255\n", y.len(), x.len());
+    let header_len = header.len();
+    let len = header_len + x.len() * y.len();
+    let header = syn::LitByteStr::new(header.as_bytes(), proc_macro2::Span::call_site());
+    let place = dense_place(input, table);
+    quote::quote! {
+        /// A grayscale image of the table in the binary PGM format, with a row per value of the
+        /// first parameter and a column per value of the second parameter, scaled so that the
+        /// smallest entry is black and the largest one is white.
+        #vis const HEATMAP_PGM: [u8; #len] = {
+            let __LUT = Self::TABLE;
+            let mut __image = [0; #len];
+            let __header = #header;
+            let mut __i = 0;
+            while __i < #header_len {
+                __image[__i] = __header[__i];
+                __i += 1;
+            }
+
+            let (mut __min, mut __max) = (f64::INFINITY, f64::NEG_INFINITY);
+            let mut #x_ident = #x_lo;
+            while #x_ident < #x_end {
+                let mut #y_ident = #y_lo;
+                while #y_ident < #y_end {
+                    let __entry = #place as f64;
+                    if __entry < __min {
+                        __min = __entry;
+                    }
+                    if __entry > __max {
+                        __max = __entry;
+                    }
+                    #y_ident += 1;
+                }
+                #x_ident += 1;
+            }
+
+            // NaNs and the entries of constant tables end up black.
+            let mut __pixel = #header_len;
+            let mut #x_ident = #x_lo;
+            while #x_ident < #x_end {
+                let mut #y_ident = #y_lo;
+                while #y_ident < #y_end {
+                    let __entry = #place as f64;
+                    if __max > __min {
+                        __image[__pixel] = ((__entry - __min) / (__max - __min) * 255.0 + 0.5) as u8;
+                    }
+                    __pixel += 1;
+                    #y_ident += 1;
+                }
+                #x_ident += 1;
+            }
+            __image
+        };
+    }
+}
+
+/// Generates the `matrix` function of the item form, if requested.
+fn generate_nalgebra_view(
+    input: &Lut,
+    vis: &syn::Visibility,
+    table: &Table,
+) -> proc_macro2::TokenStream {
+    if input.options.nalgebra_view.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let element_type = input.element_type();
+    let entries = table
+        .entry_slices(&quote::quote!(Self::TABLE))
+        .pop()
+        .expect("tables with `#[nalgebra(view)]` are never sharded");
+    let (rows, columns) = (input.inputs[0].len(), input.inputs[1].len());
+    // The table is stored row by row, and with `#[mask]` the rows are padded.
+    let row_stride = input.inputs[1].storage_len(&input.options);
+    quote::quote! {
+        /// A matrix view of the table, with a row per value of the first parameter and a column per
+        /// value of the second parameter, indexed relative to the lower bounds.
+        #vis fn matrix() -> ::nalgebra::MatrixView<
+            'static,
+            #element_type,
+            ::nalgebra::Const<#rows>,
+            ::nalgebra::Const<#columns>,
+            ::nalgebra::Const<#row_stride>,
+            ::nalgebra::Const<1>,
+        > {
+            let __entries: &'static [#element_type] = #entries;
+            ::nalgebra::MatrixView::from_slice_with_strides_generic(
+                __entries,
+                ::nalgebra::Const::<#rows>,
+                ::nalgebra::Const::<#columns>,
+                ::nalgebra::Const::<#row_stride>,
+                ::nalgebra::Const::<1>,
+            )
+        }
+    }
+}
+
+/// Generates the `view` function of the item form, if requested.
+fn generate_ndarray(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.ndarray.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let element_type = input.element_type();
+    let entries = table
+        .entry_slices(&quote::quote!(Self::TABLE))
+        .pop()
+        .expect("tables with `#[ndarray]` are never sharded");
+    let rank = input.inputs.len();
+    let shape = input.inputs.iter().map(|param| param.len());
+    // With `#[mask]`, dimensions are padded, so the strides are those of the stored table.
+    let mut strides = input
+        .inputs
+        .iter()
+        .rev()
+        .scan(1, |stride, param| {
+            let current = *stride;
+            *stride *= param.storage_len(&input.options);
+            Some(current)
+        })
+        .collect::<Vec<_>>();
+    strides.reverse();
+    quote::quote! {
+        /// A view of the table with one axis per parameter, indexed relative to the lower bounds.
+        #vis fn view() -> ::ndarray::ArrayView<'static, #element_type, ::ndarray::Dim<[usize; #rank]>> {
+            let __entries: &'static [#element_type] = #entries;
+            let __shape = ::ndarray::ShapeBuilder::strides([#(#shape),*], [#(#strides),*]);
+            match ::ndarray::ArrayView::from_shape(__shape, __entries) {
+                Ok(view) => view,
+                Err(_) => unreachable!("the shape matches the table"),
+            }
+        }
+    }
+}
+
+/// Generates the `TABLE_HASH` constant of the item form, if requested.
+fn generate_hash(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.hash.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let return_type = &input.return_type;
+    let entry_slices = table.entry_slices(&quote::quote!(Self::TABLE));
+    quote::quote! {
+        /// The 64-bit FNV-1a hash of the stored entries, each encoded with `to_le_bytes`.
+        #vis const TABLE_HASH: u64 = {
+            let __shards: &[&[#return_type]] = &[#(#entry_slices),*];
+            let mut __hash: u64 = 0xcbf2_9ce4_8422_2325;
+            let mut __s = 0;
+            while __s < __shards.len() {
+                let __entries = __shards[__s];
+                let mut __i = 0;
+                while __i < __entries.len() {
+                    let __bytes = __entries[__i].to_le_bytes();
+                    let mut __j = 0;
+                    while __j < __bytes.len() {
+                        __hash ^= __bytes[__j] as u64;
+                        __hash = __hash.wrapping_mul(0x0100_0000_01b3);
+                        __j += 1;
+                    }
+                    __i += 1;
+                }
+                __s += 1;
+            }
+            __hash
+        };
+    }
+}
+
+/// Generates the `TABLE_CRC` constant and the `verify_integrity` function of the item form, if
+/// requested.
+fn generate_crc(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.crc.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let return_type = &input.return_type;
+    let entry_slices = table.entry_slices(&quote::quote!(Self::TABLE));
+    let count = entry_slices.len();
+    quote::quote! {
+        /// The CRC-32 (as used by zlib and Ethernet) of the stored entries, each encoded with
+        /// `to_le_bytes`.
+        #vis const TABLE_CRC: u32 = Self::__crc(&[#(#entry_slices),*]);
+
+        /// Computes the CRC-32 of the stored entries in memory again, and returns whether it still
+        /// is `TABLE_CRC`, to detect corrupted flash or memory.
+        #vis fn verify_integrity() -> bool {
+            let __shards: [&[#return_type]; #count] = [#(#entry_slices),*];
+            let mut __crc: u32 = !0;
+            for __entries in __shards {
+                for __entry in __entries {
+                    // Volatile reads make the entries be read from the static in memory, instead
+                    // of folding the checksum into a constant.
+                    let __entry = unsafe { ::core::ptr::read_volatile(__entry) };
+                    __crc = Self::__crc_update(__crc, &__entry.to_le_bytes());
+                }
+            }
+            !__crc == Self::TABLE_CRC
+        }
+
+        const fn __crc(__shards: &[&[#return_type]]) -> u32 {
+            let mut __crc: u32 = !0;
+            let mut __s = 0;
+            while __s < __shards.len() {
+                let __entries = __shards[__s];
+                let mut __i = 0;
+                while __i < __entries.len() {
+                    __crc = Self::__crc_update(__crc, &__entries[__i].to_le_bytes());
+                    __i += 1;
+                }
+                __s += 1;
+            }
+            !__crc
+        }
+
+        const fn __crc_update(mut __crc: u32, __bytes: &[u8]) -> u32 {
+            let mut __j = 0;
+            while __j < __bytes.len() {
+                __crc ^= __bytes[__j] as u32;
+                let mut __k = 0;
+                while __k < 8 {
+                    __crc = (__crc >> 1) ^ (0xedb8_8320 & (__crc & 1).wrapping_neg());
+                    __k += 1;
+                }
+                __j += 1;
+            }
+            __crc
+        }
+    }
+}
+
+/// Generates the inverse of a table with `#[inverse]`.
+///
+/// The inverse is stored for every value from the smallest entry to the largest one, and is
+/// computed during constant evaluation.  Entries that occur more than once fail the build with a
+/// message that lists where they collide, which is formatted by hand since constant evaluation
+/// can't format numbers.
+fn generate_inverse(
+    input: &Lut,
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    table: &Table,
+) -> proc_macro2::TokenStream {
+    if input.options.inverse.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let return_type = &input.return_type;
+    let is_char = preset::primitive_name(return_type).is_none();
+    let param = &input.inputs[0];
+    let (lo, len) = (param.lo, param.len());
+    let hi = lo + len - 1;
+    let index_type = match hi {
+        hi if hi <= u8::MAX as usize => quote::quote!(u8),
+        hi if hi <= u16::MAX as usize => quote::quote!(u16),
+        hi if hi <= u32::MAX as usize => quote::quote!(u32),
+        _ => quote::quote!(usize),
+    };
+    // Adding zero or casting to the same type trips clippy in the invoking crate.
+    let value = match lo {
+        0 => quote::quote!(__i),
+        lo => quote::quote!((__i + #lo)),
+    };
+    let (stored, to_usize) = match hi {
+        hi if hi <= u16::MAX as usize => (
+            quote::quote!(#value as #index_type),
+            quote::quote!(.map(usize::from)),
+        ),
+        hi if hi <= u32::MAX as usize => (
+            quote::quote!(#value as #index_type),
+            quote::quote!(.map(|__x| __x as usize)),
+        ),
+        _ => (value.clone(), proc_macro2::TokenStream::new()),
+    };
+    let (widen, min, write_entry) = match is_char {
+        true => (
+            quote::quote!(as u32 as i128),
+            quote::quote! {
+                match ::core::char::from_u32(Self::__inverse_bounds().0 as u32) {
+                    ::core::option::Option::Some(__min) => __min,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                }
+            },
+            quote::quote! {
+                let mut __utf8 = [0; 4];
+                let __char = match ::core::char::from_u32(__entry as u32) {
+                    ::core::option::Option::Some(__char) => __char,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                };
+                let __utf8_len = __char.encode_utf8(&mut __utf8).len();
+                __len = Self::__inverse_write(&mut __message, __len, b"'");
+                __len = Self::__inverse_write(&mut __message, __len, __utf8.split_at(__utf8_len).0);
+                __len = Self::__inverse_write(&mut __message, __len, b"'");
+            },
+        ),
+        false => (
+            quote::quote!(as i128),
+            quote::quote!(Self::__inverse_bounds().0 as #return_type),
+            quote::quote!(__len = Self::__inverse_write_int(&mut __message, __len, __entry);),
+        ),
+    };
+    let entry_slices = table.entry_slices(&quote::quote!(Self::TABLE));
+
+    let prefix = "the entries aren't unique: ";
+    let name = format!("`{}` = ", param.ident);
+    // Every listed collision is two parameters and an entry, which all have at most 40 digits.
+    let collision = 2 * name.len() + " and ".len() + " are both ".len() + "; ".len() + 3 * 40;
+    let bytes = prefix.len() + INVERSE_COLLISIONS * collision + "...".len();
+    let max_collisions = INVERSE_COLLISIONS;
+    let too_wide = format!(
+        "the entries span more than {} values, which is too many for an inverse table",
+        MAX_INVERSE_SPAN
+    );
+    let max_span = MAX_INVERSE_SPAN;
+    // Failing builds point at the option.
+    let span = input.options.inverse.unwrap();
+    let check_span =
+        quote::quote_spanned!(span=> ::core::assert!(__max - __min < #max_span, #too_wide););
+    let fail = quote::quote_spanned! {span=>
+        match ::core::str::from_utf8(__message.split_at(__len).0) {
+            ::core::result::Result::Ok(__message) => ::core::panic!("{}", __message),
+            ::core::result::Result::Err(_) => ::core::panic!(#prefix),
+        }
+    };
+    quote::quote! {
+        /// The smallest entry, which is the first value of `INVERSE`.
+        #vis const INVERSE_MIN: #return_type = #min;
+
+        /// The parameter for every value from the smallest entry to the largest one, or `None` for
+        /// the values that aren't an entry.
+        #vis const INVERSE: &'static [::core::option::Option<#index_type>] =
+            &Self::__inverse::<{ #ident::__inverse_bounds().1 }>();
+
+        /// Looks up the parameter of the given entry, or returns `None` if it isn't an entry.
+        #vis fn inverse(entry: #return_type) -> ::core::option::Option<usize> {
+            let __slot = (entry #widen) - (Self::INVERSE_MIN #widen);
+            <usize as ::core::convert::TryFrom<i128>>::try_from(__slot)
+                .ok()
+                .and_then(|__slot| Self::INVERSE.get(__slot).copied().flatten())
+                #to_usize
+        }
+
+        const fn __inverse_entry(__i: usize) -> i128 {
+            let __shards: &[&[#return_type]] = &[#(#entry_slices),*];
+            let (mut __s, mut __i) = (0, __i);
+            while __i >= __shards[__s].len() {
+                __i -= __shards[__s].len();
+                __s += 1;
+            }
+            __shards[__s][__i] #widen
+        }
+
+        /// The smallest entry and the number of values up to the largest one.
+        const fn __inverse_bounds() -> (i128, usize) {
+            let (mut __min, mut __max) = (Self::__inverse_entry(0), Self::__inverse_entry(0));
+            let mut __i = 1;
+            while __i < #len {
+                let __entry = Self::__inverse_entry(__i);
+                if __entry < __min {
+                    __min = __entry;
+                }
+                if __entry > __max {
+                    __max = __entry;
+                }
+                __i += 1;
+            }
+            #check_span
+            (__min, (__max - __min) as usize + 1)
+        }
+
+        const fn __inverse<const N: usize>() -> [::core::option::Option<#index_type>; N] {
+            let __min = Self::__inverse_bounds().0;
+            let mut __inverse = [::core::option::Option::None; N];
+            let mut __message = [0; #bytes];
+            let mut __len = Self::__inverse_write(&mut __message, 0, #prefix.as_bytes());
+            let mut __collisions = 0;
+            let mut __i = 0;
+            while __i < #len {
+                let __entry = Self::__inverse_entry(__i);
+                let __slot = (__entry - __min) as usize;
+                match __inverse[__slot] {
+                    ::core::option::Option::None => {
+                        __inverse[__slot] = ::core::option::Option::Some(#stored);
+                    }
+                    ::core::option::Option::Some(__previous) => {
+                        if __collisions == #max_collisions {
+                            __len = Self::__inverse_write(&mut __message, __len, b"; ...");
+                        } else if __collisions < #max_collisions {
+                            if __collisions > 0 {
+                                __len = Self::__inverse_write(&mut __message, __len, b"; ");
+                            }
+                            __len = Self::__inverse_write(&mut __message, __len, #name.as_bytes());
+                            __len = Self::__inverse_write_int(&mut __message, __len, __previous as i128);
+                            __len = Self::__inverse_write(&mut __message, __len, b" and ");
+                            __len = Self::__inverse_write(&mut __message, __len, #name.as_bytes());
+                            __len = Self::__inverse_write_int(&mut __message, __len, #value as i128);
+                            __len = Self::__inverse_write(&mut __message, __len, b" are both ");
+                            #write_entry
+                        }
+                        __collisions += 1;
+                    }
+                }
+                __i += 1;
+            }
+            if __collisions > 0 {
+                #fail
+            }
+            __inverse
+        }
+
+        const fn __inverse_write(__message: &mut [u8; #bytes], __len: usize, __bytes: &[u8]) -> usize {
+            let mut __i = 0;
+            while __i < __bytes.len() {
+                __message[__len + __i] = __bytes[__i];
+                __i += 1;
+            }
+            __len + __bytes.len()
+        }
+
+        const fn __inverse_write_int(__message: &mut [u8; #bytes], __len: usize, __int: i128) -> usize {
+            let mut __digits = [0; 40];
+            let (mut __count, mut __rest) = (0, __int.unsigned_abs());
+            loop {
+                __digits[__digits.len() - 1 - __count] = b'0' + (__rest % 10) as u8;
+                __count += 1;
+                __rest /= 10;
+                if __rest == 0 {
+                    break;
+                }
+            }
+            if __int < 0 {
+                __count += 1;
+                __digits[__digits.len() - __count] = b'-';
+            }
+            Self::__inverse_write(__message, __len, __digits.split_at(__digits.len() - __count).1)
+        }
+    }
+}
+
+/// Generates a binary search for `value` over a table with a single parameter whose entries are
+/// checked to be monotone, along with whether they are increasing.
+///
+/// The search sets `__index` to the index of the first entry that is at least `value` (or at most,
+/// for decreasing entries), which is the number of entries if there is none.
+fn monotone_search(input: &Lut) -> Option<(bool, proc_macro2::TokenStream)> {
+    if input.inputs.len() != 1 {
+        return None;
+    }
+    let param = &input.inputs[0];
+    let (lo, len) = (param.lo, param.len());
+    let increasing = input
+        .options
+        .shape_checks
+        .iter()
+        .filter(|check| check.param == param.ident)
+        .find_map(|check| check.shape.is_increasing())?;
+    let before = |index: proc_macro2::TokenStream| {
+        let entry = get_at(lo, index);
+        match increasing {
+            true => quote::quote!(#entry < value),
+            false => quote::quote!(#entry > value),
+        }
+    };
+    let (middle, base) = (
+        before(quote::quote!(__base + __half)),
+        before(quote::quote!(__base)),
+    );
+    let search = quote::quote! {
+        let (mut __base, mut __size) = (0, #len);
+        while __size > 1 {
+            let __half = __size / 2;
+            if #middle {
+                __base += __half;
+            }
+            __size -= __half;
+        }
+        let __index = __base + (#base) as usize;
+    };
+    Some((increasing, search))
+}
+
+/// Generates a lookup of the entry at an index of the only parameter, counting from `lo`.
+fn get_at(lo: usize, index: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    // Adding zero trips clippy in the invoking crate.
+    match lo {
+        0 => quote::quote!(Self::get(#index)),
+        lo => quote::quote!(Self::get(#lo + #index)),
+    }
+}
+
+/// Generates the binary search of a table with `#[invert]`.
+///
+/// The search always takes the same number of steps for a table, and only selects the next range
+/// with the result of a comparison, which compiles to conditional moves instead of branches that
+/// depend on the value.
+fn generate_invert(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    let interpolate = match input.options.invert {
+        Some((_, interpolate)) => interpolate,
+        None => return proc_macro2::TokenStream::new(),
+    };
+
+    let return_type = &input.return_type;
+    let param = &input.inputs[0];
+    let (lo, len) = (param.lo, param.len());
+    let last = len - 1;
+    let (increasing, search) = monotone_search(input).unwrap();
+    let get = |index: proc_macro2::TokenStream| get_at(lo, index);
+    let order = match increasing {
+        true => "at least",
+        false => "at most",
+    };
+
+    if interpolate {
+        // Casting to the same type trips clippy in the invoking crate.
+        let widen = |value: proc_macro2::TokenStream| match preset::primitive_name(return_type)
+            .as_deref()
+        {
+            Some("f64") => value,
+            _ => quote::quote!((#value as f64)),
+        };
+        let (previous, next) = (
+            widen(get(quote::quote!((__index - 1)))),
+            widen(get(quote::quote!(__index))),
+        );
+        let value = widen(quote::quote!(value));
+        let doc = format!(
+            " Finds where the entries reach the given value, interpolating linearly between the two \
+             values of `{}` whose entries enclose it.  Values beyond the first or last entry give \
+             the first or last value of `{}`.",
+            param.ident, param.ident
+        );
+        return quote::quote! {
+            #[doc = #doc]
+            #vis fn invert(value: #return_type) -> f64 {
+                #search
+                let __index = __index.clamp(1, #last);
+                let (__previous, __next) = (#previous, #next);
+                // Equal entries give `NaN`, which ends up at the previous value.
+                let __t = (#value - __previous) / (__next - __previous);
+                let __t = if __t >= 1.0 {
+                    1.0
+                } else if __t > 0.0 {
+                    __t
+                } else {
+                    0.0
+                };
+                (#lo + __index - 1) as f64 + __t
+            }
+        };
+    }
+
+    let doc = format!(
+        " Finds the first value of `{}` whose entry is {} the given value, or the last one if \
+         there is none.",
+        param.ident, order
+    );
+    let result = match lo {
+        0 => quote::quote!(__index.min(#last)),
+        lo => quote::quote!(#lo + __index.min(#last)),
+    };
+    quote::quote! {
+        #[doc = #doc]
+        #vis fn invert(value: #return_type) -> usize {
+            #search
+            #result
+        }
+    }
+}
+
+/// Generates the search for the nearest entry of a table with `#[find_nearest]`.
+///
+/// Tables over a single parameter with entries that are checked to be monotone only compare the
+/// two entries around the result of a binary search, while others compare every entry.
+fn generate_find_nearest(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.find_nearest.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let return_type = &input.return_type;
+    let rank = input.inputs.len();
+    let len = input.inputs.iter().map(Param::len).product::<usize>();
+    let is_float = preset::primitive_name(return_type).is_some_and(|name| name.starts_with('f'));
+    let (distance, closer) = match is_float {
+        true => (
+            quote::quote! {
+                |__entry: #return_type| {
+                    let __distance = __entry - value;
+                    if __distance < 0.0 {
+                        -__distance
+                    } else {
+                        __distance
+                    }
+                }
+            },
+            // `NaN` entries are only the nearest if every entry is.
+            quote::quote!(__candidate.2 < __nearest.2 || __nearest.2.is_nan()),
+        ),
+        false => (
+            quote::quote!(|__entry: #return_type| __entry.abs_diff(value)),
+            quote::quote!(__candidate.2 < __nearest.2),
+        ),
+    };
+
+    // Patched entries may no longer be monotone.
+    let sorted = monotone_search(input).filter(|_| input.options.patchable.is_none());
+    let search = match sorted {
+        Some((_, search)) => {
+            let lo = input.inputs[0].lo;
+            let last = len - 1;
+            let (current, previous) = (
+                get_at(lo, quote::quote!(__index)),
+                get_at(lo, quote::quote!((__index - 1))),
+            );
+            let param = match lo {
+                0 => quote::quote!(__nearest.0),
+                lo => quote::quote!(#lo + __nearest.0),
+            };
+            quote::quote! {
+                let __search = |value: #return_type| {
+                    #search
+                    __index
+                };
+                // The nearest entry is either the first one past `value` or the one before it.
+                let __index = __search(value).min(#last);
+                let __entry = #current;
+                let mut __nearest = (__index, __entry, __distance(__entry));
+                if __index > 0 {
+                    let __entry = #previous;
+                    let __distance = __distance(__entry);
+                    if __distance <= __nearest.2 {
+                        // Equal entries before it are equally near, so this finds the first one.
+                        __nearest = (__search(__entry), __entry, __distance);
+                    }
+                }
+                ([#param], __nearest.1)
+            }
+        }
+        None => {
+            let unflatten = unflatten(input.inputs.iter());
+            let args = (0..rank).map(|i| quote::quote!(__params[#i]));
+            let (nearest, scan) = match len {
+                1 => (quote::quote!(__nearest), proc_macro2::TokenStream::new()),
+                len => (
+                    quote::quote!(mut __nearest),
+                    quote::quote! {
+                        for __flat in 1..#len {
+                            let __candidate = __at(__flat);
+                            if #closer {
+                                __nearest = __candidate;
+                            }
+                        }
+                    },
+                ),
+            };
+            quote::quote! {
+                let __at = |__flat: usize| {
+                    let __params = [#(#unflatten),*];
+                    let __entry = Self::get(#(#args),*);
+                    (__params, __entry, __distance(__entry))
+                };
+                let #nearest = __at(0);
+                #scan
+                (__nearest.0, __nearest.1)
+            }
+        }
+    };
+    quote::quote! {
+        /// Finds the parameters and entry of the entry that is nearest to the given value, which
+        /// is the first one in the order of the parameters if several are equally near.
+        #vis fn find_nearest(value: #return_type) -> ([usize; #rank], #return_type) {
+            let __distance = #distance;
+            #search
+        }
+    }
+}
+
+/// Generates the constants of a table with `#[aggregates]`.
+///
+/// They are reduced from the stored entries during constant evaluation, skipping the padding of
+/// `#[mask]`.  Sums of integers are widened to 128 bits, which only overflow for 128-bit entries.
+fn generate_aggregates(
+    input: &Lut,
+    vis: &syn::Visibility,
+    table: &Table,
+) -> proc_macro2::TokenStream {
+    if input.options.aggregates.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let return_type = &input.return_type;
+    let name = preset::primitive_name(return_type).unwrap();
+    let len = input.inputs.iter().map(Param::len).product::<usize>();
+    let rank = input.inputs.len();
+    let (_, _, offset) = logical_offsets(input);
+    let unflatten = unflatten(input.inputs.iter());
+    let at = generate_at(return_type);
+    let entry_slices = table.entry_slices(&quote::quote!(Self::TABLE));
+
+    // Casting to the same type trips clippy in the invoking crate.
+    let (sum_type, widened) = match name.as_str() {
+        "f64" => (quote::quote!(f64), quote::quote!(__entry)),
+        "f32" => (quote::quote!(f64), quote::quote!(__entry as f64)),
+        "i128" | "u128" => (quote::quote!(#return_type), quote::quote!(__entry)),
+        name if name.starts_with('i') => (quote::quote!(i128), quote::quote!(__entry as i128)),
+        _ => (quote::quote!(u128), quote::quote!(__entry as u128)),
+    };
+    let add = match name.as_str() {
+        "i128" | "u128" => {
+            let message = format!("the sum of the entries overflows `{}`", name);
+            quote::quote! {
+                __sum = match __sum.checked_add(#widened) {
+                    ::core::option::Option::Some(__sum) => __sum,
+                    ::core::option::Option::None => ::core::panic!(#message),
+                };
+            }
+        }
+        _ => quote::quote!(__sum += #widened;),
+    };
+    let count = proc_macro2::Literal::f64_unsuffixed(len as f64);
+    let (zero, min, max, mean) = match name.starts_with('f') {
+        true => (
+            quote::quote!(0.0),
+            // `NaN` entries are skipped, unless every entry is one.
+            quote::quote!(__entry < __min || __min.is_nan()),
+            quote::quote!(__entry > __max || __max.is_nan()),
+            quote::quote!(Self::SUM / #count),
+        ),
+        false => (
+            quote::quote!(0),
+            quote::quote!(__entry < __min),
+            quote::quote!(__entry > __max),
+            quote::quote!(Self::SUM as f64 / #count),
+        ),
+    };
+    quote::quote! {
+        /// The sum of the entries.
+        #vis const SUM: #sum_type = Self::__aggregates().0;
+
+        /// The smallest entry.
+        #vis const MIN: #return_type = Self::__aggregates().1;
+
+        /// The largest entry.
+        #vis const MAX: #return_type = Self::__aggregates().2;
+
+        /// The arithmetic mean of the entries.
+        #vis const MEAN: f64 = #mean;
+
+        /// The parameters of the first smallest entry.
+        #vis const ARGMIN: [usize; #rank] = {
+            let __flat = Self::__aggregates().3;
+            [#(#unflatten),*]
+        };
+
+        /// The parameters of the first largest entry.
+        #vis const ARGMAX: [usize; #rank] = {
+            let __flat = Self::__aggregates().4;
+            [#(#unflatten),*]
+        };
+
+        /// The sum, the smallest and largest entry, and where they first occur.
+        const fn __aggregates() -> (#sum_type, #return_type, #return_type, usize, usize) {
+            #at
+            let __shards: &[&[#return_type]] = &[#(#entry_slices),*];
+            let mut __sum: #sum_type = #zero;
+            let (mut __min, mut __max) = (__at(__shards, 0), __at(__shards, 0));
+            let (mut __argmin, mut __argmax) = (0, 0);
+            let mut __flat = 0;
+            while __flat < #len {
+                let __entry = __at(__shards, #offset);
+                #add
+                if #min {
+                    __min = __entry;
+                    __argmin = __flat;
+                }
+                if #max {
+                    __max = __entry;
+                    __argmax = __flat;
+                }
+                __flat += 1;
+            }
+            (__sum, __min, __max, __argmin, __argmax)
+        }
+    }
+}
+
+/// The atomic type that holds an entry of the given type with `#[patchable]`, and whether the
+/// entry is a float that is stored as its bits.
+fn atomic_type(ty: &syn::Type) -> Option<(syn::Ident, bool)> {
+    let name = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident()?.to_string(),
+        _ => return None,
+    };
+    let (atomic, float) = match name.as_str() {
+        "f32" => ("AtomicU32", true),
+        "f64" => ("AtomicU64", true),
+        "bool" => ("AtomicBool", false),
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+            let mut atomic = String::from("Atomic");
+            atomic.push_str(&name[..1].to_uppercase());
+            atomic.push_str(&name[1..]);
+            return Some((
+                syn::Ident::new(&atomic, proc_macro2::Span::call_site()),
+                false,
+            ));
+        }
+        _ => return None,
+    };
+    Some((
+        syn::Ident::new(atomic, proc_macro2::Span::call_site()),
+        float,
+    ))
+}
+
+/// Generates a load of the atomic at the place `entry` of a table with `#[patchable]`.
+fn patched_load(atomic: &syn::Ident, entry: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let load = quote::quote!(#entry.load(::core::sync::atomic::Ordering::Relaxed));
+    match atomic.to_string().as_str() {
+        "AtomicU32" => quote::quote!(f32::from_bits(#load)),
+        "AtomicU64" => quote::quote!(f64::from_bits(#load)),
+        _ => load,
+    }
+}
+
+/// Generates the atomics of a table with `#[patchable]`, which are seeded with the stored entries,
+/// and the functions that overwrite and restore them.
+fn generate_patchable(
+    input: &Lut,
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    table: &Table,
+) -> proc_macro2::TokenStream {
+    if input.options.patchable.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let (atomic, float) = atomic_type(&input.return_type).expect("validated");
+    let return_type = &input.return_type;
+    let atomic = quote::quote!(::core::sync::atomic::#atomic);
+    let lens = match input.inputs.len() {
+        0 => vec![1],
+        _ => input.stored().into_iter().map(Param::len).collect(),
+    };
+    let ty = lens
+        .iter()
+        .rev()
+        .fold(atomic.clone(), |ty, len| quote::quote!([#ty; #len]));
+    let zero = match return_type {
+        syn::Type::Path(syn::TypePath { path, .. }) if path.is_ident("bool") => {
+            quote::quote!(false)
+        }
+        _ => quote::quote!(0),
+    };
+    let repeat = lens.iter().rev().fold(
+        quote::quote!(#atomic::new(#zero)),
+        |repeat, len| quote::quote!([const { #repeat }; #len]),
+    );
+    let bits = |entry| match float {
+        true => quote::quote!(#entry.to_bits()),
+        false => entry,
+    };
+
+    // Seeds the atomics in nested loops over the dimensions of the table.
+    let indices = (0..lens.len())
+        .map(|i| quote::format_ident!("__i{}", i))
+        .collect::<Vec<_>>();
+    let default = bits(quote::quote!(#ident::TABLE #([#indices])*));
+    let mut seed = quote::quote!(__patched #([#indices])* = #atomic::new(#default););
+    for (index, len) in indices.iter().zip(&lens).rev() {
+        seed = quote::quote! {
+            let mut #index = 0;
+            while #index < #len {
+                #seed
+                #index += 1;
+            }
+        };
+    }
+
+    let entry = match input.inputs.len() {
+        0 => quote::quote!(__LUT[0]),
+        _ => dense_access(input.stored().into_iter(), &input.options),
+    };
+    let lut_params = params(input.inputs.iter());
+    let value = bits(quote::quote!(value));
+    let default = bits(quote::quote!(__default));
+    let patched = flattened(quote::quote!(__LUT), table.rank);
+    let defaults = flattened(quote::quote!(Self::TABLE), table.rank);
+    quote::quote! {
+        /// Overwrites the entry for the given parameters, which later lookups return instead.
+        #vis fn set(#(#lut_params,)* value: #return_type) {
+            let __LUT = Self::__patched();
+            #entry.store(#value, ::core::sync::atomic::Ordering::Relaxed);
+        }
+
+        /// Restores every entry to the one computed at compile time.
+        #vis fn reset() {
+            let __LUT = Self::__patched();
+            for (__entry, &__default) in #patched.iter().zip(#defaults) {
+                __entry.store(#default, ::core::sync::atomic::Ordering::Relaxed);
+            }
+        }
+
+        /// The entries that lookups read, seeded with the stored ones.
+        fn __patched() -> &'static #ty {
+            static __PATCHED: #ty = {
+                let mut __patched = #repeat;
+                #seed
+                __patched
+            };
+            &__PATCHED
+        }
+    }
+}
+
+/// Generates the `reload` function of a table with `#[reload]`, which parses all entries of a file
+/// before storing any of them, so that a file that is rejected leaves the entries as they were.
+fn generate_reload(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.reload.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let (atomic, float) = atomic_type(&input.return_type).expect("validated");
+    let return_type = &input.return_type;
+    let len = input.inputs.iter().map(Param::len).product::<usize>();
+    let header = input
+        .inputs
+        .iter()
+        .map(|param| param.ident.to_string())
+        .chain(std::iter::once("value".to_owned()))
+        .collect::<Vec<_>>()
+        .join(",");
+    let params = input.inputs.iter().map(|param| {
+        let ident = &param.ident;
+        let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+        let message = format!("line {{}}: `{}` must be in {}..={}", ident, lo, hi);
+        quote::quote! {
+            let #ident = __fields
+                .next()
+                .and_then(|__field| __field.parse::<usize>().ok())
+                .filter(|#ident| (#lo..=#hi).contains(#ident))
+                .ok_or_else(|| ::std::format!(#message, __number))?;
+        }
+    });
+    let flat = flat_index(input.inputs.iter());
+    let entry = match atomic.to_string().as_str() {
+        "AtomicBool" => quote::quote! {
+            |(__index, __bytes): (usize, &[u8])| match __bytes[0] {
+                0 => ::core::result::Result::Ok(false),
+                1 => ::core::result::Result::Ok(true),
+                __byte => ::core::result::Result::Err(::std::format!(
+                    "entry {} must be 0 or 1 but is {}",
+                    __index,
+                    __byte
+                )),
+            }
+        },
+        _ => quote::quote! {
+            |(_, __bytes): (usize, &[u8])| {
+                let __bytes = ::core::convert::TryInto::try_into(__bytes).expect("chunks of the size");
+                ::core::result::Result::Ok(<#return_type>::from_le_bytes(__bytes))
+            }
+        },
+    };
+    let bits = match float {
+        true => quote::quote!(__entry.to_bits()),
+        false => quote::quote!(__entry),
+    };
+    let patched = flattened(quote::quote!(Self::__patched()), table.rank);
+    quote::quote! {
+        /// Replaces every entry with the one in a file, which holds all entries as CSV like
+        /// `write_csv` if its extension is `csv`, and otherwise in little-endian byte order, in
+        /// which the last parameter changes fastest.  A file that isn't valid is rejected with an
+        /// error of the kind `InvalidData`, and leaves the entries as they were.
+        #vis fn reload<P: ::core::convert::AsRef<::std::path::Path>>(path: P) -> ::std::io::Result<()> {
+            let __path = path.as_ref();
+            let __data = ::std::fs::read(__path)?;
+            let __csv = __path
+                .extension()
+                .is_some_and(|__extension| __extension.eq_ignore_ascii_case("csv"));
+            let __entries = if __csv {
+                Self::__parse_csv(&__data)
+            } else {
+                Self::__parse_bytes(&__data)
+            };
+            let __entries = __entries.map_err(|__message| {
+                ::std::io::Error::new(
+                    ::std::io::ErrorKind::InvalidData,
+                    ::std::format!("{}: {}", __path.display(), __message),
+                )
+            })?;
+            for (__atomic, __entry) in #patched.iter().zip(__entries) {
+                __atomic.store(#bits, ::core::sync::atomic::Ordering::Relaxed);
+            }
+            ::core::result::Result::Ok(())
+        }
+
+        /// Parses the entries of a CSV file for `reload`.
+        fn __parse_csv(__data: &[u8]) -> ::core::result::Result<::std::vec::Vec<#return_type>, ::std::string::String> {
+            let __text = ::core::str::from_utf8(__data)
+                .map_err(|_| ::std::string::String::from("the file isn't UTF-8"))?;
+            let mut __lines = __text.lines().enumerate().map(|(__index, __line)| (__index + 1, __line));
+            if __lines.next().map(|(_, __line)| __line) != ::core::option::Option::Some(#header) {
+                return ::core::result::Result::Err(::std::format!(
+                    "line 1: expected the header `{}`",
+                    #header
+                ));
+            }
+            let mut __entries = ::std::vec![::core::option::Option::None; #len];
+            for (__number, __line) in __lines.filter(|(_, __line)| !__line.is_empty()) {
+                let mut __fields = __line.split(',');
+                #(#params)*
+                let __value = __fields.next().unwrap_or_default();
+                if __fields.next().is_some() {
+                    return ::core::result::Result::Err(::std::format!(
+                        "line {}: expected a field for every parameter and `value`",
+                        __number
+                    ));
+                }
+                let __entry = __value.parse::<#return_type>().map_err(|_| {
+                    ::std::format!("line {}: `{}` isn't a valid entry", __number, __value)
+                })?;
+                if __entries[#flat].replace(__entry).is_some() {
+                    return ::core::result::Result::Err(::std::format!(
+                        "line {}: the entry for these parameters is given twice",
+                        __number
+                    ));
+                }
+            }
+            let __missing = __entries.iter().filter(|__entry| __entry.is_none()).count();
+            if __missing > 0 {
+                return ::core::result::Result::Err(::std::format!(
+                    "{} of {} entries are missing",
+                    __missing,
+                    #len
+                ));
+            }
+            ::core::result::Result::Ok(__entries.into_iter().flatten().collect())
+        }
+
+        /// Parses the entries of a binary file for `reload`.
+        fn __parse_bytes(__data: &[u8]) -> ::core::result::Result<::std::vec::Vec<#return_type>, ::std::string::String> {
+            let __size = ::core::mem::size_of::<#return_type>();
+            if __data.len() != #len * __size {
+                return ::core::result::Result::Err(::std::format!(
+                    "expected {} bytes but found {}",
+                    #len * __size,
+                    __data.len()
+                ));
+            }
+            __data
+                .chunks_exact(__size)
+                .enumerate()
+                .map(#entry)
+                .collect()
+        }
+    }
+}
+
+/// Generates the reference of a table with `#[indirect]` and the `init` function that sets it.
+fn generate_indirect(
+    input: &Lut,
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    table: &Table,
+) -> proc_macro2::TokenStream {
+    if input.options.indirect.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let ty = &table.ty;
+    let message = format!("`{}::init` must be called before the first lookup", ident);
+    quote::quote! {
+        /// Sets the table that lookups read, which holds the same entries as `TABLE` but may
+        /// live anywhere, like in memory-mapped external flash or in RAM that it was copied to.
+        #vis fn init(table: &'static #ty) {
+            Self::__indirect().store(
+                ::core::ptr::from_ref(table).cast_mut(),
+                ::core::sync::atomic::Ordering::Release,
+            );
+        }
+
+        /// The table that lookups read.
+        #[allow(unsafe_code)]
+        fn __table() -> &'static #ty {
+            let __table = Self::__indirect().load(::core::sync::atomic::Ordering::Acquire);
+            ::core::assert!(!__table.is_null(), #message);
+            // SAFETY: The pointer is either null or was converted from a reference with a
+            // static lifetime by `init`.
+            unsafe { &*__table }
+        }
+
+        /// The pointer to the table that lookups read, which is null until `init` is called.
+        fn __indirect() -> &'static ::core::sync::atomic::AtomicPtr<#ty> {
+            static __INDIRECT: ::core::sync::atomic::AtomicPtr<#ty> =
+                ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut());
+            &__INDIRECT
+        }
+    }
+}
+
+/// Generates the `init` function of a table with `#[heap]` and the table on the heap that it
+/// builds, by evaluating the body for every entry.
+fn generate_heap(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.heap.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let len = input.inputs.iter().map(Param::len).product::<usize>();
+    let element_type = input.element_type();
+    let idents = input.inputs.iter().map(|param| &param.ident);
+    let values = unflatten(input.inputs.iter());
+    let body = body_tokens(input);
+    let entry = match (input.options.f16, &input.options.codec) {
+        (Some(_), _) => quote::quote!(::half::f16::from_f32({ #body })),
+        (None, Some(codec)) => codec.encode(quote::quote!({ #body }), &input.return_type),
+        (None, None) => quote::quote!({ #body }),
+    };
+    quote::quote! {
+        /// Evaluates the entries into the table on the heap unless that already happened, so that
+        /// it happens at startup rather than in the first lookup.
+        #vis fn init() {
+            let _ = Self::__heap();
+        }
+
+        /// The table on the heap, which is built by the first call.
+        fn __heap() -> &'static [#element_type] {
+            static __HEAP: ::std::sync::OnceLock<::std::boxed::Box<[#element_type]>> =
+                ::std::sync::OnceLock::new();
+            __HEAP.get_or_init(|| {
+                let mut __entries = ::std::vec::Vec::with_capacity(#len);
+                for __flat in 0..#len {
+                    #(
+                        #[allow(unused_variables)]
+                        let #idents: usize = #values;
+                    )*
+                    __entries.push(#entry);
+                }
+                __entries.into_boxed_slice()
+            })
+        }
+    }
+}
+
+/// Generates the compressed entries of a table with `#[compressed]`, the buffer in RAM that lookups
+/// read and the `init` function that decompresses the entries into it.
+///
+/// The entries are compressed during constant evaluation with a greedy LZ77 over their bytes, which
+/// finds matches through a hash table instead of trying every distance: a control byte below 128 is
+/// followed by that many literal bytes plus one, while a control byte `c` of at least 128 is
+/// followed by a distance `d` and repeats `c - 125` bytes from `d` bytes back.
+fn generate_compressed(
+    input: &Lut,
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+    table: &Table,
+) -> proc_macro2::TokenStream {
+    if input.options.compressed.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let ty = &table.ty;
+    let len = table.len;
+    let stored = match &input.options.codec {
+        Some(codec) => codec.stored.clone(),
+        None => input.return_type.clone(),
+    };
+    let zero = input.stored().iter().rev().fold(
+        zero_value(&stored).expect("validated in `Lut::validate`"),
+        |zero, param| {
+            let count = param.storage_len(&input.options);
+            quote::quote!([#zero; #count])
+        },
+    );
+    let entries = flattened(quote::quote!(#ident::TABLE), table.rank);
+    let lint = input.options.const_eval.lint_attr();
+    let message = format!("`{}::init` must be called before the first lookup", ident);
+    // Every run of up to 128 literal bytes takes one more byte, and matches only shrink the bytes.
+    let max =
+        quote::quote!(::core::mem::size_of::<#ty>() + ::core::mem::size_of::<#ty>() / 128 + 1);
+    quote::quote! {
+        /// The size of the compressed entries in the image, in bytes.
+        #lint
+        #vis const COMPRESSED_SIZE: usize = Self::__COMPRESSED.1;
+
+        /// The bytes of the entries, which are only used during constant evaluation.
+        #lint
+        const __BYTES: [u8; ::core::mem::size_of::<#ty>()] = {
+            let __entries: &[#stored] = #entries;
+            let __width = ::core::mem::size_of::<#stored>();
+            let mut __bytes = [0; ::core::mem::size_of::<#ty>()];
+            let mut __i = 0;
+            while __i < #len {
+                let __entry = __entries[__i].to_ne_bytes();
+                let mut __k = 0;
+                while __k < __width {
+                    __bytes[__i * __width + __k] = __entry[__k];
+                    __k += 1;
+                }
+                __i += 1;
+            }
+            __bytes
+        };
+
+        /// The compressed entries in the first `COMPRESSED_SIZE` bytes of an array that fits
+        /// any input, which is compressed only once and only used during constant evaluation.
+        #lint
+        const __COMPRESSED: ([u8; #max], usize) = Self::__compress(&Self::__BYTES);
+
+        /// Compresses the bytes, returning the compressed bytes with their size.
+        ///
+        /// Matches are found through a hash table of the last position of every three bytes,
+        /// which only holds candidates within the reach of the one-byte distance.
+        const fn __compress(__input: &[u8]) -> ([u8; #max], usize) {
+            let mut __out = [0; #max];
+            let mut __positions = [0usize; 4096];
+            let mut __len = 0;
+            let mut __literals = 0;
+            let mut __i = 0;
+            while __i <= __input.len() {
+                let (mut __best, mut __distance) = (0, 0);
+                if __i + 3 <= __input.len() {
+                    let __hash = Self::__hash(__input, __i);
+                    let __candidate = __positions[__hash];
+                    __positions[__hash] = __i + 1;
+                    if __candidate > 0 && __i - (__candidate - 1) <= 255 {
+                        let __d = __i - (__candidate - 1);
+                        let mut __l = 0;
+                        while __l < 130
+                            && __i + __l < __input.len()
+                            && __input[__i + __l] == __input[__i + __l - __d]
+                        {
+                            __l += 1;
+                        }
+                        __best = __l;
+                        __distance = __d;
+                    }
+                }
+                let __end = __i == __input.len();
+                if __literals > 0 && (__best >= 3 || __literals == 128 || __end) {
+                    __out[__len] = (__literals - 1) as u8;
+                    __len += 1;
+                    let mut __k = __i - __literals;
+                    while __k < __i {
+                        __out[__len] = __input[__k];
+                        __len += 1;
+                        __k += 1;
+                    }
+                    __literals = 0;
+                }
+                if __end {
+                    break;
+                }
+                if __best >= 3 {
+                    __out[__len] = (__best + 125) as u8;
+                    __out[__len + 1] = __distance as u8;
+                    __len += 2;
+                    __i += __best;
+                    // The positions at the end of the match keep periodic entries matching.
+                    let mut __k = __i - 3;
+                    while __k < __i && __k + 3 <= __input.len() {
+                        __positions[Self::__hash(__input, __k)] = __k + 1;
+                        __k += 1;
+                    }
+                } else {
+                    __literals += 1;
+                    __i += 1;
+                }
+            }
+            (__out, __len)
+        }
+
+        /// The slot of the three bytes at the given position in the hash table of `__compress`.
+        const fn __hash(__input: &[u8], __i: usize) -> usize {
+            let __bytes = u32::from_le_bytes([__input[__i], __input[__i + 1], __input[__i + 2], 0]);
+            (__bytes.wrapping_mul(2_654_435_761) >> 20) as usize
+        }
+
+        /// Decompresses the entries into RAM unless that already happened, which must be done
+        /// before the first lookup.  Calls while another thread decompresses them wait for it, so
+        /// this must not be called from an interrupt handler before it has returned once.
+        #[allow(unsafe_code)]
+        #vis fn init() {
+            #lint
+            static __COMPRESSED: [u8; #ident::COMPRESSED_SIZE] = {
+                let __compressed = &#ident::__COMPRESSED.0;
+                let mut __bytes = [0; #ident::COMPRESSED_SIZE];
+                let mut __i = 0;
+                while __i < #ident::COMPRESSED_SIZE {
+                    __bytes[__i] = __compressed[__i];
+                    __i += 1;
+                }
+                __bytes
+            };
+            #[cfg(target_has_atomic = "8")]
+            let __claimed = Self::__state()
+                .compare_exchange(
+                    0,
+                    1,
+                    ::core::sync::atomic::Ordering::Acquire,
+                    ::core::sync::atomic::Ordering::Relaxed,
+                )
+                .is_ok();
+            // Targets without compare-and-swap, like `thumbv6m`, are single-core, where only an
+            // interrupt can come between the load and the store.
+            #[cfg(not(target_has_atomic = "8"))]
+            let __claimed = Self::__state().load(::core::sync::atomic::Ordering::Acquire) == 0 && {
+                Self::__state().store(1, ::core::sync::atomic::Ordering::Relaxed);
+                true
+            };
+            if !__claimed {
+                while Self::__state().load(::core::sync::atomic::Ordering::Acquire) != 2 {
+                    ::core::hint::spin_loop();
+                }
+                return;
+            }
+            // SAFETY: Only the first call gets here, and lookups don't read the buffer before the
+            // state is set to 2 below.
+            let __out = unsafe {
+                ::core::slice::from_raw_parts_mut(
+                    Self::__buffer().cast::<u8>(),
+                    ::core::mem::size_of::<#ty>(),
+                )
+            };
+            let (mut __in, mut __at) = (0, 0);
+            while __in < __COMPRESSED.len() {
+                let __control = usize::from(__COMPRESSED[__in]);
+                __in += 1;
+                if __control < 128 {
+                    let __count = __control + 1;
+                    __out[__at..__at + __count]
+                        .copy_from_slice(&__COMPRESSED[__in..__in + __count]);
+                    __in += __count;
+                    __at += __count;
+                } else {
+                    let __distance = usize::from(__COMPRESSED[__in]);
+                    __in += 1;
+                    for _ in 0..__control - 125 {
+                        __out[__at] = __out[__at - __distance];
+                        __at += 1;
+                    }
+                }
+            }
+            Self::__state().store(2, ::core::sync::atomic::Ordering::Release);
+        }
+
+        /// The decompressed entries that lookups read.
+        #[allow(unsafe_code)]
+        fn __table() -> &'static #ty {
+            let __state = Self::__state().load(::core::sync::atomic::Ordering::Acquire);
+            ::core::assert!(__state == 2, #message);
+            // SAFETY: The buffer is only written by `init`, before it sets the state to 2.
+            unsafe { &*Self::__buffer() }
+        }
+
+        /// Whether the entries are decompressed: 0 before `init`, 1 during it and 2 after it.
+        fn __state() -> &'static ::core::sync::atomic::AtomicU8 {
+            static __STATE: ::core::sync::atomic::AtomicU8 = ::core::sync::atomic::AtomicU8::new(0);
+            &__STATE
+        }
+
+        /// The buffer in RAM that the entries are decompressed into.
+        #[allow(unsafe_code, unused_unsafe)]
+        fn __buffer() -> *mut #ty {
+            static mut __BUFFER: #ty = #zero;
+            // SAFETY: This only takes the address of the buffer, without reading or writing it.
+            unsafe { ::core::ptr::addr_of_mut!(__BUFFER) }
+        }
+    }
+}
+
+/// Generates the lookup of a table with `#[redundant]`, which reads the entry from both copies of
+/// the table and panics if they differ.
+fn generate_redundant(
+    input: &Lut,
+    ident: &syn::Ident,
+    ty: &proc_macro2::TokenStream,
+    rank: usize,
+    access: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let (copy, compare) = match input.options.redundant {
+        Some((_, true)) => {
+            // Inverts every entry in nested loops over the dimensions of the table.
+            let indices = (0..rank)
+                .map(|i| quote::format_ident!("__i{}", i))
+                .collect::<Vec<_>>();
+            let mut invert = quote::quote!(__copy #([#indices])* = !__copy #([#indices])*;);
+            for (i, index) in indices.iter().enumerate().rev() {
+                let outer = &indices[..i];
+                invert = quote::quote! {
+                    let mut #index = 0;
+                    while #index < __copy #([#outer])*.len() {
+                        #invert
+                        #index += 1;
+                    }
+                };
+            }
+            (
+                quote::quote!({
+                    let mut __copy = *#ident::TABLE;
+                    #invert
+                    __copy
+                }),
+                quote::quote!(!__copy),
+            )
+        }
+        _ => (quote::quote!(*#ident::TABLE), quote::quote!(__copy)),
+    };
+    let message = format!(
+        "the entries of `{}` differ between the two copies of the table",
+        ident
+    );
+    let lint = input.options.const_eval.lint_attr();
+    quote::quote! {
+        // A static of its own has an address of its own, so the copy isn't merged with the table.
+        #lint
+        static __COPY: #ty = #copy;
+
+        // Volatile reads of the addresses of both copies hide which entries they hold from the
+        // optimizer, and volatile reads of the entries make them be read from the statics in
+        // memory, so that comparing the copies can't be folded away.
+        let __entry = {
+            let __table: *const #ty = Self::TABLE;
+            let __LUT: &#ty = unsafe { &*::core::ptr::read_volatile(&__table) };
+            let __place = &#access;
+            unsafe { ::core::ptr::read_volatile(__place) }
+        };
+        let __copy = {
+            let __table: *const #ty = &__COPY;
+            let __LUT: &#ty = unsafe { &*::core::ptr::read_volatile(&__table) };
+            let __place = &#access;
+            unsafe { ::core::ptr::read_volatile(__place) }
+        };
+        if __entry != #compare {
+            ::core::panic!(#message);
+        }
+        __entry
+    }
+}
+
+/// Generates a tuple of the given expressions or patterns, or just the item if there is only one.
+fn tuple(items: impl IntoIterator<Item = impl quote::ToTokens>) -> proc_macro2::TokenStream {
+    let items = items.into_iter().collect::<Vec<_>>();
+    match items.as_slice() {
+        [item] => quote::quote!(#item),
+        items => quote::quote!((#(#items),*)),
+    }
+}
+
+/// Generates an expression that flattens a reference to a table of the given rank into a slice.
+fn flattened(table: proc_macro2::TokenStream, rank: usize) -> proc_macro2::TokenStream {
+    (1..rank).fold(table, |expr, _| quote::quote!(#expr.as_flattened()))
+}
+
+/// Resolves a path relative to the root of the crate that invokes the macro.
+fn manifest_path(path: &syn::LitStr) -> syn::Result<String> {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_owned());
+    let full_path = std::path::Path::new(&root).join(path.value());
+    if !full_path.is_file() {
+        return Err(syn::Error::new(
+            path.span(),
+            format!("file not found: {}", full_path.display()),
+        ));
+    }
+    Ok(full_path.to_string_lossy().into_owned())
+}
+
+/// Whether the type is left to be inferred, as in the function path form without a return type.
+fn is_inferred(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Infer(_))
+}
+
+/// Whether the type is a reference to a trait object, like `&'static dyn Handler`.
+fn is_trait_object_ref(ty: &syn::Type) -> bool {
+    let elem = match ty {
+        syn::Type::Reference(reference) => &*reference.elem,
+        _ => return false,
+    };
+    match elem {
+        syn::Type::Paren(paren) => matches!(*paren.elem, syn::Type::TraitObject(_)),
+        elem => matches!(elem, syn::Type::TraitObject(_)),
+    }
+}
+
+/// Generates the lookup function parameters for the given table parameters.
+fn params<'a>(
+    params: impl Iterator<Item = &'a Param> + 'a,
+) -> impl Iterator<Item = proc_macro2::TokenStream> + 'a {
+    params.map(|param| {
+        let ident = &param.ident;
+        quote::quote!(#ident: usize)
+    })
+}
+
+/// Generates expressions that split the flat index `__flat` of an entry in memory order into the
+/// values of the given parameters, without the identities and reductions modulo one that clippy
+/// warns about in the invoking crate.
+fn unflatten<'a>(
+    params: impl DoubleEndedIterator<Item = &'a Param>,
+) -> Vec<proc_macro2::TokenStream> {
+    let mut stride = 1;
+    let mut values = Vec::new();
+    for param in params.rev() {
+        let (lo, len) = (param.lo, param.len());
+        let mut index = match stride {
+            1 => quote::quote!(__flat),
+            stride => quote::quote!(__flat / #stride),
+        };
+        index = match len {
+            1 => quote::quote!({
+                let _ = #index;
+                0
+            }),
+            len => quote::quote!(#index % #len),
+        };
+        values.push(match lo {
+            0 => index,
+            lo => quote::quote!(#lo + #index),
+        });
+        stride *= len;
+    }
+    values.reverse();
+    values
+}
+
+/// Generates the index of the entry for the given parameters in memory order, in which the last
+/// parameter changes fastest.
+fn flat_index<'a>(params: impl DoubleEndedIterator<Item = &'a Param>) -> proc_macro2::TokenStream {
+    let mut stride = 1;
+    let mut offsets = Vec::new();
+    for param in params.rev() {
+        let ident = &param.ident;
+        // Subtracting zero or multiplying by one trips clippy in the invoking crate.
+        let index = match param.lo {
+            0 => quote::quote!(#ident),
+            lo => quote::quote!((#ident - #lo)),
+        };
+        offsets.push(match stride {
+            1 => index,
+            stride => quote::quote!(#index * #stride),
+        });
+        stride *= param.len();
+    }
+    if offsets.is_empty() {
+        offsets.push(quote::quote!(0));
+    }
+    quote::quote!(#(#offsets)+*)
+}
+
+/// Generates the `call` function of the item form for tables of function pointers, which calls the
+/// entry for the given parameters with the remaining arguments.
+fn generate_call(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    let bare_fn = match &input.return_type {
+        syn::Type::BareFn(bare_fn) if bare_fn.unsafety.is_none() && bare_fn.variadic.is_none() => {
+            bare_fn
+        }
+        _ => return proc_macro2::TokenStream::new(),
+    };
+
+    let lut_params = params(input.inputs.iter());
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let lifetimes = bare_fn
+        .lifetimes
+        .as_ref()
+        .map(|bound| bound.lifetimes.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let args = (0..bare_fn.inputs.len())
+        .map(|i| quote::format_ident!("__arg{}", i))
+        .collect::<Vec<_>>();
+    let arg_types = bare_fn.inputs.iter().map(|arg| &arg.ty);
+    let output = &bare_fn.output;
+    let indirect = quote::quote!((Self::get(#(#idents),*))(#(#args),*));
+    let call = match input.options.direct_call {
+        // Calling a constant function pointer is a direct call that can be inlined.
+        Some(_) => {
+            let return_type = &input.return_type;
+            let arm = |entry| {
+                quote::quote!({
+                    const __HANDLER: #return_type = #entry;
+                    __HANDLER(#(#args),*)
+                })
+            };
+            generate_match(input, &body_tokens(input), arm, Some(indirect))
+        }
+        None => indirect,
+    };
+    quote::quote! {
+        /// Looks up the function for the given parameters and calls it with the remaining
+        /// arguments.
+        #[inline]
+        #vis fn call<#(#lifetimes),*>(#(#lut_params,)* #(#args: #arg_types),*) #output {
+            #call
+        }
+    }
+}
+
+/// Generates the `get_t` function of the item form, if requested.
+fn generate_transposed(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.transpose.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let transposed_params = params(input.inputs.iter().rev());
+    let idents = input.inputs.iter().map(|param| &param.ident);
+    let return_type = &input.return_type;
+    quote::quote! {
+        /// Looks up the entry for the given parameters, in reverse order.
+        #[inline]
+        #vis fn get_t(#(#transposed_params),*) -> #return_type {
+            Self::get(#(#idents),*)
+        }
+    }
+}
+
+/// Wraps a lookup so that it calls the function of `#[fallback(f)]` instead if a parameter is out
+/// of range, if there is one.
+fn with_fallback(input: &Lut, lookup: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let fallback = match &input.options.fallback {
+        Some(fallback) => fallback,
+        None => return lookup,
+    };
+    // Parameters with a policy are never out of range.
+    let checks = input
+        .inputs
+        .iter()
+        .filter(|param| matches!(param.policy, Policy::Panic))
+        .map(|param| {
+            let ident = &param.ident;
+            let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+            quote::quote!((#lo..=#hi).contains(&#ident))
+        });
+    let idents = input.inputs.iter().map(|param| &param.ident);
+    quote::quote!({
+        if #(#checks)&&* {
+            #lookup
+        } else {
+            let __fallback = #fallback;
+            __fallback(#(#idents),*)
+        }
+    })
+}
+
+/// Generates the attribute that makes builds fail if the optimizer can't remove every panic from
+/// an accessor that can't panic, for tables with `#[total(no_panic)]`.
+fn no_panic(input: &Lut) -> proc_macro2::TokenStream {
+    if input.options.no_panic.is_some() {
+        quote::quote!(#[::no_panic::no_panic])
+    } else {
+        proc_macro2::TokenStream::new()
+    }
+}
+
+/// Wraps a lookup so that debug builds pass the parameters and the entry to the `log` crate or the
+/// callback of `#[trace]`, if requested.
+fn with_trace(input: &Lut, lookup: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let (_, callback) = match &input.options.trace {
+        Some(trace) => trace,
+        None => return lookup,
+    };
+    let name = match &input.form {
+        Form::Struct { ident, .. } => {
+            let name = ident.to_string();
+            quote::quote!(#name)
+        }
+        Form::Closure => quote::quote!(::core::concat!(::core::file!(), ":", ::core::line!())),
+    };
+    let idents = input.inputs.iter().map(|param| &param.ident);
+    let trace = match callback {
+        Some(callback) => quote::quote! {
+            let __trace = #callback;
+            __trace(#name, &[#(#idents),*], &__entry);
+        },
+        None => {
+            let format = format!(
+                "{{}}({}) = {{:?}}",
+                vec!["{}"; input.inputs.len()].join(", ")
+            );
+            quote::quote! {
+                ::log::trace!(target: "numeric_lut", #format, #name, #(#idents,)* __entry);
+            }
+        }
+    };
+    quote::quote!({
+        let __entry = { #lookup };
+        #[cfg(debug_assertions)]
+        {
+            #trace
+        }
+        __entry
+    })
+}
+
+/// Generates the implementation of `numeric_lut_runtime::LutN` for the item form, if requested.
+fn generate_runtime(input: &Lut, ident: &syn::Ident) -> proc_macro2::TokenStream {
+    if input.options.runtime.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+    let return_type = &input.return_type;
+    let rank = input.inputs.len();
+    let ranges = input.inputs.iter().map(|param| {
+        let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+        quote::quote!(#lo..=#hi)
+    });
+    let idents = input.inputs.iter().map(|param| &param.ident);
+    let idents = quote::quote!(#(#idents),*);
+    quote::quote! {
+        impl ::numeric_lut_runtime::LutN<#rank> for #ident {
+            type Entry = #return_type;
+
+            fn ranges(&self) -> [::core::ops::RangeInclusive<usize>; #rank] {
+                [#(#ranges),*]
+            }
+
+            fn get(&self, [#idents]: [usize; #rank]) -> #return_type {
+                #ident::get(#idents)
+            }
+        }
+    }
+}
+
+/// Generates the `try_get` function of the item form and its error type, if requested.
+fn generate_try_get(
+    input: &Lut,
+    vis: &syn::Visibility,
+    ident: &syn::Ident,
+) -> proc_macro2::TokenStream {
+    if input.options.try_get.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let error = quote::format_ident!("{}Error", ident);
+    let error_doc = format!(
+        "The error returned by `{}::try_get` for parameters outside of their ranges.",
+        ident
+    );
+    let lut_params = params(input.inputs.iter()).collect::<Vec<_>>();
+    let idents = input.inputs.iter().map(|param| &param.ident);
+    let checks = input.inputs.iter().map(|param| {
+        let name = &param.ident;
+        let parameter = name.to_string();
+        let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+        quote::quote! {
+            if !(#lo..=#hi).contains(&#name) {
+                return ::core::result::Result::Err(#error {
+                    parameter: #parameter,
+                    value: #name,
+                    range: #lo..=#hi,
+                });
+            }
+        }
+    });
+    let return_type = &input.return_type;
+    let no_panic = no_panic(input);
+    quote::quote! {
+        impl #ident {
+            /// Looks up the entry for the given parameters, or returns an error naming the first
+            /// parameter that is outside of its range.
+            #no_panic
+            #vis fn try_get(#(#lut_params),*) -> ::core::result::Result<#return_type, #error> {
+                #(#checks)*
+                ::core::result::Result::Ok(Self::get(#(#idents),*))
+            }
+        }
+
+        #[doc = #error_doc]
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        #vis struct #error {
+            /// The name of the parameter.
+            pub parameter: &'static str,
+            /// The value of the parameter.
+            pub value: usize,
+            /// The range of the parameter.
+            pub range: ::core::ops::RangeInclusive<usize>,
+        }
+
+        impl ::core::fmt::Display for #error {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(
+                    f,
+                    "`{}` must be in {}..={} but is {}",
+                    self.parameter,
+                    self.range.start(),
+                    self.range.end(),
+                    self.value
+                )
+            }
+        }
+
+        impl ::core::error::Error for #error {}
+    }
+}
+
+/// Generates the `get_or` function of the item form, if requested.
+fn generate_get_or(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.get_or.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let lut_params = params(input.inputs.iter());
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let ranges = input.inputs.iter().map(|param| {
+        let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+        quote::quote!((#lo..=#hi))
+    });
+    let return_type = &input.return_type;
+    let no_panic = no_panic(input);
+    quote::quote! {
+        /// Looks up the entry for the given parameters, or returns `default` if a parameter is
+        /// outside of its range.
+        #no_panic
+        #vis fn get_or(#(#lut_params,)* default: #return_type) -> #return_type {
+            if #(#ranges.contains(&#idents))&&* {
+                Self::get(#(#idents),*)
+            } else {
+                default
+            }
+        }
+    }
+}
+
+/// Generates the `transform_by` function of the item form, and `transform` for integer entries, if
+/// requested.
+///
+/// The buffer is processed in chunks of a cache line: the indices of a chunk are extracted before
+/// any of its entries are looked up, so that the loads of the entries don't wait on each other.
+fn generate_transform(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.transform.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let return_type = &input.return_type;
+    // Casting `usize` to itself trips clippy in the invoking crate.
+    let index = match preset::primitive_name(return_type).as_deref() {
+        Some("usize") => Some(quote::quote!(|&element| element)),
+        Some(name) if !name.starts_with('f') => Some(quote::quote!(|&element| element as usize)),
+        _ => None,
+    };
+    let transform = match index {
+        Some(index) => quote::quote! {
+            /// Replaces every element of the buffer with the entry for it, like to gamma-correct
+            /// a frame buffer in place.
+            #vis fn transform(buffer: &mut [#return_type]) {
+                Self::transform_by(buffer, #index);
+            }
+        },
+        None => proc_macro2::TokenStream::new(),
+    };
+    quote::quote! {
+        #transform
+
+        /// Replaces every element of the buffer with the entry for the parameter that `index`
+        /// extracts from it.
+        #vis fn transform_by(
+            buffer: &mut [#return_type],
+            mut index: impl FnMut(&#return_type) -> usize,
+        ) {
+            const __CHUNK: usize = {
+                let __size = ::core::mem::size_of::<#return_type>();
+                if __size == 0 || __size >= 64 {
+                    1
+                } else {
+                    64 / __size
+                }
+            };
+            for __chunk in buffer.chunks_mut(__CHUNK) {
+                let mut __indices = [0; __CHUNK];
+                for (__index, __element) in __indices.iter_mut().zip(__chunk.iter()) {
+                    *__index = index(__element);
+                }
+                for (__element, &__index) in __chunk.iter_mut().zip(__indices.iter()) {
+                    *__element = Self::get(__index);
+                }
+            }
+        }
+    }
+}
+
+/// Generates the `sample` function of the item form with `#[interpolate]`.
+///
+/// Every coordinate is mapped to a fractional position along its parameter, between the two values
+/// around it, and the entries at the corners of the cell around the coordinates are blended
+/// linearly, one parameter after the other.
+fn generate_interpolate(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    let interpolate = match &input.options.interpolate {
+        Some(interpolate) => interpolate,
+        None => return proc_macro2::TokenStream::new(),
+    };
+
+    let return_type = &input.return_type;
+    let (float, array) = interpolate_element(return_type).expect("checked by validate");
+    let positions = input.inputs.iter().enumerate().map(|(axis, param)| {
+        let ident = &param.ident;
+        let domain = interpolate
+            .domains
+            .iter()
+            .find(|domain| domain.param == *ident);
+        let periodic = domain.is_some_and(|domain| domain.periodic);
+        // The number of cells, which along a periodic parameter includes the one from the last
+        // value back to the first.
+        let cells = if periodic {
+            param.len()
+        } else {
+            param.len() - 1
+        };
+        let last = proc_macro2::Literal::f64_unsuffixed(cells as f64);
+        let position = match domain {
+            Some(Domain { start, end, .. }) => {
+                quote::quote!((#ident - (#start)) * (#last / ((#end) - (#start))))
+            }
+            None if param.lo == 0 => quote::quote!(#ident),
+            None => {
+                let lo = proc_macro2::Literal::f64_unsuffixed(param.lo as f64);
+                quote::quote!((#ident - #lo))
+            }
+        };
+        let min = cells - 1;
+        let index = quote::format_ident!("__i{}", axis);
+        let upper = quote::format_ident!("__j{}", axis);
+        let frac = quote::format_ident!("__t{}", axis);
+        if periodic {
+            // Floating point `floor` and `rem_euclid` need `std`, so the position is wrapped into
+            // the domain by subtracting the truncated number of periods instead.
+            let nan = match interpolate.extrapolate {
+                Extrapolate::None => quote::quote! {
+                    if __pos.is_nan() {
+                        return ::core::option::Option::None;
+                    }
+                },
+                Extrapolate::Clamp | Extrapolate::Linear => proc_macro2::TokenStream::new(),
+            };
+            return quote::quote! {
+                let __pos: #float = #position;
+                #nan
+                let __pos = __pos - (__pos / #last) as i64 as #float * #last;
+                let __pos = if __pos < 0.0 { __pos + #last } else { __pos };
+                let #index = (__pos as usize).min(#min);
+                let #upper = if #index == #min { 0 } else { #index + 1 };
+                let #frac = __pos - #index as #float;
+            };
+        }
+        // Positions before the domain saturate to the first cell when cast to `usize`, and the ones
+        // after it are kept in the last cell by `min`, so that linear extrapolation blends the
+        // entries at the edges with fractions outside of `0.0..=1.0`.
+        let outside = match interpolate.extrapolate {
+            Extrapolate::Clamp => quote::quote!(let __pos = __pos.clamp(0.0, #last);),
+            Extrapolate::Linear => proc_macro2::TokenStream::new(),
+            Extrapolate::None => quote::quote! {
+                if !(0.0..=#last).contains(&__pos) {
+                    return ::core::option::Option::None;
+                }
+            },
+        };
+        quote::quote! {
+            let __pos: #float = #position;
+            #outside
+            let #index = (__pos as usize).min(#min);
+            let #upper = #index + 1;
+            let #frac = __pos - #index as #float;
+        }
+    });
+    let lerp = if array {
+        quote::quote! {
+            let mut __a = __a;
+            for (__a, __b) in __a.iter_mut().zip(__b.iter()) {
+                *__a += (*__b - *__a) * __t;
+            }
+            __a
+        }
+    } else {
+        quote::quote!(__a + (__b - __a) * __t)
+    };
+    let blended = interpolate_corners(input, &mut Vec::new());
+    let coordinates = input.inputs.iter().map(|param| {
+        let ident = &param.ident;
+        quote::quote!(#ident: #float)
+    });
+    let (sample_type, blended, doc) = match interpolate.extrapolate {
+        Extrapolate::Clamp => (
+            quote::quote!(#return_type),
+            blended,
+            " Coordinates outside of the domain are clamped to it.",
+        ),
+        Extrapolate::Linear => (
+            quote::quote!(#return_type),
+            blended,
+            " Outside of the domain, the entries are extended with the slopes at its edges.",
+        ),
+        Extrapolate::None => (
+            quote::quote!(::core::option::Option<#return_type>),
+            quote::quote!(::core::option::Option::Some(#blended)),
+            " Returns `None` for coordinates outside of the domain.",
+        ),
+    };
+    quote::quote! {
+        /// Samples the table at the given coordinates, interpolating linearly between the entries
+        /// around them.
+        #[doc = #doc]
+        #vis fn sample(#(#coordinates),*) -> #sample_type {
+            fn __lerp(__a: #return_type, __b: #return_type, __t: #float) -> #return_type {
+                #lerp
+            }
+            #(#positions)*
+            #blended
+        }
+    }
+}
+
+/// Generates the blend of the entries at the corners of the cell that `sample` interpolates in,
+/// along the parameters after the ones whose sides of the cell are already picked.
+fn interpolate_corners(input: &Lut, picked: &mut Vec<bool>) -> proc_macro2::TokenStream {
+    let axis = picked.len();
+    if axis == input.inputs.len() {
+        let values =
+            input
+                .inputs
+                .iter()
+                .zip(picked.iter())
+                .enumerate()
+                .map(|(axis, (param, &upper))| {
+                    let index = if upper {
+                        quote::format_ident!("__j{}", axis)
+                    } else {
+                        quote::format_ident!("__i{}", axis)
+                    };
+                    // Adding zero trips clippy in the invoking crate.
+                    match param.lo {
+                        0 => quote::quote!(#index),
+                        lo => quote::quote!(#index + #lo),
+                    }
+                });
+        return quote::quote!(Self::get(#(#values),*));
+    }
+    let frac = quote::format_ident!("__t{}", axis);
+    picked.push(false);
+    let lower = interpolate_corners(input, picked);
+    picked.pop();
+    picked.push(true);
+    let upper = interpolate_corners(input, picked);
+    picked.pop();
+    quote::quote!(__lerp(#lower, #upper, #frac))
+}
+
+/// The float type that `#[interpolate]` blends entries of the given type in, and whether the
+/// entries are arrays of it.
+fn interpolate_element(return_type: &syn::Type) -> Option<(syn::Type, bool)> {
+    let (element, array) = match return_type {
+        syn::Type::Array(array) => (&*array.elem, true),
+        other => (other, false),
+    };
+    match preset::primitive_name(element).as_deref() {
+        Some("f32") | Some("f64") => Some((element.clone(), array)),
+        _ => None,
+    }
+}
+
+/// Parses the arguments of `#[interpolate(..)]`: the domains of parameters, like `x = 0.0..=1.0` or
+/// `angle = periodic(0.0..360.0)`, and `extrapolate = ..`.
+fn parse_interpolate(
+    span: proc_macro2::Span,
+    input: syn::parse::ParseStream,
+) -> syn::Result<Interpolate> {
+    use syn::spanned::Spanned;
+    let mut interpolate = Interpolate {
+        span,
+        domains: Vec::new(),
+        extrapolate: Extrapolate::Clamp,
+    };
+    let mut extrapolate = None;
+    while !input.is_empty() {
+        let ident = input.parse::<syn::Ident>()?;
+        input.parse::<syn::Token![=]>()?;
+        if ident == "extrapolate" {
+            if extrapolate.is_some() {
+                return Err(syn::Error::new(ident.span(), "duplicate `extrapolate`"));
+            }
+            let policy = input.parse::<syn::Ident>()?;
+            interpolate.extrapolate = match policy.to_string().as_str() {
+                "clamp" => Extrapolate::Clamp,
+                "linear" => Extrapolate::Linear,
+                "none" => Extrapolate::None,
+                _ => {
+                    return Err(syn::Error::new(
+                        policy.span(),
+                        "expected `clamp`, `linear` or `none`",
+                    ))
+                }
+            };
+            extrapolate = Some(ident);
+        } else if input.peek(syn::Ident) && input.peek2(syn::token::Paren) {
+            let periodic = input.parse::<syn::Ident>()?;
+            if periodic != "periodic" {
+                return Err(syn::Error::new(periodic.span(), "expected `periodic`"));
+            }
+            let content;
+            syn::parenthesized!(content in input);
+            match content.parse::<syn::ExprRange>()? {
+                syn::ExprRange {
+                    from: Some(start),
+                    limits: syn::RangeLimits::HalfOpen(_),
+                    to: Some(end),
+                    ..
+                } => interpolate.domains.push(Domain {
+                    param: ident,
+                    start: *start,
+                    end: *end,
+                    periodic: true,
+                }),
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "expected an exclusive range of coordinates (e.g. `angle = periodic(0.0..360.0)`)",
+                    ))
+                }
+            }
+        } else {
+            match input.parse::<syn::ExprRange>()? {
+                syn::ExprRange {
+                    from: Some(start),
+                    limits: syn::RangeLimits::Closed(_),
+                    to: Some(end),
+                    ..
+                } => interpolate.domains.push(Domain {
+                    param: ident,
+                    start: *start,
+                    end: *end,
+                    periodic: false,
+                }),
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "expected an inclusive range of coordinates (e.g. `x = 0.0..=1.0`)",
+                    ))
+                }
+            }
+        }
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<syn::Token![,]>()?;
+    }
+    Ok(interpolate)
+}
+
+/// Generates a place expression for the entry of `__LUT` selected by the parameters, for tables
+/// with the default layout and at least one parameter.
+fn dense_place(input: &Lut, table: &Table) -> proc_macro2::TokenStream {
+    let options = &input.options;
+    let stored = input.stored();
+    let row = row_access(stored[0], options, table.shards, quote::quote!(__LUT));
+    stored.iter().skip(1).fold(row, |expr, param| {
+        let index = param.index(options);
+        quote::quote!(#expr[#index])
+    })
+}
+
+/// Generates an implementation of `Index` that reads from the table of the item form, if requested.
+fn generate_index(input: &Lut, ident: &syn::Ident, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.index.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let place = dense_place(input, table);
+    let pattern = tuple(input.inputs.iter().map(|param| &param.ident));
+    let index_type = tuple(input.inputs.iter().map(|_| quote::quote!(usize)));
+    let return_type = &input.return_type;
+    quote::quote! {
+        impl ::core::ops::Index<#index_type> for #ident {
+            type Output = #return_type;
+
+            fn index(&self, #pattern: #index_type) -> &#return_type {
+                let __LUT = Self::TABLE;
+                &#place
+            }
+        }
+    }
+}
+
+/// Generates the documentation that the item form appends to the attributes of the struct, which
+/// describes the parameters, the size of the table and the expression of the entries.
+fn generate_doc(input: &Lut, attrs: &[syn::Attribute], table: &Table) -> proc_macro2::TokenStream {
+    let mut lines = Vec::new();
+    if attrs.iter().any(|attr| attr.path.is_ident("doc")) {
+        lines.push(String::new());
+    }
+    lines.push("# Table".to_owned());
+    lines.push(String::new());
+    if !input.inputs.is_empty() {
+        lines.push("| Parameter | Domain | Out of range |".to_owned());
+        lines.push("|---|---|---|".to_owned());
+        for param in input.inputs.iter() {
+            let domain = if param.exclusive_end {
+                format!("{}..{}", param.lo, param.hi)
+            } else {
+                format!("{}..={}", param.lo, param.hi)
+            };
+            let out_of_range = match param.policy {
+                _ if input.options.mask => "masked",
+                Policy::Clamp(_) => "clamped",
+                Policy::Wrap(_) => "wrapped",
+                Policy::Panic if input.options.fallback.is_some() => "calls the fallback",
+                Policy::Panic => "panics",
+            };
+            lines.push(format!(
+                "| `{}` | `{}` | {} |",
+                param.ident, domain, out_of_range
+            ));
+        }
+        lines.push(String::new());
+    }
+    let return_type = &input.return_type;
+    let (element_type, element_size) = match (input.options.f16, &input.options.codec) {
+        (Some(_), _) => ("f16".to_owned(), Some(2)),
+        (None, Some(Codec { stored, .. })) => (
+            quote::quote!(#stored).to_string(),
+            metadata::primitive_size(stored),
+        ),
+        (None, None) => (
+            quote::quote!(#return_type).to_string(),
+            metadata::primitive_size(return_type),
+        ),
+    };
+    let entries = input.inputs.iter().map(Param::len).product::<usize>();
+    let storage = match (table.strategy, element_size) {
+        (Strategy::Compute, _) => "and computes them on every lookup".to_owned(),
+        (Strategy::Match, _) => "and looks them up with a `match`".to_owned(),
+        (Strategy::Table, _) if table.deduplicated => {
+            "and stores every unique row of them once".to_owned()
+        }
+        (Strategy::Table, size) => {
+            // Padded tables, like those with `#[mask]`, store more entries than there are.
+            let stored = match table.len {
+                len if len == entries => "stores all of them".to_owned(),
+                len if len > entries => format!("pads them to {}", len),
+                len => format!("stores {} of them", len),
+            };
+            match size {
+                Some(size) => format!("and {} in {} bytes", stored, table.len * size),
+                None => format!("and {}", stored),
+            }
+        }
+    };
+    lines.push(format!(
+        "The table has {} entries of `{}`, {}.",
+        entries, element_type, storage
+    ));
+    lines.push(String::new());
+    match &input.options.built {
+        Some(name) => lines.push(format!(
+            "The entries were evaluated by `lut_build!(\"{}\", ..)` in the build script.",
+            name.value()
+        )),
+        None => {
+            let body = &input.body;
+            lines.push("Every entry is the value of:".to_owned());
+            lines.push(String::new());
+            lines.push("```text".to_owned());
+            lines.push(quote::quote!(#body).to_string());
+            lines.push("```".to_owned());
+        }
+    }
+    // Like the lines of doc comments, every line starts with the space after `///`.
+    let lines = lines.iter().map(|line| match line.as_str() {
+        "" => String::new(),
+        line => format!(" {}", line),
+    });
+    quote::quote!(#(#[doc = #lines])*)
+}
+
+/// Generates implementations of `Debug` and `Display` that render the contents of the table of the
+/// item form, if requested.
+fn generate_dump(input: &Lut, ident: &syn::Ident) -> proc_macro2::TokenStream {
+    if input.options.dump.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let name = ident.to_string();
+    let ranges = input.inputs.iter().map(|param| {
+        let end = if param.exclusive_end { ".." } else { "..=" };
+        format!("{} @ {}{}{}", param.ident, param.lo, end, param.hi)
+    });
+    let header = format!("{}({})", name, ranges.collect::<Vec<_>>().join(", "));
+
+    // `Debug` shows the ranges and the first entries in storage order.
+    let fields = input.inputs.iter().map(|param| {
+        let field = param.ident.to_string();
+        let (lo, end) = (param.lo, param.lo + param.len());
+        quote::quote!(.field(#field, &(#lo..#end)))
+    });
+    let len = input.inputs.iter().map(Param::len).product::<usize>();
+    let entries = (0..len.min(DUMP_ENTRIES)).map(|k| {
+        let mut rest = k;
+        let mut values = input
+            .inputs
+            .iter()
+            .rev()
+            .map(|param| {
+                let value = param.lo + rest % param.len();
+                rest /= param.len();
+                value
+            })
+            .collect::<Vec<_>>();
+        values.reverse();
+        quote::quote!(.entry(&#ident::get(#(#values),*)))
+    });
+    let finish = if len > DUMP_ENTRIES {
+        quote::quote!(finish_non_exhaustive)
+    } else {
+        quote::quote!(finish)
+    };
+
+    // `Display` shows a grid over the last two parameters for the first values of the others.
+    let shown = |param: &Param| {
+        let values = (param.lo..param.lo + param.len().min(DUMP_VALUES)).collect::<Vec<_>>();
+        let more = if param.len() > DUMP_VALUES {
+            quote::quote!(f.write_str(" ...")?;)
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        (values, more)
+    };
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let rank = input.inputs.len();
+    let display = match rank {
+        0 => quote::quote! {
+            f.write_str(" = ")?;
+            write!(f, "{:?}", Self::get())?;
+        },
+        _ => {
+            let row = &input.inputs[if rank == 1 { 0 } else { rank - 2 }];
+            let row_ident = &row.ident;
+            let (rows, rows_more) = shown(row);
+            let rows_len = rows.len();
+            let rows_more = if rows_more.is_empty() {
+                rows_more
+            } else {
+                quote::quote! {
+                    f.write_str("\n")?;
+                    __cell(f, __label, format_args!("..."))?;
+                }
+            };
+            let columns = if rank == 1 {
+                quote::quote! {
+                    let mut __width = 0;
+                    for &#row_ident in &__ROWS {
+                        __width = __width.max(__measure(format_args!("{:?}", Self::get(#(#idents),*))));
+                    }
+                    let __label = __ROWS
+                        .iter()
+                        .map(|row| __measure(format_args!("{}", row)))
+                        .max()
+                        .unwrap_or(0);
+                    for &#row_ident in &__ROWS {
+                        f.write_str("\n")?;
+                        __cell(f, __label, format_args!("{}", #row_ident))?;
+                        f.write_str(" | ")?;
+                        __cell(f, __width, format_args!("{:?}", Self::get(#(#idents),*)))?;
+                    }
+                }
+            } else {
+                let column = &input.inputs[rank - 1];
+                let column_ident = &column.ident;
+                let corner = format!("{}\\{}", row_ident, column_ident);
+                let (columns, columns_more) = shown(column);
+                let columns_len = columns.len();
+                quote::quote! {
+                    const __COLUMNS: [usize; #columns_len] = [#(#columns),*];
+                    let mut __widths = [0; #columns_len];
+                    for (__width, &#column_ident) in __widths.iter_mut().zip(&__COLUMNS) {
+                        *__width = __measure(format_args!("{}", #column_ident));
+                        for &#row_ident in &__ROWS {
+                            let __entry = __measure(format_args!("{:?}", Self::get(#(#idents),*)));
+                            *__width = (*__width).max(__entry);
+                        }
+                    }
+                    let __label = __ROWS
+                        .iter()
+                        .map(|row| __measure(format_args!("{}", row)))
+                        .fold(__measure(format_args!(#corner)), usize::max);
+                    f.write_str("\n")?;
+                    __cell(f, __label, format_args!(#corner))?;
+                    f.write_str(" |")?;
+                    for (&__width, &#column_ident) in __widths.iter().zip(&__COLUMNS) {
+                        f.write_str(" ")?;
+                        __cell(f, __width, format_args!("{}", #column_ident))?;
+                    }
+                    #columns_more
+                    for &#row_ident in &__ROWS {
+                        f.write_str("\n")?;
+                        __cell(f, __label, format_args!("{}", #row_ident))?;
+                        f.write_str(" |")?;
+                        for (&__width, &#column_ident) in __widths.iter().zip(&__COLUMNS) {
+                            f.write_str(" ")?;
+                            __cell(f, __width, format_args!("{:?}", Self::get(#(#idents),*)))?;
+                        }
+                        #columns_more
+                    }
+                }
+            };
+            let grid = quote::quote!({
+                const __ROWS: [usize; #rows_len] = [#(#rows),*];
+                #columns
+                #rows_more
+            });
+
+            let leading = input.inputs.iter().take(rank.saturating_sub(2));
+            let heading = leading
+                .clone()
+                .map(|param| format!("{} = {{}}", param.ident))
+                .collect::<Vec<_>>()
+                .join(", ")
+                + ":";
+            let leading_idents = leading.clone().map(|param| &param.ident);
+            let grid = if rank > 2 {
+                quote::quote! {
+                    f.write_str("\n\n")?;
+                    write!(f, #heading, #(#leading_idents),*)?;
+                    #grid
+                }
+            } else {
+                grid
+            };
+            leading.rev().fold(grid, |inner, param| {
+                let ident = &param.ident;
+                let (values, more) = shown(param);
+                let more = if more.is_empty() {
+                    more
+                } else {
+                    quote::quote!(f.write_str("\n\n...")?;)
+                };
+                quote::quote! {
+                    for &#ident in &[#(#values),*] {
+                        #inner
+                    }
+                    #more
+                }
+            })
+        }
+    };
+
+    quote::quote! {
+        impl ::core::fmt::Debug for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                struct __Entries;
+
+                impl ::core::fmt::Debug for __Entries {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.debug_list()#(#entries)*.#finish()
+                    }
+                }
+
+                f.debug_struct(#name)#(#fields)*.field("entries", &__Entries).finish()
+            }
+        }
+
+        impl ::core::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                /// Counts the characters written to it.
+                struct __Counter(usize);
+
+                impl ::core::fmt::Write for __Counter {
+                    fn write_str(&mut self, s: &str) -> ::core::fmt::Result {
+                        self.0 += s.chars().count();
+                        ::core::result::Result::Ok(())
+                    }
+                }
+
+                fn __measure(args: ::core::fmt::Arguments<'_>) -> usize {
+                    let mut counter = __Counter(0);
+                    let _ = ::core::fmt::Write::write_fmt(&mut counter, args);
+                    counter.0
+                }
+
+                /// Writes the arguments right-aligned in a cell of the given width.
+                fn __cell(
+                    f: &mut ::core::fmt::Formatter<'_>,
+                    width: usize,
+                    args: ::core::fmt::Arguments<'_>,
+                ) -> ::core::fmt::Result {
+                    for _ in __measure(args)..width {
+                        f.write_str(" ")?;
+                    }
+                    f.write_fmt(args)
+                }
+
+                f.write_str(#header)?;
+                #display
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
+
+/// Generates the `write_csv` function of the item form, if requested.
+fn generate_csv(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.csv.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let header = input
+        .inputs
+        .iter()
+        .map(|param| param.ident.to_string())
+        .chain(std::iter::once("value".to_owned()))
+        .collect::<Vec<_>>()
+        .join(",")
+        + "\n";
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let row = quote::quote! {
+        #(write!(writer, "{},", #idents)?;)*
+        let __value = Self::get(#(#idents),*).to_string();
+        if __value.contains(&[',', '"', '\n', '\r'][..]) {
+            writeln!(writer, "\"{}\"", __value.replace('"', "\"\""))?;
+        } else {
+            writeln!(writer, "{}", __value)?;
+        }
+    };
+    let rows = input.inputs.iter().rev().fold(row, |inner, param| {
+        let ident = &param.ident;
+        let (lo, end) = (param.lo, param.lo + param.len());
+        quote::quote! {
+            for #ident in #lo..#end {
+                #inner
+            }
+        }
+    });
+    quote::quote! {
+        /// Writes all entries as CSV, with a column for every parameter followed by a `value`
+        /// column.
+        #vis fn write_csv<W: ::std::io::Write + ?Sized>(writer: &mut W) -> ::std::io::Result<()> {
+            writer.write_all(#header.as_bytes())?;
+            #rows
+            ::core::result::Result::Ok(())
+        }
+    }
+}
+
+/// Generates the `sample` function of the item form, if requested.
+fn generate_sample(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    if input.options.sample.is_none() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let ranges = input.inputs.iter().map(|param| {
+        let (lo, end) = (param.lo, param.lo + param.len());
+        quote::quote!(#lo..#end)
+    });
+    let index_type = tuple(input.inputs.iter().map(|_| quote::quote!(usize)));
+    let indices = tuple(&idents);
+    let return_type = &input.return_type;
+    quote::quote! {
+        /// Picks an entry uniformly at random, returning its parameters and the entry.
+        #vis fn sample<R: ::rand::Rng + ?Sized>(rng: &mut R) -> (#index_type, #return_type) {
+            #(let #idents = rng.gen_range(#ranges);)*
+            (#indices, Self::get(#(#idents),*))
+        }
+    }
+}
+
+/// Generates the field tables and field lookup functions of the item form, if requested.
+fn generate_soa(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    let fields = match &input.options.soa {
+        Some((_, fields)) => fields,
+        None => return proc_macro2::TokenStream::new(),
+    };
+
+    let lut_params = params(input.inputs.iter()).collect::<Vec<_>>();
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let entry_slices = table.entry_slices(&quote::quote!(Self::TABLE));
+    let access = dense_access(input.stored().into_iter(), &input.options);
+    let items = fields.iter().map(|field| {
+        let name = field.ident.as_ref().expect("parsed as a named field");
+        let field_ty = &field.ty;
+        let get = quote::format_ident!("get_{}", name);
+        let doc = format!(
+            "Looks up the `{}` field of the entry for the given parameters.",
+            name
+        );
+        if table.strategy != Strategy::Table {
+            return quote::quote! {
+                #[doc = #doc]
+                #vis fn #get(#(#lut_params),*) -> #field_ty {
+                    Self::get(#(#idents),*).#name
+                }
+            };
+        }
+
+        let field_table = soa_table(name);
+        let column = column_table(
+            input,
+            &entry_slices,
+            &field_table,
+            &quote::quote!(#field_ty),
+            |entry| quote::quote!(#entry.#name),
+        );
+        quote::quote! {
+            #column
+
+            #[doc = #doc]
+            #vis fn #get(#(#lut_params),*) -> #field_ty {
+                let __LUT = Self::#field_table;
+                #access
+            }
+        }
+    });
+    quote::quote!(#(#items)*)
+}
+
+/// Generates an associated constant holding a table with one column of the entries, which is
+/// derived from each entry by `project`.
+fn column_table(
+    input: &Lut,
+    entry_slices: &[proc_macro2::TokenStream],
+    name: &syn::Ident,
+    column_ty: &proc_macro2::TokenStream,
+    project: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let return_type = &input.return_type;
+    let (ty, init) = input.stored().into_iter().rev().fold(
+        (column_ty.clone(), project(quote::quote!(__shards[0][0]))),
+        |(ty, init), param| {
+            let count = param.storage_len(&input.options);
+            (quote::quote!([#ty; #count]), quote::quote!([#init; #count]))
+        },
+    );
+    let flat = (1..input.inputs.len()).fold(
+        quote::quote!(&mut __out),
+        |expr, _| quote::quote!(#expr.as_flattened_mut()),
+    );
+    let column = project(quote::quote!(__shards[__s][__i]));
+    quote::quote! {
+        const #name: &'static #ty = &{
+            let __shards: &[&[#return_type]] = &[#(#entry_slices),*];
+            let mut __out = #init;
+            let __flat: &mut [#column_ty] = #flat;
+            let mut __k = 0;
+            let mut __s = 0;
+            while __s < __shards.len() {
+                let mut __i = 0;
+                while __i < __shards[__s].len() {
+                    __flat[__k] = #column;
+                    __k += 1;
+                    __i += 1;
+                }
+                __s += 1;
+            }
+            __out
+        };
+    }
+}
+
+/// Generates the lookups of the single fields of entries that are packed with `#[bitfields(..)]`.
+fn generate_bitfields(input: &Lut, vis: &syn::Visibility) -> proc_macro2::TokenStream {
+    let fields = match input.options.codec.as_ref().map(|codec| &codec.conversion) {
+        Some(Conversion::Bitfields(fields)) => fields,
+        _ => return proc_macro2::TokenStream::new(),
+    };
+
+    let lut_params = params(input.inputs.iter()).collect::<Vec<_>>();
+    let idents = input
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    // Only the bits of the field survive the optimizer after unpacking the entire entry.
+    let items = fields.iter().map(|field| {
+        let (name, field_ty) = (&field.ident, &field.ty);
+        let get = quote::format_ident!("get_{}", name);
+        let doc = format!(
+            "Looks up the `{}` field of the entry for the given parameters.",
+            name
+        );
+        quote::quote! {
+            #[doc = #doc]
+            #[inline]
+            #vis fn #get(#(#lut_params),*) -> #field_ty {
+                Self::get(#(#idents),*).#name
+            }
+        }
+    });
+    quote::quote!(#(#items)*)
+}
+
+/// Generates the tables and lookups of the two halves of 128-bit entries, for `#[split]`.
+fn generate_split(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.options.split.is_none() || table.strategy != Strategy::Table {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let lut_params = params(input.inputs.iter()).collect::<Vec<_>>();
+    let entry_slices = table.entry_slices(&quote::quote!(Self::TABLE));
+    let access = dense_access(input.stored().into_iter(), &input.options);
+    let u64_ty = quote::quote!(u64);
+    let halves = [("hi", "high"), ("lo", "low")];
+    let items = halves.iter().enumerate().map(|(i, (half, word))| {
+        let name = quote::format_ident!("TABLE_{}", half.to_uppercase());
+        let get = quote::format_ident!("get_{}", half);
+        let column = column_table(input, &entry_slices, &name, &u64_ty, |entry| {
+            let (hi, lo) = split_halves(&entry, &input.return_type);
+            [hi, lo][i].clone()
+        });
+        let doc = format!(
+            "Looks up the {} 64 bits of the entry for the given parameters.",
+            word
+        );
+        quote::quote! {
+            #column
+
+            #[doc = #doc]
+            #vis fn #get(#(#lut_params),*) -> u64 {
+                let __LUT = Self::#name;
+                #access
+            }
+        }
+    });
+    quote::quote!(#(#items)*)
+}
+
+/// Generates expressions for the high and low halves of a 128-bit entry as `u64`s.
+fn split_halves(
+    entry: &proc_macro2::TokenStream,
+    ty: &syn::Type,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let bits = match split_signed(ty) {
+        Some(true) => quote::quote!((#entry as u128)),
+        _ => entry.clone(),
+    };
+    (
+        quote::quote!((#bits >> 64) as u64),
+        quote::quote!(#bits as u64),
+    )
+}
+
+/// Generates an expression for the 128-bit entry with the given `u64` halves.
+fn split_join(
+    hi: &proc_macro2::TokenStream,
+    lo: &proc_macro2::TokenStream,
+    ty: &syn::Type,
+) -> proc_macro2::TokenStream {
+    let bits = quote::quote!(((#hi as u128) << 64 | #lo as u128));
+    match split_signed(ty) {
+        Some(true) => quote::quote!((#bits as i128)),
+        _ => bits,
+    }
+}
+
+/// Whether the type is `i128` (`Some(true)`) or `u128` (`Some(false)`), for `#[split]`.
+fn split_signed(ty: &syn::Type) -> Option<bool> {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            match path.get_ident()?.to_string().as_str() {
+                "i128" => Some(true),
+                "u128" => Some(false),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The name of the associated constant holding the table of the given field.
+fn soa_table(field: &syn::Ident) -> syn::Ident {
+    let name = field.to_string();
+    let name = name.trim_start_matches("r#").to_uppercase();
+    quote::format_ident!("TABLE_{}", name)
+}
+
+/// Generates the `fix` function of the item form, if there is more than one parameter.
+fn generate_fix(input: &Lut, vis: &syn::Visibility, table: &Table) -> proc_macro2::TokenStream {
+    if input.inputs.len() < 2 {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let first = &input.inputs[0];
+    let first_ident = &first.ident;
+    let rest = || input.inputs.iter().skip(1);
+    let rest_params = params(rest());
+    let rest_types = rest().map(|_| quote::quote!(usize));
+    let return_type = &input.return_type;
+
+    let lookup = match input.options.layout {
+        Layout::Dense
+            if table.strategy == Strategy::Table
+                && input.options.soa.is_none()
+                && input.options.split.is_none()
+                && input.options.f16.is_none()
+                && input.options.codec.is_none()
+                && input.options.patchable.is_none()
+                && input.options.indirect.is_none()
+                && input.options.fallback.is_none()
+                && input.inputs.iter().all(|param| param.contiguous.is_none()) =>
+        {
+            let row = row_access(
+                first,
+                &input.options,
+                table.shards,
+                quote::quote!(Self::TABLE),
+            );
+            let access = dense_access(rest(), &input.options);
+            quote::quote! {
+                let __LUT = &#row;
+                move |#(#rest_params),*| #access
+            }
+        }
+        _ => {
+            let idents = input.inputs.iter().map(|param| &param.ident);
+            quote::quote!(move |#(#rest_params),*| Self::get(#(#idents),*))
+        }
+    };
+
+    quote::quote! {
+        /// Fixes the first parameter, returning a lookup over the remaining parameters.
+        #vis fn fix(#first_ident: usize) -> impl Copy + Fn(#(#rest_types),*) -> #return_type {
+            #lookup
+        }
+    }
+}
+
+fn generate_dense(input: &Lut) -> Table {
+    let options = &input.options;
+    let body = body_tokens(input);
+    let return_type = &input.return_type;
+    let rank = input.inputs.len();
+    let len = input
+        .inputs
+        .iter()
+        .map(|p| p.storage_len(options))
+        .product();
+
+    let strategy = options.strategy(len);
+    let element_type = input.element_type();
+    let stored = input.stored();
+    let first = match stored.first().copied() {
+        Some(_) if strategy == Strategy::Compute && options.heap.is_some() => {
+            let flat = flat_index(input.inputs.iter());
+            let checks = compute_checks(input);
+            return Table {
+                ty: proc_macro2::TokenStream::new(),
+                data: proc_macro2::TokenStream::new(),
+                access: input.load(quote::quote!({
+                    #(#checks)*
+                    Self::__heap()[#flat]
+                })),
+                rank,
+                len,
+                items: proc_macro2::TokenStream::new(),
+                shards: None,
+                strategy,
+                deduplicated: false,
+            };
+        }
+        Some(_) if strategy == Strategy::Compute => {
+            return Table {
+                ty: proc_macro2::TokenStream::new(),
+                data: proc_macro2::TokenStream::new(),
+                access: match (options.f16, &options.codec) {
+                    // Computed entries are rounded like stored ones, so that they don't depend on
+                    // the strategy.
+                    (Some(_), _) => {
+                        let entry = generate_compute(input, &body);
+                        input.load(quote::quote!(::half::f16::from_f32(#entry)))
+                    }
+                    (None, Some(codec)) => {
+                        let entry = generate_compute(input, &body);
+                        input.load(codec.encode(entry, return_type))
+                    }
+                    (None, None) => generate_compute(input, &body),
+                },
+                rank,
+                len,
+                items: proc_macro2::TokenStream::new(),
+                shards: None,
+                strategy,
+                deduplicated: false,
+            };
+        }
+        Some(first) => first,
+        None => {
+            // The single entry is stored in an array too, so that it is read through a reference
+            // like the entries of other tables.
+            let entry = generate_entry(input, &[], &body);
+            return Table {
+                ty: quote::quote!([#element_type; 1]),
+                data: quote::quote!([#entry]),
+                access: input.load(quote::quote!(__LUT[0])),
+                rank: 1,
+                len,
+                items: proc_macro2::TokenStream::new(),
+                shards: None,
+                strategy: Strategy::Table,
+                deduplicated: false,
+            };
+        }
+    };
+
+    let rest = stored.iter().skip(1).copied().collect::<Vec<_>>();
+    let row_ty = rest.iter().rev().fold(element_type, |ty, param| {
+        let count = param.storage_len(options);
+        quote::quote!([#ty; #count])
+    });
+    let row_count = first.storage_len(options);
+    let row_len = len / row_count;
+    let rows = (0..row_count).map(|i| {
+        let mut bindings = vec![(&first.ident, first.lo + i % first.len())];
+        generate_arrays(input, &rest, &mut bindings, &body)
+    });
+
+    let shard_rows = options
+        .shard_rows(row_len)
+        .filter(|&rows| rows < row_count && !is_inferred(return_type))
+        .filter(|_| !input.stores_in_const());
+    let lint = options.const_eval.lint_attr();
+    let (ty, data, items, shards) = match shard_rows {
+        None => {
+            let ty = quote::quote!([#row_ty; #row_count]);
+            let data = quote::quote!([#(#rows),*]);
+            (ty, data, proc_macro2::TokenStream::new(), None)
+        }
+        Some(shard_rows) => {
+            let rows = rows.collect::<Vec<_>>();
+            let shards = rows.chunks(shard_rows).enumerate().map(|(k, rows)| {
+                let ident = quote::format_ident!("__LUT_{}", k);
+                let count = rows.len();
+                (
+                    quote::quote!(#lint static #ident: [#row_ty; #count] = [#(#rows),*];),
+                    quote::quote!(&#ident),
+                )
+            });
+            let (items, refs): (Vec<_>, Vec<_>) = shards.unzip();
+            let count = refs.len();
+            let ty = quote::quote!([&'static [#row_ty]; #count]);
+            let data = quote::quote!([#(#refs),*]);
+            let shards = Shards {
+                count,
+                rows: shard_rows,
+            };
+            (ty, data, quote::quote!(#(#items)*), Some(shards))
+        }
+    };
+
+    let access = match strategy {
+        Strategy::Table => {
+            let row = row_access(first, options, shards, quote::quote!(__LUT));
+            rest.iter().fold(row, |expr, param| {
+                let index = param.index(options);
+                quote::quote!(#expr[#index])
+            })
+        }
+        Strategy::Match => generate_match(input, &body, |entry| quote::quote!(const #entry), None),
+        Strategy::Compute => unreachable!("computed tables are returned before the rows are built"),
+    };
+    let access = input.load(access);
+
+    Table {
+        ty,
+        data,
+        access,
+        rank,
+        len,
+        items,
+        shards,
+        strategy,
+        deduplicated: false,
+    }
+}
+
+/// Generates a block that checks the parameters and evaluates the body with them.
+///
+/// With `#[mask]`, the parameters are wrapped around like for table lookups instead.
+fn generate_compute(input: &Lut, body: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let checks = compute_checks(input);
+    quote::quote!({
+        #(#checks)*
+        #body
+    })
+}
+
+/// Generates statements that check the parameters, or move them into their ranges according to
+/// their policies.
+fn compute_checks(input: &Lut) -> Vec<proc_macro2::TokenStream> {
+    let options = &input.options;
+    input
+        .inputs
+        .iter()
+        .map(|param| {
+            let ident = &param.ident;
+            let (lo, len) = (param.lo, param.len());
+            if options.mask {
+                let index = param.index(options);
+                let index = if param.storage_len(options) == len {
+                    index
+                } else {
+                    quote::quote!((#index) % #len)
+                };
+                let value = if lo == 0 {
+                    index
+                } else {
+                    quote::quote!(#lo + (#index))
+                };
+                quote::quote!(let #ident = #value;)
+            } else if let Some(value) = param.in_range(options) {
+                quote::quote!(let #ident = #value;)
+            } else {
+                let hi = lo + len - 1;
+                quote::quote! {
+                    assert!(
+                        (#lo..=#hi).contains(&#ident),
+                        "index out of bounds: `{}` must be in {}..={} but is {}",
+                        stringify!(#ident),
+                        #lo,
+                        #hi,
+                        #ident,
+                    );
+                }
+            }
+        })
+        .collect()
+}
+
+/// Generates a `match` on the parameters with one arm per entry, which `arm` generates from the
+/// expression of the entry, and an arm that evaluates `outside` (or panics) for other parameters.
+///
+/// With `#[mask]`, the indices are matched instead, and the last entry doubles as the catch-all arm
+/// so that the lookup still contains no panicking branches.
+fn generate_match(
+    input: &Lut,
+    body: &proc_macro2::TokenStream,
+    arm: impl Fn(proc_macro2::TokenStream) -> proc_macro2::TokenStream,
+    outside: Option<proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    let options = &input.options;
+    let scrutinees = input.inputs.iter().map(|param| {
+        if options.mask {
+            param.index(options)
+        } else {
+            let ident = &param.ident;
+            param
+                .in_range(options)
+                .unwrap_or_else(|| quote::quote!(#ident))
+        }
+    });
+    let scrutinee = tuple(scrutinees);
+
+    // Every combination of indices into the stored dimensions, in memory order.
+    let points = input.inputs.iter().fold(vec![Vec::new()], |points, param| {
+        points
+            .into_iter()
+            .flat_map(|point| {
+                (0..param.storage_len(options)).map(move |i| {
+                    let mut point = point.clone();
+                    point.push(i);
+                    point
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    let count = points.len();
+    let arms = points.iter().enumerate().map(|(n, point)| {
+        let bindings = input
+            .inputs
+            .iter()
+            .zip(point)
+            .map(|(param, &i)| (&param.ident, param.lo + i % param.len()))
+            .collect::<Vec<_>>();
+        let entry = generate_entry(input, &bindings, body);
+        let values =
+            input.inputs.iter().zip(point).map(
+                |(param, &i)| {
+                    if options.mask {
+                        i
+                    } else {
+                        param.lo + i
+                    }
+                },
+            );
+        let pattern = if options.mask && n == count - 1 {
+            quote::quote!(_)
+        } else {
+            tuple(values)
+        };
+        let arm = arm(entry);
+        quote::quote!(#pattern => #arm,)
+    });
+    let fallback = if options.mask {
+        proc_macro2::TokenStream::new()
+    } else if let Some(outside) = outside {
+        quote::quote!(_ => #outside,)
+    } else {
+        let idents = tuple(input.inputs.iter().map(|param| &param.ident));
+        let message = format!(
+            "index out of bounds: `{}` is {{:?}}, which is outside of the table",
+            idents
+        );
+        quote::quote!(_ => panic!(#message, #idents),)
+    };
+
+    quote::quote!(match #scrutinee {
+        #(#arms)*
+        #fallback
+    })
+}
+
+/// Generates a place expression for the row of `lut` selected by the first parameter.
+fn row_access(
+    first: &Param,
+    options: &Options,
+    shards: Option<Shards>,
+    lut: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let index = first.index(options);
+    match shards {
+        None => quote::quote!(#lut[#index]),
+        Some(Shards { rows: 1, .. }) => quote::quote!(#lut[#index][0]),
+        Some(Shards { rows, .. }) => quote::quote!(#lut[(#index) / #rows][(#index) % #rows]),
+    }
+}
+
+/// Generates an expression that indexes into `__LUT` using the given parameters.
+fn dense_access<'a>(
+    params: impl Iterator<Item = &'a Param>,
+    options: &Options,
+) -> proc_macro2::TokenStream {
+    params.fold(quote::quote!(__LUT), |expr, param| {
+        let index = param.index(options);
+        quote::quote!(#expr[#index])
+    })
+}
+
+fn generate_symmetric(input: &Lut) -> Table {
+    let body = body_tokens(input);
+    let x = &input.inputs[0];
+    let y = &input.inputs[1];
+    let len = x.len();
+    let count = len * (len + 1) / 2;
+
+    // Row `i` of the triangle holds the entries `(i, 0)..=(i, i)` and starts at offset `i * (i + 1)
+    // / 2`.
+    let entries = (0..len)
+        .flat_map(|i| (0..=i).map(move |j| (i, j)))
+        .map(|(i, j)| generate_entry(input, &[(&x.ident, x.lo + i), (&y.ident, y.lo + j)], &body));
+    let data = quote::quote!([#(#entries),*]);
+
+    let x_index = x.index(&input.options);
+    let y_index = y.index(&input.options);
+    let (index_items, index_type) = input.options.index_type((len - 1) * len);
+    let offset = match index_type.to_string().as_str() {
+        "usize" => quote::quote!(__i * (__i + 1) / 2),
+        _ => quote::quote!({
+            let __i = __i as #index_type;
+            (__i * (__i + 1) / 2) as usize
+        }),
+    };
+    let access = quote::quote!({
+        #index_items
+        let (__i, __j) = (#x_index, #y_index);
+        let (__i, __j) = if __i >= __j { (__i, __j) } else { (__j, __i) };
+        assert!(__i < #len, "index out of bounds: the len is {} but the index is {}", #len, __i);
+        __LUT[#offset + __j]
+    });
+
+    let return_type = &input.return_type;
+    let ty = quote::quote!([#return_type; #count]);
+
+    Table {
+        ty,
+        data,
+        access,
+        rank: 1,
+        items: proc_macro2::TokenStream::new(),
+        shards: None,
+        len: count,
+        strategy: Strategy::Table,
+        deduplicated: false,
+    }
+}
+
+fn generate_banded(input: &Lut, width: usize, default: &syn::Expr) -> Table {
+    let body = body_tokens(input);
+    let x = &input.inputs[0];
+    let y = &input.inputs[1];
+    let band = 2 * width + 1;
+    let y_values = y.lo..y.lo + y.len();
+
+    // Row `i` holds the entries `(x, x - k)..=(x, x + k)` for `x = lo + i`; the parts of the band
+    // that fall outside of the range of `y` are filled with the default.
+    let rows = (x.lo..x.lo + x.len()).map(|x_value| {
+        let entries = (0..band).map(|d| match (x_value + d).checked_sub(width) {
+            Some(y_value) if y_values.contains(&y_value) => {
+                generate_entry(input, &[(&x.ident, x_value), (&y.ident, y_value)], &body)
+            }
+            _ => quote::quote!(#default),
+        });
+        quote::quote!([#(#entries),*])
+    });
+    let data = quote::quote!([#(#rows),*]);
+
+    let x_ident = &x.ident;
+    let y_ident = &y.ident;
+    let x_index = x.index(&input.options);
+    // Both parameters are checked before the band, since a value far outside of its range would
+    // otherwise be outside of the band too and silently look up the default.
+    let checks = [x, y]
+        .iter()
+        .map(|param| {
+            let ident = &param.ident;
+            let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+            quote::quote! {
+                assert!(
+                    (#lo..=#hi).contains(&#ident),
+                    "index out of bounds: `{}` must be in {}..={} but is {}",
+                    stringify!(#ident),
+                    #lo,
+                    #hi,
+                    #ident,
+                );
+            }
+        })
+        .collect::<Vec<_>>();
+    let access = quote::quote!({
+        #(#checks)*
+        if #x_ident.abs_diff(#y_ident) > #width {
+            #default
+        } else {
+            __LUT[#x_index][#y_ident + #width - #x_ident]
+        }
+    });
+
+    let return_type = &input.return_type;
+    let rows = x.len();
+    let ty = quote::quote!([[#return_type; #band]; #rows]);
+
+    Table {
+        ty,
+        data,
+        access,
+        rank: 2,
+        items: proc_macro2::TokenStream::new(),
+        shards: None,
+        len: band * rows,
+        strategy: Strategy::Table,
+        deduplicated: false,
+    }
+}
+
+fn generate_deduplicated(input: &Lut) -> Table {
+    let body = body_tokens(input);
+    let (last, outer) = input
+        .inputs
+        .iter()
+        .collect::<Vec<_>>()
+        .split_last()
+        .map(|(last, outer)| (*last, outer.to_vec()))
+        .expect("validated in `Lut::validate_deduplicated`");
+    let width = last.len();
+    let count = outer.iter().map(|param| param.len()).product::<usize>();
+
+    // The rows for every combination of the other parameters, in memory order.
+    let rows = (0..count).map(|r| {
+        let mut stride = count;
+        let mut bindings = outer
+            .iter()
+            .map(|param| {
+                stride /= param.len();
+                (&param.ident, param.lo + r / stride % param.len())
+            })
+            .collect::<Vec<_>>();
+        generate_arrays(input, &[last], &mut bindings, &body)
+    });
+    let return_type = &input.return_type;
+    let index_type = match count - 1 {
+        n if n <= u8::MAX as usize => quote::quote!(u8),
+        n if n <= u16::MAX as usize => quote::quote!(u16),
+        n if n <= u32::MAX as usize => quote::quote!(u32),
+        _ => quote::quote!(usize),
+    };
+    // Floats are compared by their bits, so that rows with NaNs are merged as well.
+    let differs = match quote::quote!(#return_type).to_string().as_str() {
+        "f32" | "f64" => quote::quote!(__a[__k].to_bits() != __b[__k].to_bits()),
+        _ => quote::quote!(__a[__k] != __b[__k]),
+    };
+    let row_ty = quote::quote!([#return_type; #width]);
+    let lint = input.options.const_eval.lint_attr();
+    let data = quote::quote!({
+        #lint
+        const __ALL: [#row_ty; #count] = [#(#rows),*];
+
+        // The index of the first row that is equal to row `i`.
+        const fn __first(__i: usize) -> usize {
+            let mut __j = 0;
+            'rows: while __j < __i {
+                let (__a, __b) = (&__ALL[__i], &__ALL[__j]);
+                let mut __k = 0;
+                while __k < #width {
+                    if #differs {
+                        __j += 1;
+                        continue 'rows;
+                    }
+                    __k += 1;
+                }
+                break;
+            }
+            __j
+        }
+
+        #lint
+        const __UNIQUE: usize = {
+            let mut __unique = 0;
+            let mut __i = 0;
+            while __i < #count {
+                if __first(__i) == __i {
+                    __unique += 1;
+                }
+                __i += 1;
+            }
+            __unique
+        };
+
+        #lint
+        const __DEDUP: ([#row_ty; __UNIQUE], [#index_type; #count]) = {
+            let mut __rows = [__ALL[0]; __UNIQUE];
+            let mut __index = [0; #count];
+            let mut __unique = 0;
+            let mut __i = 0;
+            while __i < #count {
+                let __j = __first(__i);
+                if __j == __i {
+                    __rows[__unique] = __ALL[__i];
+                    __index[__i] = __unique as #index_type;
+                    __unique += 1;
+                } else {
+                    __index[__i] = __index[__j];
+                }
+                __i += 1;
+            }
+            (__rows, __index)
+        };
+
+        (&__DEDUP.0, __DEDUP.1)
+    });
+
+    // Every index is checked, since an index that is out of range could select another row.
+    let (index_items, offset_type) = input.options.index_type(count - 1);
+    let wide = offset_type.to_string() != "usize";
+    let mut stride = count;
+    let offsets = outer.iter().map(|param| {
+        let ident = &param.ident;
+        let index = param.index(&input.options);
+        let (lo, len) = (param.lo, param.len());
+        let hi = lo + len - 1;
+        stride /= len;
+        let check = quote::quote! {
+            assert!(
+                __i < #len,
+                "index out of bounds: `{}` must be in {}..={} but is {}",
+                stringify!(#ident),
+                #lo,
+                #hi,
+                #ident,
+            );
+        };
+        let term = match wide {
+            true => quote::quote!(__i as #offset_type),
+            false => quote::quote!(__i),
+        };
+        let offset = match stride {
+            1 => term,
+            // The suffix of the stride fixes the type of the index when it's computed in `usize`.
+            stride if wide => {
+                let stride = proc_macro2::Literal::usize_unsuffixed(stride);
+                quote::quote!(#term * #stride)
+            }
+            stride => quote::quote!(#term * #stride),
+        };
+        quote::quote!({
+            let __i = #index;
+            #check
+            #offset
+        })
+    });
+    let last_index = last.index(&input.options);
+    let row = match wide {
+        true => quote::quote!((#(#offsets)+*) as usize),
+        false => quote::quote!(#(#offsets)+*),
+    };
+    let access = quote::quote!({
+        #index_items
+        let __row = #row;
+        __LUT.0[__LUT.1[__row] as usize][#last_index]
+    });
+
+    Table {
+        ty: quote::quote!((&'static [#row_ty], [#index_type; #count])),
+        data,
+        access,
+        rank: 2,
+        items: proc_macro2::TokenStream::new(),
+        shards: None,
+        len: count * width,
+        strategy: Strategy::Table,
+        deduplicated: true,
+    }
+}
+
+/// Turns the body into tokens that can be placed inside of an entry block.
+///
+/// The statements of a block body are spliced into the entry block, so that the user's braces don't
+/// trip the `unused_braces` lint.
+fn body_tokens(input: &Lut) -> proc_macro2::TokenStream {
+    let body = match &input.body {
+        syn::Expr::Block(syn::ExprBlock {
+            attrs,
+            label,
+            block,
+            ..
+        }) if attrs.is_empty() && label.is_none() => {
+            let stmts = &block.stmts;
+            quote::quote!(#(#stmts)*)
+        }
+        other => quote::quote!(#other),
+    };
+    let guard = match &input.options.guard {
+        Some(guard) => guard,
+        None => return body,
+    };
+    let condition = &guard.condition;
+    let (entry, fallback) = match &guard.default {
+        _ if guard.absent => (
+            quote::quote!(::core::option::Option::Some({ #body })),
+            quote::quote!(::core::option::Option::None),
+        ),
+        Some(default) => (quote::quote!({ #body }), quote::quote!(#default)),
+        None => {
+            let zero = zero_value(&input.return_type).expect("validated in `Lut::validate`");
+            (quote::quote!({ #body }), zero)
+        }
+    };
+    quote::quote!(if #condition { #entry } else { #fallback })
+}
+
+/// The default value of primitive types, which can't be called during constant evaluation.
+fn zero_value(ty: &syn::Type) -> Option<proc_macro2::TokenStream> {
+    let name = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident()?.to_string(),
+        _ => return None,
+    };
+    let zero = match name.as_str() {
+        "bool" => quote::quote!(false),
+        "char" => quote::quote!('\0'),
+        "f32" | "f64" => quote::quote!(0.0),
+        _ if preset::primitive_name(ty).is_some() => quote::quote!(0),
+        _ => return None,
+    };
+    Some(zero)
+}
+
+/// Generates nested arrays of entries for the given parameters, given the values of the parameters
+/// outside of them.
+fn generate_arrays<'a>(
+    input: &Lut,
+    params: &[&'a Param],
+    bindings: &mut Vec<(&'a syn::Ident, usize)>,
+    body: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let (param, rest) = match params.split_first() {
+        Some(split) => split,
+        None => return generate_entry(input, bindings, body),
+    };
+    let len = param.len();
+    let mut items = Vec::with_capacity(param.storage_len(&input.options));
+    for i in 0..param.storage_len(&input.options) {
+        bindings.push((&param.ident, param.lo + i % len));
+        items.push(generate_arrays(input, rest, bindings, body));
+        bindings.pop();
+    }
+    quote::quote!([#(#items),*])
+}
+
+/// Generates a block that evaluates the body with the given parameters bound to constants.
+///
+/// For small tables or with `#[const_eval(isolate)]`, the entry is evaluated in a constant of its
+/// own whose name contains the parameter values, so that evaluation limits apply per entry and
+/// errors name the entry being evaluated.
+fn generate_entry(
+    input: &Lut,
+    bindings: &[(&syn::Ident, usize)],
+    body: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let permuted = bindings
+        .iter()
+        .map(|&(ident, n)| {
+            let param = input.inputs.iter().find(|param| param.ident == *ident);
+            match param.and_then(|param| Some((param.permute?.1, param.len()))) {
+                Some((permutation, len)) => (ident, permutation.value(n, len.trailing_zeros())),
+                None => (ident, n),
+            }
+        })
+        .collect::<Vec<_>>();
+    let bindings = permuted.as_slice();
+    let consts = bindings.iter().map(|(ident, n)| {
+        quote::quote! {
+            #[allow(non_upper_case_globals)]
+            const #ident: usize = #n;
+        }
+    });
+    let mut entry = quote::quote!({
+        #(#consts)*
+        #body
+    });
+
+    if input.options.nalgebra_entries.is_some() {
+        let return_type = &input.return_type;
+        let (scalar, rows, columns) =
+            matrix_shape(return_type).expect("validated in `Lut::validate`");
+        entry = match columns {
+            None => quote::quote!({
+                let __column: [#scalar; #rows] = #entry;
+                <#return_type>::from_array_storage(::nalgebra::ArrayStorage([__column]))
+            }),
+            Some(columns) => quote::quote!({
+            let __rows: [[#scalar; #columns]; #rows] = #entry;
+            let mut __columns = [[__rows[0][0]; #rows]; #columns];
+            let mut __r = 0;
+            while __r < #rows {
+                let mut __c = 0;
+                while __c < #columns {
+                    __columns[__c][__r] = __rows[__r][__c];
+                    __c += 1;
+                }
+                __r += 1;
+            }
+            <#return_type>::from_array_storage(::nalgebra::ArrayStorage(__columns))
+            }),
+        };
+    }
+
+    if let Some(cover) = &input.options.cover {
+        let return_type = &input.return_type;
+        let (ident, n) = bindings[0];
+        let covered = cover.ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&n));
+        let fallback = match &cover.default {
+            Some(default) if !covered => quote::quote!(#default),
+            _ => {
+                let message = if covered {
+                    format!(
+                        "`{}` = {} ({:#x}) must be covered, but the body returned `None` for it",
+                        ident, n, n
+                    )
+                } else {
+                    format!(
+                        "the body returned `None` for `{}` = {} ({:#x}), but there is no `default`",
+                        ident, n, n
+                    )
+                };
+                quote::quote!(panic!(#message))
+            }
+        };
+        entry = quote::quote!({
+            let __entry: ::core::option::Option<#return_type> = #entry;
+            match __entry {
+                ::core::option::Option::Some(__entry) => __entry,
+                ::core::option::Option::None => #fallback,
+            }
+        });
+    }
+
+    if input.isolates_entries() {
+        use syn::spanned::Spanned;
+        let name = bindings
+            .iter()
+            .fold("__lut_entry".to_owned(), |name, (ident, n)| {
+                format!("{}_{}_{}", name, ident, n)
+            });
+        // Spanning the body makes errors during evaluation point at it.
+        let name = syn::Ident::new(&name, input.body.span());
+        let return_type = &input.return_type;
+        let lint = input.options.const_eval.lint_attr();
+        entry = quote::quote!({
+            #[allow(non_upper_case_globals)]
+            #lint
+            const #name: #return_type = #entry;
+            #name
+        });
+    }
+
+    if input.options.f16.is_some() {
+        entry = quote::quote!({ ::half::f16::from_f32_const(#entry) });
+    }
+    if let Some(codec) = &input.options.codec {
+        let encoded = codec.encode(entry, &input.return_type);
+        entry = quote::quote!({ #encoded });
+    }
+    entry
+}
+
+impl Policy {
+    /// The span of the option that set the policy, if any.
+    fn span(self) -> Option<proc_macro2::Span> {
+        match self {
+            Policy::Panic => None,
+            Policy::Clamp(span) | Policy::Wrap(span) => Some(span),
+        }
+    }
+}
+
+impl Param {
+    /// The number of values in the range of this parameter.
+    fn len(&self) -> usize {
+        if self.exclusive_end {
+            self.hi - self.lo
+        } else {
+            self.hi - self.lo + 1
+        }
+    }
+
+    /// The number of entries actually stored along this dimension.
+    fn storage_len(&self, options: &Options) -> usize {
+        if options.mask {
+            self.len().next_power_of_two()
+        } else {
+            self.len()
+        }
+    }
+
+    /// An expression that turns the parameter value into an index into its dimension.
+    fn index(&self, options: &Options) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        // Empty ranges are rejected when parsing, so every range has a last value.
+        let (lo, len) = (self.lo, self.len());
+        match self.policy {
+            _ if options.mask => {
+                let mask = self.storage_len(options) - 1;
+                quote::quote!(#ident.wrapping_sub(#lo) & #mask)
+            }
+            Policy::Clamp(_) if lo == 0 => {
+                let hi = len - 1;
+                quote::quote!(#ident.min(#hi))
+            }
+            Policy::Clamp(_) => {
+                let hi = lo + len - 1;
+                quote::quote!(#ident.clamp(#lo, #hi) - #lo)
+            }
+            // Reducing modulo one is always zero, which clippy warns about in the generated code.
+            Policy::Wrap(_) if len == 1 => quote::quote!({
+                let _ = #ident;
+                0
+            }),
+            Policy::Wrap(_) if lo % len == 0 => quote::quote!(#ident % #len),
+            // Subtracting `lo` first would wrap around at zero for values below it, so the offset
+            // is added modulo the length instead.
+            Policy::Wrap(_) => {
+                let offset = len - lo % len;
+                quote::quote!((#ident % #len + #offset) % #len)
+            }
+            Policy::Panic if lo == 0 => quote::quote!(#ident),
+            Policy::Panic => quote::quote!(#ident - #lo),
+        }
+    }
+
+    /// An expression that moves the parameter value into its range according to its policy, if it
+    /// has one.
+    fn in_range(&self, options: &Options) -> Option<proc_macro2::TokenStream> {
+        let lo = self.lo;
+        match self.policy {
+            Policy::Panic => None,
+            _ if lo == 0 => Some(self.index(options)),
+            _ => {
+                let index = self.index(options);
+                Some(quote::quote!(#lo + (#index)))
+            }
+        }
+    }
+
+    fn new(
+        ident: syn::Ident,
+        lo: usize,
+        limits: syn::RangeLimits,
+        hi: usize,
+        span: proc_macro2::Span,
+    ) -> syn::Result<Self> {
+        if hi < lo {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "range lower bound {} must be less than upper bound {}",
+                    lo, hi
+                ),
+            ));
+        }
+        let exclusive_end = match limits {
+            syn::RangeLimits::Closed(_) => false,
+            syn::RangeLimits::HalfOpen(_) => true,
+        };
+        // Every layout and policy needs at least one entry along each dimension, so an empty range
+        // is rejected here once instead of by each of them.
+        if exclusive_end && hi == lo {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "range {}..{} is empty, so the table would have no entries",
+                    lo, hi
+                ),
+            ));
+        }
+        Ok(Param {
+            ident,
+            lo,
+            exclusive_end,
+            hi,
+            policy: Policy::Panic,
+            contiguous: None,
+            permute: None,
+        })
+    }
+
+    fn from_pat(pat: syn::Pat) -> syn::Result<Self> {
+        use syn::spanned::Spanned;
+        match pat {
+            syn::Pat::Ident(pat_ident) => Self::from_pat_ident(pat_ident),
+            other => Err(syn::Error::new(
+                other.span(),
+                "this parameter must have a range pattern (e.g. `x @ 1..2` or `y @ 3..=4`)",
+            )),
+        }
+    }
+
+    fn from_pat_ident(pat_ident: syn::PatIdent) -> syn::Result<Self> {
+        use syn::spanned::Spanned;
+        let syn::PatIdent { ident, subpat, .. } = pat_ident;
+        match subpat {
+            Some((_, pat)) => {
+                let pat_span = pat.span();
+                match *pat {
+                    syn::Pat::Range(syn::PatRange { lo, limits, hi, .. }) => match *lo {
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Int(lo),
+                            ..
+                        }) => {
+                            let lo = lo.base10_parse()?;
+                            match *hi {
+                                syn::Expr::Lit(syn::ExprLit {
+                                    lit: syn::Lit::Int(hi),
+                                    ..
+                                }) => {
+                                    let hi = hi.base10_parse()?;
+                                    Self::new(ident, lo, limits, hi, pat_span)
+                                }
+                                expr => {
+                                    Err(syn::Error::new(expr.span(), "must be an integer literal"))
+                                }
+                            }
+                        }
+                        expr => Err(syn::Error::new(expr.span(), "must be an integer literal")),
+                    },
+                    pat => Err(syn::Error::new(
+                        pat.span(),
+                        "only range patterns allowed (e.g. `1..2` or `3..=4`)",
+                    )),
+                }
+            }
+            None => Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "this parameter must have a specified range pattern (e.g. `{} @ 1..2`)",
+                    ident
+                ),
+            )),
+        }
+    }
+}
+
+impl syn::parse::Parse for Param {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        use syn::spanned::Spanned;
+        let mut policy = Policy::Panic;
+        let mut contiguous = None;
+        let mut permute = None;
+        for attr in input.call(syn::Attribute::parse_outer)? {
+            let span = attr.path.span();
+            let new_policy = match attr.path.get_ident().map(ToString::to_string).as_deref() {
+                Some("clamp") => Policy::Clamp(span),
+                Some("wrap") => Policy::Wrap(span),
+                Some("contiguous") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    contiguous = Some(span);
+                    continue;
+                }
+                Some("permute") => {
+                    let name = attr.parse_args::<syn::Ident>()?;
+                    let permutation = match name.to_string().as_str() {
+                        "bit_reverse" => Permutation::BitReverse,
+                        "gray" => Permutation::Gray,
+                        _ => {
+                            return Err(syn::Error::new(
+                                name.span(),
+                                "unknown permutation (expected `bit_reverse` or `gray`)",
+                            ))
+                        }
+                    };
+                    permute = Some((span, permutation));
+                    continue;
+                }
+                Some("cfg") => {
+                    return Err(syn::Error::new(
+                        span,
+                        "`#[cfg(..)]` on parameters is only supported by `lut!`",
+                    ))
+                }
+                _ => return Err(syn::Error::new(
+                    span,
+                    "unknown parameter option (expected `#[clamp]`, `#[wrap]`, `#[contiguous]` or `#[permute(..)]`)",
+                )),
+            };
+            syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+            if !matches!(policy, Policy::Panic) {
+                return Err(syn::Error::new(
+                    span,
+                    "a parameter can only have one of `#[clamp]` and `#[wrap]`",
+                ));
+            }
+            policy = new_policy;
+        }
+        let mut param = Self::parse_range(input)?;
+        param.policy = policy;
+        param.contiguous = contiguous;
+        param.permute = permute;
+        Ok(param)
+    }
+}
+
+impl Param {
+    fn parse_range(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        // Range patterns without a lower bound aren't patterns that `syn` can parse, so `x @ ..N`
+        // is parsed by hand, with the lower bound defaulting to zero.
+        let fork = input.fork();
+        let open = fork.parse::<syn::Ident>().is_ok()
+            && fork.parse::<syn::Token![@]>().is_ok()
+            && fork.peek(syn::Token![..]);
+        if !open {
+            return Self::from_pat(input.parse()?);
+        }
+        let ident = input.parse()?;
+        input.parse::<syn::Token![@]>()?;
+        let limits = input.parse()?;
+        let hi = input.parse::<syn::LitInt>()?;
+        Self::new(ident, 0, limits, hi.base10_parse()?, hi.span())
+    }
+}
+
+impl Lut {
+    /// The parameters in the order of the dimensions of the stored table, which is the declared
+    /// order except that the parameter with `#[contiguous]` comes last.
+    fn stored(&self) -> Vec<&Param> {
+        let (contiguous, mut stored): (Vec<_>, Vec<_>) = self
+            .inputs
+            .iter()
+            .partition(|param| param.contiguous.is_some());
+        stored.extend(contiguous);
+        stored
+    }
+
+    /// Whether the entries are stored in a `const` instead of a `static`, which is the case with
+    /// `#[const_table]` and for references to trait objects, which aren't `Sync` in general.
+    fn stores_in_const(&self) -> bool {
+        self.options.const_table.is_some() || is_trait_object_ref(&self.return_type)
+    }
+
+    /// Whether every entry is evaluated in a constant of its own.
+    fn isolates_entries(&self) -> bool {
+        if self.options.const_eval.isolate.is_some() {
+            return true;
+        }
+        let len = self.inputs.iter().map(Param::len).product::<usize>();
+        let max = self
+            .options
+            .const_eval
+            .isolate_up_to
+            .unwrap_or(DEFAULT_ISOLATE_UP_TO);
+        !is_inferred(&self.return_type) && len <= max
+    }
+
+    /// The type of the stored entries, which differs from the return type with `#[f16]`,
+    /// `#[codec]` and `#[bitfields]`.
+    fn element_type(&self) -> proc_macro2::TokenStream {
+        match (self.options.f16, &self.options.codec) {
+            (Some(_), _) => quote::quote!(::half::f16),
+            (None, Some(Codec { stored, .. })) => quote::quote!(#stored),
+            (None, None) => {
+                let return_type = &self.return_type;
+                quote::quote!(#return_type)
+            }
+        }
+    }
+
+    /// Converts a stored entry to the return type.
+    fn load(&self, entry: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match (self.options.f16, &self.options.codec) {
+            (Some(_), _) => quote::quote!(::half::f16::to_f32(#entry)),
+            (None, Some(codec)) => codec.decode(entry, &self.return_type),
+            (None, None) => entry,
+        }
+    }
+
+    /// Generates the table in the layout of the options.
+    fn table(&self) -> Table {
+        match self.options.layout {
+            Layout::Dense => generate_dense(self),
+            Layout::Symmetric(_) => generate_symmetric(self),
+            Layout::Banded {
+                width, ref default, ..
+            } => generate_banded(self, width, default),
+            Layout::Deduplicated(_) => generate_deduplicated(self),
+        }
+    }
+
+    /// Describes the generated table, using the given name for the closure form.
+    fn metadata(
+        &self,
+        table: &Table,
+        name: &Option<syn::LitStr>,
+        spec_hash: u64,
+    ) -> metadata::Metadata {
+        let name = match (name, &self.form) {
+            (Some(name), _) => Some(name.value()),
+            (None, Form::Struct { ident, .. }) => Some(ident.to_string()),
+            (None, Form::Closure) => None,
+        };
+        let dimensions = self
+            .stored()
+            .into_iter()
+            .map(|param| metadata::Dimension {
+                name: param.ident.to_string(),
+                lo: param.lo,
+                len: param.len(),
+            })
+            .collect();
+        let return_type = &self.return_type;
+        let (element_type, element_size) = match (self.options.f16, &self.options.codec) {
+            (Some(_), _) => ("f16".to_owned(), Some(2)),
+            (None, Some(Codec { stored, .. })) => (
+                quote::quote!(#stored).to_string(),
+                metadata::primitive_size(stored),
+            ),
+            (None, None) => (
+                quote::quote!(#return_type).to_string(),
+                metadata::primitive_size(return_type),
+            ),
+        };
+        metadata::Metadata {
+            name,
+            dimensions,
+            element_type,
+            entries: table.len,
+            // The number of unique rows is only known after constant evaluation.
+            byte_size: element_size
+                .filter(|_| !table.deduplicated)
+                .map(|size| size * table.len),
+            strategy: match table.strategy {
+                Strategy::Table => "table",
+                Strategy::Match => "match",
+                Strategy::Compute => "compute",
+            },
+            spec_hash,
+        }
+    }
+
+    /// Checks that the options are applicable to the form and parameters.
+    fn validate(&self) -> syn::Result<()> {
+        use syn::spanned::Spanned;
+        if let syn::Type::Infer(_) = self.return_type {
+            let needs_type = [
+                (
+                    "verify",
+                    self.options.verify.as_ref().map(|path| path.span()),
+                ),
+                ("const_eval(isolate)", self.options.const_eval.isolate),
+                ("built", self.options.built.as_ref().map(syn::LitStr::span)),
+                (
+                    "check",
+                    self.options.shape_checks.first().map(|check| check.span),
+                ),
+            ];
+            for (name, span) in needs_type.iter() {
+                if let Some(span) = span {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!(
+                            "`#[{}]` requires an explicit return type (e.g. `lut!(f -> u8; ...)`)",
+                            name
+                        ),
+                    ));
+                }
+            }
+            // Inferred tables are evaluated in an inline constant, which takes no attributes.
+            if let Some(level) = &self.options.const_eval.lint {
+                return Err(syn::Error::new(
+                    level.span(),
+                    format!(
+                        "`#[const_eval({})]` requires an explicit return type (e.g. `lut!(f -> u8; ...)`)",
+                        level
+                    ),
+                ));
+            }
+        }
+        let item_options = [
+            ("transpose", self.options.transpose),
+            ("hash", self.options.hash),
+            ("crc", self.options.crc),
+            ("inverse", self.options.inverse),
+            ("invert", self.options.invert.map(|invert| invert.0)),
+            ("find_nearest", self.options.find_nearest),
+            ("aggregates", self.options.aggregates),
+            (
+                "redundant",
+                self.options.redundant.as_ref().map(|redundant| redundant.0),
+            ),
+            ("patchable", self.options.patchable),
+            ("reload", self.options.reload),
+            ("indirect", self.options.indirect),
+            ("bytes", self.options.bytes),
+            ("ndarray", self.options.ndarray),
+            ("nalgebra(view)", self.options.nalgebra_view),
+            ("runtime", self.options.runtime),
+            ("total(no_panic)", self.options.no_panic),
+            ("sample", self.options.sample),
+            ("try_get", self.options.try_get),
+            ("get_or", self.options.get_or),
+            ("transform", self.options.transform),
+            (
+                "interpolate",
+                self.options
+                    .interpolate
+                    .as_ref()
+                    .map(|interpolate| interpolate.span),
+            ),
+            ("index", self.options.index),
+            ("dump", self.options.dump),
+            ("csv", self.options.csv),
+            ("heatmap", self.options.heatmap),
+            (
+                "register",
+                self.options.register.as_ref().map(|path| path.span()),
+            ),
+            (
+                self.options
+                    .export
+                    .as_ref()
+                    .map_or("export_c", Export::option),
+                self.options.export.as_ref().map(|e| e.span),
+            ),
+            (
+                "wasm_bindgen",
+                self.options.wasm_bindgen.as_ref().map(|w| w.0),
+            ),
+            ("shader", self.options.shaders_span),
+            ("soa", self.options.soa.as_ref().map(|soa| soa.0)),
+            ("split", self.options.split),
+            ("call(direct)", self.options.direct_call),
+            ("heap", self.options.heap),
+            ("compressed", self.options.compressed),
+        ];
+        for (name, span) in item_options.iter() {
+            if let (Some(span), Form::Closure) = (span, &self.form) {
+                return Err(syn::Error::new(
+                    *span,
+                    format!("`#[{}]` is only supported by the item form", name),
+                ));
+            }
+        }
+        if let (Some((span, None)), Form::Closure) = (&self.options.sidecar, &self.form) {
+            return Err(syn::Error::new(
+                *span,
+                "the closure form requires a name for the sidecar (e.g. `#[sidecar = \"name\"]`)",
+            ));
+        }
+        if let (Some(span), true) = (self.options.index, self.inputs.is_empty()) {
+            return Err(syn::Error::new(
+                span,
+                "`#[index]` requires at least one parameter",
+            ));
+        }
+        if let (Some(span), true) = (self.options.get_or, self.inputs.is_empty()) {
+            return Err(syn::Error::new(
+                span,
+                "`#[get_or]` requires at least one parameter",
+            ));
+        }
+        if let (Some(span), true) = (self.options.sample, self.inputs.is_empty()) {
+            return Err(syn::Error::new(
+                span,
+                "`#[sample]` requires at least one parameter",
+            ));
+        }
+        if let Some(span) = self.options.heatmap {
+            if self.inputs.len() != 2 {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[heatmap]` requires exactly two parameters",
+                ));
+            }
+            let name = preset::primitive_name(&self.return_type);
+            if matches!(name.as_deref(), None | Some("bool") | Some("char")) {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[heatmap]` requires integer or floating point entries",
+                ));
+            }
+        }
+        if let Some(span) = self.options.nalgebra_view {
+            if self.inputs.len() != 2 {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[nalgebra(view)]` requires exactly two parameters",
+                ));
+            }
+        }
+        if let Some(span) = self.options.nalgebra_entries {
+            if matrix_shape(&self.return_type).is_none() {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[nalgebra(entries)]` requires a statically sized matrix type like `SMatrix<f32, 3, 3>` or `Vector3<f32>`",
+                ));
+            }
+            if let Some(cover) = &self.options.cover {
+                return Err(syn::Error::new(
+                    cover.span,
+                    "`#[cover]` can't be combined with `#[nalgebra(entries)]`",
+                ));
+            }
+        }
+        if let Some(name) = &self.options.built {
+            // These change what the body returns, while the build script evaluates plain entries.
+            let conflicts = [
+                ("cover", self.options.cover.as_ref().map(|cover| cover.span)),
+                ("guard", self.options.guard.as_ref().map(|guard| guard.span)),
+                ("nalgebra(entries)", self.options.nalgebra_entries),
+            ];
+            for (other, span) in conflicts.iter() {
+                if let Some(span) = span {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!(
+                            "`#[{}]` can't be combined with `#[built = {:?}]`",
+                            other,
+                            name.value()
+                        ),
+                    ));
+                }
+            }
+        }
+        if let Some(span) = self.options.ndarray {
+            if !(1..=6).contains(&self.inputs.len()) {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[ndarray]` requires between one and six parameters",
+                ));
+            }
+        }
+        if let Some(span) = self.options.f16 {
+            let is_f32 = matches!(
+                &self.return_type,
+                syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("f32")
+            );
+            if !is_f32 {
+                return Err(syn::Error::new(span, "`#[f16]` requires `f32` entries"));
+            }
+            if let Some(codec) = &self.options.codec {
+                return Err(syn::Error::new(
+                    span,
+                    format!("`#[f16]` can't be combined with `#[{}]`", codec.name()),
+                ));
+            }
+        }
+        if let (Some(span), false) = (self.options.transform, self.inputs.len() == 1) {
+            return Err(syn::Error::new(
+                span,
+                "`#[transform]` requires a single parameter",
+            ));
+        }
+        if let Some(span) = self.options.inverse {
+            if self.inputs.len() != 1 {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[inverse]` requires a single parameter",
+                ));
+            }
+            let return_type = &self.return_type;
+            let is_integer = match preset::primitive_name(return_type) {
+                Some(name) => !name.starts_with('f') && !name.ends_with("128"),
+                None => quote::quote!(#return_type).to_string() == "char",
+            };
+            if !is_integer {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[inverse]` requires integer entries of up to 64 bits or `char` entries",
+                ));
+            }
+        }
+        if let Some((span, _)) = self.options.invert {
+            if self.inputs.len() != 1 || self.inputs[0].len() == 1 {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[invert]` requires a single parameter with more than one value",
+                ));
+            }
+            let param = &self.inputs[0];
+            let monotone =
+                self.options.shape_checks.iter().any(|check| {
+                    check.param == param.ident && check.shape.is_increasing().is_some()
+                });
+            if !monotone {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "`#[invert]` requires a `#[check]` that the entries are monotone in `{0}`, like `#[check(monotone_increasing({0}))]`",
+                        param.ident
+                    ),
+                ));
+            }
+        }
+        let numeric = [
+            ("find_nearest", self.options.find_nearest),
+            ("aggregates", self.options.aggregates),
+        ];
+        for (name, span) in numeric.iter() {
+            if let Some(span) = span {
+                if preset::primitive_name(&self.return_type).is_none() {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!("`#[{}]` requires numeric entries", name),
+                    ));
+                }
+            }
+        }
+        for check in &self.options.shape_checks {
+            if !self.inputs.iter().any(|param| param.ident == check.param) {
+                return Err(syn::Error::new(
+                    check.param.span(),
+                    format!("`{}` isn't a parameter of this table", check.param),
+                ));
+            }
+            let name = preset::primitive_name(&self.return_type).ok_or_else(|| {
+                syn::Error::new(check.span, "`#[check]` requires numeric entries")
+            })?;
+            let curved = matches!(check.shape, Shape::Convex | Shape::Concave);
+            if curved && name.ends_with("128") {
+                return Err(syn::Error::new(
+                    check.span,
+                    format!("`{}` doesn't support 128-bit entries", check.shape.name()),
+                ));
+            }
+        }
+        // These read or describe the stored entries as if they had the return type.
+        let stores_other_type = [
+            ("f16", self.options.f16),
+            (
+                self.options.codec.as_ref().map_or("codec", Codec::name),
+                self.options.codec.as_ref().map(|codec| codec.span),
+            ),
+        ];
+        for (option, span) in stores_other_type.iter() {
+            let span = match span {
+                Some(span) => *span,
+                None => continue,
+            };
+            let conflicts = [
+                (
+                    "verify",
+                    self.options.verify.as_ref().map(|path| path.span()),
+                ),
+                ("hash", self.options.hash),
+                ("crc", self.options.crc),
+                ("shader", self.options.shaders_span),
+                ("index", self.options.index),
+                ("heatmap", self.options.heatmap),
+                ("soa", self.options.soa.as_ref().map(|soa| soa.0)),
+                ("split", self.options.split),
+                (
+                    "check",
+                    self.options.shape_checks.first().map(|check| check.span),
+                ),
+                ("inverse", self.options.inverse),
+                ("aggregates", self.options.aggregates),
+                (
+                    self.options
+                        .export
+                        .as_ref()
+                        .map_or("export_c(table)", Export::table_option),
+                    self.options
+                        .export
+                        .as_ref()
+                        .and_then(|export| export.table.as_ref())
+                        .map(|table| table.span()),
+                ),
+            ];
+            for (name, other) in conflicts.iter() {
+                if other.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`#[{}]` can't be combined with `#[{}]`", option, name),
+                    ));
+                }
+            }
+        }
+        let reads_table = [
+            ("crc", self.options.crc),
+            (
+                "redundant",
+                self.options.redundant.as_ref().map(|redundant| redundant.0),
+            ),
+        ];
+        for (option, span) in reads_table.iter() {
+            let span = match span {
+                Some(span) => *span,
+                None => continue,
+            };
+            // The lookups of these read other tables than `TABLE`.
+            let conflicts = [
+                ("soa", self.options.soa.as_ref().map(|soa| soa.0)),
+                ("split", self.options.split),
+                ("f16", self.options.f16),
+                (
+                    self.options.codec.as_ref().map_or("codec", Codec::name),
+                    self.options.codec.as_ref().map(|codec| codec.span),
+                ),
+            ];
+            for (name, other) in conflicts.iter() {
+                if other.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`#[{}]` can't be combined with `#[{}]`", option, name),
+                    ));
+                }
+            }
+        }
+        if self.options.total.is_some() {
+            let panics = self
+                .inputs
+                .iter()
+                .find(|param| !self.options.mask && matches!(param.policy, Policy::Panic));
+            if let Some(param) = panics {
+                return Err(syn::Error::new(
+                    param.ident.span(),
+                    format!(
+                        "`#[total]` requires lookups that can't panic, but `{}` panics when it's out of range (add `#[clamp]` or `#[wrap]`, or use `#[mask]`)",
+                        param.ident
+                    ),
+                ));
+            }
+            if let Some((redundant, _)) = self.options.redundant {
+                return Err(syn::Error::new(
+                    redundant,
+                    "`#[redundant]` panics if the copies differ, so it can't be combined with `#[total]`",
+                ));
+            }
+            let initialized = [
+                ("indirect", self.options.indirect),
+                ("compressed", self.options.compressed),
+            ];
+            for (name, span) in initialized.iter() {
+                if let Some(span) = span {
+                    return Err(syn::Error::new(
+                        *span,
+                        format!(
+                            "`#[{}]` panics before `init` is called, so it can't be combined with `#[total]`",
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
+        if let Some((span, true)) = self.options.redundant {
+            let invertible = match preset::primitive_name(&self.return_type) {
+                Some(name) => !name.starts_with('f'),
+                None => matches!(
+                    &self.return_type,
+                    syn::Type::Path(syn::TypePath { qself: None, path }) if path.is_ident("bool")
+                ),
+            };
+            if !invertible {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[redundant(inverted)]` requires integer or `bool` entries",
+                ));
+            }
+            // The default outside of the band isn't stored, so both copies return it as it is.
+            if let Layout::Banded { .. } = self.options.layout {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[redundant(inverted)]` can't be combined with `#[banded(..)]`, whose default isn't stored in the inverted copy",
+                ));
+            }
+        }
+        if let Some(span) = self.options.const_table {
+            // These keep the entries in a static by design.
+            let conflicts = [
+                ("crc", self.options.crc),
+                (
+                    "redundant",
+                    self.options.redundant.as_ref().map(|redundant| redundant.0),
+                ),
+                ("patchable", self.options.patchable),
+                (
+                    self.options
+                        .export
+                        .as_ref()
+                        .map_or("export_c(table)", Export::table_option),
+                    self.options
+                        .export
+                        .as_ref()
+                        .and_then(|export| export.table.as_ref())
+                        .map(|table| table.span()),
+                ),
+            ];
+            for (name, other) in conflicts.iter() {
+                if other.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`#[const_table]` can't be combined with `#[{}]`", name),
+                    ));
+                }
+            }
+        }
+        if let Some((span, width)) = &self.options.index_width {
+            let max = match self.options.layout {
+                Layout::Symmetric(_) => {
+                    let len = self.inputs.first().map_or(1, Param::len);
+                    (len - 1) * len
+                }
+                Layout::Deduplicated(_) => {
+                    let outer = self.inputs.iter().rev().skip(1);
+                    outer.map(Param::len).product::<usize>() - 1
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        *span,
+                        "`#[index_width(..)]` only applies to `#[symmetric]` and `#[dedup]` tables, whose lookups compute offsets into the stored entries",
+                    ))
+                }
+            };
+            let bits = match width.to_string().as_str() {
+                "u16" => 16,
+                "u32" => 32,
+                // The width of `usize` depends on the target.
+                _ => 64,
+            };
+            if (max as u128) >> bits != 0 {
+                return Err(syn::Error::new(
+                    width.span(),
+                    format!(
+                        "`{}` is too narrow for the offsets of this table, which go up to {}",
+                        width, max
+                    ),
+                ));
+            }
+        }
+        if let Some(span) = self.options.indirect {
+            // These read the table that is built into the image instead of the one set with
+            // `init`, or keep their entries elsewhere.
+            let conflicts = [
+                ("soa", self.options.soa.as_ref().map(|soa| soa.0)),
+                ("split", self.options.split),
+                (
+                    "redundant",
+                    self.options.redundant.as_ref().map(|redundant| redundant.0),
+                ),
+                ("patchable", self.options.patchable),
+                ("const_table", self.options.const_table),
+                ("crc", self.options.crc),
+                ("index", self.options.index),
+                ("bytes", self.options.bytes),
+                ("ndarray", self.options.ndarray),
+                ("nalgebra(view)", self.options.nalgebra_view),
+                (
+                    self.options
+                        .export
+                        .as_ref()
+                        .map_or("export_c(table)", Export::table_option),
+                    self.options
+                        .export
+                        .as_ref()
+                        .and_then(|export| export.table.as_ref())
+                        .map(|table| table.span()),
+                ),
+            ];
+            for (name, other) in conflicts.iter() {
+                if other.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`#[indirect]` can't be combined with `#[{}]`", name),
+                    ));
+                }
+            }
+        }
+        if let (Some(span), None) = (self.options.reload, self.options.patchable) {
+            return Err(syn::Error::new(
+                span,
+                "`#[reload]` replaces the entries of `#[patchable]`, which this table lacks",
+            ));
+        }
+        if let Some(span) = self.options.patchable {
+            if atomic_type(&self.return_type).is_none() {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[patchable]` requires integer entries of up to 64 bits, or `bool`, `f32` or `f64` entries",
+                ));
+            }
+            if self.options.mask {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[patchable]` can't be combined with `#[mask]`, which stores some entries more than once",
+                ));
+            }
+            // These read the defaults instead of the patched entries.
+            let conflicts = [
+                ("soa", self.options.soa.as_ref().map(|soa| soa.0)),
+                ("split", self.options.split),
+                ("f16", self.options.f16),
+                (
+                    self.options.codec.as_ref().map_or("codec", Codec::name),
+                    self.options.codec.as_ref().map(|codec| codec.span),
+                ),
+                ("guard", self.options.guard.as_ref().map(|guard| guard.span)),
+                (
+                    "redundant",
+                    self.options.redundant.as_ref().map(|redundant| redundant.0),
+                ),
+                ("invert", self.options.invert.map(|invert| invert.0)),
+                ("index", self.options.index),
+                ("bytes", self.options.bytes),
+                ("ndarray", self.options.ndarray),
+                ("nalgebra(view)", self.options.nalgebra_view),
+                (
+                    self.options
+                        .export
+                        .as_ref()
+                        .map_or("export_c(table)", Export::table_option),
+                    self.options
+                        .export
+                        .as_ref()
+                        .and_then(|export| export.table.as_ref())
+                        .map(|table| table.span()),
+                ),
+            ];
+            for (name, other) in conflicts.iter() {
+                if other.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`#[patchable]` can't be combined with `#[{}]`", name),
+                    ));
+                }
+            }
+        }
+        if let Some(span) = self.options.split {
+            if self.options.soa.is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[split]` can't be combined with `#[soa]`",
+                ));
+            }
+            if split_signed(&self.return_type).is_none() {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[split]` requires `u128` or `i128` entries",
+                ));
+            }
+        }
+        if let Some(guard) = &self.options.guard {
+            let needs_default = !guard.absent && guard.default.is_none();
+            if needs_default && zero_value(&self.return_type).is_none() {
+                return Err(syn::Error::new(
+                    guard.span,
+                    "`#[guard]` requires a `default = ...` for entries of this type",
+                ));
+            }
+        }
+        if let Some(cover) = &self.options.cover {
+            let param = match self.inputs.iter().collect::<Vec<_>>().as_slice() {
+                [param] => *param,
+                _ => {
+                    return Err(syn::Error::new(
+                        cover.span,
+                        "`#[cover]` requires exactly one parameter",
+                    ))
+                }
+            };
+            let (param_lo, param_hi) = (param.lo, param.lo + param.len() - 1);
+            for &(lo, hi) in &cover.ranges {
+                if lo < param_lo || hi > param_hi {
+                    return Err(syn::Error::new(
+                        cover.span,
+                        format!(
+                            "the covered range {:#x}..={:#x} is outside of the range of `{}`",
+                            lo, hi, param.ident
+                        ),
+                    ));
+                }
+            }
+        }
+        if let (Some(span), true) = (
+            self.inputs.iter().find_map(|param| param.policy.span()),
+            self.options.mask,
+        ) {
+            return Err(syn::Error::new(
+                span,
+                "`#[mask]` already wraps every parameter, so it can't be combined with `#[clamp]` or `#[wrap]`",
+            ));
+        }
+        if let Some(fallback) = &self.options.fallback {
+            use syn::spanned::Spanned;
+            if self.options.mask {
+                return Err(syn::Error::new(
+                    fallback.span(),
+                    "`#[mask]` wraps every parameter, so the fallback would never be called",
+                ));
+            }
+            if self
+                .inputs
+                .iter()
+                .all(|param| !matches!(param.policy, Policy::Panic))
+            {
+                return Err(syn::Error::new(
+                    fallback.span(),
+                    "`#[fallback]` requires a parameter without `#[clamp]` or `#[wrap]`",
+                ));
+            }
+        }
+        if let Some(span) = self.options.transpose {
+            if self.inputs.len() < 2 {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[transpose]` requires at least two parameters",
+                ));
+            }
+        }
+        if let Some(span) = self.options.direct_call {
+            let callable = matches!(
+                &self.return_type,
+                syn::Type::BareFn(bare_fn) if bare_fn.unsafety.is_none() && bare_fn.variadic.is_none()
+            );
+            if !callable {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[call(direct)]` requires entries that are safe function pointers (like `fn(&mut Cpu)`)",
+                ));
+            }
+            if let Some(codec) = &self.options.codec {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "`#[call(direct)]` can't be combined with `#[{}]`",
+                        codec.name()
+                    ),
+                ));
+            }
+        }
+        self.validate_contiguous()?;
+        self.validate_permute()?;
+        self.validate_heap()?;
+        self.validate_compressed()?;
+        self.validate_interpolate()?;
+        self.validate_layout()
+    }
+
+    /// Checks that the options don't need a table if there is none.
+    fn validate_strategy(&self, strategy: Strategy) -> syn::Result<()> {
+        if let (Some(span), Strategy::Match) = (self.options.crc, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[crc]` verifies the stored entries, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if let (Some(span), Strategy::Match) = (self.options.aggregates, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[aggregates]` reduces the stored entries, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if let (Some(span), Strategy::Match) = (self.options.inverse, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[inverse]` inverts the stored entries, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if let (Some(span), Strategy::Match) = (self.options.patchable, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[patchable]` stores the entries in atomics, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if let (Some(span), Strategy::Match) = (self.options.indirect, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[indirect]` reads the table through a reference, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if let (Some(span), Strategy::Match) = (self.options.compressed, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[compressed]` decompresses the stored entries, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if let (Some((span, _)), Strategy::Match) = (self.options.redundant, strategy) {
+            return Err(syn::Error::new(
+                span,
+                "`#[redundant]` compares the stored entries, but this table is looked up with a `match` (lower `match_up_to`)",
+            ));
+        }
+        if strategy != Strategy::Compute {
+            return Ok(());
+        }
+        let reason = match self.options.heap {
+            Some(_) => "the entries of this table are evaluated at runtime with `#[heap]`",
+            None => {
+                "the entries of this table are computed on every lookup (raise `compute_above`)"
+            }
+        };
+        if let Some(span) = self.options.total {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "`#[total]` can't rule out panics in the body, but {}",
+                    reason
+                ),
+            ));
+        }
+        let needs_table = [
+            (
+                "verify",
+                self.options.verify.as_ref().map(|path| path.span()),
+            ),
+            ("hash", self.options.hash),
+            ("crc", self.options.crc),
+            (
+                "redundant",
+                self.options.redundant.as_ref().map(|redundant| redundant.0),
+            ),
+            ("shader", self.options.shaders_span),
+            ("index", self.options.index),
+            ("heatmap", self.options.heatmap),
+            (
+                "check",
+                self.options.shape_checks.first().map(|check| check.span),
+            ),
+            ("inverse", self.options.inverse),
+            ("aggregates", self.options.aggregates),
+            (
+                "permute(..)",
+                self.inputs
+                    .iter()
+                    .find_map(|param| param.permute.map(|(span, _)| span)),
+            ),
+            ("patchable", self.options.patchable),
+            ("const_table", self.options.const_table),
+            ("indirect", self.options.indirect),
+            ("compressed", self.options.compressed),
+            ("cover", self.options.cover.as_ref().map(|cover| cover.span)),
+            ("bytes", self.options.bytes),
+            ("ndarray", self.options.ndarray),
+            ("nalgebra(view)", self.options.nalgebra_view),
+            (
+                self.options
+                    .export
+                    .as_ref()
+                    .map_or("export_c(table)", Export::table_option),
+                self.options
+                    .export
+                    .as_ref()
+                    .and_then(|e| e.table.as_ref().map(|table| table.span())),
+            ),
+        ];
+        for (name, span) in needs_table.iter() {
+            if let Some(span) = span {
+                return Err(syn::Error::new(
+                    *span,
+                    format!("`#[{}]` requires a table, but {}", name, reason),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_layout(&self) -> syn::Result<()> {
+        let policy = self.inputs.iter().find_map(|param| param.policy.span());
+        let (span, name) = match self.options.layout {
+            Layout::Dense => return Ok(()),
+            _ if policy.is_some() => {
+                return Err(syn::Error::new(
+                    policy.unwrap(),
+                    "`#[clamp]` and `#[wrap]` require the default layout",
+                ));
+            }
+            _ if self.options.soa.is_some() => {
+                let span = self.options.soa.as_ref().map(|soa| soa.0).unwrap();
+                return Err(syn::Error::new(
+                    span,
+                    "`#[soa]` requires the default layout",
+                ));
+            }
+            _ if self.options.nalgebra_view.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.nalgebra_view.unwrap(),
+                    "`#[nalgebra(view)]` requires the default layout",
+                ));
+            }
+            _ if self.options.ndarray.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.ndarray.unwrap(),
+                    "`#[ndarray]` requires the default layout",
+                ));
+            }
+            _ if self.options.heatmap.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.heatmap.unwrap(),
+                    "`#[heatmap]` requires the default layout",
+                ));
+            }
+            _ if self.options.index.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.index.unwrap(),
+                    "`#[index]` requires the default layout",
+                ));
+            }
+            _ if self.options.f16.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.f16.unwrap(),
+                    "`#[f16]` requires the default layout",
+                ));
+            }
+            _ if self.options.inverse.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.inverse.unwrap(),
+                    "`#[inverse]` requires the default layout",
+                ));
+            }
+            _ if self.options.aggregates.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.aggregates.unwrap(),
+                    "`#[aggregates]` requires the default layout",
+                ));
+            }
+            _ if self.options.patchable.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.patchable.unwrap(),
+                    "`#[patchable]` requires the default layout",
+                ));
+            }
+            _ if !self.options.shape_checks.is_empty() => {
+                return Err(syn::Error::new(
+                    self.options.shape_checks[0].span,
+                    "`#[check]` requires the default layout",
+                ));
+            }
+            _ if self.options.codec.is_some() => {
+                let codec = self.options.codec.as_ref().unwrap();
+                return Err(syn::Error::new(
+                    codec.span,
+                    format!("`#[{}]` requires the default layout", codec.name()),
+                ));
+            }
+            _ if self.options.split.is_some() => {
+                return Err(syn::Error::new(
+                    self.options.split.unwrap(),
+                    "`#[split]` requires the default layout",
+                ));
+            }
+            Layout::Deduplicated(span) => return self.validate_deduplicated(span),
+            Layout::Symmetric(span) => (span, "symmetric"),
+            Layout::Banded { span, .. } => (span, "banded"),
+        };
+        if self.inputs.len() != 2 {
+            return Err(syn::Error::new(
+                span,
+                format!("`#[{}]` requires exactly two parameters", name),
+            ));
+        }
+        if self.options.mask {
+            return Err(syn::Error::new(
+                span,
+                format!("`#[{}]` cannot be combined with `#[mask]`", name),
+            ));
+        }
+        if let Layout::Symmetric(_) = self.options.layout {
+            let (x, y) = (&self.inputs[0], &self.inputs[1]);
+            if x.lo != y.lo || x.len() != y.len() {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[symmetric]` requires both parameters to have identical ranges",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_contiguous(&self) -> syn::Result<()> {
+        let mut contiguous = self.inputs.iter().filter_map(|param| param.contiguous);
+        let span = match contiguous.next() {
+            Some(span) => span,
+            None => return Ok(()),
+        };
+        if let Some(span) = contiguous.next() {
+            return Err(syn::Error::new(
+                span,
+                "only one parameter can be `#[contiguous]`",
+            ));
+        }
+        if !matches!(self.options.layout, Layout::Dense) {
+            return Err(syn::Error::new(
+                span,
+                "`#[contiguous]` requires the default layout",
+            ));
+        }
+        // These read the stored entries in the declared order of the parameters.
+        let declared_order = [
+            (
+                "check",
+                self.options.shape_checks.first().map(|check| check.span),
+            ),
+            ("aggregates", self.options.aggregates),
+            ("reload", self.options.reload),
+            ("heatmap", self.options.heatmap),
+            ("ndarray", self.options.ndarray),
+            ("nalgebra(view)", self.options.nalgebra_view),
+        ];
+        for (name, option) in declared_order.iter() {
+            if let Some(option) = option {
+                return Err(syn::Error::new(
+                    *option,
+                    format!(
+                        "`#[{}]` reads the entries in the declared order of the parameters, so it can't be combined with `#[contiguous]`",
+                        name
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_heap(&self) -> syn::Result<()> {
+        let span = match self.options.heap {
+            Some(span) => span,
+            None => return Ok(()),
+        };
+        if self.inputs.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "`#[heap]` requires at least one parameter",
+            ));
+        }
+        if !matches!(self.options.layout, Layout::Dense) {
+            return Err(syn::Error::new(
+                span,
+                "`#[heap]` requires the default layout",
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_compressed(&self) -> syn::Result<()> {
+        let span = match self.options.compressed {
+            Some(span) => span,
+            None => return Ok(()),
+        };
+        let stored = match &self.options.codec {
+            Some(codec) => &codec.stored,
+            None => &self.return_type,
+        };
+        if self.options.f16.is_some() || preset::primitive_name(stored).is_none() {
+            return Err(syn::Error::new(
+                span,
+                "`#[compressed]` requires entries that are stored as primitive integers or floats",
+            ));
+        }
+        if !matches!(self.options.layout, Layout::Dense) {
+            return Err(syn::Error::new(
+                span,
+                "`#[compressed]` requires the default layout",
+            ));
+        }
+        // These read the table that is built into the image instead of the decompressed one, or
+        // keep their entries elsewhere.
+        let conflicts = [
+            ("indirect", self.options.indirect),
+            ("heap", self.options.heap),
+            ("soa", self.options.soa.as_ref().map(|soa| soa.0)),
+            ("split", self.options.split),
+            (
+                "redundant",
+                self.options.redundant.as_ref().map(|redundant| redundant.0),
+            ),
+            ("patchable", self.options.patchable),
+            ("const_table", self.options.const_table),
+            ("crc", self.options.crc),
+            ("index", self.options.index),
+            ("bytes", self.options.bytes),
+            ("ndarray", self.options.ndarray),
+            ("nalgebra(view)", self.options.nalgebra_view),
+            (
+                self.options
+                    .export
+                    .as_ref()
+                    .map_or("export_c(table)", Export::table_option),
+                self.options
+                    .export
+                    .as_ref()
+                    .and_then(|export| export.table.as_ref())
+                    .map(|table| table.span()),
+            ),
+        ];
+        for (name, other) in conflicts.iter() {
+            if other.is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("`#[compressed]` can't be combined with `#[{}]`", name),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_interpolate(&self) -> syn::Result<()> {
+        let interpolate = match &self.options.interpolate {
+            Some(interpolate) => interpolate,
+            None => return Ok(()),
+        };
+        let span = interpolate.span;
+        if interpolate_element(&self.return_type).is_none() {
+            return Err(syn::Error::new(
+                span,
+                "`#[interpolate]` requires `f32` or `f64` entries, or arrays of them",
+            ));
+        }
+        if self.inputs.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "`#[interpolate]` requires at least one parameter",
+            ));
+        }
+        if let Some(param) = self.inputs.iter().find(|param| param.len() < 2) {
+            return Err(syn::Error::new(
+                param.ident.span(),
+                "`#[interpolate]` requires at least two values of every parameter",
+            ));
+        }
+        for (i, Domain { param: ident, .. }) in interpolate.domains.iter().enumerate() {
+            if !self.inputs.iter().any(|param| param.ident == *ident) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("`{}` isn't a parameter of this table", ident),
+                ));
+            }
+            if interpolate.domains[..i]
+                .iter()
+                .any(|other| other.param == *ident)
+            {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("duplicate domain of `{}`", ident),
+                ));
+            }
+        }
+        if self.options.sample.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`#[interpolate]` can't be combined with `#[sample]`, which both generate `sample`",
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate_permute(&self) -> syn::Result<()> {
+        for param in self.inputs.iter() {
+            let span = match param.permute {
+                Some((span, _)) => span,
+                None => continue,
+            };
+            if param.lo != 0 || !param.len().is_power_of_two() {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "`#[permute(..)]` requires a range of the form `0..2^n`, but `{}` has {} values starting at {}",
+                        param.ident,
+                        param.len(),
+                        param.lo
+                    ),
+                ));
+            }
+            if !matches!(self.options.layout, Layout::Dense) {
+                return Err(syn::Error::new(
+                    span,
+                    "`#[permute(..)]` requires the default layout",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_deduplicated(&self, span: proc_macro2::Span) -> syn::Result<()> {
+        if self.inputs.len() < 2 {
+            return Err(syn::Error::new(
+                span,
+                "`#[dedup]` requires at least two parameters",
+            ));
+        }
+        if self.options.mask {
+            return Err(syn::Error::new(
+                span,
+                "`#[dedup]` cannot be combined with `#[mask]`",
+            ));
+        }
+        if let Some((span, _)) = self.options.redundant {
+            return Err(syn::Error::new(
+                span,
+                "`#[redundant]` can't store a second copy of the rows of `#[dedup]`",
+            ));
+        }
+        if metadata::primitive_size(&self.return_type).is_none() {
+            return Err(syn::Error::new(
+                span,
+                "`#[dedup]` requires a primitive element type, so that rows can be compared during constant evaluation",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Options {
+    /// The integer type in which lookups compute offsets whose intermediate values go up to `max`,
+    /// and the items that declare it.
+    ///
+    /// Without `#[index_width(..)]`, offsets that fit into 16 bits are computed in `usize`, and
+    /// larger ones in `u32` on 16-bit targets, where `usize` would overflow.
+    fn index_type(&self, max: usize) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        match &self.index_width {
+            Some((_, width)) => (proc_macro2::TokenStream::new(), quote::quote!(#width)),
+            None if max <= u16::MAX as usize => {
+                (proc_macro2::TokenStream::new(), quote::quote!(usize))
+            }
+            None => (
+                quote::quote! {
+                    #[cfg(target_pointer_width = "16")]
+                    type __Index = u32;
+                    #[cfg(not(target_pointer_width = "16"))]
+                    type __Index = usize;
+                },
+                quote::quote!(__Index),
+            ),
+        }
+    }
+
+    /// The number of rows per shard for rows with the given number of entries.
+    fn shard_rows(&self, row_len: usize) -> Option<usize> {
+        let exports_table = self.export.as_ref().is_some_and(|e| e.table.is_some());
+        let needs_contiguous = self.bytes.is_some()
+            || self.ndarray.is_some()
+            || self.nalgebra_view.is_some()
+            || self.redundant.is_some()
+            || self.patchable.is_some()
+            || self.indirect.is_some()
+            || self.compressed.is_some()
+            || self.const_table.is_some();
+        if self.mask || exports_table || needs_contiguous {
+            return None;
+        }
+        let entries = self.shard_entries.unwrap_or(DEFAULT_SHARD_ENTRIES);
+        let rows = (entries / row_len.max(1)).max(1);
+        Some(1 << (usize::BITS - 1 - rows.leading_zeros()))
+    }
+
+    /// The type returned by lookups, given the type returned by the body.
+    fn lookup_type(&self, body_type: syn::Type) -> syn::Type {
+        match &self.guard {
+            Some(guard) if guard.absent => syn::parse_quote!(::core::option::Option<#body_type>),
+            _ => body_type,
+        }
+    }
+
+    /// The strategy for looking up entries of a table with the given number of entries.
+    fn strategy(&self, len: usize) -> Strategy {
+        match (self.match_up_to, self.compute_above) {
+            // Tables on the heap are evaluated like computed entries, but only once.
+            _ if self.heap.is_some() => Strategy::Compute,
+            (Some(max), _) if len <= max => Strategy::Match,
+            (_, Some(min)) if len > min => Strategy::Compute,
+            _ => Strategy::Table,
+        }
+    }
+
+    /// Extracts the options from the given attributes, returning the remaining attributes.
+    fn from_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<(Self, Vec<syn::Attribute>)> {
+        use syn::spanned::Spanned;
+        let mut options = Options::default();
+        let mut rest = Vec::new();
+        for attr in attrs {
+            let name = attr.path.get_ident().map(|ident| ident.to_string());
+            match name.as_deref() {
+                Some("mask") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.mask = true;
+                }
+                Some("verify") => match attr.parse_meta()? {
+                    syn::Meta::NameValue(syn::MetaNameValue {
+                        lit: syn::Lit::Str(path),
+                        ..
+                    }) => options.verify = Some(path),
+                    other => {
+                        return Err(syn::Error::new(
+                            other.span(),
+                            "expected a path (e.g. `#[verify = \"expected.bin\"]`)",
+                        ))
+                    }
+                },
+                Some("check") => {
+                    let checks = attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::ExprCall, syn::Token![,]>::parse_terminated,
+                    )?;
+                    for check in checks {
+                        let name = match &*check.func {
+                            syn::Expr::Path(path) => path.path.get_ident(),
+                            _ => None,
+                        };
+                        let shape = name.and_then(|name| {
+                            Shape::ALL
+                                .iter()
+                                .find(|(shape, _)| name == shape)
+                                .map(|(_, shape)| *shape)
+                        });
+                        let param = match check.args.iter().collect::<Vec<_>>().as_slice() {
+                            [syn::Expr::Path(path)] => path.path.get_ident().cloned(),
+                            _ => None,
+                        };
+                        match (shape, param) {
+                            (Some(shape), Some(param)) => options.shape_checks.push(ShapeCheck {
+                                span: check.span(),
+                                shape,
+                                param,
+                            }),
+                            _ => {
+                                return Err(syn::Error::new(
+                                    check.span(),
+                                    "expected a shape like `monotone_increasing(x)`, `monotone_decreasing(x)`, `strictly_increasing(x)`, `strictly_decreasing(x)`, `convex(x)` or `concave(x)`",
+                                ))
+                            }
+                        }
+                    }
+                }
+                Some("register") => {
+                    if cfg!(not(feature = "linkme")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[register]` requires the `linkme` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.register = Some(attr.parse_args()?);
+                }
+                Some("built") => match attr.parse_meta()? {
+                    syn::Meta::NameValue(syn::MetaNameValue {
+                        lit: syn::Lit::Str(name),
+                        ..
+                    }) => options.built = Some(name),
+                    other => {
+                        return Err(syn::Error::new(
+                            other.span(),
+                            "expected `#[built = \"name\"]`",
+                        ))
+                    }
+                },
+                Some("sidecar") => {
+                    let span = attr.path.span();
+                    let name = match attr.parse_meta()? {
+                        syn::Meta::Path(_) => None,
+                        syn::Meta::NameValue(syn::MetaNameValue {
+                            lit: syn::Lit::Str(name),
+                            ..
+                        }) => Some(name),
+                        other => {
+                            return Err(syn::Error::new(
+                                other.span(),
+                                "expected `#[sidecar]` or `#[sidecar = \"name\"]`",
+                            ))
+                        }
+                    };
+                    options.sidecar = Some((span, name));
+                }
+                Some("shard") => {
+                    let entries = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let key = input.parse::<syn::Ident>()?;
+                        if key != "entries" {
+                            return Err(syn::Error::new(key.span(), "expected `entries = ...`"));
+                        }
+                        input.parse::<syn::Token![=]>()?;
+                        input.parse::<syn::LitInt>()?.base10_parse()
+                    })?;
+                    options.shard_entries = Some(entries);
+                }
+                Some("warn_above") => {
+                    let entries = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let key = input.parse::<syn::Ident>()?;
+                        if key != "entries" {
+                            return Err(syn::Error::new(key.span(), "expected `entries = ...`"));
+                        }
+                        input.parse::<syn::Token![=]>()?;
+                        input.parse::<syn::LitInt>()?.base10_parse()
+                    })?;
+                    options.warn_above = Some(entries);
+                }
+                Some("wide_entries") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.wide_entries = Some(attr.path.span());
+                }
+                Some("const_eval") => {
+                    let args = attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                    )?;
+                    for arg in args {
+                        match arg {
+                            syn::Meta::Path(path) if path.is_ident("isolate") => {
+                                options.const_eval.isolate = Some(path.span())
+                            }
+                            syn::Meta::Path(path)
+                                if path.is_ident("allow")
+                                    || path.is_ident("warn")
+                                    || path.is_ident("deny") =>
+                            {
+                                options.const_eval.lint = path.get_ident().cloned()
+                            }
+                            syn::Meta::NameValue(syn::MetaNameValue {
+                                path,
+                                lit: syn::Lit::Int(value),
+                                ..
+                            }) if path.is_ident("isolate_up_to") => {
+                                options.const_eval.isolate_up_to = Some(value.base10_parse()?)
+                            }
+                            other => {
+                                return Err(syn::Error::new(
+                                    other.span(),
+                                    "expected `allow`, `warn`, `deny`, `isolate` or `isolate_up_to = n`",
+                                ))
+                            }
+                        }
+                    }
+                }
+                Some("export_c") => {
+                    if options.export.is_some() {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[export_c]` can't be combined with `#[export]`",
+                        ));
+                    }
+                    let mut export = Export {
+                        span: attr.path.span(),
+                        c: true,
+                        name: None,
+                        table: None,
+                    };
+                    if !attr.tokens.is_empty() {
+                        let args = attr.parse_args_with(
+                            syn::punctuated::Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated,
+                        )?;
+                        for arg in args {
+                            let value = match arg.lit {
+                                syn::Lit::Str(value) => value,
+                                other => {
+                                    return Err(syn::Error::new(other.span(), "expected a string"))
+                                }
+                            };
+                            if arg.path.is_ident("name") {
+                                export.name = Some(value);
+                            } else if arg.path.is_ident("table") {
+                                export.table = Some(value);
+                            } else {
+                                return Err(syn::Error::new(
+                                    arg.path.span(),
+                                    "expected `name = \"...\"` or `table = \"...\"`",
+                                ));
+                            }
+                        }
+                    }
+                    options.export = Some(export);
+                }
+                Some("export") => {
+                    if options.export.is_some() {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[export]` can't be combined with `#[export_c]`",
+                        ));
+                    }
+                    let arg = attr.parse_args::<syn::MetaNameValue>()?;
+                    let table = match arg.lit {
+                        syn::Lit::Str(table) if arg.path.is_ident("table") => table,
+                        _ => return Err(syn::Error::new(arg.span(), "expected `table = \"...\"`")),
+                    };
+                    options.export = Some(Export {
+                        span: attr.path.span(),
+                        c: false,
+                        name: None,
+                        table: Some(table),
+                    });
+                }
+                Some("wasm_bindgen") => {
+                    let span = attr.path.span();
+                    if cfg!(not(feature = "wasm-bindgen")) {
+                        return Err(syn::Error::new(
+                            span,
+                            "`#[wasm_bindgen]` requires the `wasm-bindgen` feature of `numeric-lut`",
+                        ));
+                    }
+                    let name = if attr.tokens.is_empty() {
+                        None
+                    } else {
+                        Some(attr.parse_args_with(|input: syn::parse::ParseStream| {
+                            let key = input.parse::<syn::Ident>()?;
+                            if key != "name" {
+                                return Err(syn::Error::new(key.span(), "expected `name = ...`"));
+                            }
+                            input.parse::<syn::Token![=]>()?;
+                            input.parse::<syn::LitStr>()
+                        })?)
+                    };
+                    options.wasm_bindgen = Some((span, name));
+                }
+                Some("shader") => {
+                    let languages = attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated,
+                    )?;
+                    for language in languages {
+                        options
+                            .shaders
+                            .push(shader::Language::from_ident(&language)?);
+                    }
+                    options.shaders_span = Some(attr.path.span());
+                }
+                Some("index_width") => {
+                    let width = attr.parse_args::<syn::Ident>()?;
+                    if !matches!(width.to_string().as_str(), "u16" | "u32" | "u64" | "usize") {
+                        return Err(syn::Error::new(
+                            width.span(),
+                            "expected `u16`, `u32`, `u64` or `usize`",
+                        ));
+                    }
+                    options.index_width = Some((attr.path.span(), width));
+                }
+                Some("indirect") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.indirect = Some(attr.path.span());
+                }
+                Some("const_table") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.const_table = Some(attr.path.span());
+                }
+                Some("call") => {
+                    let mode = attr.parse_args::<syn::Ident>()?;
+                    if mode != "direct" {
+                        return Err(syn::Error::new(mode.span(), "expected `direct`"));
+                    }
+                    options.direct_call = Some(attr.path.span());
+                }
+                Some("strategy") => {
+                    let args = attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                    )?;
+                    let mut auto = false;
+                    for arg in args {
+                        match arg {
+                            syn::Meta::Path(path) if path.is_ident("auto") => auto = true,
+                            syn::Meta::NameValue(syn::MetaNameValue {
+                                path,
+                                lit: syn::Lit::Int(value),
+                                ..
+                            }) if path.is_ident("match_up_to")
+                                || path.is_ident("compute_above") =>
+                            {
+                                let value = Some(value.base10_parse()?);
+                                if path.is_ident("match_up_to") {
+                                    options.match_up_to = value;
+                                } else {
+                                    options.compute_above = value;
+                                }
+                            }
+                            other => {
+                                return Err(syn::Error::new(
+                                    other.span(),
+                                    "expected `auto`, `match_up_to = n` or `compute_above = n`",
+                                ))
+                            }
+                        }
+                    }
+                    if auto {
+                        options.match_up_to = options.match_up_to.or(Some(DEFAULT_MATCH_UP_TO));
+                        options.compute_above =
+                            options.compute_above.or(Some(DEFAULT_COMPUTE_ABOVE));
+                    }
+                    options.strategy_span = Some(attr.path.span());
+                }
+                Some("soa") => {
+                    let fields = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        syn::punctuated::Punctuated::<syn::Field, syn::Token![,]>::parse_terminated_with(
+                            input,
+                            syn::Field::parse_named,
+                        )
+                    })?;
+                    options.soa = Some((attr.path.span(), fields.into_iter().collect()));
+                }
+                Some("guard") => {
+                    let span = attr.path.span();
+                    let guard = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let mut guard = Guard {
+                            span,
+                            condition: input.parse()?,
+                            default: None,
+                            absent: false,
+                        };
+                        while !input.is_empty() {
+                            input.parse::<syn::Token![,]>()?;
+                            if input.is_empty() {
+                                break;
+                            }
+                            let key = input.parse::<syn::Ident>()?;
+                            match key.to_string().as_str() {
+                                "default" => {
+                                    input.parse::<syn::Token![=]>()?;
+                                    guard.default = Some(input.parse()?);
+                                }
+                                "absent" => guard.absent = true,
+                                _ => {
+                                    return Err(syn::Error::new(
+                                        key.span(),
+                                        "expected `default = ...` or `absent`",
+                                    ))
+                                }
+                            }
+                        }
+                        if guard.absent && guard.default.is_some() {
+                            return Err(syn::Error::new(
+                                span,
+                                "`absent` entries don't have a `default`",
+                            ));
+                        }
+                        Ok(guard)
+                    })?;
+                    options.guard = Some(guard);
+                }
+                Some("cover") => {
+                    let span = attr.path.span();
+                    let cover = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let mut cover = Cover {
+                            span,
+                            ranges: Vec::new(),
+                            default: None,
+                        };
+                        while !input.is_empty() {
+                            if input.peek(syn::Ident) && input.peek2(syn::Token![=]) {
+                                let key = input.parse::<syn::Ident>()?;
+                                if key != "default" {
+                                    return Err(syn::Error::new(
+                                        key.span(),
+                                        "expected `default = ...`",
+                                    ));
+                                }
+                                input.parse::<syn::Token![=]>()?;
+                                cover.default = Some(input.parse()?);
+                            } else {
+                                cover.ranges.push(parse_cover_range(&input.parse()?)?);
+                            }
+                            if !input.is_empty() {
+                                input.parse::<syn::Token![,]>()?;
+                            }
+                        }
+                        Ok(cover)
+                    })?;
+                    options.cover = Some(cover);
+                }
+                Some("f16") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "half")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[f16]` requires the `half` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.f16 = Some(attr.path.span());
+                }
+                Some("codec") => {
+                    let span = attr.path.span();
+                    let codec = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let stored = input.parse()?;
+                        let mut encode = None;
+                        let mut decode = None;
+                        while !input.is_empty() {
+                            input.parse::<syn::Token![,]>()?;
+                            if input.is_empty() {
+                                break;
+                            }
+                            let key = input.parse::<syn::Ident>()?;
+                            input.parse::<syn::Token![=]>()?;
+                            match key.to_string().as_str() {
+                                "encode" => encode = Some(input.parse()?),
+                                "decode" => decode = Some(input.parse()?),
+                                _ => {
+                                    return Err(syn::Error::new(
+                                        key.span(),
+                                        "expected `encode = ...` or `decode = ...`",
+                                    ))
+                                }
+                            }
+                        }
+                        match (encode, decode) {
+                            (Some(encode), Some(decode)) => Ok(Codec {
+                                span,
+                                stored,
+                                conversion: Conversion::Functions { encode, decode },
+                            }),
+                            _ => Err(syn::Error::new(
+                                span,
+                                "`#[codec]` requires both `encode = ...` and `decode = ...`",
+                            )),
+                        }
+                    })?;
+                    if let Some(other) = &options.codec {
+                        return Err(syn::Error::new(
+                            span,
+                            format!("`#[codec]` can't be combined with `#[{}]`", other.name()),
+                        ));
+                    }
+                    options.codec = Some(codec);
+                }
+                Some("bitfields") => {
+                    let span = attr.path.span();
+                    let codec = attr.parse_args_with(|input: syn::parse::ParseStream| {
+                        let stored = input.parse::<syn::Type>()?;
+                        let width = integer_bits(&stored).ok_or_else(|| {
+                            syn::Error::new(
+                                stored.span(),
+                                "`#[bitfields]` requires an unsigned integer type (`u8` to `u128`) to pack the fields into",
+                            )
+                        })?;
+                        let mut fields = Vec::new();
+                        let mut total = 0;
+                        while !input.is_empty() {
+                            input.parse::<syn::Token![,]>()?;
+                            if input.is_empty() {
+                                break;
+                            }
+                            let ident = input.parse::<syn::Ident>()?;
+                            input.parse::<syn::Token![:]>()?;
+                            let ty = input.parse::<syn::Type>()?;
+                            input.parse::<syn::Token![=]>()?;
+                            let lit = input.parse::<syn::LitInt>()?;
+                            let bits = lit.base10_parse::<u32>()?;
+                            let max = match quote::quote!(#ty).to_string().as_str() {
+                                "bool" => Some(1),
+                                _ => integer_bits(&ty),
+                            };
+                            let max = max.ok_or_else(|| {
+                                syn::Error::new(
+                                    ty.span(),
+                                    "bitfields must be unsigned integers or `bool`",
+                                )
+                            })?;
+                            if bits == 0 || bits > max {
+                                return Err(syn::Error::new(
+                                    lit.span(),
+                                    format!("`{}` must have between 1 and {} bits", ident, max),
+                                ));
+                            }
+                            total += bits;
+                            fields.push(Bitfield { ident, ty, bits });
+                        }
+                        if fields.is_empty() {
+                            return Err(syn::Error::new(
+                                span,
+                                "`#[bitfields]` requires at least one field (e.g. `flags: u8 = 3`)",
+                            ));
+                        }
+                        if total > width {
+                            return Err(syn::Error::new(
+                                stored.span(),
+                                format!(
+                                    "the fields have {} bits, which don't fit into `{}`",
+                                    total,
+                                    quote::quote!(#stored)
+                                ),
+                            ));
+                        }
+                        Ok(Codec {
+                            span,
+                            stored,
+                            conversion: Conversion::Bitfields(fields),
+                        })
+                    })?;
+                    if let Some(other) = &options.codec {
+                        return Err(syn::Error::new(
+                            span,
+                            format!(
+                                "`#[bitfields]` can't be combined with `#[{}]`",
+                                other.name()
+                            ),
+                        ));
+                    }
+                    options.codec = Some(codec);
+                }
+                Some("split") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.split = Some(attr.path.span());
+                }
+                Some("bytes") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "bytemuck")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[bytes]` requires the `bytemuck` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.bytes = Some(attr.path.span());
+                }
+                Some("try_get") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.try_get = Some(attr.path.span());
+                }
+                Some("get_or") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.get_or = Some(attr.path.span());
+                }
+                Some("transform") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.transform = Some(attr.path.span());
+                }
+                Some("interpolate") => {
+                    let span = attr.path.span();
+                    options.interpolate = Some(if attr.tokens.is_empty() {
+                        Interpolate {
+                            span,
+                            domains: Vec::new(),
+                            extrapolate: Extrapolate::Clamp,
+                        }
+                    } else {
+                        attr.parse_args_with(|input: syn::parse::ParseStream| {
+                            parse_interpolate(span, input)
+                        })?
+                    });
+                }
+                Some("index") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.index = Some(attr.path.span());
+                }
+                Some("dump") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.dump = Some(attr.path.span());
+                }
+                Some("csv") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "std")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[csv]` requires the `std` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.csv = Some(attr.path.span());
+                }
+                Some("heatmap") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.heatmap = Some(attr.path.span());
+                }
+                Some("fallback") => {
+                    options.fallback = Some(attr.parse_args()?);
+                }
+                Some("total") => {
+                    if !attr.tokens.is_empty() {
+                        let arg = attr.parse_args::<syn::Ident>()?;
+                        if arg != "no_panic" {
+                            return Err(syn::Error::new(
+                                arg.span(),
+                                "expected `no_panic` (e.g. `#[total(no_panic)]`)",
+                            ));
+                        }
+                        if cfg!(not(feature = "no-panic")) {
+                            return Err(syn::Error::new(
+                                arg.span(),
+                                "`#[total(no_panic)]` requires the `no-panic` feature of `numeric-lut`",
+                            ));
+                        }
+                        options.no_panic = Some(arg.span());
+                    }
+                    options.total = Some(attr.path.span());
+                }
+                Some("trace") => {
+                    let span = attr.path.span();
+                    let callback = if attr.tokens.is_empty() {
+                        if cfg!(not(feature = "log")) {
+                            return Err(syn::Error::new(
+                                span,
+                                "`#[trace]` requires the `log` feature of `numeric-lut` (or a callback, e.g. `#[trace(f)]`)",
+                            ));
+                        }
+                        None
+                    } else {
+                        Some(attr.parse_args()?)
+                    };
+                    options.trace = Some((span, callback));
+                }
+                Some("sample") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "rand")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[sample]` requires the `rand` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.sample = Some(attr.path.span());
+                }
+                Some("ndarray") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "ndarray")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[ndarray]` requires the `ndarray` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.ndarray = Some(attr.path.span());
+                }
+                Some("runtime") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "runtime")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[runtime]` requires the `runtime` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.runtime = Some(attr.path.span());
+                }
+                Some("nalgebra") => {
+                    if cfg!(not(feature = "nalgebra")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[nalgebra]` requires the `nalgebra` feature of `numeric-lut`",
+                        ));
+                    }
+                    let parts = attr.parse_args_with(
+                        syn::punctuated::Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated,
+                    )?;
+                    for part in parts {
+                        match part.to_string().as_str() {
+                            "entries" => options.nalgebra_entries = Some(part.span()),
+                            "view" => options.nalgebra_view = Some(part.span()),
+                            _ => {
+                                return Err(syn::Error::new(
+                                    part.span(),
+                                    "expected `entries` or `view`",
+                                ))
+                            }
+                        }
+                    }
+                }
+                Some("hash") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.hash = Some(attr.path.span());
+                }
+                Some("redundant") => {
+                    let span = attr.path.span();
+                    let inverted = if attr.tokens.is_empty() {
+                        false
+                    } else {
+                        let arg = attr.parse_args::<syn::Ident>()?;
+                        if arg != "inverted" {
+                            return Err(syn::Error::new(arg.span(), "expected `inverted`"));
+                        }
+                        true
+                    };
+                    options.redundant = Some((span, inverted));
+                }
+                Some("patchable") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.patchable = Some(attr.path.span());
+                }
+                Some("reload") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "std")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[reload]` requires the `std` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.reload = Some(attr.path.span());
+                }
+                Some("heap") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    if cfg!(not(feature = "std")) {
+                        return Err(syn::Error::new(
+                            attr.path.span(),
+                            "`#[heap]` requires the `std` feature of `numeric-lut`",
+                        ));
+                    }
+                    options.heap = Some(attr.path.span());
+                }
+                Some("compressed") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.compressed = Some(attr.path.span());
+                }
+                Some("crc") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.crc = Some(attr.path.span());
+                }
+                Some("inverse") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.inverse = Some(attr.path.span());
+                }
+                Some("invert") => {
+                    let span = attr.path.span();
+                    let interpolate = if attr.tokens.is_empty() {
+                        false
+                    } else {
+                        let arg = attr.parse_args::<syn::Ident>()?;
+                        if arg != "interpolate" {
+                            return Err(syn::Error::new(arg.span(), "expected `interpolate`"));
+                        }
+                        true
+                    };
+                    options.invert = Some((span, interpolate));
+                }
+                Some("find_nearest") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.find_nearest = Some(attr.path.span());
+                }
+                Some("aggregates") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.aggregates = Some(attr.path.span());
+                }
+                Some("transpose") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.transpose = Some(attr.path.span());
+                }
+                Some("symmetric") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.layout = Layout::Symmetric(attr.path.span());
+                }
+                Some("dedup") => {
+                    syn::parse2::<syn::parse::Nothing>(attr.tokens)?;
+                    options.layout = Layout::Deduplicated(attr.path.span());
+                }
+                Some("banded") => {
+                    let span = attr.path.span();
+                    let (width, default) =
+                        attr.parse_args_with(|input: syn::parse::ParseStream| {
+                            let width = input.parse::<syn::LitInt>()?.base10_parse()?;
+                            input.parse::<syn::Token![,]>()?;
+                            let key = input.parse::<syn::Ident>()?;
+                            if key != "default" {
+                                return Err(syn::Error::new(
+                                    key.span(),
+                                    "expected `default = ...`",
+                                ));
+                            }
+                            input.parse::<syn::Token![=]>()?;
+                            let default = input.parse::<syn::Expr>()?;
+                            Ok((width, Box::new(default)))
+                        })?;
+                    options.layout = Layout::Banded {
+                        span,
+                        width,
+                        default,
+                    };
+                }
+                _ => rest.push(attr),
+            }
+        }
+        Ok((options, rest))
+    }
+}
+
+/// Parses a range of covered values like `0x10..=0x1f`, `0x10..0x20` or `0x10`.
+fn parse_cover_range(expr: &syn::Expr) -> syn::Result<(usize, usize)> {
+    use syn::spanned::Spanned;
+    let int = |expr: &Option<Box<syn::Expr>>| match expr.as_deref() {
+        Some(syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        })) => value.base10_parse::<usize>(),
+        _ => Err(syn::Error::new(expr.span(), "must be an integer literal")),
+    };
+    let (lo, hi) = match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        }) => {
+            let value = value.base10_parse()?;
+            (value, value)
+        }
+        syn::Expr::Range(syn::ExprRange {
+            from,
+            limits: syn::RangeLimits::Closed(_),
+            to,
+            ..
+        }) => (int(from)?, int(to)?),
+        syn::Expr::Range(syn::ExprRange {
+            from,
+            limits: syn::RangeLimits::HalfOpen(_),
+            to,
+            ..
+        }) => {
+            // An exclusive upper bound of zero would wrap around to cover every value.
+            let hi = int(to)?.checked_sub(1);
+            let hi = hi.ok_or_else(|| syn::Error::new(expr.span(), "the range is empty"))?;
+            (int(from)?, hi)
+        }
+        other => {
+            return Err(syn::Error::new(
+                other.span(),
+                "expected a range of covered values (e.g. `0x10..=0x1f`) or `default = ...`",
+            ))
+        }
+    };
+    if hi < lo {
+        return Err(syn::Error::new(expr.span(), "the range is empty"));
+    }
+    Ok((lo, hi))
+}
+
+impl syn::parse::Parse for Lut {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        use syn::spanned::Spanned;
+        let (options, attrs) = Options::from_attrs(input.call(syn::Attribute::parse_outer)?)?;
+
+        let is_item = input.peek(syn::Token![pub]) || input.peek(syn::Token![struct]);
+        if !is_item {
+            if let Some(attr) = attrs.first() {
+                return Err(syn::Error::new(
+                    attr.path.span(),
+                    "unknown option (expected e.g. `#[mask]`)",
+                ));
+            }
+        }
+
+        if !is_item && !input.peek(syn::Token![|]) {
+            let path: syn::Path = input.parse()?;
+            let return_type = if input.peek(syn::Token![->]) {
+                input.parse::<syn::Token![->]>()?;
+                input.parse()?
+            } else {
+                syn::Type::Infer(syn::TypeInfer {
+                    underscore_token: Default::default(),
+                })
+            };
+            input.parse::<syn::Token![;]>()?;
+            let inputs: syn::punctuated::Punctuated<Param, syn::Token![,]> =
+                input.parse_terminated(Param::parse)?;
+
+            // The call is spanned at the path, so that errors in entries underline the function
+            // instead of the whole invocation.
+            let args = inputs.iter().map(|param| &param.ident);
+            let body = syn::Expr::Verbatim(quote::quote_spanned!(path.span()=> #path(#(#args),*)));
+            let return_type = options.lookup_type(return_type);
+            let lut = Lut {
+                options,
+                form: Form::Closure,
+                inputs,
+                return_type,
+                body,
+            };
+            lut.validate()?;
+            return Ok(lut);
+        }
+
+        let (form, inputs) = if input.peek(syn::Token![|]) {
+            input.parse::<syn::Token![|]>()?;
+            let mut inputs = syn::punctuated::Punctuated::new();
+            loop {
+                if input.peek(syn::Token![|]) {
+                    break;
+                }
+                let value = input.parse::<Param>()?;
+                inputs.push_value(value);
+                if input.peek(syn::Token![|]) {
+                    break;
+                }
+                let punct: syn::Token![,] = input.parse()?;
+                inputs.push_punct(punct);
+            }
+            input.parse::<syn::Token![|]>()?;
+
+            (Form::Closure, inputs)
+        } else {
+            let vis: syn::Visibility = input.parse()?;
+            input.parse::<syn::Token![struct]>()?;
+            let ident: syn::Ident = input.parse()?;
+
+            let content;
+            syn::parenthesized!(content in input);
+            let inputs = content.parse_terminated(Param::parse)?;
+
+            (Form::Struct { attrs, vis, ident }, inputs)
+        };
+
+        input.parse::<syn::Token![->]>()?;
+        let return_type: syn::Type = input.parse()?;
+        // Tables that were evaluated in a build script have no body.
+        let body = match &options.built {
+            Some(name) => {
+                if input.peek(syn::Token![;]) {
+                    input.parse::<syn::Token![;]>()?;
+                }
+                build_time::body(&inputs, name)
+            }
+            None => syn::Expr::Block(syn::ExprBlock {
+                attrs: Vec::new(),
+                label: None,
+                block: input.parse()?,
+            }),
+        };
+
+        let return_type = options.lookup_type(return_type);
+        let lut = Lut {
+            options,
+            form,
+            inputs,
+            return_type,
+            body,
+        };
+        lut.validate()?;
+        Ok(lut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The lines of documentation that the item form adds to the struct of the given invocation.
+    fn doc(input: &str) -> Vec<String> {
+        let input = syn::parse_str::<Lut>(input).unwrap();
+        let attrs = match &input.form {
+            Form::Struct { attrs, .. } => attrs,
+            Form::Closure => panic!("expected the item form"),
+        };
+        let doc = generate_doc(&input, attrs, &input.table());
+        syn::parse::Parser::parse2(syn::Attribute::parse_outer, doc)
+            .unwrap()
+            .iter()
+            .map(|attr| match attr.parse_meta().unwrap() {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    lit: syn::Lit::Str(line),
+                    ..
+                }) => line.value(),
+                _ => panic!("expected `#[doc = \"..\"]`"),
+            })
+            .collect()
+    }
+
+    /// The code that the given invocation expands to.
+    fn expanded(input: &str) -> String {
+        expand(input.parse().unwrap()).to_string()
+    }
+
+    /// The message of the error that the given invocation is rejected with.
+    fn error(input: &str) -> String {
+        match syn::parse_str::<Lut>(input) {
+            Ok(_) => panic!("expected `{}` to be rejected", input),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn empty_range_with_mask() {
+        assert_eq!(
+            "range 0..0 is empty, so the table would have no entries",
+            error("#[mask] |x @ 0..0| -> u8 { x as u8 }")
+        );
+        assert_eq!(
+            "range 0..0 is empty, so the table would have no entries",
+            error("#[mask] |x @ ..0| -> u8 { x as u8 }")
+        );
+    }
+
+    #[test]
+    fn empty_range_with_symmetric() {
+        assert_eq!(
+            "range 0..0 is empty, so the table would have no entries",
+            error("#[symmetric] |x @ 0..0, y @ 0..0| -> u8 { (x + y) as u8 }")
+        );
+    }
+
+    #[test]
+    fn empty_range_with_banded() {
+        assert_eq!(
+            "range 3..3 is empty, so the table would have no entries",
+            error("#[banded(1, default = 0)] |x @ 3..3, y @ 3..3| -> u8 { (x + y) as u8 }")
+        );
+    }
+
+    #[test]
+    fn empty_range_with_shards() {
+        assert_eq!(
+            "range 0..0 is empty, so the table would have no entries",
+            error("#[shard(entries = 4)] |x @ 0..0, y @ 0..4| -> u8 { (x + y) as u8 }")
+        );
+    }
+
+    #[test]
+    fn inferred_with_lint_level() {
+        assert_eq!(
+            "`#[const_eval(allow)]` requires an explicit return type (e.g. `lut!(f -> u8; ...)`)",
+            error("#[const_eval(allow)] weight; x @ 0..4")
+        );
+    }
+
+    #[test]
+    fn empty_range_with_cover() {
+        assert_eq!(
+            "range 0..0 is empty, so the table would have no entries",
+            error("#[cover(0..4)] |op @ 0..0| -> u8 { Some(op as u8) }")
+        );
+        assert_eq!(
+            "the range is empty",
+            error("#[cover(0..0)] |op @ 0..4| -> u8 { Some(op as u8) }")
+        );
+    }
+
+    #[test]
+    fn empty_range_with_policy() {
+        assert_eq!(
+            "range 2..2 is empty, so the table would have no entries",
+            error("|#[clamp] x @ 2..2| -> u8 { x as u8 }")
+        );
+        assert_eq!(
+            "range 0..0 is empty, so the table would have no entries",
+            error("|#[wrap] x @ ..0| -> u8 { x as u8 }")
+        );
+    }
+
+    #[test]
+    fn crc_of_const_table() {
+        assert_eq!(
+            "`#[const_table]` can't be combined with `#[crc]`",
+            error("#[crc] #[const_table] struct Digits(x @ 0..9) -> u8 { b'1' + x as u8 }")
+        );
+    }
+
+    #[test]
+    fn full_table_of_compressed_tables() {
+        let table = "static __TABLE";
+        assert!(expanded("struct Plain(x @ 0..4) -> u8 { x as u8 }").contains(table));
+        let options = [
+            ("compressed", "u16"),
+            ("indirect", "u16"),
+            ("split", "u128"),
+            ("strategy(match_up_to = 4)", "u16"),
+        ];
+        for (option, ty) in options {
+            let input = format!(
+                "#[{0}] struct Small(x @ 0..4) -> {1} {{ x as {1} }}",
+                option, ty
+            );
+            let expanded = expanded(&input);
+            assert!(!expanded.contains("compile_error"), "{}", expanded);
+            assert!(!expanded.contains(table), "{}", option);
+        }
+    }
+
+    #[test]
+    fn dither_ranks_of_narrow_type() {
+        let err = preset::dither::generate(quote::quote!(bayer, size = 32, ty = i8)).unwrap_err();
+        assert_eq!(
+            "the ranks of this matrix go up to 1023, which doesn't fit into `i8`",
+            err.to_string()
+        );
+        assert!(preset::dither::generate(quote::quote!(bayer, size = 16, ty = u8)).is_ok());
+    }
+
+    #[test]
+    fn decibel_gains_at_bounds_of_integers() {
+        // Gains up to 0 dB, which is 1.0 and is scaled to 2^frac_bits.
+        let full_scale = |ty: proc_macro2::TokenStream, frac_bits: usize, max: i32| {
+            let frac_bits = quote::quote!(frac_bits = #frac_bits);
+            let input = quote::quote!(to_gain, min = -12, max = #max, step = 6, #ty, #frac_bits);
+            preset::decibel::generate(input).map_err(|err| err.to_string())
+        };
+        // 2^63 is one more than `i64::MAX`.
+        assert_eq!(
+            "1 doesn't fit into the element type with 63 fractional bits",
+            full_scale(quote::quote!(i64), 63, 0).unwrap_err()
+        );
+        assert!(full_scale(quote::quote!(i64), 63, -6).is_ok());
+        assert!(full_scale(quote::quote!(u64), 63, 0).is_ok());
+        assert_eq!(
+            "1 doesn't fit into the element type with 8 fractional bits",
+            full_scale(quote::quote!(u8), 8, 0).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn inverted_redundant_banded() {
+        assert_eq!(
+            "`#[redundant(inverted)]` can't be combined with `#[banded(..)]`, whose default isn't stored in the inverted copy",
+            error("#[redundant(inverted)] #[banded(1, default = 0)] struct Band(x @ 0..4, y @ 0..4) -> u8 { (x + y) as u8 }")
+        );
+    }
+
+    #[test]
+    fn doc_of_dense_table() {
+        assert_eq!(
+            vec![
+                "",
+                " # Table",
+                "",
+                " | Parameter | Domain | Out of range |",
+                " |---|---|---|",
+                " | `x` | `0..4` | panics |",
+                " | `y` | `1..=3` | clamped |",
+                "",
+                " The table has 12 entries of `u16`, and stores all of them in 24 bytes.",
+                "",
+                " Every entry is the value of:",
+                "",
+                " ```text",
+                " { (x * y) as u16 }",
+                " ```",
+            ],
+            doc("/// Products.\nstruct Product(x @ 0..4, #[clamp] y @ 1..=3) -> u16 { (x * y) as u16 }")
+        );
+    }
+
+    #[test]
+    fn doc_of_padded_table() {
+        assert_eq!(
+            vec![
+                " # Table",
+                "",
+                " | Parameter | Domain | Out of range |",
+                " |---|---|---|",
+                " | `x` | `0..10` | masked |",
+                "",
+                " The table has 10 entries of `u8`, and pads them to 16 in 16 bytes.",
+                "",
+                " Every entry is the value of:",
+                "",
+                " ```text",
+                " { x as u8 }",
+                " ```",
+            ],
+            doc("#[mask] struct Bits(x @ 0..10) -> u8 { x as u8 }")
+        );
     }
 }
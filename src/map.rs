@@ -0,0 +1,218 @@
+//! Static maps with string keys, for keyword tables and the like.
+//!
+//! The keys are placed with a minimal perfect hash that is found while expanding the macro, using
+//! the hash-and-displace scheme: keys are grouped into buckets by one hash, and every bucket gets a
+//! seed for a second hash that places all of its keys into free slots.  Lookups thus hash the key
+//! twice and compare it against the single key in its slot.  The values are constant expressions.
+
+/// The average number of keys per bucket.
+const KEYS_PER_BUCKET: usize = 4;
+/// How many seeds are tried for a bucket before giving up.
+const MAX_SEEDS: u32 = 1 << 24;
+
+/// The input of `lut_map!`.
+struct Map {
+    form: super::Form,
+    /// The name of the key in the closure form.
+    key: syn::Ident,
+    value_type: syn::Type,
+    entries: Vec<(syn::LitStr, syn::Expr)>,
+}
+
+impl syn::parse::Parse for Map {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        use syn::spanned::Spanned;
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let (form, key) = if input.peek(syn::Token![|]) {
+            if let Some(attr) = attrs.first() {
+                return Err(syn::Error::new(
+                    attr.path.span(),
+                    "attributes are only supported in the item form",
+                ));
+            }
+            input.parse::<syn::Token![|]>()?;
+            let key = input.parse()?;
+            input.parse::<syn::Token![|]>()?;
+            (super::Form::Closure, key)
+        } else {
+            let vis = input.parse()?;
+            input.parse::<syn::Token![struct]>()?;
+            let ident = input.parse()?;
+            let form = super::Form::Struct { attrs, vis, ident };
+            (form, syn::Ident::new("key", proc_macro2::Span::call_site()))
+        };
+        input.parse::<syn::Token![->]>()?;
+        let value_type = input.parse()?;
+
+        let content;
+        syn::braced!(content in input);
+        let mut entries: Vec<(syn::LitStr, syn::Expr)> = Vec::new();
+        while !content.is_empty() {
+            let key: syn::LitStr = content.parse()?;
+            if entries
+                .iter()
+                .any(|(other, _)| other.value() == key.value())
+            {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("duplicate key {:?}", key.value()),
+                ));
+            }
+            content.parse::<syn::Token![=>]>()?;
+            entries.push((key, content.parse()?));
+            if content.is_empty() {
+                break;
+            }
+            content.parse::<syn::Token![,]>()?;
+        }
+
+        Ok(Map {
+            form,
+            key,
+            value_type,
+            entries,
+        })
+    }
+}
+
+/// Generates a static map from string keys to values.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let map: Map = syn::parse2(input)?;
+    let key = &map.key;
+    let value_type = &map.value_type;
+    let len = map.entries.len();
+
+    let lookup = if len == 0 {
+        quote::quote!({
+            let _ = #key;
+            ::core::option::Option::None
+        })
+    } else {
+        let keys = map
+            .entries
+            .iter()
+            .map(|(key, _)| key.value())
+            .collect::<Vec<_>>();
+        let (seeds, slots) = place(&keys)?;
+        let mut by_slot = vec![0; len];
+        for (i, &slot) in slots.iter().enumerate() {
+            by_slot[slot] = i;
+        }
+        let slot_keys = by_slot.iter().map(|&i| &map.entries[i].0);
+        let slot_values = by_slot.iter().map(|&i| &map.entries[i].1);
+        let bucket_len = seeds.len();
+        // Reducing modulo one is always zero, which clippy warns about in the generated code.
+        let reduce = |hash: proc_macro2::TokenStream, len: usize| match len as u64 {
+            1 => quote::quote!(0),
+            len => quote::quote!((#hash % #len) as usize),
+        };
+        let bucket = reduce(quote::quote!(__hash(__bytes, 0)), bucket_len);
+        let slot = reduce(quote::quote!(__hash(__bytes, __SEEDS[#bucket] as u64)), len);
+        quote::quote!({
+            static __KEYS: [&str; #len] = [#(#slot_keys),*];
+            static __VALUES: [#value_type; #len] = [#(#slot_values),*];
+            static __SEEDS: [u32; #bucket_len] = [#(#seeds),*];
+
+            /// FNV-1a with a seed and a final mix, which must be the same as while placing keys.
+            fn __hash(key: &[u8], seed: u64) -> u64 {
+                let mut hash = 0xcbf2_9ce4_8422_2325u64 ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+                for &byte in key {
+                    hash = (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+                }
+                hash ^= hash >> 33;
+                hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+                hash ^ (hash >> 33)
+            }
+
+            let __bytes = #key.as_bytes();
+            let __slot = #slot;
+            if __KEYS[__slot] == #key {
+                ::core::option::Option::Some(__VALUES[__slot])
+            } else {
+                ::core::option::Option::None
+            }
+        })
+    };
+
+    let output = match &map.form {
+        super::Form::Closure => quote::quote!({
+            |#key: &str| -> ::core::option::Option<#value_type> #lookup
+        }),
+        super::Form::Struct { attrs, vis, ident } => {
+            let keys = map.entries.iter().map(|(key, _)| key);
+            quote::quote! {
+                #(#attrs)*
+                #[derive(Clone, Copy, Debug)]
+                #vis struct #ident;
+
+                impl #ident {
+                    /// The keys of the map, in the order in which they were declared.
+                    #vis const KEYS: [&'static str; #len] = [#(#keys),*];
+
+                    /// Looks up the value for the given key.
+                    #vis fn get(#key: &str) -> ::core::option::Option<#value_type> #lookup
+                }
+            }
+        }
+    };
+    Ok(output)
+}
+
+/// The same hash as the generated `__hash`.
+fn hash(key: &[u8], seed: u64) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64 ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    for &byte in key {
+        hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^ (hash >> 33)
+}
+
+/// Finds a seed for every bucket so that all keys land in distinct slots, returning the seeds and
+/// the slot of every key.
+fn place(keys: &[String]) -> syn::Result<(Vec<u32>, Vec<usize>)> {
+    let len = keys.len();
+    let bucket_count = len.div_ceil(KEYS_PER_BUCKET);
+    let mut buckets = vec![Vec::new(); bucket_count];
+    for (i, key) in keys.iter().enumerate() {
+        buckets[(hash(key.as_bytes(), 0) % bucket_count as u64) as usize].push(i);
+    }
+    // Larger buckets are the hardest to place, so they go first while most slots are free.
+    let mut order = (0..bucket_count).collect::<Vec<_>>();
+    order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+
+    let mut seeds = vec![0; bucket_count];
+    let mut slots = vec![0; len];
+    let mut taken = vec![false; len];
+    for bucket in order {
+        let members = &buckets[bucket];
+        if members.is_empty() {
+            continue;
+        }
+        let seed = (1..MAX_SEEDS).find(|&seed| {
+            let mut candidate = Vec::with_capacity(members.len());
+            for &i in members {
+                let slot = (hash(keys[i].as_bytes(), u64::from(seed)) % len as u64) as usize;
+                if taken[slot] || candidate.contains(&slot) {
+                    return false;
+                }
+                candidate.push(slot);
+            }
+            true
+        });
+        let seed = seed.ok_or_else(|| {
+            syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "failed to find a perfect hash for the keys",
+            )
+        })?;
+        seeds[bucket] = seed;
+        for &i in members {
+            let slot = (hash(keys[i].as_bytes(), u64::from(seed)) % len as u64) as usize;
+            taken[slot] = true;
+            slots[i] = slot;
+        }
+    }
+    Ok((seeds, slots))
+}
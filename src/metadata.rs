@@ -0,0 +1,277 @@
+//! Machine-readable descriptions of generated tables.
+
+/// A description of a generated table, as written to sidecar files and reports.
+pub(crate) struct Metadata {
+    /// The name of the table, which closures only have if it is given to `#[sidecar]`.
+    pub(crate) name: Option<String>,
+    pub(crate) dimensions: Vec<Dimension>,
+    pub(crate) element_type: String,
+    pub(crate) entries: usize,
+    /// The size of the stored entries in bytes, if the element type is a primitive type.
+    pub(crate) byte_size: Option<usize>,
+    /// How entries are looked up, as the name of the strategy.
+    pub(crate) strategy: &'static str,
+    /// A hash of the macro input.  The entries themselves are only known after constant evaluation,
+    /// so this identifies the specification that produced them instead.
+    pub(crate) spec_hash: u64,
+}
+
+pub(crate) struct Dimension {
+    pub(crate) name: String,
+    pub(crate) lo: usize,
+    pub(crate) len: usize,
+}
+
+impl Metadata {
+    pub(crate) fn to_json(&self) -> String {
+        let dimensions = self
+            .dimensions
+            .iter()
+            .map(|dim| {
+                format!(
+                    "{{\"name\":{},\"lo\":{},\"len\":{}}}",
+                    json_string(&dim.name),
+                    dim.lo,
+                    dim.len
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let byte_size = self
+            .byte_size
+            .map_or_else(|| "null".to_owned(), |size| size.to_string());
+        let name = self
+            .name
+            .as_deref()
+            .map_or_else(|| "null".to_owned(), json_string);
+        format!(
+            "{{\"name\":{},\"dimensions\":[{}],\"element_type\":{},\"entries\":{},\"byte_size\":{},\"strategy\":{},\"spec_hash\":\"{:016x}\"}}",
+            name,
+            dimensions,
+            json_string(&self.element_type),
+            self.entries,
+            byte_size,
+            json_string(self.strategy),
+            self.spec_hash
+        )
+    }
+
+    /// Writes the metadata to `<out_dir>/numeric-lut/<name>.json`, failing if another invocation in
+    /// the crate has already written a different description to the same file.
+    ///
+    /// The invocation is identified by its location, so that expanding it again after its table
+    /// changed, as long-running proc-macro servers like rust-analyzer do, replaces its description.
+    pub(crate) fn write_sidecar(
+        &self,
+        out_dir: &std::path::Path,
+        invocation: &str,
+    ) -> Result<(), String> {
+        let name = self.name.as_ref().expect("validated in `Lut::validate`");
+        let dir = out_dir.join("numeric-lut");
+        let path = dir.join(format!("{}.json", name));
+        let json = self.to_json();
+        // Tables in different modules can have the same name.  Tables that are described the same
+        // way can share the file, so only differing descriptions of other invocations collide.
+        let mut written = SIDECARS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match written.get(&path) {
+            Some((_, previous)) if *previous == json => return Ok(()),
+            Some((previous, _)) if previous != invocation => {
+                return Err(format!(
+                    "another table in this crate is also described in {}; give one of them a different name with `#[sidecar = \"name\"]`",
+                    path.display()
+                ))
+            }
+            _ => {}
+        }
+        std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(&path, &json))
+            .map_err(|err| format!("failed to write {}: {}", path.display(), err))?;
+        written.insert(path, (invocation.to_owned(), json));
+        Ok(())
+    }
+
+    /// Appends the metadata as a line to the report file named by `NUMERIC_LUT_REPORT`, if it is
+    /// set, along with the name of the crate being compiled.
+    pub(crate) fn append_to_report(&self) -> Result<(), String> {
+        use std::io::Write;
+
+        let path = match std::env::var_os(REPORT_VAR) {
+            Some(path) if !path.is_empty() => std::path::PathBuf::from(path),
+            _ => return Ok(()),
+        };
+        let line = self.report_line(std::env::var("CARGO_CRATE_NAME").ok().as_deref());
+        // Invocations in parallel builds append to the same file, so every line is written at once.
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|err| format!("failed to append to {}: {}", path.display(), err))
+    }
+
+    /// The line of the report describing this table, which is its JSON object with the name of the
+    /// crate spliced in front.
+    fn report_line(&self, krate: Option<&str>) -> String {
+        let krate = krate.map_or_else(|| "null".to_owned(), json_string);
+        let json = self.to_json();
+        format!("{{\"crate\":{},{}\n", krate, &json[1..])
+    }
+}
+
+/// The sidecar files written by this process, with the invocation that last wrote each of them and
+/// its contents.
+type Sidecars = std::collections::BTreeMap<std::path::PathBuf, (String, String)>;
+
+static SIDECARS: std::sync::Mutex<Sidecars> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+/// The environment variable naming the report file.
+const REPORT_VAR: &str = "NUMERIC_LUT_REPORT";
+
+/// The size in bytes of the given type, if it is a primitive numeric type.
+pub(crate) fn primitive_size(ty: &syn::Type) -> Option<usize> {
+    let ident = match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path.get_ident()?,
+        _ => return None,
+    };
+    let size = match ident.to_string().as_str() {
+        "u8" | "i8" | "bool" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" | "char" => 4,
+        "u64" | "i64" | "f64" => 8,
+        "u128" | "i128" => 16,
+        _ => return None,
+    };
+    Some(size)
+}
+
+/// The 64-bit FNV-1a hash of the given bytes.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> Metadata {
+        Metadata {
+            name: Some("Gain".to_owned()),
+            dimensions: vec![
+                Dimension {
+                    name: "x".to_owned(),
+                    lo: 2,
+                    len: 3,
+                },
+                Dimension {
+                    name: "y".to_owned(),
+                    lo: 0,
+                    len: 4,
+                },
+            ],
+            element_type: "u16".to_owned(),
+            entries: 12,
+            byte_size: Some(24),
+            strategy: "table",
+            spec_hash: 0xabc,
+        }
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(
+            concat!(
+                r#"{"name":"Gain","dimensions":[{"name":"x","lo":2,"len":3},"#,
+                r#"{"name":"y","lo":0,"len":4}],"element_type":"u16","entries":12,"#,
+                r#""byte_size":24,"strategy":"table","spec_hash":"0000000000000abc"}"#
+            ),
+            metadata().to_json()
+        );
+    }
+
+    #[test]
+    fn to_json_without_name_or_size() {
+        let metadata = Metadata {
+            name: None,
+            dimensions: Vec::new(),
+            element_type: "Rgb".to_owned(),
+            byte_size: None,
+            ..metadata()
+        };
+        assert_eq!(
+            concat!(
+                r#"{"name":null,"dimensions":[],"element_type":"Rgb","entries":12,"#,
+                r#""byte_size":null,"strategy":"table","spec_hash":"0000000000000abc"}"#
+            ),
+            metadata.to_json()
+        );
+    }
+
+    #[test]
+    fn write_sidecar_again() {
+        let out_dir =
+            std::env::temp_dir().join(format!("numeric-lut-sidecar-{}", std::process::id()));
+        let changed = Metadata {
+            entries: 16,
+            ..metadata()
+        };
+        let read = || std::fs::read_to_string(out_dir.join("numeric-lut/Gain.json")).unwrap();
+        metadata()
+            .write_sidecar(&out_dir, "src/gain.rs:3:1")
+            .unwrap();
+        // Expanding the same invocation again after its table changed replaces the description.
+        changed.write_sidecar(&out_dir, "src/gain.rs:3:1").unwrap();
+        assert_eq!(changed.to_json(), read());
+        // Other invocations may only describe their tables the same way.
+        changed.write_sidecar(&out_dir, "src/other.rs:8:5").unwrap();
+        assert!(metadata()
+            .write_sidecar(&out_dir, "src/other.rs:8:5")
+            .is_err());
+        assert_eq!(changed.to_json(), read());
+        std::fs::remove_dir_all(&out_dir).unwrap();
+    }
+
+    #[test]
+    fn json_string_escapes() {
+        assert_eq!(r#""plain""#, json_string("plain"));
+        assert_eq!(
+            r#""[&'static str; 2] \"q\" \\ \u000a \u0000 é""#,
+            json_string("[&'static str; 2] \"q\" \\ \n \0 é")
+        );
+    }
+
+    #[test]
+    fn report_line() {
+        let json = metadata().to_json();
+        assert_eq!(
+            format!("{{\"crate\":\"tables\",{}\n", &json[1..]),
+            metadata().report_line(Some("tables"))
+        );
+        assert_eq!(
+            format!("{{\"crate\":null,{}\n", &json[1..]),
+            metadata().report_line(None)
+        );
+        assert!(metadata()
+            .report_line(Some("a\"b"))
+            .starts_with(r#"{"crate":"a\"b","name":"Gain","#));
+    }
+}
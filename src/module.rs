@@ -0,0 +1,226 @@
+//! Modules that wrap a table with the functions and constants that most users of it end up writing.
+//!
+//! The module declares the table in the item form as a struct of its own, and adds free functions
+//! and constants around it.  Doc comments of the invocation document the module, while the options
+//! are passed on to the table.
+
+/// The input of `lut_mod!`.
+struct Module {
+    /// The doc comments of the module.
+    docs: Vec<syn::Attribute>,
+    /// The remaining attributes, which are passed on to the table.
+    options: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+    /// The parameters, return type and body of the table.
+    rest: proc_macro2::TokenStream,
+}
+
+impl syn::parse::Parse for Module {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let (docs, options) = input
+            .call(syn::Attribute::parse_outer)?
+            .into_iter()
+            .partition(|attr| attr.path.is_ident("doc"));
+        let vis = input.parse()?;
+        input.parse::<syn::Token![mod]>()?;
+        Ok(Module {
+            docs,
+            options,
+            vis,
+            ident: input.parse()?,
+            rest: input.parse()?,
+        })
+    }
+}
+
+/// Generates the module of a table.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    use syn::ext::IdentExt;
+
+    let Module {
+        docs,
+        options,
+        vis,
+        ident,
+        rest,
+    } = syn::parse2(input)?;
+    let spec = quote::quote! {
+        #(#options)*
+        /// The table of this module.
+        pub struct Table #rest
+    };
+    let lut: super::Lut = syn::parse2(spec.clone())?;
+    let table = proc_macro2::TokenStream::from(super::lut(spec.into()));
+
+    let return_type = &lut.return_type;
+    let rank = lut.inputs.len();
+    let len = lut.inputs.iter().map(super::Param::len).product::<usize>();
+    let params = super::params(lut.inputs.iter()).collect::<Vec<_>>();
+    let idents = lut
+        .inputs
+        .iter()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let ranges = lut
+        .inputs
+        .iter()
+        .map(|param| {
+            quote::format_ident!(
+                "{}_RANGE",
+                param.ident.unraw().to_string().to_uppercase(),
+                span = param.ident.span()
+            )
+        })
+        .collect::<Vec<_>>();
+    let bounds = lut.inputs.iter().zip(&ranges).map(|(param, range)| {
+        let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+        let doc = format!(" The values of `{}`.", param.ident);
+        quote::quote! {
+            #[doc = #doc]
+            pub const #range: ::core::ops::RangeInclusive<usize> = #lo..=#hi;
+        }
+    });
+    let shape = lut.inputs.iter().map(super::Param::len);
+    let clamped = lut.inputs.iter().map(|param| {
+        let ident = &param.ident;
+        let (lo, hi) = (param.lo, param.lo + param.len() - 1);
+        match lo {
+            0 => quote::quote!(#ident.min(#hi)),
+            lo => quote::quote!(#ident.clamp(#lo, #hi)),
+        }
+    });
+    let unflatten = super::unflatten(lut.inputs.iter());
+    let in_range = match rank {
+        0 => quote::quote!(true),
+        _ => quote::quote!(#(#ranges.contains(&#idents))&&*),
+    };
+    let flat = (0..rank).map(|i| quote::quote!(__params[#i]));
+    let flat = quote::quote!(#(#flat),*);
+
+    let super::Table { ty, strategy, .. } = lut.table();
+    let static_table = match strategy {
+        super::Strategy::Table => quote::quote! {
+            /// The stored entries of the table.
+            pub static TABLE: &#ty = Table::TABLE;
+        },
+        super::Strategy::Match | super::Strategy::Compute => proc_macro2::TokenStream::new(),
+    };
+
+    // These make lookups differ from the body by design.
+    let options = &lut.options;
+    let exact = options.f16.is_none()
+        && options.codec.is_none()
+        && options.cover.is_none()
+        && options.guard.is_none()
+        && options.nalgebra_entries.is_none();
+    let self_test = if exact {
+        let body = match &lut.body {
+            body @ syn::Expr::Block(_) => quote::quote!(#body),
+            body => quote::quote!({ #body }),
+        };
+        let message = format!(
+            "entry for {}",
+            idents
+                .iter()
+                .map(|ident| format!("{} = {{}}", ident))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        // Entries outside of the band of `#[banded(..)]` are the default instead of the body.
+        let expected = match &options.layout {
+            super::Layout::Banded { width, default, .. } => quote::quote! {
+                if __params[0].abs_diff(__params[1]) > #width {
+                    #default
+                } else {
+                    __direct(#flat)
+                }
+            },
+            _ => quote::quote!(__direct(#flat)),
+        };
+        quote::quote! {
+            #[cfg(test)]
+            mod tests {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #[test]
+                fn entries_match_body() {
+                    let __direct = |#(#params),*| -> #return_type #body;
+                    for (__params, __entry) in super::entries() {
+                        ::core::assert_eq!(#expected, __entry, #message, #flat);
+                    }
+                }
+            }
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+
+    Ok(quote::quote! {
+        #(#docs)*
+        #vis mod #ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #table
+
+            #static_table
+
+            #(#bounds)*
+
+            /// The number of values of every parameter.
+            pub const SHAPE: [usize; #rank] = [#(#shape),*];
+
+            /// The number of entries.
+            pub const LEN: usize = #len;
+
+            /// Looks up the entry for the given parameters.
+            pub fn get(#(#params),*) -> #return_type {
+                Table::get(#(#idents),*)
+            }
+
+            /// Looks up the entry for the given parameters, or returns `None` if one of them is
+            /// outside of its range.
+            pub fn get_checked(#(#params),*) -> ::core::option::Option<#return_type> {
+                if #in_range {
+                    ::core::option::Option::Some(Table::get(#(#idents),*))
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            /// Looks up the entry for the given parameters, after moving each of them to the
+            /// nearest value in its range.
+            pub fn get_clamped(#(#params),*) -> #return_type {
+                Table::get(#(#clamped),*)
+            }
+
+            /// Looks up the entry at the given index in the order of `entries()`, in which the
+            /// last parameter changes fastest.
+            pub fn get_raw(index: usize) -> #return_type {
+                ::core::assert!(
+                    index < LEN,
+                    "the index must be less than {} but is {}",
+                    LEN,
+                    index
+                );
+                let __params = __params(index);
+                Table::get(#flat)
+            }
+
+            /// Iterates over the parameters and entries of the table, in which the last parameter
+            /// changes fastest.
+            pub fn entries() -> impl ::core::iter::Iterator<Item = ([usize; #rank], #return_type)> {
+                (0..LEN).map(|__flat| (__params(__flat), get_raw(__flat)))
+            }
+
+            /// Splits an index in the order of `entries()` into the parameters.
+            fn __params(__flat: usize) -> [usize; #rank] {
+                [#(#unflatten),*]
+            }
+
+            #self_test
+        }
+    })
+}
@@ -0,0 +1,225 @@
+//! Longest-prefix-match tables over fixed-width integer keys, like routing tables or memory maps.
+//!
+//! The declared prefixes are nested or disjoint ranges of keys, so they are flattened while
+//! expanding the macro into sorted, disjoint ranges that each know their most specific prefix.
+//! Lookups then binary search the start of the range containing the key.
+
+/// Marks ranges that aren't covered by any prefix.
+const NONE: u32 = u32::MAX;
+
+/// The input of `lut_prefix!`.
+struct Prefixes {
+    form: super::Form,
+    key: syn::Ident,
+    key_type: syn::Type,
+    /// The width of the key in bits.
+    width: u32,
+    value_type: syn::Type,
+    entries: Vec<Prefix>,
+}
+
+struct Prefix {
+    /// The first key with the prefix.
+    start: u128,
+    /// The number of leading bits of the prefix.
+    len: u32,
+    value: syn::Expr,
+}
+
+impl Prefix {
+    /// The last key with the prefix.
+    fn end(&self, width: u32) -> u128 {
+        self.start | mask(width - self.len)
+    }
+}
+
+/// The lowest `bits` bits set.
+fn mask(bits: u32) -> u128 {
+    if bits == 128 {
+        u128::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+impl syn::parse::Parse for Prefixes {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        use syn::spanned::Spanned;
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let (form, key, key_type) = if input.peek(syn::Token![|]) {
+            if let Some(attr) = attrs.first() {
+                return Err(syn::Error::new(
+                    attr.path.span(),
+                    "attributes are only supported in the item form",
+                ));
+            }
+            input.parse::<syn::Token![|]>()?;
+            let key = input.parse()?;
+            input.parse::<syn::Token![:]>()?;
+            let key_type = input.parse()?;
+            input.parse::<syn::Token![|]>()?;
+            (super::Form::Closure, key, key_type)
+        } else {
+            let vis = input.parse()?;
+            input.parse::<syn::Token![struct]>()?;
+            let ident = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+            let key = content.parse()?;
+            content.parse::<syn::Token![:]>()?;
+            let key_type = content.parse()?;
+            let form = super::Form::Struct { attrs, vis, ident };
+            (form, key, key_type)
+        };
+        let width = match super::preset::primitive_name(&key_type).as_deref() {
+            Some("u8") => 8,
+            Some("u16") => 16,
+            Some("u32") => 32,
+            Some("u64") => 64,
+            Some("u128") => 128,
+            _ => {
+                return Err(syn::Error::new(
+                    key_type.span(),
+                    "expected an unsigned integer type with a fixed width (e.g. `u32`)",
+                ))
+            }
+        };
+        input.parse::<syn::Token![->]>()?;
+        let value_type = input.parse()?;
+
+        let content;
+        syn::braced!(content in input);
+        let mut entries: Vec<Prefix> = Vec::new();
+        while !content.is_empty() {
+            let start: syn::LitInt = content.parse()?;
+            content.parse::<syn::Token![/]>()?;
+            let len: syn::LitInt = content.parse()?;
+            let span = start
+                .span()
+                .join(len.span())
+                .unwrap_or_else(|| start.span());
+            let prefix = Prefix {
+                start: start.base10_parse()?,
+                len: len.base10_parse()?,
+                value: {
+                    content.parse::<syn::Token![=>]>()?;
+                    content.parse()?
+                },
+            };
+            if prefix.len > width {
+                return Err(syn::Error::new(
+                    len.span(),
+                    format!("the prefix can be at most {} bits long", width),
+                ));
+            }
+            if prefix.start > mask(width) {
+                return Err(syn::Error::new(
+                    start.span(),
+                    format!("`{}` doesn't fit into the key type", start),
+                ));
+            }
+            if prefix.start & mask(width - prefix.len) != 0 {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "`{}` has bits set beyond the first {} bits of the prefix",
+                        start, prefix.len
+                    ),
+                ));
+            }
+            if entries
+                .iter()
+                .any(|other| (other.start, other.len) == (prefix.start, prefix.len))
+            {
+                return Err(syn::Error::new(span, "duplicate prefix"));
+            }
+            entries.push(prefix);
+            if content.is_empty() {
+                break;
+            }
+            content.parse::<syn::Token![,]>()?;
+        }
+
+        Ok(Prefixes {
+            form,
+            key,
+            key_type,
+            width,
+            value_type,
+            entries,
+        })
+    }
+}
+
+/// Generates a longest-prefix-match lookup.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let prefixes: Prefixes = syn::parse2(input)?;
+    let width = prefixes.width;
+    let entries = &prefixes.entries;
+
+    // Every range starts at the start of a prefix or right after the end of one.
+    let mut starts = vec![0];
+    for prefix in entries {
+        starts.push(prefix.start);
+        let end = prefix.end(width);
+        if end < mask(width) {
+            starts.push(end + 1);
+        }
+    }
+    starts.sort_unstable();
+    starts.dedup();
+
+    let mut ranges: Vec<(u128, u32)> = Vec::new();
+    for start in starts {
+        let index = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, prefix)| (prefix.start..=prefix.end(width)).contains(&start))
+            .max_by_key(|(_, prefix)| prefix.len)
+            .map_or(NONE, |(i, _)| i as u32);
+        match ranges.last() {
+            Some(&(_, last)) if last == index => {}
+            _ => ranges.push((start, index)),
+        }
+    }
+
+    let key = &prefixes.key;
+    let key_type = &prefixes.key_type;
+    let value_type = &prefixes.value_type;
+    let values = entries.iter().map(|prefix| &prefix.value);
+    let value_len = entries.len();
+    let range_len = ranges.len();
+    let range_starts = ranges
+        .iter()
+        .map(|&(start, _)| proc_macro2::Literal::u128_unsuffixed(start));
+    let range_values = ranges.iter().map(|&(_, index)| index);
+    let lookup = quote::quote!({
+        static __VALUES: [#value_type; #value_len] = [#(#values),*];
+        static __STARTS: [#key_type; #range_len] = [#(#range_starts),*];
+        static __INDICES: [u32; #range_len] = [#(#range_values),*];
+
+        // The first range starts at zero, so there always is one.
+        let __range = __STARTS.partition_point(|&__start| __start <= #key) - 1;
+        match __INDICES[__range] {
+            #NONE => ::core::option::Option::None,
+            __index => ::core::option::Option::Some(__VALUES[__index as usize]),
+        }
+    });
+
+    let output = match &prefixes.form {
+        super::Form::Closure => quote::quote!({
+            |#key: #key_type| -> ::core::option::Option<#value_type> #lookup
+        }),
+        super::Form::Struct { attrs, vis, ident } => quote::quote! {
+            #(#attrs)*
+            #[derive(Clone, Copy, Debug)]
+            #vis struct #ident;
+
+            impl #ident {
+                /// Looks up the value of the longest prefix that matches the key.
+                #vis fn get(#key: #key_type) -> ::core::option::Option<#value_type> #lookup
+            }
+        },
+    };
+    Ok(output)
+}
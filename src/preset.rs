@@ -0,0 +1,277 @@
+//! Macros that generate commonly used tables from a few parameters.
+//!
+//! The entries of presets are computed while expanding the macro, since they typically need
+//! functions (like `f64::sin`) that aren't available during constant evaluation.
+
+pub(crate) mod decibel;
+pub(crate) mod dither;
+pub(crate) mod easing;
+pub(crate) mod gf256;
+pub(crate) mod huffman;
+pub(crate) mod sbox;
+pub(crate) mod srgb;
+pub(crate) mod trig;
+pub(crate) mod window;
+
+/// The `key = value` arguments of a preset.
+#[derive(Default)]
+pub(crate) struct Args {
+    args: Vec<(syn::Ident, syn::Expr)>,
+}
+
+impl syn::parse::Parse for Args {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let mut args = Vec::new();
+        while !input.is_empty() {
+            let key: syn::Ident = input.call(syn::ext::IdentExt::parse_any)?;
+            // A bare type name like `f32` is shorthand for `ty = f32`.
+            let (key, value) = if input.peek(syn::Token![=]) {
+                input.parse::<syn::Token![=]>()?;
+                (key, input.parse()?)
+            } else {
+                let ty = syn::Ident::new("ty", key.span());
+                (ty, syn::parse_quote!(#key))
+            };
+            if args.iter().any(|(other, _)| *other == key) {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("duplicate argument `{}`", key),
+                ));
+            }
+            args.push((key, value));
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(Args { args })
+    }
+}
+
+impl Args {
+    /// Removes the argument with the given name, if it was given.
+    pub(crate) fn take(&mut self, name: &str) -> Option<syn::Expr> {
+        let i = self.args.iter().position(|(key, _)| key == name)?;
+        Some(self.args.remove(i).1)
+    }
+
+    /// Removes the argument with the given name, which must be an integer literal.
+    pub(crate) fn take_usize(&mut self, name: &str) -> syn::Result<Option<usize>> {
+        use syn::spanned::Spanned;
+        match self.take(name) {
+            None => Ok(None),
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(value),
+                ..
+            })) => value.base10_parse().map(Some),
+            Some(other) => Err(syn::Error::new(
+                other.span(),
+                format!("`{}` must be an integer literal", name),
+            )),
+        }
+    }
+
+    /// Removes the argument with the given name, which must be an array of integer literals.
+    pub(crate) fn take_usize_list(&mut self, name: &str) -> syn::Result<Option<Vec<usize>>> {
+        use syn::spanned::Spanned;
+        let elems = match self.take(name) {
+            None => return Ok(None),
+            Some(syn::Expr::Array(syn::ExprArray { elems, .. })) => elems,
+            Some(other) => {
+                return Err(syn::Error::new(
+                    other.span(),
+                    format!("`{}` must be an array of integer literals", name),
+                ))
+            }
+        };
+        elems
+            .iter()
+            .map(|elem| match elem {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(value),
+                    ..
+                }) => value.base10_parse(),
+                other => Err(syn::Error::new(other.span(), "expected an integer literal")),
+            })
+            .collect::<syn::Result<_>>()
+            .map(Some)
+    }
+
+    /// Removes the argument with the given name, which must be a number literal, optionally
+    /// negated.
+    pub(crate) fn take_f64(&mut self, name: &str) -> syn::Result<Option<f64>> {
+        use syn::spanned::Spanned;
+        match self.take(name) {
+            None => Ok(None),
+            Some(expr) => number(&expr).map(Some).ok_or_else(|| {
+                syn::Error::new(expr.span(), format!("`{}` must be a number literal", name))
+            }),
+        }
+    }
+
+    /// Removes the argument with the given name, which must be `true` or `false`.
+    pub(crate) fn take_bool(&mut self, name: &str) -> syn::Result<Option<bool>> {
+        use syn::spanned::Spanned;
+        match self.take(name) {
+            None => Ok(None),
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Bool(value),
+                ..
+            })) => Ok(Some(value.value)),
+            Some(other) => Err(syn::Error::new(
+                other.span(),
+                format!("`{}` must be `true` or `false`", name),
+            )),
+        }
+    }
+
+    /// Removes the argument with the given name, which must be a single identifier.
+    pub(crate) fn take_ident(&mut self, name: &str) -> syn::Result<Option<syn::Ident>> {
+        use syn::spanned::Spanned;
+        match self.take(name) {
+            None => Ok(None),
+            Some(syn::Expr::Path(syn::ExprPath {
+                path, qself: None, ..
+            })) if path.get_ident().is_some() => Ok(path.get_ident().cloned()),
+            Some(other) => Err(syn::Error::new(
+                other.span(),
+                format!("`{}` must be an identifier", name),
+            )),
+        }
+    }
+
+    /// Removes the argument with the given name, which must be a type.
+    pub(crate) fn take_type(&mut self, name: &str) -> syn::Result<Option<syn::Type>> {
+        match self.take(name) {
+            None => Ok(None),
+            Some(expr) => syn::parse2(quote::quote!(#expr)).map(Some),
+        }
+    }
+
+    /// Checks that all arguments have been used.
+    pub(crate) fn finish(self) -> syn::Result<()> {
+        match self.args.first() {
+            None => Ok(()),
+            Some((key, _)) => Err(syn::Error::new(
+                key.span(),
+                format!("unknown argument `{}`", key),
+            )),
+        }
+    }
+}
+
+/// The value of a number literal, optionally negated.
+fn number(expr: &syn::Expr) -> Option<f64> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Float(value),
+            ..
+        }) => value.base10_parse().ok(),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        }) => value.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => number(expr).map(|value| -value),
+        _ => None,
+    }
+}
+
+/// Parses the arguments of a preset that start with the kind of table to generate.
+pub(crate) fn parse_with_kind(input: proc_macro2::TokenStream) -> syn::Result<(syn::Ident, Args)> {
+    syn::parse::Parser::parse2(
+        |input: syn::parse::ParseStream| {
+            let kind = input.parse::<syn::Ident>()?;
+            if input.is_empty() {
+                return Ok((kind, Args::default()));
+            }
+            input.parse::<syn::Token![,]>()?;
+            Ok((kind, input.parse()?))
+        },
+        input,
+    )
+}
+
+/// Errors for a missing argument of a preset.
+pub(crate) fn missing(name: &str) -> syn::Error {
+    syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!("missing argument `{}`", name),
+    )
+}
+
+/// The name of the given type, if it is a primitive numeric type.
+pub(crate) fn primitive_name(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            let name = path.get_ident()?.to_string();
+            is_primitive(&name).then_some(name)
+        }
+        _ => None,
+    }
+}
+
+fn is_primitive(name: &str) -> bool {
+    matches!(
+        name,
+        "u8" | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+            | "usize"
+            | "i8"
+            | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "isize"
+            | "f32"
+            | "f64"
+    )
+}
+
+/// Infers the type of an expression like `i16::MAX` or `1000u32`, if possible.
+pub(crate) fn infer_type(expr: &syn::Expr) -> Option<syn::Type> {
+    let name = match expr {
+        syn::Expr::Path(syn::ExprPath {
+            qself: None, path, ..
+        }) if path.segments.len() == 2 => path.segments[0].ident.to_string(),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => lit.suffix().to_owned(),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Float(lit),
+            ..
+        }) => lit.suffix().to_owned(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => return infer_type(expr),
+        _ => return None,
+    };
+    if !is_primitive(&name) {
+        return None;
+    }
+    syn::parse_str(&name).ok()
+}
+
+/// Whether the type is a primitive floating point type.
+pub(crate) fn is_float(ty: &syn::Type) -> bool {
+    matches!(primitive_name(ty).as_deref(), Some("f32" | "f64"))
+}
+
+/// Generates an expression that converts an `f64` expression to the given type.
+///
+/// Integers are rounded to the nearest value, with ties rounded away from zero.
+pub(crate) fn convert(value: proc_macro2::TokenStream, ty: &syn::Type) -> proc_macro2::TokenStream {
+    match primitive_name(ty).as_deref() {
+        Some("f64") => value,
+        Some("f32") => quote::quote!((#value) as f32),
+        _ => quote::quote!((#value).round() as #ty),
+    }
+}
@@ -0,0 +1,224 @@
+//! Conversions between decibels and linear gains, for audio.
+
+/// The largest supported number of entries.
+const MAX_LEN: usize = 1 << 20;
+/// The largest supported level resolution of `to_db` in bits.
+const MAX_BITS: usize = 16;
+
+/// Generates a lookup that converts between decibels and linear amplitude gains.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let output = match kind.to_string().as_str() {
+        "to_gain" => to_gain(&mut args)?,
+        "to_db" => to_db(&mut args)?,
+        _ => {
+            return Err(syn::Error::new(
+                kind.span(),
+                "expected a conversion (`to_gain` or `to_db`)",
+            ))
+        }
+    };
+    args.finish()?;
+    Ok(output)
+}
+
+/// Generates a lookup from decibels to gains, over evenly spaced decibels.
+fn to_gain(args: &mut super::Args) -> syn::Result<proc_macro2::TokenStream> {
+    let min = args.take_f64("min")?.ok_or_else(|| super::missing("min"))?;
+    let max = args.take_f64("max")?.unwrap_or(0.0);
+    let step = args.take_f64("step")?.unwrap_or(1.0);
+    let (ty, entry) = Entry::parse(args)?;
+
+    if step <= 0.0 || max <= min {
+        return Err(error(
+            "`max` must be larger than `min`, and `step` positive",
+        ));
+    }
+    let steps = (max - min) / step;
+    if (steps - steps.round()).abs() > 1e-9 {
+        return Err(error("`max - min` must be a multiple of `step`"));
+    }
+    let len = steps.round() as usize + 1;
+    if len > MAX_LEN {
+        return Err(error(&format!(
+            "the table would have {} entries, more than the supported {}",
+            len, MAX_LEN
+        )));
+    }
+
+    let entries = (0..len)
+        .map(|i| entry.literal(10f64.powf((min + i as f64 * step) / 20.0)))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let input_ty = if entry.is_f64() {
+        quote::quote!(f64)
+    } else {
+        quote::quote!(f32)
+    };
+    let (scale, offset) = (float(1.0 / step), float(0.5 - min / step));
+    let last = float((len - 1) as f64);
+    // Rounding to the nearest step with `round` would need `std`, so the position is offset by half
+    // a step and truncated instead, which is only correct after clamping it to be non-negative.
+    Ok(quote::quote!({
+        static __LUT: [#ty; #len] = [#(#entries),*];
+        |db: #input_ty| -> #ty { __LUT[(db * #scale + #offset).clamp(0.0, #last) as usize] }
+    }))
+}
+
+/// Generates a lookup from quantized levels to decibels.
+fn to_db(args: &mut super::Args) -> syn::Result<proc_macro2::TokenStream> {
+    let bits = args
+        .take_usize("bits")?
+        .ok_or_else(|| super::missing("bits"))?;
+    let max = args.take_f64("max")?.unwrap_or(0.0);
+    let min = args.take_f64("min")?;
+    let (ty, entry) = Entry::parse(args)?;
+
+    if !(1..=MAX_BITS).contains(&bits) {
+        return Err(error(&format!("`bits` must be between 1 and {}", MAX_BITS)));
+    }
+    let len = 1usize << bits;
+    let full_scale = (len - 1) as f64;
+    let db = |level: usize| max + 20.0 * (level as f64 / full_scale).log10();
+    let min = min.unwrap_or_else(|| db(1));
+    if max <= min {
+        return Err(error("`max` must be larger than `min`"));
+    }
+
+    let entries = (0..len)
+        .map(|level| entry.literal(db(level).max(min)))
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(quote::quote!({
+        static __LUT: [#ty; #len] = [#(#entries),*];
+        |level: usize| -> #ty { __LUT[level] }
+    }))
+}
+
+/// The representation of the entries.
+enum Entry {
+    F32,
+    F64,
+    /// Fixed-point numbers with the given number of fractional bits, in an integer type with the
+    /// given range, whose upper bound is exclusive.
+    Fixed {
+        frac_bits: usize,
+        range: (f64, f64),
+    },
+}
+
+impl Entry {
+    /// Takes the element type and the number of fractional bits of fixed-point entries.
+    fn parse(args: &mut super::Args) -> syn::Result<(syn::Type, Entry)> {
+        use syn::spanned::Spanned;
+        let ty = args
+            .take_type("ty")?
+            .unwrap_or_else(|| syn::parse_quote!(f32));
+        let frac_bits = args.take_usize("frac_bits")?;
+        let name = super::primitive_name(&ty);
+        let entry = match (name.as_deref(), frac_bits) {
+            (Some("f32"), None) => Entry::F32,
+            (Some("f64"), None) => Entry::F64,
+            (Some("f32" | "f64"), Some(_)) => {
+                return Err(error(
+                    "`frac_bits` is only supported by integer element types",
+                ))
+            }
+            (Some(name), frac_bits) => {
+                let range = integer_range(name).ok_or_else(|| {
+                    syn::Error::new(
+                        ty.span(),
+                        "the element type must be `f32`, `f64` or an integer of at most 64 bits",
+                    )
+                })?;
+                let frac_bits = frac_bits.ok_or_else(|| super::missing("frac_bits"))?;
+                if frac_bits >= 64 {
+                    return Err(error("`frac_bits` must be less than 64"));
+                }
+                Entry::Fixed { frac_bits, range }
+            }
+            (None, _) => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "the element type must be `f32`, `f64` or an integer of at most 64 bits",
+                ))
+            }
+        };
+        Ok((ty, entry))
+    }
+
+    fn is_f64(&self) -> bool {
+        matches!(self, Entry::F64)
+    }
+
+    /// The literal of an entry with the given value, which must be representable.
+    fn literal(&self, value: f64) -> syn::Result<proc_macro2::TokenStream> {
+        match *self {
+            Entry::F32 => Ok(float_suffixed(value, |value| {
+                proc_macro2::Literal::f32_suffixed(value as f32)
+            })),
+            Entry::F64 => Ok(float_suffixed(value, proc_macro2::Literal::f64_suffixed)),
+            Entry::Fixed {
+                frac_bits,
+                range: (lo, hi),
+            } => {
+                let fixed = (value * (1u64 << frac_bits) as f64).round();
+                if fixed < lo || fixed >= hi {
+                    return Err(error(&format!(
+                        "{} doesn't fit into the element type with {} fractional bits",
+                        value, frac_bits
+                    )));
+                }
+                let magnitude = proc_macro2::Literal::u64_unsuffixed(fixed.abs() as u64);
+                Ok(if fixed < 0.0 {
+                    quote::quote!(-#magnitude)
+                } else {
+                    quote::quote!(#magnitude)
+                })
+            }
+        }
+    }
+}
+
+/// The range of the given integer type, if it has at most 64 bits, with an exclusive upper bound.
+///
+/// The bounds are powers of two, which `f64` holds exactly, unlike `MAX` of 64-bit types that
+/// rounds up to the exclusive bound.
+fn integer_range(name: &str) -> Option<(f64, f64)> {
+    let (signed, bits) = match name {
+        "u8" => (false, 8),
+        "u16" => (false, 16),
+        "u32" => (false, 32),
+        "u64" => (false, 64),
+        "i8" => (true, 8),
+        "i16" => (true, 16),
+        "i32" => (true, 32),
+        "i64" => (true, 64),
+        _ => return None,
+    };
+    Some(match signed {
+        true => (-2f64.powi(bits - 1), 2f64.powi(bits - 1)),
+        false => (0.0, 2f64.powi(bits)),
+    })
+}
+
+/// An unsuffixed float literal, whose type is inferred from the lookup.
+fn float(value: f64) -> proc_macro2::TokenStream {
+    float_suffixed(value, proc_macro2::Literal::f64_unsuffixed)
+}
+
+/// A float literal made by the given constructor, with the sign of negative values as a separate
+/// token.
+fn float_suffixed(
+    value: f64,
+    literal: impl Fn(f64) -> proc_macro2::Literal,
+) -> proc_macro2::TokenStream {
+    let magnitude = literal(value.abs());
+    if value.is_sign_negative() {
+        quote::quote!(-#magnitude)
+    } else {
+        quote::quote!(#magnitude)
+    }
+}
+
+fn error(message: &str) -> syn::Error {
+    syn::Error::new(proc_macro2::Span::call_site(), message)
+}
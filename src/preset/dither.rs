@@ -0,0 +1,224 @@
+//! Threshold matrices for ordered dithering.
+
+/// The largest supported size of Bayer matrices.
+const MAX_BAYER_SIZE: usize = 256;
+/// The largest supported size of blue noise matrices, which are much slower to generate.
+const MAX_BLUE_NOISE_SIZE: usize = 64;
+/// The standard deviation of the Gaussian filter that measures clustering in blue noise.
+const SIGMA: f64 = 1.5;
+
+/// Generates a lookup of a tiling threshold matrix.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let size = args
+        .take_usize("size")?
+        .ok_or_else(|| super::missing("size"))?;
+    let seed = args.take_usize("seed")?;
+    let ty = args.take_type("ty")?;
+    args.finish()?;
+
+    let (ranks, max_size): (fn(usize, u64) -> Vec<usize>, _) =
+        match (kind.to_string().as_str(), seed) {
+            ("bayer", None) => (bayer, MAX_BAYER_SIZE),
+            ("bayer", Some(_)) => {
+                return Err(syn::Error::new(
+                    kind.span(),
+                    "Bayer matrices don't take a `seed`",
+                ))
+            }
+            ("blue_noise", _) => (blue_noise, MAX_BLUE_NOISE_SIZE),
+            _ => {
+                return Err(syn::Error::new(
+                    kind.span(),
+                    "expected a matrix (`bayer` or `blue_noise`)",
+                ))
+            }
+        };
+    if !size.is_power_of_two() || !(2..=max_size).contains(&size) {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "`size` must be a power of two between 2 and {} for this matrix",
+                max_size
+            ),
+        ));
+    }
+    let max_rank = size * size - 1;
+    let ty = match ty {
+        Some(ty) => {
+            use syn::spanned::Spanned;
+            // Wider types hold the ranks of the largest matrices.
+            let max = match super::primitive_name(&ty).as_deref() {
+                Some("u8") => u8::MAX as usize,
+                Some("i8") => i8::MAX as usize,
+                Some("i16") => i16::MAX as usize,
+                _ => usize::MAX,
+            };
+            if max_rank > max {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    format!(
+                        "the ranks of this matrix go up to {}, which doesn't fit into `{}`",
+                        max_rank,
+                        quote::quote!(#ty)
+                    ),
+                ));
+            }
+            ty
+        }
+        None if max_rank <= u8::MAX as usize => syn::parse_quote!(u8),
+        None => syn::parse_quote!(u16),
+    };
+
+    let ranks = ranks(size, seed.unwrap_or(0) as u64);
+    let rows = ranks.chunks(size).map(|row| {
+        let row = row
+            .iter()
+            .map(|&rank| proc_macro2::Literal::usize_unsuffixed(rank));
+        quote::quote!([#(#row),*])
+    });
+    let mask = size - 1;
+    Ok(quote::quote!({
+        static __LUT: [[#ty; #size]; #size] = [#(#rows),*];
+        |x: usize, y: usize| -> #ty { __LUT[y & #mask][x & #mask] }
+    }))
+}
+
+/// The ranks of a Bayer matrix of the given size, row by row.
+fn bayer(size: usize, _: u64) -> Vec<usize> {
+    let mut ranks = vec![0];
+    let mut n = 1;
+    // Each step tiles four copies of the matrix, scaled by four and offset by the ranks of the 2×2
+    // matrix `[0, 2; 3, 1]`.
+    while n < size {
+        let mut next = vec![0; 4 * n * n];
+        for (i, &rank) in ranks.iter().enumerate() {
+            let (x, y) = (i % n, i / n);
+            for (block, offset) in [0, 2, 3, 1].iter().enumerate() {
+                let (bx, by) = (block % 2, block / 2);
+                next[(by * n + y) * 2 * n + bx * n + x] = 4 * rank + offset;
+            }
+        }
+        ranks = next;
+        n *= 2;
+    }
+    ranks
+}
+
+/// The ranks of a blue noise matrix of the given size, row by row, generated with the
+/// void-and-cluster method from a seeded initial pattern.
+fn blue_noise(size: usize, seed: u64) -> Vec<usize> {
+    let len = size * size;
+    let filter = Filter::new(size);
+    let mut rng = SplitMix64(seed);
+
+    // A random initial pattern with a tenth of the cells set, relaxed until the tightest cluster
+    // is the same cell as the largest void.
+    let mut initial = vec![false; len];
+    let ones = (len / 10).max(1);
+    let mut count = 0;
+    while count < ones {
+        let i = (rng.next() % len as u64) as usize;
+        if !initial[i] {
+            initial[i] = true;
+            count += 1;
+        }
+    }
+    let mut energy = filter.energy(&initial);
+    loop {
+        let cluster = extreme(&initial, &energy, true);
+        initial[cluster] = false;
+        filter.update(&mut energy, cluster, -1.0);
+        let void = extreme(&initial, &energy, false);
+        initial[void] = true;
+        filter.update(&mut energy, void, 1.0);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; len];
+
+    // Removing the tightest clusters of the initial pattern ranks its cells from the top down.
+    let mut pattern = initial.clone();
+    let mut energy = filter.energy(&pattern);
+    for rank in (0..ones).rev() {
+        let cluster = extreme(&pattern, &energy, true);
+        pattern[cluster] = false;
+        filter.update(&mut energy, cluster, -1.0);
+        ranks[cluster] = rank;
+    }
+
+    // Filling the largest voids ranks the remaining cells from the bottom up.
+    let mut pattern = initial;
+    let mut energy = filter.energy(&pattern);
+    for rank in ones..len {
+        let void = extreme(&pattern, &energy, false);
+        pattern[void] = true;
+        filter.update(&mut energy, void, 1.0);
+        ranks[void] = rank;
+    }
+
+    ranks
+}
+
+/// Finds the set cell with the highest energy, or the unset cell with the lowest energy.
+fn extreme(pattern: &[bool], energy: &[f64], set: bool) -> usize {
+    let candidates = (0..pattern.len()).filter(|&i| pattern[i] == set);
+    let key = |&i: &usize| if set { energy[i] } else { -energy[i] };
+    candidates
+        .max_by(|a, b| key(a).total_cmp(&key(b)))
+        .expect("the pattern has cells of both kinds")
+}
+
+/// A Gaussian filter over a torus of the given size.
+struct Filter {
+    size: usize,
+    weights: Vec<f64>,
+}
+
+impl Filter {
+    fn new(size: usize) -> Self {
+        let weights = (0..size * size)
+            .map(|i| {
+                let distance = |d: usize| d.min(size - d) as f64;
+                let (dx, dy) = (distance(i % size), distance(i / size));
+                (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+            })
+            .collect();
+        Filter { size, weights }
+    }
+
+    /// The filtered pattern.
+    fn energy(&self, pattern: &[bool]) -> Vec<f64> {
+        let mut energy = vec![0.0; pattern.len()];
+        for i in (0..pattern.len()).filter(|&i| pattern[i]) {
+            self.update(&mut energy, i, 1.0);
+        }
+        energy
+    }
+
+    /// Adds the filter centered on the cell, scaled by the sign, to the energy.
+    fn update(&self, energy: &mut [f64], cell: usize, sign: f64) {
+        let size = self.size;
+        let (cx, cy) = (cell % size, cell / size);
+        for (i, e) in energy.iter_mut().enumerate() {
+            let dx = (i % size + size - cx) % size;
+            let dy = (i / size + size - cy) % size;
+            *e += sign * self.weights[dy * size + dx];
+        }
+    }
+}
+
+/// A small deterministic pseudo-random number generator.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,204 @@
+//! Easing curves for animations.
+
+/// Generates a tabulated easing curve with an interpolating accessor.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let len = args
+        .take_usize("len")?
+        .ok_or_else(|| super::missing("len"))?;
+    let ty = args
+        .take_type("ty")?
+        .unwrap_or_else(|| syn::parse_quote!(f32));
+    let kernel = match args.take_ident("kernel")? {
+        None => Kernel::Linear,
+        Some(kernel) => match kernel.to_string().as_str() {
+            "linear" => Kernel::Linear,
+            "hermite" => Kernel::Hermite,
+            "catmull_rom" => Kernel::CatmullRom,
+            _ => {
+                return Err(syn::Error::new(
+                    kernel.span(),
+                    "expected `linear`, `hermite` or `catmull_rom`",
+                ))
+            }
+        },
+    };
+    args.finish()?;
+
+    let curve: fn(f64) -> f64 = match kind.to_string().as_str() {
+        "ease_in_cubic" => |t| t * t * t,
+        "ease_out_cubic" => |t| 1.0 - (1.0 - t).powi(3),
+        "ease_in_out_cubic" => |t| {
+            if t < 0.5 {
+                4.0 * t * t * t
+            } else {
+                1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+            }
+        },
+        "ease_in_elastic" => |t| 1.0 - ease_out_elastic(1.0 - t),
+        "ease_out_elastic" => ease_out_elastic,
+        "ease_in_bounce" => |t| 1.0 - ease_out_bounce(1.0 - t),
+        "ease_out_bounce" => ease_out_bounce,
+        "ease_in_out_bounce" => |t| {
+            if t < 0.5 {
+                (1.0 - ease_out_bounce(1.0 - 2.0 * t)) / 2.0
+            } else {
+                (1.0 + ease_out_bounce(2.0 * t - 1.0)) / 2.0
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                kind.span(),
+                "expected an easing curve (like `ease_in_out_cubic`, `ease_out_elastic` or `ease_out_bounce`)",
+            ))
+        }
+    };
+    let literal: fn(f64) -> proc_macro2::Literal = match super::primitive_name(&ty).as_deref() {
+        Some("f32") => |value| proc_macro2::Literal::f32_suffixed(value as f32),
+        Some("f64") => proc_macro2::Literal::f64_suffixed,
+        _ => {
+            use syn::spanned::Spanned;
+            return Err(syn::Error::new(
+                ty.span(),
+                "the element type must be `f32` or `f64`",
+            ));
+        }
+    };
+    if len < 2 {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`len` must be at least 2",
+        ));
+    }
+
+    let last = len - 1;
+    let points = (0..len)
+        .map(|i| curve(i as f64 / last as f64))
+        .collect::<Vec<_>>();
+    let entries = points.iter().map(|&point| literal(point));
+    let scale = literal(last as f64);
+
+    // The cubic kernels interpolate with the slopes at the points, in units of the entries per
+    // step between points.
+    let slopes = match kernel {
+        Kernel::Linear => None,
+        Kernel::Hermite => {
+            // The slope of the curve itself, from a central difference that stays inside the
+            // domain of the curve.
+            let h = 1e-6;
+            Some(
+                (0..len)
+                    .map(|i| {
+                        let t = i as f64 / last as f64;
+                        let (a, b) = ((t - h).max(0.0), (t + h).min(1.0));
+                        (curve(b) - curve(a)) / (b - a) / last as f64
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        }
+        Kernel::CatmullRom => Some(
+            (0..len)
+                .map(|i| {
+                    let (a, b) = (i.saturating_sub(1), (i + 1).min(last));
+                    (points[b] - points[a]) / (b - a) as f64
+                })
+                .collect(),
+        ),
+    };
+    let (slope_table, interpolate, doc) = match slopes {
+        None => (
+            proc_macro2::TokenStream::new(),
+            quote::quote!(__LUT[__i] + (__LUT[__i + 1] - __LUT[__i]) * __frac),
+            " Samples the curve at `t`, interpolating linearly between the tabulated points.",
+        ),
+        Some(slopes) => {
+            let slopes = slopes.into_iter().map(literal);
+            let doc = if kernel == Kernel::Hermite {
+                " Samples the curve at `t`, interpolating between the tabulated points with cubic \
+                 Hermite splines through the slopes of the curve."
+            } else {
+                " Samples the curve at `t`, interpolating between the tabulated points with \
+                 Catmull-Rom splines."
+            };
+            (
+                quote::quote!(static __SLOPES: [#ty; #len] = [#(#slopes),*];),
+                quote::quote!({
+                    let (__p0, __p1) = (__LUT[__i], __LUT[__i + 1]);
+                    let (__m0, __m1) = (__SLOPES[__i], __SLOPES[__i + 1]);
+                    let __frac2 = __frac * __frac;
+                    let __frac3 = __frac2 * __frac;
+                    (2.0 * __frac3 - 3.0 * __frac2 + 1.0) * __p0
+                        + (__frac3 - 2.0 * __frac2 + __frac) * __m0
+                        + (3.0 * __frac2 - 2.0 * __frac3) * __p1
+                        + (__frac3 - __frac2) * __m1
+                }),
+                doc,
+            )
+        }
+    };
+    Ok(quote::quote!({
+        static __LUT: [#ty; #len] = [#(#entries),*];
+        #slope_table
+
+        /// An easing curve tabulated at evenly spaced points of `0.0..=1.0`.
+        #[derive(Clone, Copy, Debug)]
+        struct __Easing;
+
+        #[allow(dead_code)]
+        impl __Easing {
+            #[doc = #doc]
+            /// `t` is clamped to `0.0..=1.0`.
+            fn sample(self, t: #ty) -> #ty {
+                let __pos = t.clamp(0.0, 1.0) * #scale;
+                let __i = __pos as usize;
+                if __i >= #last {
+                    return __LUT[#last];
+                }
+                let __frac = __pos - __i as #ty;
+                #interpolate
+            }
+
+            /// Looks up the tabulated point with the given index, at `t = i / (len - 1)`.
+            fn get(self, i: usize) -> #ty {
+                __LUT[i]
+            }
+        }
+
+        __Easing
+    }))
+}
+
+/// How `sample` interpolates between the tabulated points.
+#[derive(Clone, Copy, PartialEq)]
+enum Kernel {
+    Linear,
+    /// Cubic Hermite splines through the slopes of the curve, which are tabulated alongside it.
+    Hermite,
+    /// Cubic Hermite splines through slopes estimated from the neighboring points.
+    CatmullRom,
+}
+
+fn ease_out_elastic(t: f64) -> f64 {
+    if t <= 0.0 || t >= 1.0 {
+        return t.clamp(0.0, 1.0);
+    }
+    let c4 = std::f64::consts::TAU / 3.0;
+    2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * c4).sin() + 1.0
+}
+
+fn ease_out_bounce(t: f64) -> f64 {
+    const N1: f64 = 7.5625;
+    const D1: f64 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984_375
+    }
+}
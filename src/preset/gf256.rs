@@ -0,0 +1,117 @@
+//! Arithmetic tables for the finite field GF(2^8).
+
+/// The reduction polynomial used by AES, `x^8 + x^4 + x^3 + x + 1`.
+pub(super) const DEFAULT_POLY: usize = 0x11b;
+
+/// Generates a lookup for one operation of GF(2^8) with the given reduction polynomial.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let poly = args.take_usize("poly")?.unwrap_or(DEFAULT_POLY);
+    let generator = args.take_usize("generator")?;
+    args.finish()?;
+
+    let mul = |a: usize, b: usize| multiply(a, b, poly);
+    let first_generator = first_generator(poly)?;
+    let generator = match generator {
+        None => first_generator,
+        Some(g) if g < 256 && order(g, mul) == 255 => g,
+        Some(g) => {
+            let message = format!(
+                "{:#x} doesn't generate the multiplicative group of the field (e.g. {:#x} does)",
+                g, first_generator
+            );
+            return Err(syn::Error::new(proc_macro2::Span::call_site(), message));
+        }
+    };
+
+    let mut exp = [0u8; 256];
+    let mut log = [0u8; 256];
+    let mut x = 1;
+    for (i, entry) in exp.iter_mut().enumerate() {
+        *entry = x as u8;
+        if i < 255 {
+            log[x] = i as u8;
+        }
+        x = mul(x, generator);
+    }
+
+    let output = match kind.to_string().as_str() {
+        "mul" => {
+            let rows = (0..256).map(|a| {
+                let entries = (0..256).map(|b| mul(a, b) as u8);
+                quote::quote!([#(#entries),*])
+            });
+            quote::quote!({
+                static __LUT: [[u8; 256]; 256] = [#(#rows),*];
+                |a: u8, b: u8| -> u8 { __LUT[a as usize][b as usize] }
+            })
+        }
+        "exp" => {
+            let entries = exp.iter();
+            quote::quote!({
+                static __LUT: [u8; 256] = [#(#entries),*];
+                |i: u8| -> u8 { __LUT[i as usize] }
+            })
+        }
+        "log" => {
+            let entries = log.iter();
+            quote::quote!({
+                static __LUT: [u8; 256] = [#(#entries),*];
+                |a: u8| -> u8 { __LUT[a as usize] }
+            })
+        }
+        _ => {
+            return Err(syn::Error::new(
+                kind.span(),
+                "expected a table (`mul`, `exp` or `log`)",
+            ))
+        }
+    };
+    Ok(output)
+}
+
+/// Finds the smallest generator of the multiplicative group of the field, which only exists if the
+/// polynomial is irreducible.
+pub(super) fn first_generator(poly: usize) -> syn::Result<usize> {
+    if !(0x100..0x200).contains(&poly) {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`poly` must be a polynomial of degree 8 (between 0x100 and 0x1ff)",
+        ));
+    }
+    let mul = |a: usize, b: usize| multiply(a, b, poly);
+    (2..256).find(|&g| order(g, mul) == 255).ok_or_else(|| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("{:#x} is not an irreducible polynomial", poly),
+        )
+    })
+}
+
+/// Multiplies two elements of GF(2^8), reducing by the given polynomial.
+pub(super) fn multiply(mut a: usize, mut b: usize, poly: usize) -> usize {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= poly;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// The multiplicative order of the element, or 0 if its powers never reach 1.
+fn order(g: usize, mul: impl Fn(usize, usize) -> usize) -> usize {
+    let mut x = g;
+    for n in 1..=255 {
+        if x == 1 {
+            return n;
+        }
+        x = mul(x, g);
+    }
+    0
+}
@@ -0,0 +1,149 @@
+//! Decode tables for canonical prefix codes.
+
+/// The default number of bits that are decoded with the first table, as in zlib.
+const DEFAULT_FAST_BITS: usize = 9;
+/// The length of the longest supported code.
+const MAX_LEN: usize = 16;
+/// Marks entries of the first table that link to an overflow table.
+const LINK: u32 = 1 << 31;
+
+/// Generates two-level decode tables for the canonical prefix code with the given code lengths.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let mut args: super::Args = syn::parse2(input)?;
+    let lengths = args
+        .take_usize_list("lengths")?
+        .ok_or_else(|| super::missing("lengths"))?;
+    let symbols = args.take_usize_list("symbols")?;
+    let fast_bits = args.take_usize("fast_bits")?.unwrap_or(DEFAULT_FAST_BITS);
+    args.finish()?;
+
+    let error = |message: String| syn::Error::new(proc_macro2::Span::call_site(), message);
+    let symbols = symbols.unwrap_or_else(|| (0..lengths.len()).collect());
+    if symbols.len() != lengths.len() {
+        return Err(error(format!(
+            "there are {} `symbols` but {} `lengths`",
+            symbols.len(),
+            lengths.len()
+        )));
+    }
+    if let Some(symbol) = symbols.iter().find(|&&symbol| symbol > 0xffff) {
+        return Err(error(format!("symbol {} doesn't fit into a `u16`", symbol)));
+    }
+    if !(1..=MAX_LEN).contains(&fast_bits) {
+        return Err(error(format!(
+            "`fast_bits` must be between 1 and {}",
+            MAX_LEN
+        )));
+    }
+    let max_len = lengths.iter().copied().max().unwrap_or(0);
+    if max_len > MAX_LEN {
+        return Err(error(format!("codes can be at most {} bits long", MAX_LEN)));
+    }
+    if max_len == 0 {
+        return Err(error("at least one code length must be nonzero".to_owned()));
+    }
+    let kraft: u64 = lengths
+        .iter()
+        .filter(|&&len| len > 0)
+        .map(|&len| 1 << (MAX_LEN - len))
+        .sum();
+    if kraft > 1 << MAX_LEN {
+        return Err(error("the code lengths are over-subscribed".to_owned()));
+    }
+
+    // Canonical codes as in RFC 1951: shorter codes come first, and codes of the same length are
+    // assigned in the order of the symbols.
+    let mut next_code = vec![0; max_len + 2];
+    for len in 1..=max_len {
+        let count = lengths.iter().filter(|&&l| l == len).count();
+        next_code[len + 1] = (next_code[len] + count) << 1;
+    }
+    let mut codes = Vec::new();
+    for (&len, &symbol) in lengths.iter().zip(&symbols) {
+        if len > 0 {
+            // Codes are packed starting with their most significant bit, so the bits of the stream
+            // hold them reversed.
+            let code = reverse(next_code[len], len);
+            next_code[len] += 1;
+            codes.push((code, len, symbol));
+        }
+    }
+
+    let fast_len = 1 << fast_bits;
+    let mut fast = vec![0u32; fast_len];
+    let mut overflow = Vec::new();
+    for &(code, len, symbol) in codes.iter().filter(|&&(_, len, _)| len <= fast_bits) {
+        for high in 0..1 << (fast_bits - len) {
+            fast[code | high << len] = entry(symbol, len);
+        }
+    }
+    let long = codes
+        .iter()
+        .filter(|&&(_, len, _)| len > fast_bits)
+        .collect::<Vec<_>>();
+    for (prefix, fast_entry) in fast.iter_mut().enumerate() {
+        let group = long
+            .iter()
+            .filter(|&&&(code, _, _)| code & (fast_len - 1) == prefix)
+            .collect::<Vec<_>>();
+        let sub_bits = match group.iter().map(|&&&(_, len, _)| len).max() {
+            Some(len) => len - fast_bits,
+            None => continue,
+        };
+        let offset = overflow.len();
+        overflow.resize(offset + (1 << sub_bits), 0);
+        for &&&(code, len, symbol) in &group {
+            let rest = code >> fast_bits;
+            for high in 0..1 << (fast_bits + sub_bits - len) {
+                overflow[offset + (rest | high << (len - fast_bits))] = entry(symbol, len);
+            }
+        }
+        *fast_entry = LINK | (sub_bits as u32) << 16 | offset as u32;
+    }
+
+    let fast_mask = fast_len as u32 - 1;
+    let overflow_len = overflow.len().max(1);
+    if overflow.is_empty() {
+        overflow.push(0);
+    }
+    Ok(quote::quote!({
+        static __FAST: [u32; #fast_len] = [#(#fast),*];
+        static __OVERFLOW: [u32; #overflow_len] = [#(#overflow),*];
+
+        /// Decode tables for a canonical prefix code.
+        #[derive(Clone, Copy, Debug)]
+        struct __Huffman;
+
+        impl __Huffman {
+            /// Decodes the code at the start of `bits`, which holds the next bits of the stream
+            /// starting with the least significant bit, returning the symbol and the length of its
+            /// code, or `None` if `bits` doesn't start with a valid code.
+            fn decode(self, bits: u32) -> Option<(u16, u32)> {
+                let mut __entry = __FAST[(bits & #fast_mask) as usize];
+                if __entry & #LINK != 0 {
+                    let __sub_bits = (__entry >> 16) & 0xff;
+                    let __offset = (__entry & 0xffff) as usize;
+                    let __index = ((bits >> #fast_bits) & ((1 << __sub_bits) - 1)) as usize;
+                    __entry = __OVERFLOW[__offset + __index];
+                }
+                if __entry == 0 {
+                    None
+                } else {
+                    Some(((__entry & 0xffff) as u16, __entry >> 16))
+                }
+            }
+        }
+
+        __Huffman
+    }))
+}
+
+/// The table entry for a symbol with a code of the given length.
+fn entry(symbol: usize, len: usize) -> u32 {
+    (len as u32) << 16 | symbol as u32
+}
+
+/// Reverses the lowest `len` bits.
+fn reverse(code: usize, len: usize) -> usize {
+    (0..len).fold(0, |reversed, i| reversed << 1 | (code >> i) & 1)
+}
@@ -0,0 +1,88 @@
+//! Substitution boxes for block ciphers.
+
+/// The rotations of the affine transform of AES, `b ^ rotl(b, 1) ^ ... ^ rotl(b, 4)`.
+const AES_ROTATIONS: usize = 0x1f;
+/// The constant of the affine transform of AES.
+const AES_CONSTANT: usize = 0x63;
+
+/// Generates a lookup of an S-box that applies an affine transform to the multiplicative inverse in
+/// GF(2^8).
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let (poly, rotations, constant) = match kind.to_string().as_str() {
+        "aes" => (super::gf256::DEFAULT_POLY, AES_ROTATIONS, AES_CONSTANT),
+        "affine" => (
+            args.take_usize("poly")?
+                .unwrap_or(super::gf256::DEFAULT_POLY),
+            args.take_usize("rotations")?
+                .ok_or_else(|| super::missing("rotations"))?,
+            args.take_usize("constant")?.unwrap_or(0),
+        ),
+        _ => {
+            return Err(syn::Error::new(
+                kind.span(),
+                "expected an S-box (`aes` or `affine`)",
+            ))
+        }
+    };
+    let inverse = args.take_bool("inverse")?.unwrap_or(false);
+    let constant_time = args.take_bool("constant_time")?.unwrap_or(true);
+    args.finish()?;
+
+    super::gf256::first_generator(poly)?;
+    if rotations > 0xff || constant > 0xff {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`rotations` and `constant` must be bytes",
+        ));
+    }
+
+    let mul = |a: usize, b: usize| super::gf256::multiply(a, b, poly);
+    // The inverse is `a^254`, since `a^255 == 1` for all nonzero `a`, and zero maps to itself.
+    let invert = |a: usize| (0..253).fold(a, |x, _| mul(x, a));
+    let transform = |b: usize| {
+        let b = b as u8;
+        let rotated = (0..8)
+            .filter(|k| rotations & (1 << k) != 0)
+            .fold(0, |acc, k| acc ^ b.rotate_left(k));
+        (rotated ^ constant as u8) as usize
+    };
+    let mut sbox = (0..256).map(|a| transform(invert(a))).collect::<Vec<_>>();
+    if inverse {
+        let mut inverted = vec![None; 256];
+        for (a, &s) in sbox.iter().enumerate() {
+            inverted[s] = Some(a);
+        }
+        sbox = inverted
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    "the S-box isn't invertible, since the affine transform isn't",
+                )
+            })?;
+    }
+
+    let entries = sbox.iter().map(|&s| s as u8);
+    let lookup = if constant_time {
+        quote::quote! {
+            // Every entry is read and all but the selected one are masked out, so that neither the
+            // memory access pattern nor the control flow depend on the input.
+            let mut __result = 0u8;
+            let mut __i = 0;
+            while __i < 256 {
+                let __diff = (__i ^ x as u32).wrapping_neg() >> 31;
+                __result |= __LUT[__i as usize] & (__diff as u8).wrapping_sub(1);
+                __i += 1;
+            }
+            __result
+        }
+    } else {
+        quote::quote!(__LUT[x as usize])
+    };
+    Ok(quote::quote!({
+        static __LUT: [u8; 256] = [#(#entries),*];
+        |x: u8| -> u8 { #lookup }
+    }))
+}
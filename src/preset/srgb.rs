@@ -0,0 +1,77 @@
+//! Conversions between sRGB-encoded and linear color components.
+
+/// Generates a lookup that converts quantized components between sRGB and linear light.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let input_ty = args
+        .take_type("input")?
+        .unwrap_or_else(|| syn::parse_quote!(u8));
+    let output_ty = args
+        .take_type("output")?
+        .unwrap_or_else(|| syn::parse_quote!(f32));
+    args.finish()?;
+
+    let convert: fn(f64) -> f64 = match kind.to_string().as_str() {
+        "to_linear" => to_linear,
+        "to_srgb" => to_srgb,
+        _ => {
+            return Err(syn::Error::new(
+                kind.span(),
+                "expected a conversion (`to_linear` or `to_srgb`)",
+            ))
+        }
+    };
+    let input_max = match super::primitive_name(&input_ty).as_deref() {
+        Some("u8") => u8::MAX as usize,
+        Some("u16") => u16::MAX as usize,
+        _ => return Err(unsupported(&input_ty, "input", "`u8` or `u16`")),
+    };
+    let output_max = match super::primitive_name(&output_ty).as_deref() {
+        Some("u8") => Some(u8::MAX as f64),
+        Some("u16") => Some(u16::MAX as f64),
+        Some("f32") => None,
+        _ => return Err(unsupported(&output_ty, "output", "`u8`, `u16` or `f32`")),
+    };
+
+    let entries = (0..=input_max).map(|i| {
+        let value = convert(i as f64 / input_max as f64);
+        match output_max {
+            Some(max) => {
+                let value = proc_macro2::Literal::u64_unsuffixed((value * max).round() as u64);
+                quote::quote!(#value)
+            }
+            None => {
+                let value = proc_macro2::Literal::f32_suffixed(value as f32);
+                quote::quote!(#value)
+            }
+        }
+    });
+    let count = input_max + 1;
+    Ok(quote::quote!({
+        static __LUT: [#output_ty; #count] = [#(#entries),*];
+        |c: #input_ty| -> #output_ty { __LUT[c as usize] }
+    }))
+}
+
+fn unsupported(ty: &syn::Type, name: &str, expected: &str) -> syn::Error {
+    use syn::spanned::Spanned;
+    syn::Error::new(ty.span(), format!("the {} type must be {}", name, expected))
+}
+
+/// Decodes an sRGB-encoded component in `0.0..=1.0` to linear light, per IEC 61966-2-1.
+fn to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes a linear light component in `0.0..=1.0` as sRGB, per IEC 61966-2-1.
+fn to_srgb(l: f64) -> f64 {
+    if l <= 0.003_130_8 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,91 @@
+//! Sine and cosine tables.
+
+/// The smallest supported phase resolution in bits.
+const MIN_BITS: usize = 3;
+/// The largest supported phase resolution in bits.
+const MAX_BITS: usize = 20;
+
+/// A periodic function that is tabulated over one period.
+#[derive(Clone, Copy)]
+pub(crate) enum Wave {
+    Sin,
+    Cos,
+}
+
+/// Generates a lookup of the wave over a phase of `2^bits` steps per period.
+///
+/// Only the first quarter of the sine wave is stored; the rest of the period is reconstructed by
+/// mirroring and negating it.
+pub(crate) fn generate(
+    wave: Wave,
+    input: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut args: super::Args = syn::parse2(input)?;
+    let bits = args
+        .take_usize("bits")?
+        .ok_or_else(|| super::missing("bits"))?;
+    let amplitude = args.take("amplitude");
+    let explicit = args.take_type("ty")?;
+    let ty = match (&explicit, &amplitude) {
+        (Some(ty), _) => ty.clone(),
+        (None, Some(amplitude)) => super::infer_type(amplitude).ok_or_else(|| {
+            use syn::spanned::Spanned;
+            syn::Error::new(
+                amplitude.span(),
+                "can't infer the element type from the amplitude; specify it with `ty = ...`",
+            )
+        })?,
+        (None, None) => syn::parse_quote!(f32),
+    };
+    args.finish()?;
+
+    // The second half of the period negates the entries.
+    let signed = super::primitive_name(&ty).filter(|name| !name.starts_with('u'));
+    if signed.is_none() {
+        let message = "the element type must be a signed integer, `f32` or `f64`";
+        return Err(match (&explicit, &amplitude) {
+            (None, Some(amplitude)) => syn::Error::new_spanned(amplitude, message),
+            _ => syn::Error::new_spanned(&ty, message),
+        });
+    }
+    if !(MIN_BITS..=MAX_BITS).contains(&bits) {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("`bits` must be between {} and {}", MIN_BITS, MAX_BITS),
+        ));
+    }
+    let amplitude = match amplitude {
+        Some(amplitude) => quote::quote!((#amplitude) as f64),
+        None if super::is_float(&ty) => quote::quote!(1.0),
+        None => quote::quote!(#ty::MAX as f64),
+    };
+
+    let len = 1usize << bits;
+    let quarter = len / 4;
+    let entries = (0..=quarter).map(|i| {
+        let angle = std::f64::consts::TAU * i as f64 / len as f64;
+        let value = proc_macro2::Literal::f64_suffixed(angle.sin());
+        super::convert(quote::quote!(__AMPLITUDE * #value), &ty)
+    });
+    let count = quarter + 1;
+    let (mask, quarter_mask, shift) = (len - 1, quarter - 1, bits - 2);
+    let phase = match wave {
+        Wave::Sin => quote::quote!(phase & #mask),
+        Wave::Cos => quote::quote!(phase.wrapping_add(#quarter) & #mask),
+    };
+
+    Ok(quote::quote!({
+        const __AMPLITUDE: f64 = #amplitude;
+        static __QUARTER: [#ty; #count] = [#(#entries),*];
+        |phase: usize| -> #ty {
+            let __phase = #phase;
+            let __i = __phase & #quarter_mask;
+            match __phase >> #shift {
+                0 => __QUARTER[__i],
+                1 => __QUARTER[#quarter - __i],
+                2 => -__QUARTER[__i],
+                _ => -__QUARTER[#quarter - __i],
+            }
+        }
+    }))
+}
@@ -0,0 +1,96 @@
+//! Window functions for spectral analysis.
+
+/// Generates a lookup of a window function of the given length.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let (kind, mut args) = super::parse_with_kind(input)?;
+    let len = args
+        .take_usize("len")?
+        .ok_or_else(|| super::missing("len"))?;
+    let periodic = args.take_bool("periodic")?.unwrap_or(false);
+    let beta = args.take_f64("beta")?;
+    let ty = args
+        .take_type("ty")?
+        .unwrap_or_else(|| syn::parse_quote!(f32));
+    args.finish()?;
+
+    let window: Box<dyn Fn(f64) -> f64> =
+        match (kind.to_string().as_str(), beta) {
+            ("hann", None) => Box::new(|x| cosine_sum(&[0.5, 0.5], x)),
+            ("hamming", None) => Box::new(|x| cosine_sum(&[0.54, 0.46], x)),
+            ("blackman", None) => Box::new(|x| cosine_sum(&[0.42, 0.5, 0.08], x)),
+            ("blackman_harris", None) => {
+                Box::new(|x| cosine_sum(&[0.358_75, 0.488_29, 0.141_28, 0.011_68], x))
+            }
+            ("kaiser", Some(beta)) => Box::new(move |x| {
+                let r = 2.0 * x - 1.0;
+                bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(beta)
+            }),
+            ("kaiser", None) => return Err(super::missing("beta")),
+            ("hann" | "hamming" | "blackman" | "blackman_harris", Some(_)) => {
+                return Err(syn::Error::new(
+                    kind.span(),
+                    "only the `kaiser` window takes a `beta`",
+                ))
+            }
+            _ => return Err(syn::Error::new(
+                kind.span(),
+                "expected a window (`hann`, `hamming`, `blackman`, `blackman_harris` or `kaiser`)",
+            )),
+        };
+    if !super::is_float(&ty) {
+        use syn::spanned::Spanned;
+        return Err(syn::Error::new(
+            ty.span(),
+            "the element type must be `f32` or `f64`",
+        ));
+    }
+    if len < 2 {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`len` must be at least 2",
+        ));
+    }
+
+    // Symmetric windows span `len - 1` intervals, while periodic windows span `len` intervals and
+    // leave out the last sample, which repeats the first one.
+    let span = if periodic { len } else { len - 1 };
+    let is_f64 = super::primitive_name(&ty).as_deref() == Some("f64");
+    let entries = (0..len).map(|n| {
+        let value = window(n as f64 / span as f64);
+        if is_f64 {
+            proc_macro2::Literal::f64_suffixed(value)
+        } else {
+            proc_macro2::Literal::f32_suffixed(value as f32)
+        }
+    });
+    Ok(quote::quote!({
+        static __LUT: [#ty; #len] = [#(#entries),*];
+        |n: usize| -> #ty { __LUT[n] }
+    }))
+}
+
+/// Evaluates `a0 - a1 cos(2πx) + a2 cos(4πx) - ...` for `x` in `0.0..=1.0`.
+fn cosine_sum(coefficients: &[f64], x: f64) -> f64 {
+    coefficients
+        .iter()
+        .enumerate()
+        .map(|(k, a)| {
+            let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+            sign * a * (std::f64::consts::TAU * k as f64 * x).cos()
+        })
+        .sum()
+}
+
+/// The zeroth-order modified Bessel function of the first kind.
+fn bessel_i0(x: f64) -> f64 {
+    let quarter_x2 = x * x / 4.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-17 {
+        term *= quarter_x2 / (k * k);
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
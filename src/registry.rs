@@ -0,0 +1,123 @@
+//! A registry of tables that can be listed at runtime, like from a diagnostic command.
+//!
+//! The registry is a `linkme` distributed slice: `lut_registry!` declares it together with the
+//! struct describing a table, and every table with `#[register(..)]` adds its description to it
+//! with a static of its own.  The linker gathers all of them into one slice, so tables can be
+//! registered from any module or crate without a central list.
+
+/// The name of the struct that describes a registered table, which is declared next to the
+/// registry.
+const INFO: &str = "LutInfo";
+
+/// The input of `lut_registry!`.
+struct Registry {
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+}
+
+impl syn::parse::Parse for Registry {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<syn::Token![static]>()?;
+        let ident = input.parse()?;
+        if !input.is_empty() {
+            input.parse::<syn::Token![;]>()?;
+        }
+        Ok(Registry { attrs, vis, ident })
+    }
+}
+
+/// Generates the registry and the struct describing registered tables.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let Registry { attrs, vis, ident } = syn::parse2(input)?;
+    if cfg!(not(feature = "linkme")) {
+        return Err(syn::Error::new(
+            ident.span(),
+            "`lut_registry!` requires the `linkme` feature of `numeric-lut`",
+        ));
+    }
+    let info = syn::Ident::new(INFO, ident.span());
+    Ok(quote::quote! {
+        /// The description of a table registered with `#[register(..)]`.
+        #[derive(Clone, Copy, Debug)]
+        #vis struct #info {
+            /// The name of the struct of the table.
+            pub name: &'static str,
+            /// The path of the module that declares the table.
+            pub module_path: &'static str,
+            /// The number of values of every parameter.
+            pub shape: &'static [usize],
+            /// The element type, as written in the declaration (or the stored type with `#[f16]`,
+            /// `#[codec]` and `#[bitfields]`).
+            pub element_type: &'static str,
+            /// The size of a stored element in bytes.
+            pub element_size: usize,
+            /// The size of the stored entries in bytes, which is zero for tables whose entries are
+            /// computed on every lookup.
+            pub byte_size: usize,
+            /// The `TABLE_HASH` of the table, if it has the `#[hash]` option.
+            pub hash: ::core::option::Option<u64>,
+        }
+
+        #(#attrs)*
+        #[::linkme::distributed_slice]
+        #vis static #ident: [#info];
+    })
+}
+
+/// Generates the registration of the item form, if requested.
+pub(crate) fn register(
+    input: &super::Lut,
+    ident: &syn::Ident,
+    table: &super::Table,
+) -> proc_macro2::TokenStream {
+    let registry = match &input.options.register {
+        Some(registry) => registry,
+        None => return proc_macro2::TokenStream::new(),
+    };
+    // The struct of the registry is declared in the same module as the registry itself.
+    let mut info = registry.clone();
+    if let Some(last) = info.segments.last_mut() {
+        *last = syn::PathSegment::from(syn::Ident::new(INFO, last.ident.span()));
+    }
+
+    let return_type = &input.return_type;
+    let stored = input.element_type();
+    let name = ident.to_string();
+    let element_type = match (input.options.f16, &input.options.codec) {
+        (Some(_), _) => "f16".to_owned(),
+        (None, Some(super::Codec { stored, .. })) => quote::quote!(#stored).to_string(),
+        (None, None) => quote::quote!(#return_type).to_string(),
+    };
+    let shape = input.inputs.iter().map(super::Param::len);
+    let byte_size = match table.strategy {
+        super::Strategy::Compute => quote::quote!(0),
+        super::Strategy::Table if table.deduplicated => quote::quote! {
+            ::core::mem::size_of_val(#ident::TABLE.0) + ::core::mem::size_of_val(&#ident::TABLE.1)
+        },
+        super::Strategy::Table | super::Strategy::Match => {
+            let len = table.len;
+            quote::quote!(#len * ::core::mem::size_of::<#stored>())
+        }
+    };
+    let hash = match input.options.hash {
+        Some(_) => quote::quote!(::core::option::Option::Some(#ident::TABLE_HASH)),
+        None => quote::quote!(::core::option::Option::None),
+    };
+    quote::quote! {
+        const _: () = {
+            #[::linkme::distributed_slice(#registry)]
+            static __REGISTRATION: #info = #info {
+                name: #name,
+                module_path: ::core::module_path!(),
+                shape: &[#(#shape),*],
+                element_type: #element_type,
+                element_size: ::core::mem::size_of::<#stored>(),
+                byte_size: #byte_size,
+                hash: #hash,
+            };
+        };
+    }
+}
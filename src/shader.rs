@@ -0,0 +1,200 @@
+//! Rendering of tables as shader source code.
+//!
+//! The entries are only known after constant evaluation, so the source is rendered by generated
+//! `const fn`s: once to measure its length, and once more into a buffer of that length.
+
+/// A shading language that tables can be rendered as.
+#[derive(Clone, Copy)]
+pub(crate) enum Language {
+    Wgsl,
+    Glsl,
+}
+
+impl Language {
+    pub(crate) fn from_ident(ident: &syn::Ident) -> syn::Result<Self> {
+        match ident.to_string().as_str() {
+            "wgsl" => Ok(Language::Wgsl),
+            "glsl" => Ok(Language::Glsl),
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "expected a shading language (`wgsl` or `glsl`)",
+            )),
+        }
+    }
+
+    /// The name of the associated constant holding the source.
+    fn const_name(self) -> syn::Ident {
+        let name = match self {
+            Language::Wgsl => "WGSL",
+            Language::Glsl => "GLSL",
+        };
+        syn::Ident::new(name, proc_macro2::Span::call_site())
+    }
+}
+
+/// Generates an associated constant holding the declaration of the table as a constant array in
+/// the given language.
+///
+/// `entry_slices` must evaluate to slices covering all stored entries, in memory order.
+pub(crate) fn generate(
+    language: Language,
+    vis: &syn::Visibility,
+    name: &str,
+    element_type: &syn::Type,
+    len: usize,
+    entry_slices: &[proc_macro2::TokenStream],
+) -> syn::Result<proc_macro2::TokenStream> {
+    use syn::spanned::Spanned;
+
+    let element = match element_type {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path
+            .get_ident()
+            .map(|ident| ident.to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    let (shader_type, render_entry) = match (language, element.as_str()) {
+        (Language::Wgsl, "u32") => ("u32", quote::quote!(__put_uint(buf, pos, __entry, b"u"))),
+        (Language::Wgsl, "i32") => ("i32", quote::quote!(__put_int(buf, pos, __entry, b"i"))),
+        (Language::Wgsl, "f32") => (
+            "f32",
+            quote::quote!(__put_float(buf, pos, __entry, b"bitcast<f32>(")),
+        ),
+        (Language::Glsl, "u32") => ("uint", quote::quote!(__put_uint(buf, pos, __entry, b"u"))),
+        (Language::Glsl, "i32") => ("int", quote::quote!(__put_int(buf, pos, __entry, b""))),
+        (Language::Glsl, "f32") => (
+            "float",
+            quote::quote!(__put_float(buf, pos, __entry, b"uintBitsToFloat(")),
+        ),
+        _ => {
+            return Err(syn::Error::new(
+                element_type.span(),
+                "shader export supports `u32`, `i32` and `f32` entries",
+            ))
+        }
+    };
+
+    let name = name.to_uppercase();
+    let (prefix, suffix) = match language {
+        Language::Wgsl => (
+            format!(
+                "const {}: array<{}, {}> = array<{}, {}>(",
+                name, shader_type, len, shader_type, len
+            ),
+            ");\n".to_owned(),
+        ),
+        Language::Glsl => (
+            format!(
+                "const {} {}[{}] = {}[{}](",
+                shader_type, name, len, shader_type, len
+            ),
+            ");\n".to_owned(),
+        ),
+    };
+    let prefix = syn::LitByteStr::new(prefix.as_bytes(), proc_macro2::Span::call_site());
+    let suffix = syn::LitByteStr::new(suffix.as_bytes(), proc_macro2::Span::call_site());
+    let const_name = language.const_name();
+    let doc = format!(
+        "The stored entries in memory order, declared as the constant array `{}` in {}.",
+        name,
+        match language {
+            Language::Wgsl => "WGSL",
+            Language::Glsl => "GLSL",
+        }
+    );
+
+    Ok(quote::quote! {
+        #[doc = #doc]
+        #vis const #const_name: &'static str = {
+            const fn __render(buf: &mut [u8]) -> usize {
+                let __shards: &[&[#element_type]] = &[#(#entry_slices),*];
+                let mut pos = __put(buf, 0, #prefix);
+                let mut __s = 0;
+                while __s < __shards.len() {
+                    let mut __i = 0;
+                    while __i < __shards[__s].len() {
+                        if __s > 0 || __i > 0 {
+                            pos = __put(buf, pos, b", ");
+                        }
+                        let __entry = __shards[__s][__i];
+                        pos = #render_entry;
+                        __i += 1;
+                    }
+                    __s += 1;
+                }
+                __put(buf, pos, #suffix)
+            }
+
+            /// Writes the bytes at `pos`, as far as they fit, returning the position after them.
+            const fn __put(buf: &mut [u8], mut pos: usize, bytes: &[u8]) -> usize {
+                let mut i = 0;
+                while i < bytes.len() {
+                    if pos < buf.len() {
+                        buf[pos] = bytes[i];
+                    }
+                    pos += 1;
+                    i += 1;
+                }
+                pos
+            }
+
+            const fn __put_digits(buf: &mut [u8], pos: usize, mut value: u64, radix: u64) -> usize {
+                let mut digits = [0u8; 20];
+                let mut start = digits.len();
+                loop {
+                    start -= 1;
+                    digits[start] = b"0123456789abcdef"[(value % radix) as usize];
+                    value /= radix;
+                    if value == 0 {
+                        break;
+                    }
+                }
+                let (_, digits) = digits.split_at(start);
+                __put(buf, pos, digits)
+            }
+
+            #[allow(dead_code)]
+            const fn __put_uint(buf: &mut [u8], pos: usize, value: u32, suffix: &[u8]) -> usize {
+                let pos = __put_digits(buf, pos, value as u64, 10);
+                __put(buf, pos, suffix)
+            }
+
+            #[allow(dead_code)]
+            const fn __put_int(buf: &mut [u8], mut pos: usize, value: i32, suffix: &[u8]) -> usize {
+                if value == i32::MIN {
+                    // The magnitude of the minimum doesn't fit into a literal of the type.
+                    pos = __put(buf, pos, b"(-2147483647");
+                    pos = __put(buf, pos, suffix);
+                    pos = __put(buf, pos, b" - 1");
+                    pos = __put(buf, pos, suffix);
+                    return __put(buf, pos, b")");
+                }
+                if value < 0 {
+                    pos = __put(buf, pos, b"-");
+                }
+                pos = __put_digits(buf, pos, value.unsigned_abs() as u64, 10);
+                __put(buf, pos, suffix)
+            }
+
+            #[allow(dead_code)]
+            const fn __put_float(buf: &mut [u8], mut pos: usize, value: f32, cast: &[u8]) -> usize {
+                // Reinterpreting the bits keeps the values exactly the same as on the CPU.
+                pos = __put(buf, pos, cast);
+                pos = __put(buf, pos, b"0x");
+                pos = __put_digits(buf, pos, value.to_bits() as u64, 16);
+                __put(buf, pos, b"u)")
+            }
+
+            const __LEN: usize = __render(&mut []);
+            const __SOURCE: [u8; __LEN] = {
+                let mut buf = [0; __LEN];
+                __render(&mut buf);
+                buf
+            };
+            match ::core::str::from_utf8(&__SOURCE) {
+                Ok(source) => source,
+                Err(_) => panic!("generated invalid shader source"),
+            }
+        };
+    })
+}
@@ -0,0 +1,196 @@
+//! Warnings about likely mistakes in the input, with suggested rewrites.
+//!
+//! Proc macros can't emit warnings on stable Rust, so by default a warning is reported through the
+//! `deprecated` lint: the macro generates a deprecated item whose note holds the message, and uses
+//! it with the span of the offending tokens.  With the `nightly` feature, warnings are emitted
+//! through the unstable diagnostics API instead, which shows the suggestion as a separate help.
+
+/// A warning about the input.
+pub(crate) struct Warning {
+    span: proc_macro2::Span,
+    message: String,
+    help: String,
+}
+
+impl Warning {
+    /// Emits the warning, returning the items that report it if diagnostics are unavailable.
+    #[cfg(not(feature = "nightly"))]
+    pub(crate) fn emit(&self) -> proc_macro2::TokenStream {
+        let note = format!("{}; {}", self.message, self.help);
+        quote::quote_spanned! {self.span=>
+            const _: () = {
+                #[deprecated(note = #note)]
+                struct NumericLutWarning;
+                let _ = NumericLutWarning;
+            };
+        }
+    }
+
+    /// Emits the warning, returning the items that report it if diagnostics are unavailable.
+    #[cfg(feature = "nightly")]
+    pub(crate) fn emit(&self) -> proc_macro2::TokenStream {
+        proc_macro::Diagnostic::spanned(
+            self.span.unwrap(),
+            proc_macro::Level::Warning,
+            self.message.as_str(),
+        )
+        .help(self.help.as_str())
+        .emit();
+        proc_macro2::TokenStream::new()
+    }
+}
+
+/// Checks the input for likely mistakes.
+pub(crate) fn check(input: &super::Lut) -> Vec<Warning> {
+    let body = &input.body;
+    let mut warnings = Vec::new();
+    exclusive_ends(input, &quote::quote!(#body), &mut warnings);
+    warnings
+}
+
+/// Warns about tables that store more entries than the threshold of `#[warn_above]`, which are
+/// often caused by typos in the ranges like `0..=65536`.
+pub(crate) fn size(input: &super::Lut, table: &super::Table) -> Option<Warning> {
+    let threshold = input
+        .options
+        .warn_above
+        .unwrap_or(super::DEFAULT_WARN_ABOVE);
+    if table.strategy == super::Strategy::Compute || table.len <= threshold {
+        return None;
+    }
+    let bytes = match super::metadata::primitive_size(&input.return_type) {
+        Some(size) => format!(" ({} bytes)", size * table.len),
+        None => String::new(),
+    };
+    // The first of the largest parameters, which is the likeliest to have a typo in its range.
+    let largest = input.inputs.iter().rev().max_by_key(|param| param.len())?;
+    Some(Warning {
+        span: largest.ident.span(),
+        message: format!(
+            "the table stores {} entries{}, more than the threshold of {}",
+            table.len, bytes, threshold
+        ),
+        help: format!(
+            "check the ranges, or raise the threshold with `#[warn_above(entries = {})]`",
+            table.len
+        ),
+    })
+}
+
+/// Warns about comparisons of parameters with the excluded end of their range, like `x == 8` for
+/// `x @ 0..8`, which suggest that the range was meant to be inclusive.
+fn exclusive_ends(
+    input: &super::Lut,
+    tokens: &proc_macro2::TokenStream,
+    warnings: &mut Vec<Warning>,
+) {
+    use proc_macro2::TokenTree;
+
+    let tokens = tokens.clone().into_iter().collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        if let TokenTree::Group(group) = token {
+            exclusive_ends(input, &group.stream(), warnings);
+            continue;
+        }
+        let (ident, literal) = match tokens.get(i..i + 4) {
+            Some(
+                [TokenTree::Ident(ident), TokenTree::Punct(a), TokenTree::Punct(b), TokenTree::Literal(literal)]
+                | [TokenTree::Literal(literal), TokenTree::Punct(a), TokenTree::Punct(b), TokenTree::Ident(ident)],
+            ) if a.as_char() == '=' && b.as_char() == '=' => (ident, literal),
+            _ => continue,
+        };
+        let param = match input.inputs.iter().find(|param| param.ident == *ident) {
+            Some(param) if param.exclusive_end => param,
+            _ => continue,
+        };
+        let value = syn::LitInt::from(literal.clone()).base10_parse::<usize>();
+        if value.ok() == Some(param.hi) {
+            warnings.push(Warning {
+                span: literal.span(),
+                message: format!(
+                    "`{}` is never {}, since the range `{}..{}` excludes its end",
+                    ident, param.hi, param.lo, param.hi
+                ),
+                help: format!(
+                    "use `{} @ {}..={}` to include it",
+                    ident, param.lo, param.hi
+                ),
+            });
+        }
+    }
+}
+
+/// Warns about stored tables of integer literals, like `[1, 2, 3][x]`, whose entries all fit into a
+/// narrower integer type than the declared one, unless the table has `#[wide_entries]`.
+pub(crate) fn narrower_type(input: &super::Lut, table: &super::Table) -> Option<Warning> {
+    use syn::spanned::Spanned;
+
+    if table.strategy != super::Strategy::Table || input.options.wide_entries.is_some() {
+        return None;
+    }
+    let declared = super::preset::primitive_name(&input.return_type)?;
+    let declared_size = super::metadata::primitive_size(&input.return_type)?;
+    if declared == "bool" || declared == "char" || declared.starts_with('f') {
+        return None;
+    }
+    let mut expr = &input.body;
+    while let syn::Expr::Block(syn::ExprBlock { block, .. }) = expr {
+        match block.stmts.as_slice() {
+            [syn::Stmt::Expr(inner)] => expr = inner,
+            _ => return None,
+        }
+    }
+    let entries = match expr {
+        syn::Expr::Index(syn::ExprIndex { expr, .. }) => match &**expr {
+            syn::Expr::Array(array) => &array.elems,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let values = entries
+        .iter()
+        .map(|entry| match entry {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            }) if lit.suffix().is_empty() => lit.base10_parse::<i128>().ok(),
+            syn::Expr::Unary(syn::ExprUnary {
+                op: syn::UnOp::Neg(_),
+                expr,
+                ..
+            }) => match &**expr {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(lit),
+                    ..
+                }) if lit.suffix().is_empty() => lit.base10_parse::<i128>().ok().map(|v| -v),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let (min, max) = (*values.iter().min()?, *values.iter().max()?);
+
+    // Keep the signedness of the declared type, so that the entries keep their meaning.
+    let signed = declared.starts_with('i');
+    let (name, size) = [8, 16, 32, 64].iter().find_map(|&bits| {
+        let (lo, hi, prefix) = if signed {
+            (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1, 'i')
+        } else {
+            (0, (1i128 << bits) - 1, 'u')
+        };
+        (lo <= min && max <= hi).then(|| (format!("{}{}", prefix, bits), bits / 8))
+    })?;
+    if size >= declared_size {
+        return None;
+    }
+    Some(Warning {
+        span: input.return_type.span(),
+        message: format!("all entries fit into `{}`", name),
+        help: format!(
+            "declare the table as `-> {}` to shrink it from {} to {} bytes, or keep the type with `#[wide_entries]`",
+            name,
+            declared_size * table.len,
+            size * table.len
+        ),
+    })
+}
@@ -0,0 +1,163 @@
+//! Tables that combine the entries of two specs elementwise into a single table.
+//!
+//! Both specs are evaluated for every entry of the fused table during constant evaluation, so
+//! neither of them is stored on its own.
+
+/// The input of `lut_zip!`.
+struct Zip {
+    first: super::Lut,
+    second: super::Lut,
+    /// The options of the fused table.
+    attrs: Vec<syn::Attribute>,
+    combine: syn::ExprClosure,
+}
+
+impl syn::parse::Parse for Zip {
+    fn parse(input: syn::parse::ParseStream) -> syn::parse::Result<Self> {
+        let first = parse_spec(input)?;
+        input.parse::<syn::Token![,]>()?;
+        let second = parse_spec(input)?;
+        input.parse::<syn::Token![,]>()?;
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let combine: syn::ExprClosure = input.parse()?;
+        if combine.inputs.len() != 2 {
+            return Err(syn::Error::new_spanned(
+                &combine.inputs,
+                "expected a closure that combines two entries, like `|a, b| a * b`",
+            ));
+        }
+        if input.peek(syn::Token![,]) {
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(Zip {
+            first,
+            second,
+            attrs,
+            combine,
+        })
+    }
+}
+
+/// Parses one of the specs that are combined, which must be in the closure form without options.
+fn parse_spec(input: syn::parse::ParseStream) -> syn::parse::Result<super::Lut> {
+    if input.peek(syn::Token![#]) {
+        let attr = input.call(syn::Attribute::parse_outer)?.remove(0);
+        return Err(syn::Error::new_spanned(
+            attr,
+            "the tables of `lut_zip!` aren't stored, so options go before the closure that combines their entries",
+        ));
+    }
+    if !input.peek(syn::Token![|]) {
+        return Err(
+            input.error("expected a table in the closure form, like `|x @ 0..4| -> T { .. }`")
+        );
+    }
+    input.parse()
+}
+
+/// Generates a table whose entries combine the entries of two specs.
+pub(crate) fn generate(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let Zip {
+        first,
+        second,
+        attrs,
+        combine,
+    } = syn::parse2(input)?;
+    if first.inputs.len() != second.inputs.len() {
+        let span = second
+            .inputs
+            .first()
+            .map_or_else(proc_macro2::Span::call_site, |param| param.ident.span());
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "the tables must have the same parameters, but the first one has {} and this one {}",
+                first.inputs.len(),
+                second.inputs.len()
+            ),
+        ));
+    }
+    for (a, b) in first.inputs.iter().zip(&second.inputs) {
+        if (a.lo, a.len()) != (b.lo, b.len()) {
+            return Err(syn::Error::new(
+                b.ident.span(),
+                format!(
+                    "`{}` must have the same values as `{}` of the first table, {}..={}",
+                    b.ident,
+                    a.ident,
+                    a.lo,
+                    a.lo + a.len() - 1
+                ),
+            ));
+        }
+    }
+
+    let params = first.inputs.iter().map(param);
+    let (first_type, first_body) = (&first.return_type, &first.body);
+    let (second_type, second_body) = (&second.return_type, &second.body);
+    // The second body sees the values under its own names, which would be redundant to rebind
+    // under the same one.
+    let renames = first
+        .inputs
+        .iter()
+        .zip(&second.inputs)
+        .filter(|(a, b)| a.ident != b.ident)
+        .map(|(a, b)| {
+            let (a, b) = (&a.ident, &b.ident);
+            quote::quote!(let #b = #a;)
+        })
+        .collect::<Vec<_>>();
+    let second_body = match renames.is_empty() {
+        true => quote::quote!(#second_body),
+        false => {
+            let second_body = statements(second_body);
+            quote::quote!({ #(#renames)* #second_body })
+        }
+    };
+    let (left, right) = (&combine.inputs[0], &combine.inputs[1]);
+    let return_type = match &combine.output {
+        syn::ReturnType::Default => quote::quote!(_),
+        syn::ReturnType::Type(_, ty) => quote::quote!(#ty),
+    };
+    let body = statements(&combine.body);
+    let spec = quote::quote! {
+        #(#attrs)*
+        |#(#params),*| -> #return_type {
+            let __first: #first_type = #first_body;
+            let __second: #second_type = #second_body;
+            let #left = __first;
+            let #right = __second;
+            #body
+        }
+    };
+    Ok(super::lut(spec.into()).into())
+}
+
+/// Declares a parameter of the fused table like the parameter of a spec.
+pub(crate) fn param(param: &super::Param) -> proc_macro2::TokenStream {
+    let ident = &param.ident;
+    let lo = proc_macro2::Literal::usize_unsuffixed(param.lo);
+    let hi = proc_macro2::Literal::usize_unsuffixed(param.lo + param.len() - 1);
+    let policy = match param.policy {
+        super::Policy::Panic => proc_macro2::TokenStream::new(),
+        super::Policy::Clamp(span) => quote::quote_spanned!(span=> #[clamp]),
+        super::Policy::Wrap(span) => quote::quote_spanned!(span=> #[wrap]),
+    };
+    quote::quote!(#policy #ident @ #lo..=#hi)
+}
+
+/// The statements of a block, so that they can follow other statements without nesting the block
+/// in another one, which `unused_braces` warns about.
+pub(crate) fn statements(expr: &syn::Expr) -> proc_macro2::TokenStream {
+    match expr {
+        syn::Expr::Block(syn::ExprBlock {
+            attrs,
+            label: None,
+            block,
+        }) if attrs.is_empty() => {
+            let stmts = &block.stmts;
+            quote::quote!(#(#stmts)*)
+        }
+        expr => quote::quote!(#expr),
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "criterion")]
+//! The functions of `lut_bench!` use the API of `criterion`, which these tests stand in for, so
+//! that the benchmarks can be run once and the values they pass to `black_box` compared.
+
+extern crate self as criterion;
+
+use std::cell::RefCell;
+
+thread_local! {
+    /// The values passed to `black_box` by the running benchmark.
+    static BLACK_BOXED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Records the value instead of hiding it from the optimizer.
+pub fn black_box<T: std::fmt::Debug>(value: T) -> T {
+    BLACK_BOXED.with(|values| values.borrow_mut().push(format!("{:?}", value)));
+    value
+}
+
+/// Runs every benchmark once, recording its name and the values it passed to `black_box`.
+#[derive(Default)]
+pub struct Criterion {
+    runs: Vec<(String, Vec<String>)>,
+}
+
+impl Criterion {
+    pub fn benchmark_group(&mut self, name: &str) -> BenchmarkGroup<'_> {
+        BenchmarkGroup {
+            name: name.to_owned(),
+            criterion: self,
+        }
+    }
+}
+
+pub struct BenchmarkGroup<'a> {
+    name: String,
+    criterion: &'a mut Criterion,
+}
+
+impl BenchmarkGroup<'_> {
+    pub fn bench_function(
+        &mut self,
+        id: BenchmarkId,
+        mut routine: impl FnMut(&mut Bencher),
+    ) -> &mut Self {
+        BLACK_BOXED.with(|values| values.borrow_mut().clear());
+        routine(&mut Bencher);
+        let values = BLACK_BOXED.with(RefCell::take);
+        let name = format!("{}/{}", self.name, id.0);
+        self.criterion.runs.push((name, values));
+        self
+    }
+
+    pub fn finish(self) {}
+}
+
+pub struct BenchmarkId(String);
+
+impl BenchmarkId {
+    pub fn new(function: &str, parameter: &str) -> Self {
+        BenchmarkId(format!("{}/{}", function, parameter))
+    }
+}
+
+pub struct Bencher;
+
+impl Bencher {
+    pub fn iter<R>(&mut self, mut routine: impl FnMut() -> R) {
+        routine();
+    }
+}
+
+numeric_lut::lut_bench!(popcount, |x @ 0..256| -> u32 { (x as u32).count_ones() });
+
+numeric_lut::lut_bench!(
+    grid,
+    #[strategy(match_up_to = 0)]
+    |x @ 2..5, y @ ..=3| -> u16 { (x * 10 + y) as u16 }
+);
+
+numeric_lut::lut_bench!(single, |x @ 7..=7| -> u8 { x as u8 * 2 });
+
+/// Runs the given benchmarks and returns the recorded runs.
+fn run(bench: fn(&mut Criterion)) -> Vec<(String, Vec<String>)> {
+    let mut c = Criterion::default();
+    bench(&mut c);
+    c.runs
+}
+
+#[test]
+fn bench_names() {
+    let names = run(popcount)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "popcount/direct/sequential",
+            "popcount/table/sequential",
+            "popcount/direct/random",
+            "popcount/table/random",
+        ],
+        names
+    );
+}
+
+#[test]
+fn bench_same_lookups() {
+    for (bench, params) in [(popcount as fn(&mut Criterion), 1), (grid, 2), (single, 1)] {
+        let runs = run(bench);
+        // Every lookup passes its parameters and its result to `black_box`.
+        assert_eq!((params + 1) * 4096, runs[0].1.len());
+        assert_eq!(runs[0].1, runs[1].1);
+        assert_eq!(runs[2].1, runs[3].1);
+    }
+}
+
+#[test]
+fn bench_sequential_order() {
+    let runs = run(grid);
+    // The parameters of every lookup are followed by its result.
+    assert_eq!(["2", "0", "20", "2", "1", "21"], runs[0].1[..6]);
+    assert_eq!(["4", "3", "43", "2", "0", "20"], runs[0].1[33..39]);
+    let runs = run(single);
+    assert_eq!(["7", "14", "7", "14"], runs[2].1[..4]);
+}
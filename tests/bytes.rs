@@ -0,0 +1,41 @@
+#![cfg(feature = "bytemuck")]
+//! The byte views of `#[bytes]` are cast with `bytemuck`, which these tests stand in for, so that
+//! the bytes can be compared with the entries.
+
+extern crate self as bytemuck;
+
+/// Reinterprets a slice of plain old data as its bytes, like `bytemuck::cast_slice` to `u8`.
+pub fn cast_slice<A: Copy>(entries: &[A]) -> &[u8] {
+    // SAFETY: The tables below only have integer entries, which have no padding.
+    unsafe { std::slice::from_raw_parts(entries.as_ptr().cast(), std::mem::size_of_val(entries)) }
+}
+
+numeric_lut::lut! {
+    #[bytes]
+    pub struct Gamma(x @ 0..256) -> u16 { (x * x) as u16 }
+}
+
+numeric_lut::lut! {
+    #[bytes]
+    pub struct Grid(x @ 1..4, y @ 0..2) -> i32 { x as i32 * 10 - y as i32 }
+}
+
+#[test]
+fn bytes_of_entries() {
+    let expected = (0..256)
+        .flat_map(|x| Gamma::get(x).to_ne_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(512, Gamma::as_bytes().len());
+    assert_eq!(expected, Gamma::as_bytes());
+}
+
+#[test]
+fn bytes_in_memory_order() {
+    // The last parameter changes fastest.
+    let expected = [10, 9, 20, 19, 30, 29]
+        .iter()
+        .flat_map(|entry: &i32| entry.to_ne_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(expected, Grid::as_bytes());
+    assert_eq!(Grid::as_bytes().as_ptr(), Grid::TABLE.as_ptr().cast());
+}
@@ -0,0 +1,102 @@
+use numeric_lut::lut;
+
+#[test]
+fn verify_one_dimensional() {
+    let lut = lut!(
+        #[verify = "tests/data/triple.bin"]
+        |x @ 0..4| -> u16 { x as u16 * 3 }
+    );
+    assert_eq!(6, lut(2));
+}
+
+lut! {
+    #[verify = "tests/data/product.bin"]
+    struct Product(x @ 0..4, y @ 0..3) -> i32 { (x as i32 - 2) * y as i32 }
+}
+
+#[test]
+fn verify_item() {
+    assert_eq!(-2, Product::get(0, 1));
+}
+
+#[test]
+fn verify_sharded() {
+    let lut = lut!(
+        #[shard(entries = 3)]
+        #[verify = "tests/data/product.bin"]
+        |x @ 0..4, y @ 0..3| -> i32 { (x as i32 - 2) * y as i32 }
+    );
+    assert_eq!(2, lut(3, 2));
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Nop,
+    Push(u8),
+    Jump { relative: bool },
+    Illegal,
+}
+
+lut! {
+    #[cover(0x00, 0x10..0x18, 0x20..=0x21, default = Op::Illegal)]
+    struct Decode(op @ 0..=255) -> Op {
+        match op {
+            0x00 => Some(Op::Nop),
+            0x10..=0x17 => Some(Op::Push(op as u8 & 7)),
+            0x20 | 0x21 => Some(Op::Jump { relative: op == 0x21 }),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn cover_decode() {
+    assert_eq!(Op::Nop, Decode::get(0x00));
+    assert_eq!(Op::Push(5), Decode::get(0x15));
+    assert_eq!(Op::Jump { relative: true }, Decode::get(0x21));
+    assert_eq!(Op::Illegal, Decode::get(0x18));
+    assert_eq!(Op::Illegal, Decode::get(0xff));
+}
+
+#[test]
+fn cover_wide_sharded() {
+    let decode = lut!(
+        #[shard(entries = 4096)]
+        #[cover(0x0000..=0x7fff, default = u16::MAX)]
+        |op @ 0..=0xffff| -> u16 {
+            if op < 0x8000 {
+                Some(op as u16 >> 12)
+            } else {
+                None
+            }
+        }
+    );
+    assert_eq!(7, decode(0x7abc));
+    assert_eq!(u16::MAX, decode(0x8000));
+}
+
+#[test]
+fn cover_without_default() {
+    let decode = lut!(
+        #[cover(0..4)]
+        |op @ 0..4| -> char { Some((b'a' + op as u8) as char) }
+    );
+    assert_eq!('c', decode(2));
+}
+
+lut! {
+    #[check(monotone_increasing(x), strictly_decreasing(y), convex(x))]
+    #[shard(entries = 8)]
+    struct Calibration(x @ 1..6, y @ 0..3) -> f32 { (x * x) as f32 / (y as f32 + 1.0) }
+}
+
+#[test]
+fn shape_checks() {
+    assert_eq!(12.5, Calibration::get(5, 1));
+    let masked = lut!(
+        #[mask]
+        #[check(strictly_increasing(y), concave(y), monotone_decreasing(x))]
+        |x @ 0..3, y @ 0..5| -> u8 { (y * (10 - y)) as u8 / (x as u8 + 1) + y as u8 }
+    );
+    assert_eq!(16, masked(1, 4));
+}
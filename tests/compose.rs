@@ -0,0 +1,44 @@
+use numeric_lut::lut_compose;
+
+#[test]
+fn chain() {
+    let lut = lut_compose!(
+        |x @ 0..16, y @ 0..4| -> u8 { (x * 4 + y) as u8 },
+        |index @ 0..64| -> u32 { index as u32 * index as u32 },
+    );
+    for x in 0..16 {
+        for y in 0..4 {
+            assert_eq!(((x * 4 + y) * (x * 4 + y)) as u32, lut(x, y));
+        }
+    }
+}
+
+#[test]
+fn wide_and_signed_entries() {
+    let signed = lut_compose!(
+        |x @ 0..5| -> i8 { x as i8 * 2 - 4 },
+        |#[wrap] v @ 0..3| -> usize { v },
+    );
+    assert_eq!([2, 1, 0, 2, 1], [0, 1, 2, 3, 4].map(signed));
+    let wide = lut_compose!(
+        |x @ 0..4| -> u128 { x as u128 * u64::MAX as u128 },
+        |#[clamp] v @ 1..=8| -> usize { v },
+    );
+    assert_eq!([1, 8, 8, 8], [0, 1, 2, 3].map(wide));
+    let wrapped = lut_compose!(
+        |x @ 0..6| -> u128 { x as u128 },
+        |#[wrap] v @ 2..5| -> usize { v },
+    );
+    assert_eq!([3, 4, 2, 3, 4, 2], [0, 1, 2, 3, 4, 5].map(wrapped));
+}
+
+#[test]
+fn options_of_the_first_table() {
+    let lut = lut_compose!(
+        #[mask]
+        |x @ 0..8| -> usize { 9 - x },
+        |v @ 2..10| -> u8 { v as u8 * 10 },
+    );
+    assert_eq!(90, lut(0));
+    assert_eq!(lut(3), lut(11));
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "half")]
+//! Tables with `#[f16]` store their entries as `half::f16`, which these tests stand in for with a
+//! type that rounds `f32` values to the precision of half floats.
+
+extern crate self as half;
+
+/// An `f32` rounded to the 11 significant bits of a half float, ignoring its smaller range.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct f16(f32);
+
+impl f16 {
+    pub const fn from_f32_const(value: f32) -> Self {
+        // Rounds the 13 bits that half floats don't have to the nearest, with ties away from zero.
+        f16(f32::from_bits((value.to_bits() + 0x1000) & !0x1fff))
+    }
+
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_f32_const(value)
+    }
+
+    pub fn to_f32(self) -> f32 {
+        self.0
+    }
+}
+
+numeric_lut::lut! {
+    #[f16]
+    struct Stored(x @ 0..64) -> f32 { 1.0 + x as f32 / 1000.0 }
+}
+
+numeric_lut::lut! {
+    #[f16]
+    #[strategy(compute_above = 0)]
+    struct Computed(x @ 0..64) -> f32 { 1.0 + x as f32 / 1000.0 }
+}
+
+#[test]
+fn stored_and_computed_entries() {
+    for x in 0..64 {
+        let exact = 1.0 + x as f32 / 1000.0;
+        let rounded = f16::from_f32(exact).to_f32();
+        assert_eq!(rounded, Stored::get(x), "x = {}", x);
+        assert_eq!(rounded, Computed::get(x), "x = {}", x);
+    }
+    // Half floats have a precision of about 0.001 around 1.
+    assert_eq!(1.0, Stored::get(0));
+    assert_ne!(1.003, Stored::get(3));
+}
+
+#[test]
+fn closure_form() {
+    let lut = numeric_lut::lut!(
+        #[f16]
+        |x @ 0..4| -> f32 { x as f32 / 3.0 }
+    );
+    assert_eq!(f16::from_f32(1.0 / 3.0).to_f32(), lut(1));
+}
@@ -0,0 +1,74 @@
+numeric_lut::lut_from_file! {
+    "tests/data/calibration.json",
+    /// The readings of the calibration bench.
+    pub struct Calibration(sensor @ 1..=3, step @ 0..4) -> f32
+}
+
+numeric_lut::lut_from_file! {
+    "tests/data/offsets.toml",
+    struct Offsets(#[clamp] sensor @ 0..2, #[clamp] step @ 0..3) -> i16
+}
+
+#[test]
+fn json_entries() {
+    assert_eq!(0.5, Calibration::get(1, 1));
+    assert_eq!(-0.25, Calibration::get(1, 3));
+    assert_eq!(3.0, Calibration::get(2, 2));
+    assert_eq!(13.5, Calibration::get(3, 3));
+    assert_eq!([10.5, 11.5, 12.5, 13.5], Calibration::TABLE[2]);
+}
+
+#[test]
+fn toml_entries() {
+    assert_eq!(-3, Offsets::get(0, 0));
+    assert_eq!(1000, Offsets::get(1, 2));
+    assert_eq!(1000, Offsets::get(5, 9));
+}
+
+#[test]
+fn closure_form() {
+    let flags = numeric_lut::lut_from_file!("tests/data/flags.json", |x @ 0..3| -> bool);
+    assert!(flags(0));
+    assert!(!flags(1));
+    assert!(flags(2));
+}
+
+numeric_lut::lut_from_file! {
+    "tests/data/gains.csv",
+    struct Gains(channel @ 0..2, band @ 1..3) -> f64
+}
+
+#[test]
+fn csv_rows() {
+    assert_eq!(0.5, Gains::get(0, 1));
+    assert_eq!(0.75, Gains::get(0, 2));
+    assert_eq!(1.25, Gains::get(1, 1));
+    assert_eq!(1.5, Gains::get(1, 2));
+}
+
+numeric_lut::lut_from_file! {
+    "tests/data/response.npy",
+    struct Response(x @ 0..2, y @ 0..3) -> f64
+}
+
+numeric_lut::lut_from_file! {
+    "tests/data/levels.npy",
+    struct Levels(x @ 0..3, y @ 0..2) -> i16
+}
+
+#[test]
+fn npy_entries() {
+    assert_eq!(0.1, Response::get(0, 0));
+    assert_eq!(0.1 + 0.2, Response::get(0, 2));
+    assert_eq!(1e-300, Response::get(1, 0));
+    assert_eq!(f64::INFINITY, Response::get(1, 2));
+}
+
+#[test]
+fn npy_fortran_order() {
+    for x in 0..3 {
+        for y in 0..2 {
+            assert_eq!(x as i16 * 10 - y as i16 * 100, Levels::get(x, y));
+        }
+    }
+}
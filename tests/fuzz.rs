@@ -0,0 +1,92 @@
+#![cfg(feature = "libfuzzer-sys")]
+//! The targets of `lut_fuzz!` expand to a `fuzz_target!` of `libfuzzer-sys`, which these tests
+//! stand in for, so that the body of every target can be run over sample inputs.
+
+extern crate self as libfuzzer_sys;
+
+/// Declares the body of a fuzz target as the function `fuzz_target` of the invoking module.
+#[macro_export]
+macro_rules! fuzz_target {
+    (|$data:ident: &[u8]| $body:block) => {
+        pub fn fuzz_target($data: &[u8]) $body
+    };
+}
+
+mod clamped {
+    numeric_lut::lut_fuzz!(|#[clamp] x @ 0..256| -> u8 { ((x * x) >> 8) as u8 });
+}
+
+mod wrapped {
+    numeric_lut::lut_fuzz!(|#[wrap] x @ 3..10, y @ 1..=4| -> u32 { (x * 10 + y) as u32 });
+}
+
+mod masked {
+    numeric_lut::lut_fuzz!(
+        #[mask]
+        |x @ 2..12| -> u16 { (x * x) as u16 }
+    );
+}
+
+mod fallback {
+    numeric_lut::lut_fuzz!(
+        #[fallback(|x, y| (x + y) as i64)]
+        |x @ 0..4, y @ 0..8| -> i64 { (x * y) as i64 }
+    );
+}
+
+mod banded {
+    numeric_lut::lut_fuzz!(
+        #[banded(1, default = 0)]
+        |x @ 0..8, y @ 0..8| -> u16 { (x * 8 + y) as u16 + 1 }
+    );
+}
+
+/// Pseudo-random inputs of the fuzzer for tables with the given number of parameters, in both
+/// modes and with values of all magnitudes.
+fn inputs(params: usize) -> impl Iterator<Item = Vec<u8>> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..1024).map(move |i| {
+        let mut data = vec![i as u8];
+        for _ in 0..params {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            // Small values are inside of or close to the ranges.
+            let value = if i % 4 == 0 { state } else { state % 32 };
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    })
+}
+
+#[test]
+fn fuzz_clamped() {
+    inputs(1).for_each(|data| clamped::fuzz_target(&data));
+}
+
+#[test]
+fn fuzz_wrapped() {
+    inputs(2).for_each(|data| wrapped::fuzz_target(&data));
+}
+
+#[test]
+fn fuzz_masked() {
+    inputs(1).for_each(|data| masked::fuzz_target(&data));
+}
+
+#[test]
+fn fuzz_fallback() {
+    inputs(2).for_each(|data| fallback::fuzz_target(&data));
+}
+
+#[test]
+fn fuzz_banded() {
+    inputs(2).for_each(|data| banded::fuzz_target(&data));
+}
+
+#[test]
+fn fuzz_short_input() {
+    // Missing bytes of the values are zero.
+    wrapped::fuzz_target(&[]);
+    wrapped::fuzz_target(&[1, 5]);
+}
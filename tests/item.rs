@@ -0,0 +1,1258 @@
+use numeric_lut::lut;
+
+lut! {
+    /// The sum of two numbers.
+    struct Sum(x @ 0..8, y @ 0..16) -> u32 { x as u32 + y as u32 }
+}
+
+lut! {
+    #[symmetric]
+    struct Distance(x @ 0..8, y @ 0..8) -> u32 { (x as u32).abs_diff(y as u32) }
+}
+
+lut! {
+    struct Linear(x @ 2..=6) -> u32 { x as u32 * 3 }
+}
+
+#[test]
+fn get_all() {
+    for x in 0..8 {
+        for y in 0..16 {
+            assert_eq!((x + y) as u32, Sum::get(x, y));
+        }
+    }
+}
+
+#[test]
+fn get_one_dimensional() {
+    for x in 2..=6 {
+        assert_eq!(x as u32 * 3, Linear::get(x));
+    }
+}
+
+#[test]
+#[should_panic]
+fn get_out_of_bounds() {
+    Sum::get(10, 3);
+}
+
+#[test]
+fn table_is_static() {
+    // A reference to a static has the same address wherever it is taken.
+    let tables = [Sum::TABLE, Sum::TABLE];
+    assert!(std::ptr::eq(tables[0], tables[1]));
+}
+
+#[test]
+fn fix_all() {
+    for x in 0..8 {
+        let row = Sum::fix(x);
+        for y in 0..16 {
+            assert_eq!((x + y) as u32, row(y));
+        }
+    }
+}
+
+#[test]
+fn fix_symmetric() {
+    let row = Distance::fix(5);
+    assert_eq!(3, row(2));
+    assert_eq!(2, row(7));
+}
+
+lut! {
+    #[transpose]
+    struct Weighted(x @ 0..4, y @ 0..8, z @ 0..2) -> u32 { (x * 100 + y * 10 + z) as u32 }
+}
+
+#[test]
+fn transposed_all() {
+    for x in 0..4 {
+        for y in 0..8 {
+            for z in 0..2 {
+                assert_eq!(Weighted::get(x, y, z), Weighted::get_t(z, y, x));
+            }
+        }
+    }
+}
+
+lut! {
+    #[index]
+    struct Strided(#[contiguous] x @ 1..5, y @ 0..4, z @ 0..2) -> u32 {
+        (x * 100 + y * 10 + z) as u32
+    }
+}
+
+#[test]
+fn contiguous_all() {
+    assert_eq!([121, 221, 321, 421], Strided::TABLE[2][1]);
+    for x in 1..5 {
+        for y in 0..4 {
+            for z in 0..2 {
+                assert_eq!((x * 100 + y * 10 + z) as u32, Strided::get(x, y, z));
+                assert_eq!(Strided::get(x, y, z), Strided[(x, y, z)]);
+                assert_eq!(Strided::get(x, y, z), Strided::fix(x)(y, z));
+            }
+        }
+    }
+}
+
+lut! {
+    #[index]
+    struct Configured(
+        #[cfg(test)] x @ 0..16,
+        #[cfg(not(test))] x @ 0..4,
+        #[cfg(target_os = "none")] #[clamp] y @ 0..2,
+    ) -> u32 {
+        x as u32 * 3
+    }
+}
+
+#[test]
+fn cfg_params() {
+    assert_eq!(16, Configured::TABLE.len());
+    assert_eq!(45, Configured::get(15));
+    assert_eq!(45, Configured[15]);
+}
+
+lut! {
+    #[export(table = "NUMERIC_LUT_TEST_CUBES")]
+    #[shard(entries = 4)]
+    pub struct Cubes(x @ 0..8, y @ 0..2) -> u64 { (x * x * x + y) as u64 }
+}
+
+#[test]
+fn export_table() {
+    assert_eq!(28, Cubes::get(3, 1));
+    assert_eq!([8, 9], NUMERIC_LUT_TEST_CUBES[2]);
+    assert!(std::ptr::eq(Cubes::TABLE, &NUMERIC_LUT_TEST_CUBES));
+}
+
+numeric_lut::lut_extern! {
+    /// The cubes of `Cubes`, declared through their symbol.
+    #[link_name = "NUMERIC_LUT_TEST_CUBES"]
+    unsafe struct ExternCubes(#[wrap] x @ 0..8, y @ 0..2) -> u64;
+}
+
+#[test]
+fn extern_table() {
+    assert!(std::ptr::eq(ExternCubes::table(), &NUMERIC_LUT_TEST_CUBES));
+    for x in 0..8 {
+        for y in 0..2 {
+            assert_eq!(Cubes::get(x, y), ExternCubes::get(x, y));
+            assert_eq!(Cubes::get(x, y), ExternCubes::fix(x)(y));
+        }
+    }
+    assert_eq!(Cubes::get(1, 0), ExternCubes::get(9, 0));
+}
+
+lut! {
+    #[heap]
+    struct Heaped(#[clamp] x @ 1..101, y @ 0..3) -> f32 { (x * 3 + y) as f32 * 0.5 }
+}
+
+#[test]
+fn heap() {
+    assert_eq!(3.5, Heaped::get(2, 1));
+    Heaped::init();
+    assert_eq!(151.0, Heaped::get(1000, 2));
+    assert_eq!(2.0, Heaped::fix(0)(1));
+}
+
+lut! {
+    #[compressed]
+    #[codec(i32, encode = widen, decode = narrow)]
+    struct Compressed(x @ 0..300, #[wrap] y @ 0..3) -> i16 { ((x / 7) as i16 - 20) * (y as i16 + 1) }
+}
+
+const fn widen(value: i16) -> i32 {
+    value as i32
+}
+
+const fn narrow(value: i32) -> i16 {
+    value as i16
+}
+
+#[test]
+fn compressed() {
+    Compressed::init();
+    Compressed::init();
+    for x in 0..300 {
+        for y in 0..3 {
+            assert_eq!(
+                ((x / 7) as i16 - 20) * (y as i16 + 1),
+                Compressed::get(x, y)
+            );
+        }
+    }
+    assert_eq!(Compressed::get(299, 1), Compressed::get(299, 4));
+    const { assert!(Compressed::COMPRESSED_SIZE < 300 * 3 * 4 / 4) };
+}
+
+lut! {
+    #[compressed]
+    struct Terrain(x @ 0..64, y @ 0..256) -> u32 { (x / 4 * 1000 + y / 16) as u32 }
+}
+
+#[test]
+fn compressed_init_from_threads() {
+    let barrier = std::sync::Barrier::new(8);
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                barrier.wait();
+                Terrain::init();
+                // Calls that lose the race only return once every entry is decompressed.
+                for x in 0..64 {
+                    for y in 0..256 {
+                        assert_eq!((x / 4 * 1000 + y / 16) as u32, Terrain::get(x, y));
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(3002, Terrain::get(12, 40));
+    const { assert!(Terrain::COMPRESSED_SIZE < 64 * 256 * 4 / 8) };
+}
+
+lut! {
+    #[transform]
+    struct Halve(x @ 0..100) -> usize { x / 2 }
+}
+
+lut! {
+    #[transform]
+    struct Saturate(#[clamp] x @ 0..200) -> i16 { x as i16 - 100 }
+}
+
+#[test]
+fn transform() {
+    let mut buffer = (0..100).collect::<Vec<_>>();
+    Halve::transform(&mut buffer);
+    assert_eq!((0..100).map(|x| x / 2).collect::<Vec<_>>(), buffer);
+
+    let mut samples = [-150, -3, 0, 42, 99, 150];
+    Saturate::transform_by(&mut samples, |&sample| (sample + 100).max(0) as usize);
+    assert_eq!([-100, -3, 0, 42, 99, 99], samples);
+}
+
+lut! {
+    #[hash]
+    struct Hashed(x @ 0..4) -> u16 { x as u16 * 3 }
+}
+
+lut! {
+    #[hash]
+    struct HashedOther(x @ 0..4) -> u16 { x as u16 * 4 }
+}
+
+#[test]
+fn hash_depends_on_contents() {
+    assert_ne!(Hashed::TABLE_HASH, HashedOther::TABLE_HASH);
+}
+
+lut! {
+    #[hash]
+    #[shard(entries = 4)]
+    struct ShardedHashed(x @ 0..4) -> u16 { x as u16 * 3 }
+}
+
+lut! {
+    #[shard(entries = 32)]
+    struct ShardedSum(x @ 0..8, y @ 0..16) -> u32 { x as u32 + y as u32 }
+}
+
+#[test]
+fn hash_independent_of_sharding() {
+    assert_eq!(Hashed::TABLE_HASH, ShardedHashed::TABLE_HASH);
+}
+
+#[test]
+fn sharded_fix_all() {
+    for x in 0..8 {
+        let row = ShardedSum::fix(x);
+        for y in 0..16 {
+            assert_eq!((x + y) as u32, row(y));
+            assert_eq!((x + y) as u32, ShardedSum::get(x, y));
+        }
+    }
+}
+
+lut! {
+    #[export_c]
+    struct ExportedSquare(x @ 0..16) -> u32 { (x * x) as u32 }
+}
+
+lut! {
+    #[export_c(name = "exported_cube", table = "EXPORTED_CUBE")]
+    struct ExportedCube(x @ 0..16) -> u32 { (x * x * x) as u32 }
+}
+
+#[test]
+fn exported_all() {
+    for x in 0..16 {
+        assert_eq!((x * x) as u32, exported_square(x));
+        assert_eq!((x * x * x) as u32, exported_cube(x));
+    }
+    for (x, &r) in EXPORTED_CUBE.iter().enumerate() {
+        assert_eq!((x * x * x) as u32, r);
+    }
+}
+
+lut! {
+    #[shader(wgsl, glsl)]
+    struct HalfSteps(x @ 0..2, y @ 0..2) -> f32 { x as f32 - 0.5 * y as f32 }
+}
+
+lut! {
+    #[shader(wgsl)]
+    #[shard(entries = 2)]
+    struct ShaderExtremes(x @ 0..4) -> i32 {
+        [i32::MIN, -7, 0, i32::MAX][x]
+    }
+}
+
+#[test]
+fn shader_floats() {
+    assert_eq!(
+        "const HALF_STEPS: array<f32, 4> = array<f32, 4>(bitcast<f32>(0x0u), \
+         bitcast<f32>(0xbf000000u), bitcast<f32>(0x3f800000u), bitcast<f32>(0x3f000000u));\n",
+        HalfSteps::WGSL
+    );
+    assert_eq!(
+        "const float HALF_STEPS[4] = float[4](uintBitsToFloat(0x0u), \
+         uintBitsToFloat(0xbf000000u), uintBitsToFloat(0x3f800000u), \
+         uintBitsToFloat(0x3f000000u));\n",
+        HalfSteps::GLSL
+    );
+}
+
+#[test]
+fn shader_sharded_extremes() {
+    assert_eq!(
+        "const SHADER_EXTREMES: array<i32, 4> = array<i32, 4>((-2147483647i - 1i), -7i, 0i, \
+         2147483647i);\n",
+        ShaderExtremes::WGSL
+    );
+}
+
+lut! {
+    #[hash]
+    #[strategy(match_up_to = 8)]
+    struct Matched(x @ 0..2, y @ 0..4) -> u8 { (x * 4 + y) as u8 }
+}
+
+lut! {
+    #[hash]
+    struct Unmatched(x @ 0..2, y @ 0..4) -> u8 { (x * 4 + y) as u8 }
+}
+
+#[test]
+fn matched_item() {
+    assert_eq!(6, Matched::get(1, 2));
+    assert_eq!(7, Matched::fix(1)(3));
+    assert_eq!(Unmatched::TABLE_HASH, Matched::TABLE_HASH);
+}
+
+lut! {
+    #[export_c(name = "computed_lookup")]
+    #[strategy(compute_above = 4)]
+    struct Computed(x @ 0..2, y @ 0..4) -> u8 { (x * 4 + y) as u8 }
+}
+
+#[test]
+fn computed_item() {
+    assert_eq!(6, Computed::get(1, 2));
+    assert_eq!(7, Computed::fix(1)(3));
+    assert_eq!(5, computed_lookup(1, 1));
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rgb {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+lut! {
+    #[soa(r: u8, g: u8, b: u8)]
+    struct Palette(i @ 1..=4) -> Rgb {
+        Rgb { r: i as u8, g: i as u8 * 2, b: 255 - i as u8 }
+    }
+}
+
+lut! {
+    #[mask]
+    #[soa(r: u8, g: u8, b: u8)]
+    #[shard(entries = 4)]
+    struct Gradient(x @ 0..3, y @ 0..4) -> Rgb {
+        Rgb { r: x as u8, g: y as u8, b: (x * y) as u8 }
+    }
+}
+
+#[test]
+fn soa_fields() {
+    assert_eq!(3, Palette::get_r(3));
+    assert_eq!(6, Palette::get_g(3));
+    assert_eq!(252, Palette::get_b(3));
+    assert_eq!(Rgb { r: 4, g: 8, b: 251 }, Palette::get(4));
+}
+
+#[test]
+fn soa_sharded_mask() {
+    for x in 0..3 {
+        for y in 0..4 {
+            let expected = Rgb {
+                r: x as u8,
+                g: y as u8,
+                b: (x * y) as u8,
+            };
+            assert_eq!(expected, Gradient::get(x, y));
+            assert_eq!(expected, Gradient::fix(x)(y));
+            assert_eq!(expected.b, Gradient::get_b(x, y));
+        }
+    }
+    assert_eq!(Gradient::get(0, 1), Gradient::get(3, 5));
+}
+
+#[test]
+#[should_panic]
+fn soa_out_of_bounds() {
+    Palette::get_g(5);
+}
+
+lut! {
+    #[soa(r: u8, g: u8, b: u8)]
+    #[strategy(compute_above = 2)]
+    struct ComputedPalette(i @ 0..4) -> Rgb {
+        Rgb { r: i as u8, g: 0, b: 0 }
+    }
+}
+
+#[test]
+fn soa_computed() {
+    assert_eq!(3, ComputedPalette::get_r(3));
+    assert_eq!(Rgb { r: 2, g: 0, b: 0 }, ComputedPalette::get(2));
+}
+
+lut! {
+    #[split]
+    struct Powers(x @ 0..128) -> u128 { 1 << x }
+}
+
+lut! {
+    #[split]
+    #[shard(entries = 8)]
+    struct SignedProducts(x @ 0..4, y @ 0..8) -> i128 {
+        (x as i128 - 2) * (i128::MAX / 2) / (y as i128 + 1)
+    }
+}
+
+#[test]
+fn split_unsigned() {
+    for x in 0..128 {
+        assert_eq!(1u128 << x, Powers::get(x));
+    }
+    assert_eq!(1 << 6, Powers::get_hi(70));
+    assert_eq!(0, Powers::get_lo(70));
+    assert_eq!(1 << 3, Powers::get_lo(3));
+}
+
+#[test]
+fn split_signed_sharded() {
+    for x in 0..4 {
+        for y in 0..8 {
+            let expected = (x as i128 - 2) * (i128::MAX / 2) / (y as i128 + 1);
+            assert_eq!(expected, SignedProducts::get(x, y));
+            assert_eq!(expected, SignedProducts::fix(x)(y));
+            assert_eq!((expected >> 64) as u64, SignedProducts::get_hi(x, y));
+        }
+    }
+}
+
+#[test]
+fn unsplit_wide() {
+    let lut = lut!(|x @ 0..4| -> i128 { i128::MIN + x as i128 });
+    assert_eq!(i128::MIN + 3, lut(3));
+}
+
+lut! {
+    #[try_get]
+    #[mask]
+    pub struct Checked(x @ 2..5, y @ 0..2) -> u8 { (x * 2 + y) as u8 }
+}
+
+#[test]
+fn try_get_in_range() {
+    assert_eq!(Ok(9), Checked::try_get(4, 1));
+}
+
+#[test]
+fn try_get_out_of_range() {
+    let err = Checked::try_get(1, 0).unwrap_err();
+    assert_eq!(
+        CheckedError {
+            parameter: "x",
+            value: 1,
+            range: 2..=4,
+        },
+        err
+    );
+    assert_eq!(
+        "`y` must be in 0..=1 but is 2",
+        Checked::try_get(3, 2).unwrap_err().to_string()
+    );
+    let _: &dyn std::error::Error = &err;
+}
+
+lut! {
+    #[get_or]
+    #[shard(entries = 4)]
+    struct Fallback(x @ 1..4, y @ 0..3) -> i16 { (x * 10 + y) as i16 }
+}
+
+#[test]
+fn get_or_fallback() {
+    assert_eq!(32, Fallback::get_or(3, 2, -1));
+    assert_eq!(-1, Fallback::get_or(0, 2, -1));
+    assert_eq!(-1, Fallback::get_or(2, 3, -1));
+}
+
+lut! {
+    #[check(monotone_increasing(x))]
+    #[invert]
+    #[shard(entries = 16)]
+    struct Staircase(x @ 5..105) -> u32 { (x as u32 / 3) * 3 }
+}
+
+#[test]
+fn invert_matches_linear_search() {
+    for value in 0..110 {
+        let expected = (5..105)
+            .find(|&x| Staircase::get(x) >= value)
+            .unwrap_or(104);
+        assert_eq!(expected, Staircase::invert(value), "value = {}", value);
+    }
+}
+
+lut! {
+    #[check(strictly_decreasing(t))]
+    #[invert(interpolate)]
+    struct Resistance(t @ 0..5) -> f32 { [12.0, 8.0, 5.0, 3.0, 2.0][t] }
+}
+
+#[test]
+fn invert_interpolate() {
+    assert_eq!(0.0, Resistance::invert(20.0));
+    assert_eq!(0.0, Resistance::invert(12.0));
+    assert_eq!(0.5, Resistance::invert(10.0));
+    assert_eq!(2.0, Resistance::invert(5.0));
+    assert_eq!(3.5, Resistance::invert(2.5));
+    assert_eq!(4.0, Resistance::invert(1.0));
+}
+
+lut! {
+    #[find_nearest]
+    struct Mixer(a @ 1..4, b @ 0..3) -> i32 { a as i32 * 10 - b as i32 * 4 }
+}
+
+#[test]
+fn find_nearest_scan() {
+    assert_eq!(([3, 0], 30), Mixer::find_nearest(i32::MAX));
+    assert_eq!(([1, 2], 2), Mixer::find_nearest(i32::MIN));
+    assert_eq!(([2, 1], 16), Mixer::find_nearest(17));
+    // 20 and 22 are equally near, and the first one in the order of the parameters wins.
+    assert_eq!(([2, 0], 20), Mixer::find_nearest(21));
+}
+
+lut! {
+    #[check(monotone_increasing(x))]
+    #[find_nearest]
+    #[shard(entries = 8)]
+    struct Steps(x @ 2..40) -> f64 { (x / 4) as f64 * 0.5 }
+}
+
+#[test]
+fn find_nearest_sorted() {
+    for value in -10..100 {
+        let value = value as f64 * 0.1;
+        let mut nearest = (2, Steps::get(2));
+        for x in 3..40 {
+            if (Steps::get(x) - value).abs() < (nearest.1 - value).abs() {
+                nearest = (x, Steps::get(x));
+            }
+        }
+        assert_eq!(
+            ([nearest.0], nearest.1),
+            Steps::find_nearest(value),
+            "value = {}",
+            value
+        );
+    }
+}
+
+lut! {
+    #[index]
+    #[mask]
+    struct Indexed(x @ 0..3) -> char { (b'a' + x as u8) as char }
+}
+
+lut! {
+    #[index]
+    #[shard(entries = 4)]
+    #[strategy(match_up_to = 64)]
+    struct IndexedSharded(x @ 1..5, y @ 0..4, z @ 0..2) -> u32 { (x * 100 + y * 10 + z) as u32 }
+}
+
+struct Holder {
+    lut: IndexedSharded,
+}
+
+#[test]
+fn index_one_dimensional() {
+    assert_eq!('c', Indexed[2]);
+    assert_eq!('a', Indexed[4]);
+}
+
+#[test]
+fn index_tuple() {
+    let holder = Holder {
+        lut: IndexedSharded,
+    };
+    for x in 1..5 {
+        for y in 0..4 {
+            for z in 0..2 {
+                assert_eq!(IndexedSharded::get(x, y, z), holder.lut[(x, y, z)]);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_bounds() {
+    let _ = IndexedSharded[(5, 0, 0)];
+}
+
+lut! {
+    #[dump]
+    struct Dumped(x @ 1..=3, y @ 0..3) -> i32 { (x as i32 - 2) * 5 * y as i32 }
+}
+
+lut! {
+    #[dump]
+    struct DumpedWide(i @ 0..20) -> u8 { i as u8 }
+}
+
+lut! {
+    #[dump]
+    struct DumpedCube(z @ 0..9, x @ 0..2, y @ 0..10) -> u16 { (z * 100 + x * 10 + y) as u16 }
+}
+
+#[test]
+fn dump_debug() {
+    assert_eq!(
+        "Dumped { x: 1..4, y: 0..3, entries: [0, -5, -10, 0, 0, 0, 0, 5, 10] }",
+        format!("{:?}", Dumped)
+    );
+    assert_eq!(
+        "DumpedWide { i: 0..20, entries: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, ..] }",
+        format!("{:?}", DumpedWide)
+    );
+}
+
+#[test]
+fn dump_display() {
+    assert_eq!(
+        "Dumped(x @ 1..=3, y @ 0..3)\n\
+         x\\y | 0  1   2\n  \
+           1 | 0 -5 -10\n  \
+           2 | 0  0   0\n  \
+           3 | 0  5  10",
+        Dumped.to_string()
+    );
+    let wide = DumpedWide.to_string();
+    assert!(wide.starts_with("DumpedWide(i @ 0..20)\n0 | 0\n1 | 1\n"));
+    assert!(wide.ends_with("\n7 | 7\n..."));
+}
+
+#[test]
+fn dump_display_leading() {
+    let cube = DumpedCube.to_string();
+    assert!(cube.starts_with(
+        "DumpedCube(z @ 0..9, x @ 0..2, y @ 0..10)\n\nz = 0:\nx\\y |  0  1  2  3  4  5  6  7 ...\n  0 |  0  1  2"
+    ));
+    assert!(cube.contains("\n\nz = 7:\nx\\y |   0   1"));
+    assert!(cube.ends_with("717 ...\n\n..."));
+}
+
+lut! {
+    #[csv]
+    #[strategy(compute_above = 2)]
+    struct Labels(i @ 0..3) -> &'static str { ["plain", "with,comma", "\"quoted\""][i] }
+}
+
+#[test]
+fn csv_quoted() {
+    let mut csv = Vec::new();
+    Labels::write_csv(&mut csv).unwrap();
+    assert_eq!(
+        "i,value\n0,plain\n1,\"with,comma\"\n2,\"\"\"quoted\"\"\"\n",
+        String::from_utf8(csv).unwrap()
+    );
+}
+
+lut! {
+    #[heatmap]
+    #[mask]
+    struct Surface(x @ 1..3, y @ 0..3) -> f32 { x as f32 * 0.5 - y as f32 }
+}
+
+lut! {
+    #[heatmap]
+    #[shard(entries = 2)]
+    struct Flat(x @ 0..3, y @ 0..2) -> u8 { 7 }
+}
+
+#[test]
+fn heatmap_scaled() {
+    // The entries are 0.5, -0.5, -1.5 and 1, 0, -1.
+    let (header, pixels) = Surface::HEATMAP_PGM.split_at(11);
+    assert_eq!(b"P5\n3 2\n255\n", header);
+    assert_eq!([204, 102, 0, 255, 153, 51], pixels);
+}
+
+#[test]
+fn heatmap_flat_sharded() {
+    assert_eq!(b"P5\n2 3\n255\n\0\0\0\0\0\0", &Flat::HEATMAP_PGM);
+}
+
+lut! {
+    #[dump]
+    #[hash]
+    struct Constant() -> u8 { 7 }
+}
+
+#[test]
+fn zero_parameters() {
+    assert_eq!(7, Constant::get());
+    assert_eq!("Constant() = 7", Constant.to_string());
+    assert_eq!("Constant { entries: [7] }", format!("{:?}", Constant));
+}
+
+lut! {
+    #[fallback(|_, _| u16::MAX)]
+    #[shard(entries = 4)]
+    struct WithFallback(x @ 0..4, y @ 0..4) -> u16 { (x * 4 + y) as u16 }
+}
+
+#[test]
+fn fallback_item() {
+    assert_eq!(9, WithFallback::get(2, 1));
+    assert_eq!(u16::MAX, WithFallback::get(2, 4));
+    assert_eq!(u16::MAX, WithFallback::fix(5)(0));
+    assert_eq!(13, WithFallback::fix(3)(1));
+}
+
+thread_local! {
+    static TRACED: std::cell::Cell<Option<(&'static str, usize)>> =
+        const { std::cell::Cell::new(None) };
+}
+
+fn record(name: &'static str, params: &[usize], entry: &bool) {
+    assert_eq!(params[0] % 2 == 1, *entry);
+    TRACED.with(|traced| traced.set(Some((name, params[0]))));
+}
+
+lut! {
+    #[trace(record)]
+    struct Traced(x @ 0..4) -> bool { x % 2 == 1 }
+}
+
+#[test]
+fn trace_item() {
+    assert!(Traced::get(3));
+    assert_eq!(Some(("Traced", 3)), TRACED.with(std::cell::Cell::get));
+}
+
+fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn sub(a: u32, b: u32) -> u32 {
+    a - b
+}
+
+fn first<'a>(words: &'a [&'a str]) -> &'a str {
+    words[0]
+}
+
+lut! {
+    struct Arithmetic(op @ 0..2, swap @ 0..2) -> fn(u32, u32) -> u32 {
+        match (op, swap) {
+            (0, _) => add,
+            _ => sub,
+        }
+    }
+}
+
+lut! {
+    #[strategy(match_up_to = 4)]
+    struct Words(x @ 0..2) -> for<'a> fn(&'a [&'a str]) -> &'a str { first }
+}
+
+lut! {
+    #[call(direct)]
+    #[fallback(|_, _| sub)]
+    struct DirectArithmetic(op @ 0..2, swap @ 0..2) -> fn(u32, u32) -> u32 {
+        match (op, swap) {
+            (0, _) => add,
+            _ => sub,
+        }
+    }
+}
+
+lut! {
+    #[call(direct)]
+    #[mask]
+    struct DirectWords(x @ 0..2) -> for<'a> fn(&'a [&'a str]) -> &'a str { first }
+}
+
+#[test]
+fn call_direct() {
+    for op in 0..2 {
+        for swap in 0..2 {
+            assert_eq!(
+                Arithmetic::call(op, swap, 9, 4),
+                DirectArithmetic::call(op, swap, 9, 4)
+            );
+        }
+    }
+    assert_eq!(5, DirectArithmetic::call(7, 0, 9, 4));
+    assert_eq!("b", DirectWords::call(3, &["b"]));
+}
+
+#[test]
+fn call_function_pointers() {
+    assert_eq!(7, Arithmetic::call(0, 1, 3, 4));
+    assert_eq!(1, Arithmetic::call(1, 0, 4, 3));
+    assert_eq!("a", Words::call(1, &["a", "b"]));
+}
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+struct Rectangle(u32, u32);
+
+impl Shape for Rectangle {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+}
+
+static UNIT: Square = Square(1);
+static WIDE: Rectangle = Rectangle(4, 2);
+
+lut! {
+    #[shard(entries = 2)]
+    struct Shapes(x @ 0..4, y @ 0..2) -> &'static dyn Shape {
+        if x == y { &UNIT } else { &WIDE }
+    }
+}
+
+#[test]
+fn trait_objects() {
+    assert_eq!(1, Shapes::get(1, 1).area());
+    assert_eq!(8, Shapes::get(3, 1).area());
+    let shapes = lut!(|x @ 0..2| -> &'static dyn Shape { [&UNIT as &dyn Shape, &WIDE][x] });
+    assert_eq!(8, shapes(1).area());
+}
+
+lut! {
+    #[crc]
+    #[shard(entries = 8)]
+    struct Checksummed(x @ 0..4, y @ 0..5) -> i16 { x as i16 * 100 - y as i16 }
+}
+
+#[test]
+fn crc_sharded() {
+    let mut crc = !0u32;
+    for x in 0..4 {
+        for y in 0..5 {
+            for byte in Checksummed::get(x, y).to_le_bytes() {
+                crc ^= u32::from(byte);
+                for _ in 0..8 {
+                    crc = if crc & 1 == 1 {
+                        (crc >> 1) ^ 0xedb8_8320
+                    } else {
+                        crc >> 1
+                    };
+                }
+            }
+        }
+    }
+    assert_eq!(!crc, Checksummed::TABLE_CRC);
+    assert!(Checksummed::verify_integrity());
+}
+
+lut! {
+    #[inverse]
+    #[shard(entries = 8)]
+    struct Scrambled(x @ 3..30) -> i16 { (x as i16 * 7 % 27) - 13 }
+}
+
+#[test]
+fn inverse_sharded() {
+    assert_eq!(-13, Scrambled::INVERSE_MIN);
+    assert_eq!(27, Scrambled::INVERSE.len());
+    for x in 3..30 {
+        assert_eq!(Some(x), Scrambled::inverse(Scrambled::get(x)));
+    }
+    assert_eq!(None, Scrambled::inverse(-14));
+    assert_eq!(None, Scrambled::inverse(14));
+    assert_eq!(None, Scrambled::inverse(i16::MAX));
+}
+
+lut! {
+    #[inverse]
+    struct Base64(x @ 0..64) -> char {
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"[x] as char
+    }
+}
+
+#[test]
+fn inverse_sparse() {
+    assert_eq!('+', Base64::INVERSE_MIN);
+    assert_eq!(Some(0), Base64::inverse('A'));
+    assert_eq!(Some(62), Base64::inverse('+'));
+    assert_eq!(Some(63), Base64::inverse('/'));
+    assert_eq!(None, Base64::inverse(','));
+    assert_eq!(None, Base64::inverse('='));
+    assert_eq!(None, Base64::inverse('é'));
+}
+
+lut! {
+    #[aggregates]
+    #[mask]
+    #[shard(entries = 4)]
+    struct Padded(x @ 0..3, y @ 0..5) -> i16 { x as i16 * 100 - y as i16 * 7 }
+}
+
+#[test]
+fn aggregates_skip_padding() {
+    let entries = (0..3).flat_map(|x| (0..5).map(move |y| Padded::get(x, y)));
+    assert_eq!(entries.clone().map(i128::from).sum::<i128>(), Padded::SUM);
+    assert_eq!(entries.clone().min(), Some(Padded::MIN));
+    assert_eq!(entries.max(), Some(Padded::MAX));
+    assert_eq!(Padded::SUM as f64 / 15.0, Padded::MEAN);
+    assert_eq!([0, 4], Padded::ARGMIN);
+    assert_eq!([2, 0], Padded::ARGMAX);
+}
+
+lut! {
+    #[aggregates]
+    struct Response(gain @ 1..4, freq @ 2..8) -> f64 {
+        let peak = 5.0;
+        gain as f64 - (freq as f64 - peak) * (freq as f64 - peak)
+    }
+}
+
+#[test]
+fn aggregates_first_peak() {
+    assert_eq!([3, 5], Response::ARGMAX);
+    assert_eq!(3.0, Response::MAX);
+    assert_eq!([1, 2], Response::ARGMIN);
+    assert_eq!(-8.0, Response::MIN);
+}
+
+lut! {
+    #[aggregates]
+    struct Samples(x @ 0..5) -> f32 { [f32::NAN, 0.25, -2.0, 3.5, f32::NAN][x] }
+}
+
+#[test]
+fn aggregates_nan() {
+    assert!(Samples::SUM.is_nan());
+    assert!(Samples::MEAN.is_nan());
+    assert_eq!(-2.0, Samples::MIN);
+    assert_eq!(3.5, Samples::MAX);
+    assert_eq!(([2], [3]), (Samples::ARGMIN, Samples::ARGMAX));
+}
+
+lut! {
+    #[redundant]
+    struct Mirrored(#[clamp] x @ 1..4, y @ 0..3) -> (u8, bool) { (x as u8, y == 2) }
+}
+
+lut! {
+    #[redundant(inverted)]
+    #[shard(entries = 4)]
+    struct Inverted(x @ 0..4, y @ 0..3) -> i32 { x as i32 * 3 - y as i32 }
+}
+
+lut! {
+    #[redundant]
+    #[banded(1, default = u16::MAX)]
+    struct MirroredBand(x @ 0..6, y @ 0..6) -> u16 { (x * 6 + y) as u16 }
+}
+
+#[test]
+fn redundant_copies() {
+    assert_eq!((3, true), Mirrored::get(7, 2));
+    assert_eq!((1, false), Mirrored::get(1, 0));
+    for x in 0..4 {
+        for y in 0..3 {
+            assert_eq!(x as i32 * 3 - y as i32, Inverted::get(x, y));
+        }
+    }
+    assert_eq!(22, MirroredBand::get(3, 4));
+    assert_eq!(u16::MAX, MirroredBand::get(3, 5));
+}
+
+lut! {
+    #[patchable]
+    struct Calibration(#[clamp] sensor @ 0..3, point @ 1..=4) -> f32 { (sensor * 4 + point) as f32 / 8.0 }
+}
+
+#[test]
+fn patchable_set_and_reset() {
+    assert_eq!(0.75, Calibration::get(1, 2));
+    Calibration::set(1, 2, -3.5);
+    assert_eq!(-3.5, Calibration::get(1, 2));
+    assert_eq!(-3.5, Calibration::fix(1)(2));
+    assert_eq!(0.875, Calibration::get(1, 3));
+    Calibration::set(7, 4, 9.0);
+    assert_eq!(9.0, Calibration::get(2, 4));
+    Calibration::reset();
+    assert_eq!(0.75, Calibration::get(1, 2));
+    assert_eq!(1.5, Calibration::get(2, 4));
+}
+
+lut! {
+    #[patchable]
+    #[reload]
+    struct Balance(unit @ 0..2, tier @ 1..=2) -> i32 { (unit * 100 + tier) as i32 }
+}
+
+#[test]
+fn reload_validates_whole_file() {
+    let dir = std::env::temp_dir();
+    let csv = dir.join(format!("numeric-lut-balance-{}.csv", std::process::id()));
+    std::fs::write(
+        &csv,
+        "unit,tier,value\r\n1,2,-7\r\n0,1,3\r\n1,1,4\r\n0,2,5\r\n",
+    )
+    .unwrap();
+    Balance::reload(&csv).unwrap();
+    assert_eq!(
+        [3, 5, 4, -7],
+        [
+            Balance::get(0, 1),
+            Balance::get(0, 2),
+            Balance::get(1, 1),
+            Balance::get(1, 2)
+        ]
+    );
+
+    std::fs::write(&csv, "unit,tier,value\n0,1,8\n0,3,9\n").unwrap();
+    let err = Balance::reload(&csv).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+    assert!(
+        err.to_string().ends_with("line 3: `tier` must be in 1..=2"),
+        "{}",
+        err
+    );
+    assert_eq!(3, Balance::get(0, 1));
+
+    let bin = dir.join(format!("numeric-lut-balance-{}.bin", std::process::id()));
+    let bytes = [1i32, 2, 3, 4]
+        .iter()
+        .flat_map(|entry| entry.to_le_bytes())
+        .collect::<Vec<_>>();
+    std::fs::write(&bin, &bytes).unwrap();
+    Balance::reload(&bin).unwrap();
+    assert_eq!(2, Balance::get(0, 2));
+    std::fs::write(&bin, &bytes[..15]).unwrap();
+    assert!(Balance::reload(&bin)
+        .unwrap_err()
+        .to_string()
+        .ends_with("expected 16 bytes but found 15"));
+
+    std::fs::remove_file(csv).unwrap();
+    std::fs::remove_file(bin).unwrap();
+}
+
+lut! {
+    #[dedup]
+    #[hash]
+    struct Periodic(x @ 0..64, y @ 0..8) -> u16 { (x % 4 * 8 + y) as u16 }
+}
+
+lut! {
+    #[const_table]
+    #[shard(entries = 4)]
+    #[hash]
+    struct Folded(x @ 0..4, y @ 0..4) -> u16 { (x * 4 + y) as u16 }
+}
+
+/// An entry read in constant evaluation.
+const ELEVEN: u16 = Folded::ENTRIES[2][3];
+
+#[test]
+fn const_table_entries() {
+    assert_eq!(11, ELEVEN);
+    assert_eq!(&Folded::ENTRIES, Folded::TABLE);
+    assert_eq!(11, Folded::get(2, 3));
+    assert_eq!(11, Folded::fix(2)(3));
+}
+
+lut! {
+    #[indirect]
+    #[mask]
+    struct External(x @ 0..3, y @ 0..4) -> i8 { x as i8 * 4 - y as i8 }
+}
+
+lut! {
+    #[indirect]
+    struct Uninitialized(#[clamp] x @ 0..4) -> u8 { x as u8 }
+}
+
+#[test]
+fn indirect_reads_initialized_table() {
+    let copy: &'static mut [[i8; 4]; 4] = Box::leak(Box::new(*External::TABLE));
+    copy[2][1] = 100;
+    External::init(copy);
+    assert_eq!(100, External::get(2, 1));
+    assert_eq!(-3, External::get(0, 3));
+    assert_eq!(4, External::fix(1)(0));
+    External::init(External::TABLE);
+    assert_eq!(7, External::get(2, 1));
+}
+
+#[test]
+#[should_panic(expected = "`Uninitialized::init` must be called before the first lookup")]
+fn indirect_uninitialized() {
+    Uninitialized::get(1);
+}
+
+#[test]
+fn deduplicated_rows() {
+    assert_eq!(4, Periodic::TABLE.0.len());
+    assert_eq!([0, 1, 2, 3, 0, 1, 2, 3], Periodic::TABLE.1[..8]);
+    assert_eq!(21, Periodic::get(62, 5));
+    assert_eq!(21, Periodic::fix(2)(5));
+}
+
+/// Stores a temperature in hundredths of a degree.
+const fn centi(degrees: f64) -> i16 {
+    (degrees * 100.0) as i16
+}
+
+fn degrees(centi: i16) -> f64 {
+    f64::from(centi) / 100.0
+}
+
+lut! {
+    #[codec(i16, encode = centi, decode = degrees)]
+    struct Celsius(fahrenheit @ 0..212) -> f64 { (fahrenheit as f64 - 32.0) * 5.0 / 9.0 }
+}
+
+#[test]
+fn codec_scaled_integers() {
+    assert_eq!(-1777, Celsius::TABLE[0]);
+    assert_eq!(0.0, Celsius::get(32));
+    assert_eq!(-17.77, Celsius::get(0));
+    assert_eq!(37.77, Celsius::get(100));
+}
+
+numeric_lut::lut! {
+    #[interpolate(x = -1.0..=1.0)]
+    struct Parabola(x @ 0..9) -> f64 {
+        let t = x as f64 / 4.0 - 1.0;
+        t * t
+    }
+}
+
+numeric_lut::lut! {
+    #[interpolate(r = 0.0..=1.0, g = 0.0..=1.0, b = 0.0..=1.0)]
+    struct Identity(r @ 0..3, g @ 0..3, b @ 0..3) -> [f32; 3] {
+        [r as f32 / 2.0, g as f32 / 2.0, b as f32 / 2.0]
+    }
+}
+
+numeric_lut::lut! {
+    #[interpolate]
+    struct Plane(x @ 2..=4, y @ 0..2) -> f32 { x as f32 + 10.0 * y as f32 }
+}
+
+#[test]
+fn interpolate() {
+    assert_eq!(1.0, Parabola::sample(-1.0));
+    assert_eq!(0.0, Parabola::sample(0.0));
+    assert_eq!(0.03125, Parabola::sample(0.125));
+    assert_eq!(1.0, Parabola::sample(7.0));
+
+    let [r, g, b] = Identity::sample(0.1, 0.6, 0.9);
+    assert!((r - 0.1).abs() < 1e-6 && (g - 0.6).abs() < 1e-6 && (b - 0.9).abs() < 1e-6);
+    assert_eq!([1.0, 0.0, 0.5], Identity::sample(2.0, -1.0, 0.5));
+
+    assert_eq!(2.0, Plane::sample(0.0, 0.0));
+    assert_eq!(8.5, Plane::sample(3.5, 0.5));
+    assert_eq!(14.0, Plane::sample(4.0, 1.0));
+}
+
+numeric_lut::lut! {
+    #[interpolate(x = 0.0..=1.0, extrapolate = linear)]
+    struct Extended(x @ 0..3, y @ 0..2) -> f32 { (x * x) as f32 + y as f32 }
+}
+
+numeric_lut::lut! {
+    #[interpolate(extrapolate = none)]
+    struct Partial(x @ 1..=4) -> [f64; 2] { [x as f64, -(x as f64)] }
+}
+
+#[test]
+fn interpolate_extrapolate() {
+    assert_eq!(-2.0, Extended::sample(-1.0, 0.0));
+    assert_eq!(7.0, Extended::sample(1.5, 0.0));
+    assert_eq!(9.0, Extended::sample(1.5, 2.0));
+
+    assert_eq!(Some([2.5, -2.5]), Partial::sample(2.5));
+    assert_eq!(Some([4.0, -4.0]), Partial::sample(4.0));
+    assert_eq!(None, Partial::sample(0.5));
+    assert_eq!(None, Partial::sample(f64::NAN));
+}
+
+numeric_lut::lut! {
+    #[interpolate(turn = periodic(0.0..1.0), radius = 0.0..=2.0)]
+    struct Polar(turn @ 0..8, radius @ 0..3) -> f64 { (turn * 10 + radius) as f64 }
+}
+
+numeric_lut::lut! {
+    #[interpolate(phase = periodic(-4.0..4.0), extrapolate = none)]
+    struct Phase(phase @ 1..=4) -> f32 { phase as f32 }
+}
+
+#[test]
+fn interpolate_periodic() {
+    assert_eq!(0.0, Polar::sample(0.0, 0.0));
+    assert_eq!(5.0, Polar::sample(0.0625, 0.0));
+    assert_eq!(6.0, Polar::sample(-0.9375, 1.0));
+    // Across the seam from the last value back to the first.
+    assert_eq!(35.0, Polar::sample(0.9375, 0.0));
+    assert_eq!(72.0, Polar::sample(7.875, 2.0));
+
+    assert_eq!(Some(1.0), Phase::sample(-4.0));
+    assert_eq!(Some(2.5), Phase::sample(3.0));
+    assert_eq!(Some(4.0), Phase::sample(10.0));
+    assert_eq!(None, Phase::sample(f32::NAN));
+}
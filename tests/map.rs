@@ -0,0 +1,85 @@
+use numeric_lut::lut_map;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Fn,
+    Let,
+    Mut,
+    Return,
+}
+
+lut_map! {
+    /// The keywords of a small language.
+    struct Keywords -> Token {
+        "fn" => Token::Fn,
+        "let" => Token::Let,
+        "mut" => Token::Mut,
+        "return" => Token::Return,
+    }
+}
+
+#[test]
+fn item_get() {
+    assert_eq!(Some(Token::Fn), Keywords::get("fn"));
+    assert_eq!(Some(Token::Return), Keywords::get("return"));
+    assert_eq!(None, Keywords::get("ret"));
+    assert_eq!(None, Keywords::get(""));
+    assert_eq!(4, Keywords::KEYS.len());
+}
+
+#[test]
+fn closure_many_keys() {
+    let lut = lut_map!(|name| -> usize {
+        "zero" => 0, "one" => 1, "two" => 2, "three" => 3, "four" => 4, "five" => 5,
+        "six" => 6, "seven" => 7, "eight" => 8, "nine" => 9, "ten" => 10, "eleven" => 11,
+        "twelve" => 12, "thirteen" => 13, "fourteen" => 14, "fifteen" => 15, "sixteen" => 16,
+        "seventeen" => 17, "eighteen" => 18, "nineteen" => 19, "twenty" => 20,
+    });
+    let names = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+        "twenty",
+    ];
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(Some(i), lut(name));
+    }
+    assert_eq!(None, lut("twentyone"));
+}
+
+#[test]
+fn closure_const_values() {
+    const BASE: u32 = 100;
+    let lut = lut_map!(|unit| -> u32 { "ms" => 1, "s" => BASE * 10 });
+    assert_eq!(Some(1000), lut("s"));
+}
+
+#[test]
+fn closure_single_key() {
+    let lut = lut_map!(|key| -> char { "a" => 'a' });
+    assert_eq!(Some('a'), lut("a"));
+    assert_eq!(None, lut("b"));
+}
+
+#[test]
+fn closure_empty() {
+    let lut = lut_map!(|key| -> u8 {});
+    assert_eq!(None, lut("a"));
+}
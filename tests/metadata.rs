@@ -0,0 +1,236 @@
+//! Sidecar files, the report named by `NUMERIC_LUT_REPORT` and the cache named by
+//! `NUMERIC_LUT_CACHE` are written during expansion, so these tests compile crates of their own
+//! with the macros built for the tests.
+
+/// The most recently built dynamic library of the macros, next to the test executable.
+fn macros() -> std::path::PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    let prefix = format!("{}numeric_lut-", std::env::consts::DLL_PREFIX);
+    std::fs::read_dir(deps)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(std::env::consts::DLL_SUFFIX)
+        })
+        .max_by_key(|path| path.metadata().unwrap().modified().unwrap())
+        .expect("the macros are built before the tests")
+}
+
+/// Compiles the given source as the library crate `tables` in a fresh directory, which is also its
+/// `OUT_DIR`.
+fn compile(
+    test: &str,
+    source: &str,
+    envs: &[(&str, &std::path::Path)],
+) -> (std::path::PathBuf, std::process::Output) {
+    let dir = std::env::temp_dir().join(format!("numeric-lut-{}-{}", test, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tables.rs");
+    std::fs::write(&path, source).unwrap();
+    let output = std::process::Command::new(std::env::var_os("RUSTC").unwrap_or("rustc".into()))
+        .args([
+            "--edition",
+            "2018",
+            "--crate-type",
+            "lib",
+            "--crate-name",
+            "tables",
+        ])
+        .arg("--extern")
+        .arg(format!("numeric_lut={}", macros().display()))
+        .arg("--out-dir")
+        .arg(&dir)
+        .arg(&path)
+        .env("CARGO_CRATE_NAME", "tables")
+        .env("OUT_DIR", &dir)
+        .envs(envs.iter().copied())
+        .output()
+        .unwrap();
+    (dir, output)
+}
+
+#[test]
+fn sidecar_collision() {
+    let (dir, output) = compile(
+        "sidecar-collision",
+        r#"
+pub mod audio {
+    numeric_lut::lut! {
+        #[sidecar]
+        pub struct Gain(x @ 0..4) -> u16 { x as u16 * 3 }
+    }
+}
+
+pub mod video {
+    numeric_lut::lut! {
+        #[sidecar]
+        pub struct Gain(x @ 0..8) -> u8 { x as u8 }
+    }
+}
+"#,
+        &[],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("another table in this crate is also described in"),
+        "{}",
+        stderr
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let (dir, output) = compile(
+        "sidecar-renamed",
+        r#"
+pub mod audio {
+    numeric_lut::lut! {
+        #[sidecar]
+        pub struct Gain(x @ 0..4) -> u16 { x as u16 * 3 }
+    }
+}
+
+pub mod video {
+    numeric_lut::lut! {
+        #[sidecar = "VideoGain"]
+        pub struct Gain(x @ 0..8) -> u8 { x as u8 }
+    }
+}
+"#,
+        &[],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let read = |name| std::fs::read_to_string(dir.join("numeric-lut").join(name)).unwrap();
+    assert!(read("Gain.json")
+        .starts_with(r#"{"name":"Gain","dimensions":[{"name":"x","lo":0,"len":4}]"#));
+    assert!(read("VideoGain.json")
+        .starts_with(r#"{"name":"VideoGain","dimensions":[{"name":"x","lo":0,"len":8}]"#));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn report_lines() {
+    let report = std::env::temp_dir().join(format!("numeric-lut-{}.jsonl", std::process::id()));
+    let (dir, output) = compile(
+        "report",
+        r#"
+numeric_lut::lut! {
+    #[sidecar]
+    pub struct Gain(x @ 2..5) -> u16 { x as u16 * 3 }
+}
+
+pub fn squares(x: usize) -> u32 {
+    numeric_lut::lut!(|x @ 0..4| -> u32 { (x * x) as u32 })(x)
+}
+"#,
+        &[("NUMERIC_LUT_REPORT", &report)],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let lines = std::fs::read_to_string(&report).unwrap();
+    let lines = lines.lines().collect::<Vec<_>>();
+    assert_eq!(2, lines.len(), "{:?}", lines);
+    assert!(lines[0].starts_with(
+        r#"{"crate":"tables","name":"Gain","dimensions":[{"name":"x","lo":2,"len":3}],"element_type":"u16","entries":3,"byte_size":6,"strategy":"table","spec_hash":""#
+    ));
+    assert!(lines[1].starts_with(
+        r#"{"crate":"tables","name":null,"dimensions":[{"name":"x","lo":0,"len":4}],"element_type":"u32","entries":4,"byte_size":16,"strategy":"table","spec_hash":""#
+    ));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("numeric-lut/Gain.json")).unwrap(),
+        lines[0].replace(r#""crate":"tables","#, "")
+    );
+    std::fs::remove_file(&report).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cached_expansions() {
+    // The directory that `compile` builds the crate in, which holds the data file.
+    let dir = std::env::temp_dir().join(format!("numeric-lut-cache-{}", std::process::id()));
+    let (cache, report) = (dir.join("cache"), dir.join("luts.jsonl"));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("levels.json"), "[1, 2, 3]").unwrap();
+    let source = r#"
+pub fn sin(phase: usize) -> f32 {
+    numeric_lut::lut_sin!(bits = 6, ty = f32)(phase)
+}
+
+numeric_lut::lut_from_file! {
+    "levels.json",
+    pub struct Levels(x @ 0..3) -> u8
+}
+"#;
+    let envs = [
+        ("NUMERIC_LUT_CACHE", &*cache),
+        ("NUMERIC_LUT_REPORT", &*report),
+        ("CARGO_MANIFEST_DIR", &*dir),
+    ];
+    let compile = || {
+        let (_, output) = compile("cache", source, &envs);
+        (
+            output.status.success(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        )
+    };
+    let (success, stderr) = compile();
+    assert!(success, "{}", stderr);
+    let cached = |kind: &str| {
+        std::fs::read_dir(&cache)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with(kind)
+            })
+            .unwrap()
+    };
+    let (sin, levels) = (cached("lut_sin-"), cached("lut_from_file-"));
+    assert_eq!(
+        "1u8, 2u8, 3u8",
+        std::fs::read_to_string(&levels)
+            .unwrap()
+            .lines()
+            .nth(1)
+            .unwrap()
+    );
+
+    // The cached tokens are used instead of computing them again.
+    let header = std::fs::read_to_string(&sin)
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .to_owned();
+    std::fs::write(&sin, format!("{}\ncompile_error!(\"cached\")", header)).unwrap();
+    let (success, stderr) = compile();
+    assert!(!success && stderr.contains("cached"), "{}", stderr);
+    std::fs::remove_file(&sin).unwrap();
+
+    // Entries of data files are read again when the file changes, and the table is still reported
+    // on every expansion.
+    std::fs::write(dir.join("levels.json"), "[1, 2, 4]").unwrap();
+    let (success, stderr) = compile();
+    assert!(success, "{}", stderr);
+    assert_eq!(
+        "1u8, 2u8, 4u8",
+        std::fs::read_to_string(&levels)
+            .unwrap()
+            .lines()
+            .nth(1)
+            .unwrap()
+    );
+    assert_eq!(3, std::fs::read_to_string(&report).unwrap().lines().count());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,57 @@
+const fn weight(x: usize, y: usize) -> u16 {
+    (x * 3 + y) as u16
+}
+
+numeric_lut::lut_mod! {
+    /// The weights of a grid.
+    pub mod grid(x @ 2..5, #[wrap] y @ 0..=3) -> u16 { weight(x, y) }
+}
+
+#[test]
+fn accessors() {
+    assert_eq!(weight(3, 1), grid::get(3, 1));
+    assert_eq!(weight(3, 1), grid::get(3, 5));
+    assert_eq!(Some(weight(4, 3)), grid::get_checked(4, 3));
+    assert_eq!(None, grid::get_checked(5, 0));
+    assert_eq!(None, grid::get_checked(2, 4));
+    assert_eq!(weight(2, 3), grid::get_clamped(0, 9));
+    assert_eq!(weight(4, 0), grid::get_clamped(7, 0));
+    assert_eq!(weight(3, 2), grid::get_raw(6));
+    assert_eq!(weight(2, 2), grid::Table::get(2, 2));
+}
+
+#[test]
+#[should_panic(expected = "the index must be less than 12 but is 12")]
+fn raw_out_of_bounds() {
+    grid::get_raw(grid::LEN);
+}
+
+#[test]
+fn constants() {
+    assert_eq!(2..=4, grid::X_RANGE);
+    assert_eq!(0..=3, grid::Y_RANGE);
+    assert_eq!([3, 4], grid::SHAPE);
+    assert_eq!(12, grid::LEN);
+    assert_eq!(weight(4, 1), grid::TABLE[2][1]);
+}
+
+#[test]
+fn entries() {
+    let entries = grid::entries().collect::<Vec<_>>();
+    assert_eq!(grid::LEN, entries.len());
+    assert_eq!(([2, 0], weight(2, 0)), entries[0]);
+    assert_eq!(([2, 1], weight(2, 1)), entries[1]);
+    assert_eq!(([4, 3], weight(4, 3)), entries[11]);
+}
+
+numeric_lut::lut_mod! {
+    /// The generated test expects the default outside of the band.
+    #[banded(1, default = 0)]
+    pub mod band(x @ 0..6, y @ 0..6) -> u16 { (x * 6 + y) as u16 + 1 }
+}
+
+#[test]
+fn banded() {
+    assert_eq!(9, band::get(1, 2));
+    assert_eq!(0, band::get(1, 3));
+}
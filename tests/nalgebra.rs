@@ -0,0 +1,76 @@
+#![cfg(feature = "nalgebra")]
+//! The views of `#[nalgebra(view)]` are `nalgebra::MatrixView`s, which these tests stand in for
+//! with a view that indexes the entries with its strides, so that rows and columns can be compared
+//! with lookups.
+
+extern crate self as nalgebra;
+
+use std::marker::PhantomData;
+
+pub struct Const<const N: usize>;
+
+pub struct MatrixView<'a, T, R, C, RStride, CStride> {
+    entries: &'a [T],
+    shape: PhantomData<(R, C, RStride, CStride)>,
+}
+
+impl<'a, T: Copy, const R: usize, const C: usize, const RS: usize, const CS: usize>
+    MatrixView<'a, T, Const<R>, Const<C>, Const<RS>, Const<CS>>
+{
+    pub fn from_slice_with_strides_generic(
+        entries: &'a [T],
+        _: Const<R>,
+        _: Const<C>,
+        _: Const<RS>,
+        _: Const<CS>,
+    ) -> Self {
+        assert!((R - 1) * RS + (C - 1) * CS < entries.len());
+        MatrixView {
+            entries,
+            shape: PhantomData,
+        }
+    }
+
+    pub fn shape(&self) -> (usize, usize) {
+        (R, C)
+    }
+
+    /// The entry in the given row and column, which panics outside of the matrix.
+    pub fn at(&self, row: usize, column: usize) -> T {
+        assert!(row < R && column < C, "index out of bounds");
+        self.entries[row * RS + column * CS]
+    }
+}
+
+numeric_lut::lut! {
+    #[nalgebra(view)]
+    pub struct Costs(from @ 1..4, to @ 0..5) -> f64 { (from * 10 + to) as f64 }
+}
+
+numeric_lut::lut! {
+    #[nalgebra(view)]
+    #[mask]
+    pub struct Padded(row @ 0..2, column @ 0..3) -> u8 { (row * 3 + column) as u8 }
+}
+
+#[test]
+fn rows_and_columns() {
+    let matrix = Costs::matrix();
+    assert_eq!((3, 5), matrix.shape());
+    // A row per value of the first parameter, and a column per value of the second one.
+    assert_eq!(10.0, matrix.at(0, 0));
+    assert_eq!(14.0, matrix.at(0, 4));
+    assert_eq!(32.0, matrix.at(2, 2));
+    for from in 1..4 {
+        for to in 0..5 {
+            assert_eq!(Costs::get(from, to), matrix.at(from - 1, to));
+        }
+    }
+}
+
+#[test]
+fn padded_rows() {
+    let matrix = Padded::matrix();
+    assert_eq!((2, 3), matrix.shape());
+    assert_eq!([3, 4, 5], [0, 1, 2].map(|column| matrix.at(1, column)));
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "ndarray")]
+//! The views of `#[ndarray]` are `ndarray::ArrayView`s, which these tests stand in for with a view
+//! that only checks its shape and strides against the entries, so that the views can be indexed.
+
+extern crate self as ndarray;
+
+pub struct Dim<I>(I);
+
+/// A shape with explicit strides.
+pub struct StrideShape<const N: usize> {
+    shape: [usize; N],
+    strides: [usize; N],
+}
+
+pub trait ShapeBuilder<const N: usize> {
+    fn strides(self, strides: [usize; N]) -> StrideShape<N>;
+}
+
+impl<const N: usize> ShapeBuilder<N> for [usize; N] {
+    fn strides(self, strides: [usize; N]) -> StrideShape<N> {
+        StrideShape {
+            shape: self,
+            strides,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShapeError;
+
+pub struct ArrayView<'a, A, D> {
+    entries: &'a [A],
+    dim: D,
+    strides: D,
+}
+
+impl<'a, A: Copy, const N: usize> ArrayView<'a, A, Dim<[usize; N]>> {
+    pub fn from_shape(shape: StrideShape<N>, entries: &'a [A]) -> Result<Self, ShapeError> {
+        let last = (0..N)
+            .map(|axis| (shape.shape[axis] - 1) * shape.strides[axis])
+            .sum::<usize>();
+        if shape.shape.contains(&0) || last >= entries.len() {
+            return Err(ShapeError);
+        }
+        Ok(ArrayView {
+            entries,
+            dim: Dim(shape.shape),
+            strides: Dim(shape.strides),
+        })
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.dim.0
+    }
+
+    /// The entry at the given index, which panics outside of the shape.
+    pub fn at(&self, index: [usize; N]) -> A {
+        let axes = index.iter().zip(self.dim.0).zip(self.strides.0);
+        let mut offset = 0;
+        for ((&index, len), stride) in axes {
+            assert!(index < len, "index out of bounds");
+            offset += index * stride;
+        }
+        self.entries[offset]
+    }
+}
+
+numeric_lut::lut! {
+    #[ndarray]
+    pub struct Grid(x @ 1..4, y @ 0..5) -> u32 { (x * 10 + y) as u32 }
+}
+
+numeric_lut::lut! {
+    #[ndarray]
+    #[mask]
+    pub struct Padded(x @ 0..3, y @ 0..3, z @ 0..2) -> u16 { (x * 100 + y * 10 + z) as u16 }
+}
+
+numeric_lut::lut! {
+    #[ndarray]
+    #[transpose]
+    pub struct Transposed(row @ 0..2, col @ 0..3) -> u8 { (row * 3 + col) as u8 }
+}
+
+numeric_lut::lut! {
+    #[ndarray]
+    pub struct Reversed(#[permute(bit_reverse)] i @ 0..8) -> u8 { i as u8 }
+}
+
+#[test]
+fn shape_and_order() {
+    let view = Grid::view();
+    assert_eq!([3, 5], view.shape());
+    for x in 1..4 {
+        for y in 0..5 {
+            assert_eq!(Grid::get(x, y), view.at([x - 1, y]));
+        }
+    }
+}
+
+#[test]
+fn padded_strides() {
+    let view = Padded::view();
+    assert_eq!([3, 3, 2], view.shape());
+    assert_eq!(210, view.at([2, 1, 0]));
+    assert_eq!(221, view.at([2, 2, 1]));
+}
+
+#[test]
+fn transposed_storage() {
+    let view = Transposed::view();
+    assert_eq!([2, 3], view.shape());
+    for row in 0..2 {
+        for col in 0..3 {
+            assert_eq!(Transposed::get_t(col, row), view.at([row, col]));
+        }
+    }
+}
+
+#[test]
+fn permuted_storage() {
+    // The view has the stored order, with the entry of every value at its bit-reversed index.
+    let view = Reversed::view();
+    assert_eq!(
+        [0, 4, 2, 6, 1, 5, 3, 7],
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| view.at([i]))
+    );
+    for i in 0..8 {
+        assert_eq!(Reversed::get(i), view.at([i]));
+    }
+}
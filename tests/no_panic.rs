@@ -0,0 +1,57 @@
+#![cfg(feature = "no-panic")]
+#![feature(macro_attr)]
+//! The accessors of tables with `#[total(no_panic)]` are annotated with `#[no_panic]` of the
+//! `no-panic` crate, which these tests stand in for with an attribute that counts the calls of the
+//! functions it annotates.
+
+extern crate self as no_panic;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of calls of functions annotated with `#[no_panic]`.
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts the calls of the annotated function, which must return a value like the real attribute
+/// requires.
+#[macro_export]
+macro_rules! no_panic {
+    attr() {
+        $(#[$attr:meta])* $vis:vis fn $name:ident($($params:tt)*) -> $return:ty $body:block
+    } => {
+        $(#[$attr])*
+        $vis fn $name($($params)*) -> $return {
+            $crate::CALLS.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+            $body
+        }
+    };
+}
+
+numeric_lut::lut! {
+    #[total(no_panic)]
+    #[try_get]
+    #[get_or]
+    pub struct Grid(#[clamp] x @ 0..4, #[wrap] y @ 0..3) -> u16 { (x * 10 + y) as u16 }
+}
+
+numeric_lut::lut! {
+    #[total]
+    pub struct Plain(#[clamp] x @ 0..4) -> u16 { x as u16 }
+}
+
+#[test]
+fn annotated_accessors() {
+    let calls = || CALLS.load(Ordering::Relaxed);
+    assert_eq!(31, Grid::get(3, 4));
+    assert_eq!(1, calls());
+    // Both of these look the entry up with `get`, which is annotated as well.
+    assert_eq!(Ok(12), Grid::try_get(1, 2).map_err(|_| ()));
+    assert_eq!(3, calls());
+    assert_eq!(7, Grid::get_or(9, 0, 7));
+    assert_eq!(4, calls());
+    assert_eq!(21, Grid::get_or(2, 1, 7));
+    assert_eq!(6, calls());
+
+    // Without `no_panic`, the accessors aren't annotated.
+    assert_eq!(3, Plain::get(10));
+    assert_eq!(6, calls());
+}
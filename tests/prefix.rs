@@ -0,0 +1,51 @@
+use numeric_lut::lut_prefix;
+
+lut_prefix! {
+    /// Which device handles an address.
+    struct Devices(addr: u16) -> &'static str {
+        0x0000/1 => "ram",
+        0x8000/2 => "rom",
+        0xc000/4 => "io",
+        0xc010/12 => "timer",
+        0xc011/16 => "timer control",
+    }
+}
+
+#[test]
+fn item_nested() {
+    assert_eq!(Some("ram"), Devices::get(0x0000));
+    assert_eq!(Some("ram"), Devices::get(0x7fff));
+    assert_eq!(Some("rom"), Devices::get(0xbfff));
+    assert_eq!(Some("io"), Devices::get(0xc00f));
+    assert_eq!(Some("timer"), Devices::get(0xc010));
+    assert_eq!(Some("timer control"), Devices::get(0xc011));
+    assert_eq!(Some("timer"), Devices::get(0xc01f));
+    assert_eq!(Some("io"), Devices::get(0xc020));
+    assert_eq!(None, Devices::get(0xd000));
+    assert_eq!(None, Devices::get(0xffff));
+}
+
+#[test]
+fn closure_full_width() {
+    let lut = lut_prefix!(|x: u8| -> u8 { 0/0 => 0, 0xff/8 => 1, 0x00/8 => 2 });
+    assert_eq!(Some(2), lut(0x00));
+    assert_eq!(Some(0), lut(0x7f));
+    assert_eq!(Some(1), lut(0xff));
+}
+
+#[test]
+fn closure_u128() {
+    let lut = lut_prefix!(|addr: u128| -> u32 {
+        0x2001_0db8_0000_0000_0000_0000_0000_0000/32 => 1,
+        0xffff_0000_0000_0000_0000_0000_0000_0000/16 => 2,
+    });
+    assert_eq!(Some(1), lut(0x2001_0db8_1234_0000_0000_0000_0000_0001));
+    assert_eq!(Some(2), lut(u128::MAX));
+    assert_eq!(None, lut(0));
+}
+
+#[test]
+fn closure_empty() {
+    let lut = lut_prefix!(|addr: u32| -> u8 {});
+    assert_eq!(None, lut(1234));
+}
@@ -0,0 +1,288 @@
+use numeric_lut::{
+    lut_cos, lut_decibel, lut_dither, lut_easing, lut_gf256, lut_huffman, lut_sbox, lut_sin,
+    lut_srgb, lut_window,
+};
+
+#[test]
+fn sin_full_period() {
+    let sin = lut_sin!(bits = 6, amplitude = 1000i32);
+    for phase in 0..64 {
+        let expected = (std::f64::consts::TAU * phase as f64 / 64.0).sin() * 1000.0;
+        assert_eq!(expected.round() as i32, sin(phase));
+    }
+}
+
+#[test]
+fn sin_default_float() {
+    let sin = lut_sin!(bits = 4);
+    for phase in 0..16 {
+        let expected = (std::f64::consts::TAU * phase as f64 / 16.0).sin() as f32;
+        assert!((expected - sin(phase)).abs() <= f32::EPSILON);
+    }
+}
+
+#[test]
+fn cos_full_period() {
+    let cos = lut_cos!(bits = 5, amplitude = i8::MAX);
+    for phase in 0..96 {
+        let expected = (std::f64::consts::TAU * phase as f64 / 32.0).cos() * 127.0;
+        assert_eq!(expected.round() as i8, cos(phase));
+    }
+}
+
+#[test]
+fn sin_explicit_type() {
+    let sin = lut_sin!(bits = 3, amplitude = 100, ty = i64);
+    assert_eq!(
+        [0, 71, 100, 71, 0, -71, -100, -71],
+        [0, 1, 2, 3, 4, 5, 6, 7].map(sin)
+    );
+}
+
+#[test]
+fn gf256_log_exp_roundtrip() {
+    let exp = lut_gf256!(exp);
+    let log = lut_gf256!(log);
+    let mul = lut_gf256!(mul);
+    for a in 1..=255u8 {
+        assert_eq!(a, exp(log(a)));
+        for b in 1..=255u8 {
+            let sum = (log(a) as usize + log(b) as usize) % 255;
+            assert_eq!(mul(a, b), exp(sum as u8));
+        }
+    }
+    assert_eq!(1, exp(255));
+}
+
+#[test]
+fn gf256_mul_identities() {
+    let mul = lut_gf256!(mul, poly = 0x11d);
+    for a in 0..=255u8 {
+        assert_eq!(0, mul(a, 0));
+        assert_eq!(a, mul(a, 1));
+        assert_eq!(a, mul(1, a));
+    }
+    assert_eq!(0x1d, mul(0x80, 2));
+}
+
+#[test]
+fn srgb_roundtrip_8_bit() {
+    let to_linear = lut_srgb!(to_linear, output = u16);
+    let to_srgb = lut_srgb!(to_srgb, input = u16, output = u8);
+    for c in 0..=255u8 {
+        assert_eq!(c, to_srgb(to_linear(c)));
+    }
+}
+
+#[test]
+fn srgb_reference_values() {
+    let to_linear = lut_srgb!(to_linear);
+    let to_srgb = lut_srgb!(to_srgb, output = f32);
+    assert_eq!(0.0, to_linear(0));
+    assert!((to_linear(10) - 0.003_035_27).abs() < 1e-7);
+    assert!((to_srgb(1) - 0.049_840_09).abs() < 1e-7);
+    assert_eq!(1.0, to_srgb(255));
+}
+
+#[test]
+fn window_reference_values() {
+    let hamming = lut_window!(hamming, len = 5, f64);
+    let blackman = lut_window!(blackman, len = 5, f64);
+    let harris = lut_window!(blackman_harris, len = 5, periodic = true, f64);
+    let expected_hamming = [0.08, 0.54, 1.0, 0.54, 0.08];
+    let expected_blackman = [0.0, 0.34, 1.0, 0.34, 0.0];
+    for n in 0..5 {
+        assert!((expected_hamming[n] - hamming(n)).abs() < 1e-12);
+        assert!((expected_blackman[n] - blackman(n)).abs() < 1e-12);
+    }
+    assert!((harris(0) - 0.000_06).abs() < 1e-12);
+}
+
+#[test]
+fn window_periodic_hann() {
+    let periodic = lut_window!(hann, len = 8, periodic = true);
+    let symmetric = lut_window!(hann, len = 9);
+    for n in 0..8 {
+        assert_eq!(symmetric(n), periodic(n));
+    }
+}
+
+#[test]
+fn window_kaiser_zero_beta_is_rectangular() {
+    let kaiser = lut_window!(kaiser, len = 4, beta = 0);
+    for n in 0..4 {
+        assert_eq!(1.0, kaiser(n));
+    }
+}
+
+#[test]
+fn easing_endpoints() {
+    let curves = [
+        lut_easing!(ease_in_cubic, len = 16).sample(1.0),
+        lut_easing!(ease_out_cubic, len = 16).sample(1.0),
+        lut_easing!(ease_in_elastic, len = 16).sample(1.0),
+        lut_easing!(ease_out_elastic, len = 16).sample(1.0),
+        lut_easing!(ease_in_bounce, len = 16).sample(1.0),
+        lut_easing!(ease_out_bounce, len = 16).sample(1.0),
+        lut_easing!(ease_in_out_bounce, len = 16).sample(1.0),
+    ];
+    for end in curves.iter() {
+        assert!((end - 1.0).abs() < 1e-6);
+    }
+    assert_eq!(0.0, lut_easing!(ease_out_bounce, len = 16).get(0));
+}
+
+#[test]
+fn easing_interpolates() {
+    let ease = lut_easing!(ease_in_cubic, len = 3, f64);
+    assert_eq!(0.125, ease.get(1));
+    assert_eq!(0.0625, ease.sample(0.25));
+    assert_eq!(0.5625, ease.sample(0.75));
+    assert_eq!(1.0, ease.sample(2.0));
+}
+
+#[test]
+fn easing_cubic_kernels() {
+    // A Hermite spline through the points and slopes of a cubic is the cubic itself.
+    let hermite = lut_easing!(ease_in_cubic, len = 3, f64, kernel = hermite);
+    let catmull_rom = lut_easing!(ease_in_cubic, len = 17, f64, kernel = catmull_rom);
+    let linear = lut_easing!(ease_in_cubic, len = 17, f64);
+    for i in 0..=20 {
+        let t = i as f64 / 20.0;
+        assert!((hermite.sample(t) - t * t * t).abs() < 1e-6);
+        let cubic_error = (catmull_rom.sample(t) - t * t * t).abs();
+        let linear_error = (linear.sample(t) - t * t * t).abs();
+        assert!(cubic_error <= linear_error + 1e-12);
+    }
+    assert_eq!(1.0, catmull_rom.sample(1.0));
+}
+
+#[test]
+fn decibel_to_gain() {
+    let gain = lut_decibel!(to_gain, min = -20, max = 20, step = 0.25, f64);
+    for i in 0..=160 {
+        let db = -20.0 + i as f64 * 0.25;
+        assert!((gain(db) - 10f64.powf(db / 20.0)).abs() < 1e-12);
+    }
+    assert_eq!(gain(-0.25), gain(-0.3));
+    assert_eq!(gain(20.0), gain(f64::INFINITY));
+
+    let q15 = lut_decibel!(to_gain, min = -90, u16, frac_bits = 15);
+    assert_eq!(32768, q15(0.0));
+    assert_eq!(16423, q15(-6.0));
+    assert_eq!(1, q15(-90.0));
+}
+
+#[test]
+fn decibel_to_db() {
+    let db = lut_decibel!(to_db, bits = 12, max = 6.0);
+    assert_eq!(6.0, db(4095));
+    assert_eq!(db(1), db(0));
+    assert!((db(2048) + 0.0185).abs() < 1e-3);
+
+    let fixed = lut_decibel!(to_db, bits = 4, min = -12.0, i8, frac_bits = 2);
+    assert_eq!(0, fixed(15));
+    assert_eq!(-48, fixed(0));
+    assert_eq!(-26, fixed(7));
+}
+
+#[test]
+fn dither_bayer_reference() {
+    let bayer = lut_dither!(bayer, size = 4);
+    let expected = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+    for (y, row) in expected.iter().enumerate() {
+        for (x, &rank) in row.iter().enumerate() {
+            assert_eq!(rank, bayer(x, y));
+        }
+    }
+}
+
+#[test]
+fn dither_ranks_are_permutations() {
+    let bayer = lut_dither!(bayer, size = 32);
+    let noise = lut_dither!(blue_noise, size = 32, seed = 3);
+    for lut in [&bayer as &dyn Fn(usize, usize) -> u16, &noise] {
+        let mut seen = vec![false; 1024];
+        for y in 0..32 {
+            for x in 0..32 {
+                seen[lut(x, y) as usize] = true;
+            }
+        }
+        assert!(seen.iter().all(|&seen| seen));
+    }
+}
+
+#[test]
+fn dither_blue_noise_is_seeded() {
+    let a = lut_dither!(blue_noise, size = 8, seed = 1);
+    let b = lut_dither!(blue_noise, size = 8, seed = 1);
+    let c = lut_dither!(blue_noise, size = 8, seed = 2);
+    let cells =
+        |lut: &dyn Fn(usize, usize) -> u8| (0..64).map(|i| lut(i % 8, i / 8)).collect::<Vec<_>>();
+    assert_eq!(cells(&a), cells(&b));
+    assert_ne!(cells(&a), cells(&c));
+}
+
+#[test]
+fn sbox_aes_roundtrip() {
+    let sbox = lut_sbox!(aes);
+    let fast = lut_sbox!(aes, constant_time = false);
+    let inverse = lut_sbox!(aes, inverse = true);
+    for x in 0..=255u8 {
+        assert_eq!(fast(x), sbox(x));
+        assert_eq!(x, inverse(sbox(x)));
+    }
+    assert_eq!(0x7c, sbox(0x01));
+    assert_eq!(0x16, sbox(0xff));
+}
+
+#[test]
+fn sbox_affine_matches_aes() {
+    let aes = lut_sbox!(aes, constant_time = false);
+    let affine = lut_sbox!(affine, poly = 0x11b, rotations = 0x1f, constant = 0x63);
+    let identity = lut_sbox!(affine, rotations = 0x01, constant_time = false);
+    let mul = lut_gf256!(mul);
+    for x in 0..=255u8 {
+        assert_eq!(aes(x), affine(x));
+        if x != 0 {
+            assert_eq!(1, mul(x, identity(x)));
+        }
+    }
+}
+
+#[test]
+fn huffman_deflate_fixed_literals() {
+    // The fixed literal/length code of DEFLATE.
+    let lengths = lut_huffman!(
+        lengths = [
+            8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8,
+            8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8,
+            8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8,
+            8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8,
+            8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 9,
+            9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9,
+            9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9,
+            9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9,
+            9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 9, 7, 7, 7, 7, 7,
+            7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 8, 8, 8, 8, 8, 8, 8, 8
+        ],
+        fast_bits = 7
+    );
+    let reverse = |code: u32, len: u32| code.reverse_bits() >> (32 - len);
+    assert_eq!(Some((0, 8)), lengths.decode(reverse(0b0011_0000, 8)));
+    assert_eq!(Some((143, 8)), lengths.decode(reverse(0b1011_1111, 8)));
+    assert_eq!(Some((144, 9)), lengths.decode(reverse(0b1_1001_0000, 9)));
+    assert_eq!(Some((255, 9)), lengths.decode(reverse(0b1_1111_1111, 9)));
+    assert_eq!(Some((256, 7)), lengths.decode(0));
+    assert_eq!(Some((279, 7)), lengths.decode(reverse(0b001_0111, 7)));
+    assert_eq!(Some((287, 8)), lengths.decode(reverse(0b1100_0111, 8)));
+}
+
+#[test]
+fn huffman_incomplete_code() {
+    let code = lut_huffman!(lengths = [0, 2, 3], fast_bits = 1);
+    assert_eq!(Some((1, 2)), code.decode(0b00));
+    assert_eq!(Some((2, 3)), code.decode(0b010));
+    assert_eq!(None, code.decode(0b110));
+    assert_eq!(None, code.decode(0b01));
+}
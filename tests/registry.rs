@@ -0,0 +1,98 @@
+#![cfg(feature = "linkme")]
+#![cfg(target_os = "linux")]
+#![feature(macro_attr)]
+//! Registries of `lut_registry!` are distributed slices of `linkme`, which these tests stand in
+//! for the way `linkme` implements them on Linux: every element is a static in a link section
+//! named after the slice, which the linker delimits with `__start_` and `__stop_` symbols.
+
+extern crate self as linkme;
+
+/// The elements that the linker gathered into a section.
+pub struct DistributedSlice<T: 'static> {
+    start: *const T,
+    stop: *const T,
+}
+
+// SAFETY: The elements are statics, which are `Sync` themselves.
+unsafe impl<T: Sync> Sync for DistributedSlice<T> {}
+
+impl<T> DistributedSlice<T> {
+    pub fn iter(&self) -> std::slice::Iter<'static, T> {
+        // SAFETY: The linker places the elements of the section next to each other, between the
+        // symbols that delimit it.
+        unsafe {
+            let len = self.stop.offset_from(self.start) as usize;
+            std::slice::from_raw_parts(self.start, len).iter()
+        }
+    }
+}
+
+/// Declares a slice without arguments, and adds an element to the slice named by the argument.
+macro_rules! distributed_slice {
+    attr() { $(#[$attr:meta])* $vis:vis static $name:ident: [$ty:ty]; } => {
+        $(#[$attr])*
+        $vis static $name: $crate::DistributedSlice<$ty> = {
+            extern "Rust" {
+                #[link_name = concat!("__start_lut_", stringify!($name))]
+                static START: $ty;
+                #[link_name = concat!("__stop_lut_", stringify!($name))]
+                static STOP: $ty;
+            }
+            $crate::DistributedSlice {
+                start: unsafe { std::ptr::addr_of!(START) },
+                stop: unsafe { std::ptr::addr_of!(STOP) },
+            }
+        };
+    };
+    attr($slice:ident) { static $element:ident: $ty:ty = $value:expr; } => {
+        #[used]
+        #[link_section = concat!("lut_", stringify!($slice))]
+        static $element: $ty = $value;
+    };
+}
+
+pub(crate) use distributed_slice;
+
+numeric_lut::lut_registry!(pub static LUTS);
+
+numeric_lut::lut! {
+    #[register(LUTS)]
+    #[hash]
+    pub struct Gamma(x @ 0..256) -> u8 { ((x * x) / 255) as u8 }
+}
+
+mod nested {
+    // The stand-in names the section after the slice as written, so the registration names it
+    // without a path, and only the struct describing the table is imported.
+    use super::LutInfo;
+
+    numeric_lut::lut! {
+        #[register(LUTS)]
+        #[strategy(compute_above = 0)]
+        pub struct Grid(x @ 1..4, y @ 0..5) -> u32 { (x * 10 + y) as u32 }
+    }
+}
+
+#[test]
+fn registered_tables() {
+    let mut luts = LUTS.iter().collect::<Vec<_>>();
+    luts.sort_by_key(|lut| lut.name);
+    assert_eq!(2, luts.len());
+
+    let grid = luts[1];
+    assert_eq!(("Gamma", "Grid"), (luts[0].name, grid.name));
+    assert_eq!("registry::nested", grid.module_path);
+    assert_eq!([3, 5], grid.shape);
+    assert_eq!(("u32", 4), (grid.element_type, grid.element_size));
+    // Computed tables store nothing.
+    assert_eq!((0, None), (grid.byte_size, grid.hash));
+
+    let gamma = luts[0];
+    assert_eq!("registry", gamma.module_path);
+    assert_eq!([256], gamma.shape);
+    assert_eq!(
+        ("u8", 1, 256),
+        (gamma.element_type, gamma.element_size, gamma.byte_size)
+    );
+    assert_eq!(Some(Gamma::TABLE_HASH), gamma.hash);
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "runtime")]
+
+use numeric_lut_runtime::{Lut1, Lut2, LutN};
+
+// The traits have methods of the same names, so they are called by their paths.
+
+numeric_lut::lut! {
+    /// The sum of two numbers.
+    #[runtime]
+    pub struct Sum(x @ 0..8, #[clamp] y @ 1..=16) -> u32 { x as u32 + y as u32 }
+}
+
+numeric_lut::lut! {
+    #[runtime]
+    struct Ascii(c @ 97..=99) -> char { c as u8 as char }
+}
+
+numeric_lut::lut! {
+    #[runtime]
+    struct Volume(x @ 1..3, y @ 1..3, z @ 1..3) -> usize { x * y * z }
+}
+
+#[test]
+fn one_parameter() {
+    assert_eq!('b', Lut1::get(&Ascii, 'b' as usize));
+    assert_eq!(None, Lut1::get_checked(&Ascii, 0));
+    assert_eq!(3, Lut1::shape(&Ascii));
+    assert_eq!(
+        "abc",
+        Lut1::iter(&Ascii).map(|(_, c)| c).collect::<String>()
+    );
+}
+
+#[test]
+fn two_parameters() {
+    assert_eq!(13, Lut2::get(&Sum, 3, 10));
+    // The checked lookup doesn't clamp.
+    assert_eq!(Some(10), Lut2::get_checked(&Sum, 0, 10));
+    assert_eq!(None, Lut2::get_checked(&Sum, 0, 17));
+    assert_eq!((8, 16), Lut2::shape(&Sum));
+    assert_eq!(Some(((0, 1), 1)), Lut2::iter(&Sum).next());
+    assert_eq!(1536, Lut2::iter(&Sum).map(|(_, entry)| entry).sum::<u32>());
+}
+
+#[test]
+fn any_parameters() {
+    assert_eq!([2, 2, 2], LutN::shape(&Volume));
+    assert_eq!(8, LutN::get(&Volume, [2, 2, 2]));
+    assert_eq!(
+        27,
+        LutN::iter(&Volume).map(|(_, entry)| entry).sum::<usize>()
+    );
+}
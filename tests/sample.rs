@@ -0,0 +1,58 @@
+#![cfg(feature = "rand")]
+//! The `sample` functions of `#[sample]` draw parameters with a `rand::Rng`, which these tests
+//! stand in for with a small deterministic generator.
+
+extern crate self as rand;
+
+use std::ops::Range;
+
+pub trait Rng {
+    fn gen_range(&mut self, range: Range<usize>) -> usize;
+}
+
+/// An xorshift generator, which draws from every range it is given.
+struct XorShift(u64);
+
+impl Rng for XorShift {
+    fn gen_range(&mut self, range: Range<usize>) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        range.start + (self.0 % (range.end - range.start) as u64) as usize
+    }
+}
+
+numeric_lut::lut! {
+    #[sample]
+    pub struct Grid(x @ 3..7, #[clamp] y @ 10..=12) -> u32 { (x * 100 + y) as u32 }
+}
+
+numeric_lut::lut! {
+    #[sample]
+    pub struct Single(x @ 250..256) -> u8 { x as u8 }
+}
+
+#[test]
+fn samples_inside_of_ranges() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let mut seen = [[false; 3]; 4];
+    for _ in 0..1000 {
+        let ((x, y), entry) = Grid::sample(&mut rng);
+        assert!((3..7).contains(&x), "{}", x);
+        assert!((10..=12).contains(&y), "{}", y);
+        assert_eq!(Grid::get(x, y), entry);
+        seen[x - 3][y - 10] = true;
+    }
+    // Every entry is drawn.
+    assert!(seen.iter().flatten().all(|&seen| seen));
+}
+
+#[test]
+fn samples_of_single_parameter() {
+    let mut rng: Box<dyn Rng> = Box::new(XorShift(7));
+    for _ in 0..100 {
+        let (x, entry) = Single::sample(&mut *rng);
+        assert!((250..256).contains(&x), "{}", x);
+        assert_eq!(x as u8, entry);
+    }
+}
@@ -1,5 +1,3 @@
-#![feature(proc_macro_hygiene)]
-
 use numeric_lut::lut;
 
 #[test]
@@ -54,3 +52,732 @@ fn bad_range() {
     lut!(|x @ 8..0, y @ 8..0| -> u32 { x as u32 + y as u32 });
 }
 */
+
+#[test]
+fn offset_access_all() {
+    let lut = lut!(|x @ 3..8, y @ 2..=4| -> u32 { x as u32 * 10 + y as u32 });
+
+    for x in 3..8 {
+        for y in 2..=4 {
+            let r = lut(x, y);
+            assert_eq!((x * 10 + y) as u32, r);
+        }
+    }
+}
+
+#[test]
+fn mask_access_all() {
+    let lut = lut!(
+        #[mask]
+        |x @ 0..8, y @ 0..16| -> u32 { x as u32 + y as u32 }
+    );
+
+    for x in 0..8 {
+        for y in 0..16 {
+            let r = lut(x, y);
+            assert_eq!((x + y) as u32, r);
+        }
+    }
+}
+
+#[test]
+fn mask_wraps() {
+    let lut = lut!(
+        #[mask]
+        |x @ 0..8| -> u32 { x as u32 }
+    );
+    assert_eq!(2, lut(10));
+}
+
+#[test]
+fn mask_padded_wraps() {
+    let lut = lut!(
+        #[mask]
+        |x @ 2..=4| -> u32 { x as u32 }
+    );
+    assert_eq!(4, lut(4));
+    // Offset 3 is padding and repeats offset 0; offset 4 wraps around to offset 0.
+    assert_eq!(2, lut(5));
+    assert_eq!(2, lut(6));
+}
+
+#[test]
+fn symmetric_access_all() {
+    let lut = lut!(
+        #[symmetric]
+        |x @ 1..=6, y @ 1..=6| -> u32 { (x * 10 + y) as u32 }
+    );
+
+    for x in 1..=6 {
+        for y in 1..=6 {
+            let r = lut(x, y);
+            assert_eq!((x.max(y) * 10 + x.min(y)) as u32, r);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn symmetric_out_of_bounds() {
+    let lut = lut!(
+        #[symmetric]
+        |x @ 0..8, y @ 0..8| -> u32 { (x + y) as u32 }
+    );
+    lut(8, 0);
+}
+
+#[test]
+fn banded_access_all() {
+    let lut = lut!(
+        #[banded(2, default = 0)]
+        |x @ 1..10, y @ 0..=12| -> u32 { (x * 100 + y) as u32 }
+    );
+
+    for x in 1..10 {
+        for y in 0..=12 {
+            let r = lut(x, y);
+            if x.abs_diff(y) <= 2 {
+                assert_eq!((x * 100 + y) as u32, r);
+            } else {
+                assert_eq!(0, r);
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn banded_out_of_bounds() {
+    let lut = lut!(
+        #[banded(2, default = 0)]
+        |x @ 0..8, y @ 0..8| -> u32 { (x + y) as u32 }
+    );
+    lut(8, 7);
+}
+
+#[test]
+#[should_panic]
+fn banded_row_out_of_bounds() {
+    let lut = lut!(
+        #[banded(1, default = 7)]
+        |x @ 0..10, y @ 0..10| -> u32 { (x + y) as u32 }
+    );
+    lut(500, 5);
+}
+
+#[test]
+fn sharded_access_all() {
+    let lut = lut!(
+        #[shard(entries = 64)]
+        |x @ 0..100, y @ 0..10| -> u32 { (x * 10 + y) as u32 }
+    );
+
+    for x in 0..100 {
+        for y in 0..10 {
+            let r = lut(x, y);
+            assert_eq!((x * 10 + y) as u32, r);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn sharded_out_of_bounds() {
+    let lut = lut!(
+        #[shard(entries = 64)]
+        |x @ 0..100, y @ 0..10| -> u32 { (x * 10 + y) as u32 }
+    );
+    lut(100, 0);
+}
+
+#[test]
+fn large_access_all() {
+    let lut = lut!(
+        #[warn_above(entries = 90000)]
+        |x @ 0..300, y @ 0..300| -> u32 { (x * 300 + y) as u32 }
+    );
+
+    for x in 0..300 {
+        for y in 0..300 {
+            let r = lut(x, y);
+            assert_eq!((x * 300 + y) as u32, r);
+        }
+    }
+}
+
+#[test]
+fn isolated_access_all() {
+    let lut = lut!(
+        #[const_eval(warn, isolate)]
+        |x @ 0..8, y @ 0..16| -> u32 { x as u32 + y as u32 }
+    );
+
+    for x in 0..8 {
+        for y in 0..16 {
+            let r = lut(x, y);
+            assert_eq!((x + y) as u32, r);
+        }
+    }
+}
+
+#[test]
+fn unisolated_access_all() {
+    let lut = lut!(
+        #[const_eval(isolate_up_to = 0)]
+        |x @ 0..8, y @ 0..16| -> u32 { x as u32 * y as u32 }
+    );
+
+    for x in 0..8 {
+        for y in 0..16 {
+            let r = lut(x, y);
+            assert_eq!((x * y) as u32, r);
+        }
+    }
+}
+
+const fn product(x: usize, y: usize) -> u32 {
+    (x * y) as u32
+}
+
+#[test]
+fn path_access_all() {
+    let lut = lut!(product; x @ 0..8, y @ 2..=4);
+
+    for x in 0..8 {
+        for y in 2..=4 {
+            let r = lut(x, y);
+            assert_eq!((x * y) as u32, r);
+        }
+    }
+}
+
+#[test]
+fn path_typed_access_all() {
+    let lut = lut!(#[shard(entries = 8)] product -> u32; x @ 0..8, y @ 2..=4);
+
+    for x in 0..8 {
+        for y in 2..=4 {
+            let r = lut(x, y);
+            assert_eq!((x * y) as u32, r);
+        }
+    }
+}
+
+#[test]
+fn path_symmetric() {
+    let lut = lut!(#[symmetric] product; x @ 0..8, y @ 0..8);
+    assert_eq!(21, lut(3, 7));
+    assert_eq!(21, lut(7, 3));
+}
+
+#[test]
+fn match_access_all() {
+    let lut = lut!(
+        #[strategy(match_up_to = 16)]
+        |x @ 1..=3, y @ 2..6| -> u32 { x as u32 * 10 + y as u32 }
+    );
+
+    for x in 1..=3 {
+        for y in 2..6 {
+            assert_eq!((x * 10 + y) as u32, lut(x, y));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "`(x, y)` is (4, 2), which is outside of the table")]
+fn match_out_of_bounds() {
+    let lut = lut!(
+        #[strategy(match_up_to = 16)]
+        |x @ 1..=3, y @ 2..6| -> u32 { x as u32 * 10 + y as u32 }
+    );
+    lut(4, 2);
+}
+
+#[test]
+fn match_mask_access() {
+    let lut = lut!(
+        #[mask]
+        #[strategy(match_up_to = 16)]
+        |x @ 2..5| -> u8 { x as u8 }
+    );
+
+    assert_eq!(2, lut(2));
+    assert_eq!(4, lut(4));
+    assert_eq!(2, lut(5));
+    assert_eq!(4, lut(8));
+}
+
+#[test]
+fn match_above_threshold() {
+    let lut = lut!(
+        #[strategy(match_up_to = 4)]
+        |x @ 0..5| -> u8 { x as u8 }
+    );
+    assert_eq!(4, lut(4));
+}
+
+#[test]
+fn compute_access_all() {
+    let lut = lut!(
+        #[strategy(compute_above = 4)]
+        |x @ 1..=3, y @ 2..6| -> u32 { x as u32 * 10 + y as u32 }
+    );
+
+    for x in 1..=3 {
+        for y in 2..6 {
+            assert_eq!((x * 10 + y) as u32, lut(x, y));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "`y` must be in 2..=5 but is 6")]
+fn compute_out_of_bounds() {
+    let lut = lut!(
+        #[strategy(compute_above = 4)]
+        |x @ 1..=3, y @ 2..6| -> u32 { x as u32 * 10 + y as u32 }
+    );
+    lut(1, 6);
+}
+
+#[test]
+fn compute_mask_access() {
+    let lut = lut!(
+        #[mask]
+        #[strategy(compute_above = 1)]
+        |x @ 2..5, y @ 0..4| -> usize { x * 10 + y }
+    );
+
+    assert_eq!(40, lut(4, 4));
+    assert_eq!(21, lut(5, 1));
+    assert_eq!(43, lut(8, 3));
+}
+
+#[test]
+fn auto_strategy() {
+    let small = lut!(
+        #[strategy(auto)]
+        |x @ 0..4| -> u8 { x as u8 }
+    );
+    let medium = lut!(
+        #[strategy(auto)]
+        |x @ 0..64| -> u8 { x as u8 }
+    );
+    let large = lut!(
+        #[strategy(auto, compute_above = 64)]
+        |x @ 0..65| -> u8 { x as u8 }
+    );
+    assert_eq!(3, small(3));
+    assert_eq!(63, medium(63));
+    assert_eq!(64, large(64));
+}
+
+#[test]
+fn guard_default() {
+    let lut = lut!(
+        #[guard(x + y < 16, default = u16::MAX)]
+        |x @ 0..16, y @ 0..16| -> u16 { 15 - (x + y) as u16 }
+    );
+    for x in 0..16 {
+        for y in 0..16 {
+            let expected = if x + y < 16 {
+                15 - (x + y) as u16
+            } else {
+                u16::MAX
+            };
+            assert_eq!(expected, lut(x, y));
+        }
+    }
+}
+
+#[test]
+fn guard_zero_default() {
+    let lut = lut!(
+        #[guard(x & 1 == 0)]
+        |x @ 0..8| -> f32 { 1.0 / (x as f32 - 3.0) }
+    );
+    assert_eq!(-1.0, lut(2));
+    assert_eq!(0.0, lut(3));
+}
+
+#[test]
+fn guard_absent() {
+    let lut = lut!(
+        #[guard(y <= x, absent)]
+        |x @ 1..5, y @ 1..5| -> usize { x / y }
+    );
+    assert_eq!(Some(2), lut(4, 2));
+    assert_eq!(None, lut(2, 4));
+}
+
+#[test]
+fn guard_computed() {
+    let lut = lut!(
+        #[guard(x != 0, absent)]
+        #[strategy(compute_above = 0)]
+        |x @ 0..4| -> u8 { 6 / x as u8 }
+    );
+    assert_eq!(Some(3), lut(2));
+    assert_eq!(None, lut(0));
+}
+
+#[test]
+fn radix_literal_bounds() {
+    let lut = lut!(|reg @ 0x00..=0xFF, bit @ 0b000..0b1000| -> bool { reg >> bit & 1 == 1 });
+    assert!(lut(0xA5, 7));
+    assert!(!lut(0xA5, 6));
+
+    let lut = lut!(|x @ 1_000..1_024| -> usize { x - 1_000 });
+    assert_eq!(23, lut(1_023));
+}
+
+#[test]
+fn trailing_commas() {
+    let lut = lut!(|x @ 0..4, y @ 0..2| -> u8 { (x * 2 + y) as u8 });
+    assert_eq!(7, lut(3, 1));
+
+    const fn double(x: usize) -> u8 {
+        x as u8 * 2
+    }
+    let lut = lut!(double; x @ 0..4,);
+    assert_eq!(6, lut(3));
+}
+
+const fn seven() -> u8 {
+    7
+}
+
+#[test]
+fn zero_parameters() {
+    let lut = lut!(|| -> u8 { 3 + 4 });
+    assert_eq!(7, lut());
+
+    let lut = lut!(|| -> _ { 7u8 });
+    assert_eq!(7, lut());
+
+    let lut = lut!(seven;);
+    assert_eq!(7, lut());
+}
+
+#[test]
+fn open_lower_bound() {
+    let lut = lut!(|x @ ..4, y @ ..=2| -> u8 { (x * 3 + y) as u8 });
+    assert_eq!(11, lut(3, 2));
+    assert_eq!(0, lut(0, 0));
+}
+
+#[test]
+fn parameter_policies() {
+    let lut =
+        lut!(|#[clamp] gain @ 2..6, #[wrap] phase @ 1..=4| -> u32 { (gain * 10 + phase) as u32 });
+    assert_eq!(34, lut(3, 4));
+    assert_eq!(21, lut(0, 1));
+    assert_eq!(51, lut(100, 5));
+    assert_eq!(24, lut(2, 0));
+}
+
+#[test]
+fn wrap_below_lo() {
+    let lut = lut!(|#[wrap] x @ 2..5| -> usize { x });
+    assert_eq!([3, 4, 2, 3, 4, 2], [0, 1, 2, 3, 4, 5].map(lut));
+    assert_eq!((usize::MAX - 2) % 3 + 2, lut(usize::MAX));
+}
+
+#[test]
+fn parameter_policies_match() {
+    let lut = lut!(
+        #[strategy(match_up_to = 16)]
+        |#[wrap] x @ 0..4, #[clamp] y @ 0..1| -> u8 { (x * 2 + y) as u8 }
+    );
+    assert_eq!(6, lut(7, 9));
+    assert_eq!(2, lut(1, 0));
+}
+
+#[test]
+fn parameter_policies_computed() {
+    let lut = lut!(
+        #[strategy(compute_above = 1)]
+        |#[clamp] x @ 1..=3, #[wrap] y @ 0..1| -> usize { x * 10 + y }
+    );
+    assert_eq!(30, lut(9, 5));
+    assert_eq!(10, lut(0, 0));
+}
+
+#[test]
+#[should_panic]
+fn parameter_policies_others_panic() {
+    let lut = lut!(|#[clamp] x @ 0..4, y @ 0..4| -> usize { x + y });
+    lut(9, 4);
+}
+
+fn sentinel(x: usize, y: usize) -> i32 {
+    -((x * 100 + y) as i32)
+}
+
+#[test]
+fn fallback_function() {
+    let lut = lut!(
+        #[fallback(sentinel)]
+        |x @ 1..4, y @ 0..3| -> i32 { (x * 10 + y) as i32 }
+    );
+    assert_eq!(32, lut(3, 2));
+    assert_eq!(-403, lut(4, 3));
+    assert_eq!(-5, lut(0, 5));
+}
+
+#[test]
+fn fallback_closure_with_policy() {
+    let lut = lut!(
+        #[strategy(compute_above = 2)]
+        #[fallback(|x, _| x as u8)]
+        |x @ 0..4, #[wrap] y @ 0..2| -> u8 { (x * 2 + y) as u8 + 100 }
+    );
+    assert_eq!(103, lut(1, 3));
+    assert_eq!(9, lut(9, 3));
+}
+
+thread_local! {
+    static TRACED: std::cell::RefCell<Vec<(String, Vec<usize>, u32)>> =
+        const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn record(name: &str, params: &[usize], entry: &u32) {
+    TRACED.with(|traced| {
+        traced
+            .borrow_mut()
+            .push((name.to_owned(), params.to_vec(), *entry))
+    });
+}
+
+#[test]
+fn trace_callback() {
+    let lut = lut!(
+        #[trace(record)]
+        #[fallback(|_, _| 0)]
+        |x @ 0..4, y @ 0..2| -> u32 { (x * 2 + y) as u32 }
+    );
+    assert_eq!(7, lut(3, 1));
+    assert_eq!(0, lut(4, 0));
+
+    let traced = TRACED.with(|traced| traced.take());
+    assert_eq!(2, traced.len());
+    assert!(
+        traced[0].0.starts_with("tests/simple.rs:"),
+        "{:?}",
+        traced[0].0
+    );
+    assert_eq!((vec![3, 1], 7), (traced[0].1.clone(), traced[0].2));
+    assert_eq!((vec![4, 0], 0), (traced[1].1.clone(), traced[1].2));
+}
+
+#[test]
+fn build_script_entries() {
+    let out_dir = std::env::temp_dir().join(format!("numeric-lut-{}", std::process::id()));
+    std::env::set_var("OUT_DIR", &out_dir);
+    numeric_lut::lut_build!("squares", |x @ 1..3, y @ ..2| -> f32 {
+        (1..=x).map(|i| (i * i) as f32).sum::<f32>() - y as f32
+    });
+    numeric_lut::lut_build!("flags", |#[wrap] x @ 0..=2| -> bool { x != 1 });
+
+    let read = |name| std::fs::read_to_string(out_dir.join("numeric-lut").join(name)).unwrap();
+    assert_eq!(
+        "([(1, 2), (0, 2)], *b\"\\x00\\x00\\x80\\x3f\\x00\\x00\\x00\\x00\
+         \\x00\\x00\\xa0\\x40\\x00\\x00\\x80\\x40\")\n",
+        read("squares.rs")
+    );
+    assert_eq!("([(0, 3)], *b\"\\x01\\x00\\x01\")\n", read("flags.rs"));
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[test]
+fn total_function() {
+    let lut = lut!(
+        #[total]
+        |#[wrap] x @ 2..5, #[clamp] y @ 1..=3| -> u32 { (x * 10 + y) as u32 }
+    );
+    assert_eq!(23, lut(2, 3));
+    assert_eq!(21, lut(5, 0));
+    assert_eq!(43, lut(7, usize::MAX));
+
+    let masked = lut!(
+        #[total]
+        #[mask]
+        |x @ 0..3| -> u8 { x as u8 }
+    );
+    assert_eq!(1, masked(5));
+}
+
+#[test]
+fn deduplicated_rows() {
+    let lut = lut!(
+        #[dedup]
+        |x @ 0..6, y @ 1..4, z @ 0..3| -> u32 { ((x % 2 + y % 2) * 10 + z) as u32 }
+    );
+    for x in 0..6 {
+        for y in 1..4 {
+            for z in 0..3 {
+                assert_eq!(((x % 2 + y % 2) * 10 + z) as u32, lut(x, y, z));
+            }
+        }
+    }
+
+    let floats = lut!(
+        #[dedup]
+        |x @ 0..4, y @ 0..2| -> f32 {
+            if x == 2 {
+                f32::NAN
+            } else {
+                y as f32
+            }
+        }
+    );
+    assert_eq!(1.0, floats(3, 1));
+    assert!(floats(2, 0).is_nan());
+}
+
+#[test]
+#[should_panic(expected = "`y` must be in 1..=3 but is 4")]
+fn deduplicated_rows_out_of_range() {
+    let lut = lut!(
+        #[dedup]
+        |x @ 0..2, y @ 1..4, z @ 0..3| -> u8 { (y / 2) as u8 }
+    );
+    lut(0, 4, 0);
+}
+
+#[test]
+fn index_width() {
+    let symmetric = lut!(
+        #[symmetric]
+        #[index_width(u16)]
+        |x @ 0..32, y @ 0..32| -> u32 { (x * y) as u32 }
+    );
+    assert_eq!(31 * 30, symmetric(30, 31));
+
+    let deduplicated = lut!(
+        #[dedup]
+        #[index_width(u32)]
+        |x @ 0..100, y @ 0..3, z @ 0..2| -> u32 { ((x + y) % 3 + z) as u32 }
+    );
+    assert_eq!(3, deduplicated(99, 2, 1));
+}
+
+/// Packs a pair of nibbles into a byte.
+const fn pack((hi, lo): (u8, u8)) -> u8 {
+    hi << 4 | lo
+}
+
+fn unpack(byte: u8) -> (u8, u8) {
+    (byte >> 4, byte & 0xf)
+}
+
+#[test]
+fn codec_bitfields() {
+    let lut = lut!(
+        #[codec(u8, encode = pack, decode = unpack)]
+        |x @ 0..16, y @ 0..16| -> (u8, u8) { ((x ^ y) as u8, (15 - y) as u8) }
+    );
+    for x in 0..16 {
+        for y in 0..16 {
+            assert_eq!(((x ^ y) as u8, (15 - y) as u8), lut(x, y));
+        }
+    }
+
+    let computed = lut!(
+        #[codec(u8, encode = pack, decode = unpack)]
+        #[strategy(compute_above = 1)]
+        |x @ 0..4| -> (u8, u8) { (x as u8, 1) }
+    );
+    assert_eq!((3, 1), computed(3));
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cell {
+    flags: u8,
+    open: bool,
+    cost: u16,
+}
+
+#[test]
+fn packed_bitfields() {
+    let lut = lut!(
+        #[bitfields(u16, flags: u8 = 3, open: bool = 1, cost: u16 = 12)]
+        |x @ 0..8, y @ 0..64| -> Cell {
+            Cell {
+                flags: x as u8,
+                open: y & 1 == 0,
+                cost: (y * 64 + x) as u16,
+            }
+        }
+    );
+    for x in 0..8 {
+        for y in 0..64 {
+            let expected = Cell {
+                flags: x as u8,
+                open: y & 1 == 0,
+                cost: (y * 64 + x) as u16,
+            };
+            assert_eq!(expected, lut(x, y));
+        }
+    }
+
+    let computed = lut!(
+        #[bitfields(u16, flags: u8 = 7, open: bool = 1, cost: u16 = 8)]
+        #[strategy(compute_above = 1)]
+        |x @ 0..4| -> Cell {
+            Cell {
+                flags: 127,
+                open: x > 1,
+                cost: 255,
+            }
+        }
+    );
+    assert_eq!(
+        Cell {
+            flags: 127,
+            open: true,
+            cost: 255
+        },
+        computed(3)
+    );
+}
+
+#[test]
+fn const_table_closure() {
+    let lut = lut!(
+        #[const_table]
+        #[shard(entries = 8)]
+        #[check(monotone_increasing(x))]
+        |x @ 0..100, y @ 0..2| -> u32 { (x * 2 + y) as u32 }
+    );
+    assert_eq!(0, lut(0, 0));
+    assert_eq!(199, lut(99, 1));
+}
+
+#[test]
+fn cfg_params() {
+    let lut = lut!(|#[cfg(test)] x @ 0..4,
+                    #[cfg(not(test))] x @ 0..2,
+                    #[cfg(target_os = "none")] y @ 0..2|
+     -> usize { x * 2 });
+    assert_eq!(6, lut(3));
+}
+
+#[test]
+fn permute() {
+    let reversed = lut!(
+        |#[permute(bit_reverse)] k @ 0..16, #[permute(gray)] g @ 0..4| -> usize { k * 10 + g }
+    );
+    let matched = lut!(
+        #[strategy(match_up_to = 64)]
+        |#[permute(bit_reverse)] k @ 0..16, #[permute(gray)] g @ 0..4| -> usize { k * 10 + g }
+    );
+    for raw in 0..16 {
+        let k = (raw as u8).reverse_bits() as usize >> 4;
+        for (code, g) in [(0b00, 0), (0b01, 1), (0b11, 2), (0b10, 3)] {
+            assert_eq!(k * 10 + g, reversed(raw, code));
+            assert_eq!(k * 10 + g, matched(raw, code));
+        }
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "wasm-bindgen")]
+#![feature(macro_attr)]
+//! The lookups that `#[wasm_bindgen]` exports are annotated with the attribute of `wasm-bindgen`,
+//! which these tests stand in for, so that the exported functions can be called from Rust.
+
+extern crate self as wasm_bindgen;
+
+/// Leaves the exported function as it is, and only accepts the JavaScript names of the tables
+/// below, so that a lookup exported under another name fails to build.
+macro_rules! wasm_bindgen {
+    attr(js_name = "gammaCorrect") { $($item:tt)* } => {
+        $($item)*
+    };
+    attr(js_name = "gridSum") { $($item:tt)* } => {
+        $($item)*
+    };
+}
+
+pub mod prelude {
+    pub(crate) use wasm_bindgen;
+}
+
+numeric_lut::lut! {
+    #[wasm_bindgen(name = "gammaCorrect")]
+    pub struct Gamma(x @ 0..256) -> u8 { ((x * x) / 255) as u8 }
+}
+
+numeric_lut::lut! {
+    #[wasm_bindgen]
+    pub struct GridSum(#[clamp] x @ 2..5, y @ 0..4) -> u32 { (x * 10 + y) as u32 }
+}
+
+#[test]
+fn exported_lookups() {
+    for x in 0..256 {
+        assert_eq!(Gamma::get(x as usize), gamma_wasm(x));
+    }
+    assert_eq!(43, grid_sum_wasm(4, 3));
+    assert_eq!(21, grid_sum_wasm(0, 1));
+}
@@ -0,0 +1,53 @@
+use numeric_lut::lut_zip;
+
+#[test]
+fn product() {
+    let lut = lut_zip!(
+        |x @ 1..=8| -> u32 { x as u32 * x as u32 },
+        |x @ 1..=8| -> u32 { 9 - x as u32 },
+        |square, rest| -> u32 { square * rest },
+    );
+    for x in 1..=8 {
+        assert_eq!((x * x * (9 - x)) as u32, lut(x));
+    }
+}
+
+#[test]
+fn renamed_parameters() {
+    let lut = lut_zip!(
+        |row @ 0..3, col @ 0..4| -> i32 { row as i32 * 10 },
+        |y @ 0..3, x @ 0..4| -> i32 { x as i32 - y as i32 },
+        |tens, diff| -> (i32, i32, usize) { (tens, diff, col) }
+    );
+    assert_eq!((20, 1, 3), lut(2, 3));
+    assert_eq!((10, -1, 0), lut(1, 0));
+}
+
+#[test]
+fn inferred_different_types() {
+    let lut = lut_zip!(
+        |#[clamp] x @ 0..4| -> bool { x & 1 == 0 },
+        |x @ 0..4| -> char { (b'a' + x as u8) as char },
+        |even, letter| if even {
+            letter.to_ascii_uppercase()
+        } else {
+            letter
+        },
+    );
+    assert_eq!('A', lut(0));
+    assert_eq!('b', lut(1));
+    assert_eq!('C', lut(2));
+    assert_eq!('d', lut(10));
+}
+
+#[test]
+fn options() {
+    let lut = lut_zip!(
+        |x @ 0..8| -> u8 { x as u8 },
+        |x @ 0..8| -> u8 { 7 - x as u8 },
+        #[strategy(compute_above = 1)]
+        |a, b| -> u8 { a * b },
+    );
+    assert_eq!(12, lut(3));
+    assert_eq!(0, lut(7));
+}